@@ -0,0 +1,299 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config::RetryConfig;
+use crate::errors::GameEngineError;
+use crate::match_state_machine::GameEngineAction;
+use crate::match_store::persistence_error;
+use crate::match_tracker::TrackedAction;
+
+/// Failed action waiting in the retry queue
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingAction {
+    pub id: i64,
+    pub action: TrackedAction,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// Persistent retry queue for state machine actions that failed transiently
+///
+/// Actions are retried with exponential backoff. Once `max_attempts` is used up
+/// they stay in the table as dead letters for an operator to follow up on.
+pub struct ActionRetryQueue {
+    connection: Mutex<Connection>,
+    config: RetryConfig,
+}
+
+impl ActionRetryQueue {
+    /// Open (or create) the queue in the given SQLite database
+    pub fn open(path: impl AsRef<Path>, config: RetryConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    /// Queue that only lives as long as the process, for when persistence is disabled
+    pub fn in_memory(config: RetryConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    fn with_connection(
+        connection: Connection,
+        config: RetryConfig,
+    ) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS action_queue (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    match_id TEXT NOT NULL,
+                    action TEXT NOT NULL,
+                    attempts INTEGER NOT NULL,
+                    next_attempt_at INTEGER NOT NULL,
+                    last_error TEXT NOT NULL,
+                    dead INTEGER NOT NULL DEFAULT 0
+                );",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            config,
+        })
+    }
+
+    /// Actions whose loss would cost a player loot, a wager refund or locked tokens
+    pub fn is_retryable(action: &GameEngineAction) -> bool {
+        matches!(
+            action,
+            GameEngineAction::DistributeLoot { .. }
+                | GameEngineAction::InvalidateMatch { .. }
+                | GameEngineAction::RefundWagers { .. }
+                | GameEngineAction::CancelChallenge { .. }
+                | GameEngineAction::CollectMatchFee { .. }
+        )
+    }
+
+    /// Delay before the next attempt, doubling per attempt up to the configured cap
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+        let seconds = self
+            .config
+            .base_delay_seconds
+            .saturating_mul(factor)
+            .min(self.config.max_delay_seconds);
+        Duration::seconds(seconds as i64)
+    }
+
+    /// Queue an action after its first failed attempt
+    pub fn enqueue(&self, action: &TrackedAction, error: &str) -> Result<(), GameEngineError> {
+        let encoded = serde_json::to_string(action)
+            .map_err(|e| GameEngineError::Persistence(format!("Failed to encode action: {e}")))?;
+        let next_attempt_at = Utc::now() + self.backoff(1);
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO action_queue (match_id, action, attempts, next_attempt_at, last_error)
+                 VALUES (?1, ?2, 1, ?3, ?4)",
+                params![action.match_id, encoded, next_attempt_at.timestamp(), error],
+            )
+            .map_err(persistence_error)?;
+
+        info!(
+            "📥 Queued failed action for match {} (retry at {})",
+            action.match_id, next_attempt_at
+        );
+        Ok(())
+    }
+
+    /// Live actions due for another attempt
+    pub fn due(&self, now: DateTime<Utc>) -> Result<Vec<PendingAction>, GameEngineError> {
+        self.query(
+            "SELECT id, action, attempts, last_error FROM action_queue
+             WHERE dead = 0 AND next_attempt_at <= ?1 ORDER BY next_attempt_at",
+            now.timestamp(),
+        )
+    }
+
+    /// Actions that ran out of attempts
+    pub fn dead_letters(&self) -> Result<Vec<PendingAction>, GameEngineError> {
+        self.query(
+            "SELECT id, action, attempts, last_error FROM action_queue
+             WHERE dead = ?1 ORDER BY id",
+            1,
+        )
+    }
+
+    /// Drop an action that finally succeeded
+    pub fn complete(&self, id: i64) -> Result<(), GameEngineError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM action_queue WHERE id = ?1", params![id])
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+
+    /// Record another failure, returning false once the action became a dead letter
+    ///
+    /// Failures that retrying cannot fix go straight to the dead letters.
+    pub fn reschedule(
+        &self,
+        pending: &PendingAction,
+        error: &GameEngineError,
+    ) -> Result<bool, GameEngineError> {
+        let attempts = pending.attempts + 1;
+        let dead = attempts >= self.config.max_attempts || !error.is_retryable();
+        let error = error.to_string();
+        let next_attempt_at = Utc::now() + self.backoff(attempts);
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE action_queue
+                 SET attempts = ?2, next_attempt_at = ?3, last_error = ?4, dead = ?5
+                 WHERE id = ?1",
+                params![
+                    pending.id,
+                    attempts,
+                    next_attempt_at.timestamp(),
+                    error,
+                    dead
+                ],
+            )
+            .map_err(persistence_error)?;
+
+        if dead {
+            warn!(
+                "☠️ Action for match {} moved to dead letters after {} attempts: {}",
+                pending.action.match_id, attempts, error
+            );
+        }
+        Ok(!dead)
+    }
+
+    fn query(&self, sql: &str, param: i64) -> Result<Vec<PendingAction>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(sql).map_err(persistence_error)?;
+
+        let rows = statement
+            .query_map(params![param], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u32>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(persistence_error)?;
+
+        let mut pending = Vec::new();
+        for row in rows {
+            let (id, action, attempts, last_error) = row.map_err(persistence_error)?;
+            let action = serde_json::from_str(&action).map_err(|e| {
+                GameEngineError::Persistence(format!("Corrupt queued action {id}: {e}"))
+            })?;
+            pending.push(PendingAction {
+                id,
+                action,
+                attempts,
+                last_error,
+            });
+        }
+        Ok(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            base_delay_seconds: 5,
+            max_delay_seconds: 60,
+            max_attempts: 3,
+            ..RetryConfig::default()
+        }
+    }
+
+    fn loot_action() -> TrackedAction {
+        TrackedAction {
+            match_id: "match_1".to_string(),
+            action: GameEngineAction::DistributeLoot {
+                match_id: "match_1".to_string(),
+                winner_npub: Some("npub1alice".to_string()),
+            },
+            triggered_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let queue = ActionRetryQueue::in_memory(config()).unwrap();
+        assert_eq!(queue.backoff(1), Duration::seconds(5));
+        assert_eq!(queue.backoff(3), Duration::seconds(20));
+        assert_eq!(queue.backoff(10), Duration::seconds(60));
+    }
+
+    #[test]
+    fn test_failed_action_retries_then_dead_letters() {
+        let queue = ActionRetryQueue::in_memory(config()).unwrap();
+        queue.enqueue(&loot_action(), "mint down").unwrap();
+
+        assert!(queue.due(Utc::now()).unwrap().is_empty());
+        let later = Utc::now() + Duration::hours(1);
+        let pending = queue.due(later).unwrap().remove(0);
+        assert_eq!(pending.action.match_id, "match_1");
+
+        let mint_down = GameEngineError::transient("mint down");
+        assert!(queue.reschedule(&pending, &mint_down).unwrap());
+        let pending = queue.due(later).unwrap().remove(0);
+        assert!(!queue.reschedule(&pending, &mint_down).unwrap());
+
+        assert!(queue.due(later).unwrap().is_empty());
+        let dead = queue.dead_letters().unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].last_error, "Transient failure: mint down");
+
+        queue.complete(dead[0].id).unwrap();
+        assert!(queue.dead_letters().unwrap().is_empty());
+
+        // A refusal on the merits will not change on retry
+        queue.enqueue(&loot_action(), "mint down").unwrap();
+        let pending = queue.due(later).unwrap().remove(0);
+        let refused = GameEngineError::CashuError("quote already issued".to_string());
+        assert!(!queue.reschedule(&pending, &refused).unwrap());
+        assert_eq!(queue.dead_letters().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_failed_match_fee_is_queued_with_its_amount() {
+        let fee = GameEngineAction::CollectMatchFee {
+            match_id: "match_1".to_string(),
+            amount: 10,
+        };
+        assert!(ActionRetryQueue::is_retryable(&fee));
+
+        let queue = ActionRetryQueue::in_memory(config()).unwrap();
+        let action = TrackedAction {
+            action: fee,
+            ..loot_action()
+        };
+        queue.enqueue(&action, "mint down").unwrap();
+
+        let later = Utc::now() + Duration::hours(1);
+        let pending = queue.due(later).unwrap().remove(0);
+        assert!(matches!(
+            pending.action.action,
+            GameEngineAction::CollectMatchFee { amount: 10, .. }
+        ));
+    }
+}
@@ -0,0 +1,372 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+use crate::action_queue::{ActionRetryQueue, PendingAction};
+use crate::audit::AuditRecord;
+use crate::config::AdminConfig;
+use crate::errors::GameEngineError;
+use crate::match_state_machine::Invalidation;
+use crate::match_tracker::{MatchTracker, TrackedMatch};
+
+/// Operator command, sent as a text frame such as `inspect <match_id>`
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminCommand {
+    ListMatches,
+    Inspect { match_id: String },
+    ForceInvalidate { match_id: String, reason: String },
+    DeadLetters,
+}
+
+impl AdminCommand {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut words = input.split_whitespace();
+        let command = words.next().ok_or("Empty command")?;
+        let match_id = words.next().map(str::to_string);
+
+        match (command, match_id) {
+            ("list-matches", None) => Ok(Self::ListMatches),
+            ("dead-letters", None) => Ok(Self::DeadLetters),
+            ("inspect", Some(match_id)) => Ok(Self::Inspect { match_id }),
+            ("force-invalidate", Some(match_id)) => {
+                let reason = words.collect::<Vec<_>>().join(" ");
+                Ok(Self::ForceInvalidate {
+                    match_id,
+                    reason: if reason.is_empty() {
+                        "Invalidated by operator".to_string()
+                    } else {
+                        reason
+                    },
+                })
+            }
+            ("list-matches" | "dead-letters", Some(_)) => {
+                Err(format!("{command} takes no arguments"))
+            }
+            ("inspect" | "force-invalidate", None) => Err(format!("{command} requires a match_id")),
+            _ => Err(format!("Unknown command: {command}")),
+        }
+    }
+}
+
+/// One row of `list-matches`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchSummary {
+    pub match_id: String,
+    pub phase: String,
+    pub players: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_updated: DateTime<Utc>,
+    pub action_count: u64,
+}
+
+impl MatchSummary {
+    fn new(match_id: String, tracked_match: &TrackedMatch) -> Self {
+        Self {
+            match_id,
+            phase: tracked_match.state.phase_name().to_string(),
+            players: tracked_match.state.players(),
+            created_at: tracked_match.created_at,
+            last_updated: tracked_match.last_updated,
+            action_count: tracked_match.action_count,
+        }
+    }
+}
+
+/// JSON message sent to operators, tagged by `type`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AdminMessage {
+    Transition(AuditRecord),
+    Matches {
+        matches: Vec<MatchSummary>,
+    },
+    Match {
+        match_id: String,
+        tracked_match: Box<TrackedMatch>,
+    },
+    Invalidated {
+        match_id: String,
+        reason: String,
+    },
+    DeadLetters {
+        actions: Vec<PendingAction>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Run an operator command against the tracker and the action retry queue
+pub async fn execute(
+    tracker: &MatchTracker,
+    retry_queue: &ActionRetryQueue,
+    command: AdminCommand,
+) -> AdminMessage {
+    match command {
+        AdminCommand::ListMatches => AdminMessage::Matches {
+            matches: tracker
+                .tracked_matches()
+                .await
+                .into_iter()
+                .map(|(match_id, tracked_match)| MatchSummary::new(match_id, &tracked_match))
+                .collect(),
+        },
+        AdminCommand::Inspect { match_id } => match tracker.get_tracked_match(&match_id).await {
+            Some(tracked_match) => AdminMessage::Match {
+                match_id,
+                tracked_match: Box::new(tracked_match),
+            },
+            None => AdminMessage::Error {
+                message: GameEngineError::MatchNotFound(match_id).to_string(),
+            },
+        },
+        AdminCommand::ForceInvalidate { match_id, reason } => {
+            match tracker.get_match_state(&match_id).await {
+                None => {
+                    return AdminMessage::Error {
+                        message: GameEngineError::MatchNotFound(match_id).to_string(),
+                    }
+                }
+                Some(state) if state.is_terminal() => {
+                    return AdminMessage::Error {
+                        message: format!(
+                            "Match {match_id} already finished as {}",
+                            state.phase_name()
+                        ),
+                    }
+                }
+                Some(_) => {}
+            }
+
+            warn!(
+                "🛑 Operator force-invalidating match {}: {}",
+                match_id, reason
+            );
+            match tracker
+                .invalidate_match(&match_id, Invalidation::new(reason.clone()))
+                .await
+            {
+                Ok(()) => AdminMessage::Invalidated { match_id, reason },
+                Err(e) => AdminMessage::Error {
+                    message: e.to_string(),
+                },
+            }
+        }
+        AdminCommand::DeadLetters => match retry_queue.dead_letters() {
+            Ok(actions) => AdminMessage::DeadLetters { actions },
+            Err(e) => AdminMessage::Error {
+                message: e.to_string(),
+            },
+        },
+    }
+}
+
+#[derive(Clone)]
+struct AdminState {
+    tracker: Arc<MatchTracker>,
+    retry_queue: Arc<ActionRetryQueue>,
+    token: Arc<str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Serve the operator WebSocket at `/admin/ws`
+pub async fn run_admin_server(
+    tracker: Arc<MatchTracker>,
+    retry_queue: Arc<ActionRetryQueue>,
+    config: AdminConfig,
+) -> Result<(), GameEngineError> {
+    if config.token.is_empty() {
+        return Err(GameEngineError::Internal(
+            "Admin API is enabled but no token is configured".to_string(),
+        ));
+    }
+
+    let state = AdminState {
+        tracker,
+        retry_queue,
+        token: Arc::from(config.token.as_str()),
+    };
+    let app = Router::new()
+        .route("/admin/ws", get(admin_socket))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(&config.bind_address)
+        .await
+        .map_err(|e| {
+            GameEngineError::Internal(format!(
+                "Failed to bind admin endpoint {}: {e}",
+                config.bind_address
+            ))
+        })?;
+
+    info!(
+        "🛠️ Admin API listening on ws://{}/admin/ws",
+        config.bind_address
+    );
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| GameEngineError::Internal(format!("Admin endpoint failed: {e}")))
+}
+
+async fn admin_socket(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !is_authorized(&state.token, &headers, query.token.as_deref()) {
+        warn!("🔒 Rejected unauthenticated admin connection");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    ws.on_upgrade(move |socket| serve_operator(socket, state.tracker, state.retry_queue))
+}
+
+/// Accept the token as a bearer header, or as `?token=` for clients that cannot set headers
+fn is_authorized(expected: &str, headers: &HeaderMap, query_token: Option<&str>) -> bool {
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(query_token);
+    presented.is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Answer commands and stream transitions until the operator disconnects
+async fn serve_operator(
+    mut socket: WebSocket,
+    tracker: Arc<MatchTracker>,
+    retry_queue: Arc<ActionRetryQueue>,
+) {
+    info!("🛠️ Operator connected to admin API");
+    let mut transitions = tracker.subscribe_transitions();
+
+    loop {
+        let reply = tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Text(text))) => match AdminCommand::parse(&text) {
+                    Ok(command) => execute(&tracker, &retry_queue, command).await,
+                    Err(message) => AdminMessage::Error { message },
+                },
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue, // Pings are answered by axum
+                Some(Err(e)) => {
+                    warn!("🛠️ Admin socket error: {}", e);
+                    break;
+                }
+            },
+            transition = transitions.recv() => match transition {
+                Ok(record) => AdminMessage::Transition(record),
+                Err(RecvError::Lagged(skipped)) => AdminMessage::Error {
+                    message: format!("Fell behind, {skipped} transitions were dropped"),
+                },
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        let text = match serde_json::to_string(&reply) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("🛠️ Failed to encode admin message: {}", e);
+                continue;
+            }
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+
+    info!("🛠️ Operator disconnected from admin API");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_parse_commands_and_authorize() {
+        assert_eq!(
+            AdminCommand::parse("list-matches"),
+            Ok(AdminCommand::ListMatches)
+        );
+        assert_eq!(
+            AdminCommand::parse("inspect match_1"),
+            Ok(AdminCommand::Inspect {
+                match_id: "match_1".to_string()
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("force-invalidate match_1 collusion reported"),
+            Ok(AdminCommand::ForceInvalidate {
+                match_id: "match_1".to_string(),
+                reason: "collusion reported".to_string()
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("dead-letters"),
+            Ok(AdminCommand::DeadLetters)
+        );
+        assert!(AdminCommand::parse("inspect").is_err());
+        assert!(AdminCommand::parse("drop-tables").is_err());
+
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized("s3cret", &headers, None));
+        assert!(is_authorized("s3cret", &headers, Some("s3cret")));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cre"),
+        );
+        assert!(!is_authorized("s3cret", &headers, None));
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer s3cret"),
+        );
+        assert!(is_authorized("s3cret", &headers, None));
+    }
+
+    #[tokio::test]
+    async fn test_commands_on_unknown_match_report_errors() {
+        let (tracker, _actions) = MatchTracker::new(10, 30);
+        let retry_queue = ActionRetryQueue::in_memory(RetryConfig::default()).unwrap();
+
+        let listed = execute(&tracker, &retry_queue, AdminCommand::ListMatches).await;
+        assert!(matches!(listed, AdminMessage::Matches { matches } if matches.is_empty()));
+
+        let invalidated = execute(
+            &tracker,
+            &retry_queue,
+            AdminCommand::ForceInvalidate {
+                match_id: "missing".to_string(),
+                reason: "test".to_string(),
+            },
+        )
+        .await;
+        assert!(
+            matches!(invalidated, AdminMessage::Error { message } if message.contains("missing"))
+        );
+
+        let dead = execute(&tracker, &retry_queue, AdminCommand::DeadLetters).await;
+        assert!(matches!(dead, AdminMessage::DeadLetters { actions } if actions.is_empty()));
+    }
+}
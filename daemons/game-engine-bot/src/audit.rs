@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::AuditConfig;
+use crate::errors::GameEngineError;
+use crate::match_state_machine::{MatchEvent, MatchState};
+
+/// One state-machine transition, as written to the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub recorded_at: DateTime<Utc>,
+    pub match_id: String,
+    pub event: String, // State machine event name, e.g. "TokenRevealed"
+    pub actor_npub: Option<String>,
+    pub player_npubs: Vec<String>,
+    pub commitment_hashes: Vec<String>,
+    pub from_phase: String,
+    pub to_phase: String,
+    pub verdict: String, // accepted, rejected, invalidated or completed
+    pub reason: Option<String>,
+}
+
+impl AuditRecord {
+    /// Describe the transition `event` caused from `previous` to `new_state`
+    pub fn from_transition(
+        match_id: &str,
+        event: &MatchEvent,
+        previous: &MatchState,
+        new_state: &MatchState,
+        errors: &[String],
+    ) -> Self {
+        let (verdict, reason) = if !errors.is_empty() {
+            ("rejected", Some(errors.join("; ")))
+        } else {
+            match new_state {
+                MatchState::Invalid { reason, .. } if !previous.is_terminal() => {
+                    ("invalidated", Some(reason.clone()))
+                }
+                MatchState::Completed { .. } if !previous.is_terminal() => ("completed", None),
+                _ => ("accepted", None),
+            }
+        };
+
+        let mut player_npubs = previous.players();
+        for npub in new_state.players() {
+            if !player_npubs.contains(&npub) {
+                player_npubs.push(npub);
+            }
+        }
+
+        Self {
+            recorded_at: Utc::now(),
+            match_id: match_id.to_string(),
+            event: event_name(event).to_string(),
+            actor_npub: actor_npub(event),
+            player_npubs,
+            commitment_hashes: commitment_hashes(event),
+            from_phase: previous.phase_name().to_string(),
+            to_phase: new_state.phase_name().to_string(),
+            verdict: verdict.to_string(),
+            reason,
+        }
+    }
+}
+
+fn event_name(event: &MatchEvent) -> &'static str {
+    match event {
+        MatchEvent::ChallengePosted(_) => "ChallengePosted",
+        MatchEvent::ChallengeAccepted(_) => "ChallengeAccepted",
+        MatchEvent::TokenRevealed(_) => "TokenRevealed",
+        MatchEvent::BanCommitted(_) => "BanCommitted",
+        MatchEvent::BansRevealed(_) => "BansRevealed",
+        MatchEvent::CombatMoveSubmitted(_) => "CombatMoveSubmitted",
+        MatchEvent::ResultSubmitted(_) => "ResultSubmitted",
+        MatchEvent::LootDistributed(_) => "LootDistributed",
+        MatchEvent::InvalidationTriggered(_) => "InvalidationTriggered",
+        MatchEvent::SuddenDeathOrdered => "SuddenDeathOrdered",
+        MatchEvent::TimeoutExpired => "TimeoutExpired",
+    }
+}
+
+fn actor_npub(event: &MatchEvent) -> Option<String> {
+    match event {
+        MatchEvent::ChallengePosted(challenge) => Some(challenge.challenger_npub.clone()),
+        MatchEvent::ChallengeAccepted(acceptance) => Some(acceptance.acceptor_npub.clone()),
+        MatchEvent::TokenRevealed(reveal) => Some(reveal.player_npub.clone()),
+        MatchEvent::BanCommitted(commitment) => Some(commitment.player_npub.clone()),
+        MatchEvent::BansRevealed(reveal) => Some(reveal.player_npub.clone()),
+        MatchEvent::CombatMoveSubmitted(combat_move) => Some(combat_move.player_npub.clone()),
+        MatchEvent::ResultSubmitted(result) => Some(result.player_npub.clone()),
+        MatchEvent::LootDistributed(loot) => Some(loot.game_engine_npub.clone()),
+        MatchEvent::InvalidationTriggered(invalidation) => invalidation.offending_npub.clone(),
+        MatchEvent::SuddenDeathOrdered | MatchEvent::TimeoutExpired => None,
+    }
+}
+
+/// Commitments and evidence the event binds the match to
+fn commitment_hashes(event: &MatchEvent) -> Vec<String> {
+    match event {
+        MatchEvent::ChallengePosted(challenge) => vec![
+            challenge.cashu_token_commitment.clone(),
+            challenge.army_commitment.clone(),
+        ],
+        MatchEvent::ChallengeAccepted(acceptance) => vec![
+            acceptance.cashu_token_commitment.clone(),
+            acceptance.army_commitment.clone(),
+        ],
+        MatchEvent::BanCommitted(commitment) => vec![commitment.ban_commitment.clone()],
+        MatchEvent::CombatMoveSubmitted(combat_move) => {
+            combat_move.previous_event_hash.iter().cloned().collect()
+        }
+        MatchEvent::InvalidationTriggered(invalidation) => invalidation.evidence_hashes.clone(),
+        _ => Vec::new(),
+    }
+}
+
+struct AuditFile {
+    file: File,
+    size: u64,
+}
+
+/// Append-only JSON lines log of every state-machine transition, rotated by size
+pub struct AuditLog {
+    config: AuditConfig,
+    file: Mutex<AuditFile>,
+}
+
+impl AuditLog {
+    pub fn open(config: AuditConfig) -> Result<Self, GameEngineError> {
+        let file = open_append(Path::new(&config.path))?;
+        Ok(Self {
+            config,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append a record, rotating the file first if it has grown past the size limit
+    pub fn record(&self, record: &AuditRecord) -> Result<(), GameEngineError> {
+        let mut line = serde_json::to_string(record).map_err(|e| {
+            GameEngineError::Internal(format!("Failed to encode audit record: {e}"))
+        })?;
+        line.push('\n');
+
+        let mut current = self.file.lock().unwrap();
+        if current.size > 0 && current.size + line.len() as u64 > self.config.max_file_bytes {
+            self.rotate()?;
+            *current = open_append(Path::new(&self.config.path))?;
+        }
+
+        current
+            .file
+            .write_all(line.as_bytes())
+            .and_then(|_| current.file.flush())
+            .map_err(|e| GameEngineError::Internal(format!("Failed to write audit log: {e}")))?;
+        current.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift `audit.jsonl` to `audit.jsonl.1`, `.1` to `.2`, ... dropping the oldest
+    fn rotate(&self) -> Result<(), GameEngineError> {
+        let rotation_error = |e: std::io::Error| {
+            GameEngineError::Internal(format!("Failed to rotate audit log: {e}"))
+        };
+
+        let keep = self.config.max_files.max(1);
+        let oldest = rotated_path(&self.config.path, keep);
+        if oldest.exists() {
+            fs::remove_file(&oldest).map_err(rotation_error)?;
+        }
+        for index in (1..keep).rev() {
+            let from = rotated_path(&self.config.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.config.path, index + 1))
+                    .map_err(rotation_error)?;
+            }
+        }
+        fs::rename(&self.config.path, rotated_path(&self.config.path, 1)).map_err(rotation_error)
+    }
+}
+
+fn rotated_path(path: &str, index: u32) -> PathBuf {
+    PathBuf::from(format!("{path}.{index}"))
+}
+
+fn open_append(path: &Path) -> Result<AuditFile, GameEngineError> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).map_err(|e| {
+            GameEngineError::Internal(format!("Failed to create audit log directory: {e}"))
+        })?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| {
+            GameEngineError::Internal(format!("Failed to open audit log {}: {e}", path.display()))
+        })?;
+    let size = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    Ok(AuditFile { file, size })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_events::{test_support, MatchChallenge};
+
+    fn challenge() -> MatchChallenge {
+        test_support::challenge("match_1")
+    }
+
+    #[test]
+    fn test_transitions_are_appended_and_rotated() {
+        let dir = std::env::temp_dir().join(format!("manastr-audit-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("audit.jsonl").to_string_lossy().to_string();
+        let log = AuditLog::open(AuditConfig {
+            enabled: true,
+            path: path.clone(),
+            max_file_bytes: 1,
+            max_files: 2,
+        })
+        .unwrap();
+
+        let event = MatchEvent::ChallengePosted(challenge());
+        let previous = MatchState::new_challenge(challenge());
+        let record = AuditRecord::from_transition("match_1", &event, &previous, &previous, &[]);
+        assert_eq!(record.verdict, "accepted");
+        assert_eq!(
+            record.commitment_hashes,
+            ["alice_token_commitment", "alice_army_commitment"]
+        );
+
+        for _ in 0..4 {
+            log.record(&record).unwrap();
+        }
+
+        // One record per file once the size limit is hit, oldest beyond max_files dropped
+        let current = fs::read_to_string(&path).unwrap();
+        let parsed: AuditRecord = serde_json::from_str(current.trim_end()).unwrap();
+        assert_eq!(parsed.match_id, "match_1");
+        assert!(rotated_path(&path, 1).exists());
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,63 +1,449 @@
+use crate::circuit_breaker::CircuitBreaker;
+use crate::config::{FeeRecipient, LeagueMintConfig, MintHttpConfig, MintTrustPolicy};
 use crate::errors::GameEngineError;
+use crate::loot_token::{
+    blind_outputs, encode_token, hash_to_curve, p2pk_secret, random_secret, split_amount,
+    unblind_signatures, BlindSignature, BlindedMessage, MintKeyset, Proof,
+};
+use crate::metrics::EngineMetrics;
+use crate::mint_auth::authorization_header;
+use crate::mint_policy::SpendLimiter;
+use crate::reconciliation::{MintLedgerEntry, MintedLoot, PayoutLedger};
+use chrono::Utc;
 use nostr::util::hex;
-use reqwest::Client;
+use nostr::{Keys, PublicKey};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Currency unit loot is minted in
+pub const LOOT_UNIT: &str = "loot";
+
+/// How long to wait for the mint to mark a loot quote paid
+const QUOTE_POLL_ATTEMPTS: u32 = 20;
+const QUOTE_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone)]
 pub struct CashuClient {
     client: Client,
     mint_url: String,
+    spend_limiter: Arc<SpendLimiter>,
+    signing_keys: Option<Keys>, // Engine identity for authority-only mint endpoints
+    metrics: Option<Arc<EngineMetrics>>,
+    payout_ledger: Option<Arc<PayoutLedger>>, // Holds minted loot until it is swapped to the winner
+    http: MintHttpConfig,
+    breaker: Arc<CircuitBreaker>, // Shared by clones so every request feeds one circuit
+    league_mints: HashMap<u8, Arc<CashuClient>>, // Leagues served by a mint other than `mint_url`
 }
 
+/// NUT-04 mint quote request
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MintQuoteRequest {
     pub amount: u64,
-    pub currency: Option<String>,
+    pub unit: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MintQuoteResponse {
     pub quote: String,
     pub request: String, // Lightning payment request
-    pub amount: u64,
-    pub currency: String,
-    pub state: String,
+    pub state: String,   // UNPAID, PAID or ISSUED
     pub expiry: Option<u64>,
 }
 
+/// NUT-04 request to mint outputs against a paid quote
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MintRequest {
+    pub quote: String,
+    pub outputs: Vec<BlindedMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MintResponse {
+    pub signatures: Vec<BlindSignature>,
+}
+
+/// NUT-01 active keysets
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeysResponse {
+    pub keysets: Vec<MintKeyset>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LootTokenResult {
     pub quote: String,
     pub amount: u64,
     pub winner_npub: String,
     pub match_id: String,
+    pub token: String, // cashuA token locked to the winner
 }
 
+/// One winner's loot in a batched mint
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootAward {
+    pub winner_npub: String,
+    pub amount: u64,
+    pub match_id: String,
+}
+
+/// Award admitted to a batch mint, with the proofs an earlier attempt minted for it
+struct BatchAward<'a> {
+    award: &'a LootAward,
+    key: String, // NUT-11 lock key of the winner
+    minted: Option<MintedLoot>,
+}
+
+/// NUT-05 melt quote request for a Lightning invoice
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeltQuoteRequest {
+    pub request: String, // bolt11 invoice to pay
+    pub unit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeltQuoteResponse {
+    pub quote: String,
+    pub amount: u64,
+    pub fee_reserve: u64, // Routing fee the inputs must cover on top of `amount`
+    pub state: String,    // UNPAID, PENDING or PAID
+    pub expiry: Option<u64>,
+}
+
+/// NUT-05 request to pay a melt quote with proofs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeltRequest {
+    pub quote: String,
+    pub inputs: Vec<Proof>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeltResponse {
+    pub state: String,
+    pub payment_preimage: Option<String>,
+}
+
+/// LUD-06 pay request served for a LUD-16 Lightning address
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LnurlPayRequest {
+    callback: String,
+    min_sendable: u64, // millisatoshis
+    max_sendable: u64, // millisatoshis
+}
+
+#[derive(Debug, Deserialize)]
+struct LnurlInvoice {
+    pr: Option<String>,
+    reason: Option<String>, // Set instead of `pr` when the service refuses
+}
+
+/// How a match fee reached the operator wallet
+#[derive(Debug)]
+pub enum FeePayout {
+    /// Loot locked to the operator's npub
+    Token(LootTokenResult),
+    /// Loot melted to the operator's Lightning address
+    Lightning(MeltResponse),
+}
+
+/// NUT-07 proof state query
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckStateRequest {
+    #[serde(rename = "Ys")]
+    pub ys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofState {
+    #[serde(rename = "Y")]
+    pub y: String,
+    pub state: String, // UNSPENT, PENDING or SPENT
+    pub witness: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckStateResponse {
+    pub states: Vec<ProofState>,
+}
+
+/// Engine-authorized burn of the mana both players wagered on a match
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaBurnRequest {
+    pub match_id: String,
+    #[serde(rename = "Ys")]
+    pub ys: Vec<String>, // hash_to_curve(secret) of every revealed mana token
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaBurnResponse {
+    pub burned: u64,
+}
+
+/// Engine-authorized hold on the mana both players wagered, until the match settles
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaEscrowRequest {
+    pub match_id: String,
+    #[serde(rename = "Ys")]
+    pub ys: Vec<String>, // hash_to_curve(secret) of every revealed mana token
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaEscrowResponse {
+    pub escrowed: u64,
+}
+
+/// Engine-authorized return of a drawn match's wagers to the players who staked them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaRefundRequest {
+    pub match_id: String,
+    pub refunds: Vec<ManaRefund>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaRefund {
+    pub npub: String,
+    #[serde(rename = "Ys")]
+    pub ys: Vec<String>, // hash_to_curve(secret) of the mana tokens this player revealed
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManaRefundResponse {
+    pub refunded: u64,
+}
+
+/// Engine-authorized lookup of what revealed mana tokens are worth
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofAmountsRequest {
+    pub match_id: String,
+    #[serde(rename = "Ys")]
+    pub ys: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofAmountsResponse {
+    pub amounts: Vec<ProofAmount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofAmount {
+    #[serde(rename = "Y")]
+    pub y: String,
+    pub amount: Option<u64>, // None when the mint never signed the proof
+}
+
+/// NUT-03 swap of proofs for new outputs
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SwapRequest {
-    pub inputs: Vec<serde_json::Value>,  // Proofs to spend
-    pub outputs: Vec<serde_json::Value>, // New blind messages
+    pub inputs: Vec<Proof>,           // Proofs to spend
+    pub outputs: Vec<BlindedMessage>, // New blind messages
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SwapResponse {
-    pub signatures: Vec<serde_json::Value>, // Blind signatures from mint
+    pub signatures: Vec<BlindSignature>, // Blind signatures from mint
 }
 
 impl CashuClient {
     pub fn new(mint_url: String) -> Self {
+        Self::with_trust_policy(mint_url, MintTrustPolicy::default())
+    }
+
+    /// Create a client whose payouts are bounded by the given mint trust policy
+    pub fn with_trust_policy(mint_url: String, policy: MintTrustPolicy) -> Self {
+        info!(
+            "🛡️ Mint trust policy for {}: max {} loot/day, max {} per payout, checkstate before payout: {}",
+            mint_url,
+            policy.max_loot_per_day,
+            policy.max_single_payout,
+            policy.require_checkstate_before_payout
+        );
+
+        let http = MintHttpConfig::default();
         Self {
-            client: Client::new(),
+            client: http_client(&http),
+            breaker: Arc::new(circuit_breaker(&mint_url, &http)),
+            http,
             mint_url,
+            spend_limiter: Arc::new(SpendLimiter::new(policy)),
+            signing_keys: None,
+            metrics: None,
+            payout_ledger: None,
+            league_mints: HashMap::new(),
         }
     }
 
+    /// Apply timeouts, retries and the circuit breaker of the given HTTP policy
+    pub fn with_http_policy(mut self, http: &MintHttpConfig) -> Self {
+        self.client = http_client(http);
+        self.breaker = Arc::new(circuit_breaker(&self.mint_url, http));
+        self.http = http.clone();
+        self
+    }
+
+    /// Sign authority-only mint requests (mana burns and refunds) with the engine's Nostr keys
+    pub fn with_signing_keys(mut self, keys: Keys) -> Self {
+        self.signing_keys = Some(keys);
+        self
+    }
+
+    /// Record the latency of every mint request
+    pub fn with_metrics(mut self, metrics: Arc<EngineMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Keep loot minted to the engine in the payout ledger until it is swapped to the winner
+    pub fn with_payout_ledger(mut self, payout_ledger: Arc<PayoutLedger>) -> Self {
+        self.payout_ledger = Some(payout_ledger);
+        self
+    }
+
+    /// Route leagues to their own mints, each signed and timed like this client
+    ///
+    /// Call after `with_signing_keys`, `with_metrics`, `with_payout_ledger` and
+    /// `with_http_policy` so the league mints inherit them; each league mint gets a
+    /// circuit breaker of its own.
+    pub fn with_league_mints(
+        mut self,
+        league_mints: &[LeagueMintConfig],
+        default_trust: &MintTrustPolicy,
+    ) -> Self {
+        for league_mint in league_mints {
+            let trust = league_mint
+                .trust
+                .clone()
+                .unwrap_or_else(|| default_trust.clone());
+            let client = Arc::new(Self {
+                signing_keys: self.signing_keys.clone(),
+                metrics: self.metrics.clone(),
+                payout_ledger: self.payout_ledger.clone(),
+                ..Self::with_trust_policy(league_mint.mint_url.clone(), trust)
+                    .with_http_policy(&self.http)
+            });
+            for league_id in &league_mint.league_ids {
+                self.league_mints.insert(*league_id, Arc::clone(&client));
+            }
+        }
+        self
+    }
+
+    /// Client for the mint serving a league, falling back to the primary mint
+    pub fn for_league(&self, league_id: Option<u8>) -> &CashuClient {
+        league_id
+            .and_then(|league_id| self.league_mints.get(&league_id))
+            .map_or(self, |client| client.as_ref())
+    }
+
+    /// The primary mint followed by each distinct league mint
+    pub fn all_mints(&self) -> Vec<&CashuClient> {
+        let mut mints = vec![self];
+        for client in self.league_mints.values() {
+            if !mints.iter().any(|mint| mint.mint_url == client.mint_url) {
+                mints.push(client.as_ref());
+            }
+        }
+        mints
+    }
+
+    pub fn mint_url(&self) -> &str {
+        &self.mint_url
+    }
+
+    /// Whether the mint is answering, i.e. its circuit breaker is closed
+    pub fn is_available(&self) -> bool {
+        !self.breaker.is_open()
+    }
+
+    /// Send a mint request through the circuit breaker, timing each round trip when
+    /// metrics are enabled
+    ///
+    /// Connection errors, 5xx and 429 are retried with jittered backoff. Mint
+    /// endpoints are keyed by quote or blinded outputs, so a repeated request
+    /// cannot pay out twice. The last response is returned whatever its status.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, GameEngineError> {
+        let mut attempt = 0;
+        loop {
+            if !self.breaker.allow() {
+                return Err(GameEngineError::transient(format!(
+                    "Mint {} is unavailable, its circuit is open",
+                    self.mint_url
+                )));
+            }
+            let Some(this_attempt) = request.try_clone() else {
+                return Err(GameEngineError::Internal(
+                    "Mint request body cannot be resent".to_string(),
+                ));
+            };
+
+            let started = Instant::now();
+            let response = this_attempt.send().await;
+            if let Some(metrics) = &self.metrics {
+                metrics.cashu_rpc_latency.observe(started.elapsed());
+            }
+
+            let retryable = match &response {
+                Ok(response) if response.status().is_server_error() => {
+                    self.breaker.record_failure();
+                    true
+                }
+                Ok(response) => {
+                    self.breaker.record_success();
+                    response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(_) => {
+                    self.breaker.record_failure();
+                    true
+                }
+            };
+            if !retryable || attempt >= self.http.max_retries {
+                return Ok(response?);
+            }
+
+            attempt += 1;
+            let delay = retry_backoff(&self.http, attempt);
+            debug!(
+                "🔁 Retrying mint request to {} in {:?} (attempt {})",
+                self.mint_url,
+                delay,
+                attempt + 1
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Loot minted through this client so far today
+    pub fn loot_minted_today(&self) -> u64 {
+        self.spend_limiter.minted_today()
+    }
+
+    /// Count loot minted earlier today, before a restart, against each mint's daily ceiling
+    pub fn restore_daily_spend(&self) -> Result<(), GameEngineError> {
+        let Some(ledger) = &self.payout_ledger else {
+            return Ok(());
+        };
+        let today = Utc::now().date_naive();
+        for client in std::iter::once(self).chain(self.league_mints.values().map(Arc::as_ref)) {
+            let minted = ledger.minted_on(&client.mint_url, today)?;
+            client.spend_limiter.restore(today, minted);
+            if minted > 0 {
+                info!(
+                    "📅 {} loot already minted today at {}",
+                    minted, client.mint_url
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Verify that the mint is accessible
     pub async fn health_check(&self) -> Result<bool, GameEngineError> {
         let url = format!("{}/health", self.mint_url);
 
-        match self.client.get(&url).send().await {
+        match self.send(self.client.get(&url)).await {
             Ok(response) => Ok(response.status().is_success()),
             Err(e) => {
                 warn!("Cashu mint health check failed: {}", e);
@@ -71,9 +457,7 @@ impl CashuClient {
         let url = format!("{}/v1/info", self.mint_url);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .send(self.client.get(&url))
             .await?
             .json::<serde_json::Value>()
             .await?;
@@ -81,53 +465,795 @@ impl CashuClient {
         Ok(response)
     }
 
-    /// Request a mint quote for loot tokens
-    /// This simulates the process - in a real implementation, the game engine
-    /// would have special authority to mint loot tokens directly
+    /// Query proof states from the mint (NUT-07)
+    pub async fn check_proof_states(
+        &self,
+        ys: Vec<String>,
+    ) -> Result<Vec<ProofState>, GameEngineError> {
+        let url = format!("{}/v1/checkstate", self.mint_url);
+
+        let response = self
+            .send(self.client.post(&url).json(&CheckStateRequest { ys }))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(mint_status_error(
+                response.status(),
+                format!("Checkstate request failed: {}", response.status()),
+            ));
+        }
+
+        let check_state: CheckStateResponse = response.json().await?;
+        Ok(check_state.states)
+    }
+
+    /// Y values of token secrets the mint reports as already spent or pending elsewhere
+    pub async fn find_spent_secrets(
+        &self,
+        secrets: &[String],
+    ) -> Result<Vec<String>, GameEngineError> {
+        if secrets.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let states = self.check_proof_states(hash_secrets(secrets)?).await?;
+        Ok(states
+            .into_iter()
+            .filter(|proof| proof.state == "SPENT" || proof.state == "PENDING")
+            .map(|proof| proof.y)
+            .collect())
+    }
+
+    /// Fetch the mint's ledger of loot issued for a match (None if the mint has no entry)
+    ///
+    /// `GET /v1/ledger/match/{id}` is not a Cashu NUT and the CDK mint has no such route;
+    /// it is the per-match issuance report a game mint must expose for reconciliation.
+    /// A stock mint answers 404, which reads as "nothing issued", so reconciliation
+    /// against one reports every validated winner as unpaid.
+    pub async fn get_match_ledger(
+        &self,
+        match_id: &str,
+    ) -> Result<Option<MintLedgerEntry>, GameEngineError> {
+        let url = format!("{}/v1/ledger/match/{}", self.mint_url, match_id);
+
+        let response = self.send(self.client.get(&url)).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(mint_status_error(
+                response.status(),
+                format!(
+                    "Failed to fetch mint ledger for match {match_id}: {}",
+                    response.status()
+                ),
+            ));
+        }
+
+        Ok(Some(response.json().await?))
+    }
+
+    /// Request a mint quote for loot tokens, bounded by the mint trust policy
     pub async fn create_loot_token(
         &self,
         winner_npub: &str,
         amount: u64,
         match_id: &str,
     ) -> Result<LootTokenResult, GameEngineError> {
+        let award = LootAward {
+            winner_npub: winner_npub.to_string(),
+            amount,
+            match_id: match_id.to_string(),
+        };
+        let minted = self.admit(&award)?;
+
+        // Resolve the lock key before minting so an unparseable winner never costs a mint
+        let key = match locking_key(winner_npub) {
+            Ok(key) => key,
+            Err(e) => {
+                if minted.is_none() {
+                    self.spend_limiter.release(amount);
+                }
+                return Err(e);
+            }
+        };
+
+        let mut results = self
+            .mint_loot_batch(&[BatchAward {
+                award: &award,
+                key,
+                minted,
+            }])
+            .await?;
+        Ok(results.remove(0))
+    }
+
+    /// Mint loot for several winners with one quote, one mint and one swap
+    ///
+    /// Each award is held to the trust policy as if it were paid alone. Results follow
+    /// the order of `awards`, so one refused winner does not sink the others.
+    pub async fn create_loot_tokens(
+        &self,
+        awards: &[LootAward],
+    ) -> Vec<Result<LootTokenResult, GameEngineError>> {
+        let mut results: Vec<Option<Result<LootTokenResult, GameEngineError>>> = Vec::new();
+        let mut batch = Vec::new();
+        let mut batch_indices = Vec::new();
+        for (index, award) in awards.iter().enumerate() {
+            let admitted = locking_key(&award.winner_npub).and_then(|key| {
+                Ok(BatchAward {
+                    award,
+                    key,
+                    minted: self.admit(award)?,
+                })
+            });
+            match admitted {
+                Ok(batch_award) => {
+                    batch.push(batch_award);
+                    batch_indices.push(index);
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        if !batch.is_empty() {
+            match self.mint_loot_batch(&batch).await {
+                Ok(minted) => {
+                    for (index, result) in batch_indices.into_iter().zip(minted) {
+                        results[index] = Some(Ok(result));
+                    }
+                }
+                Err(e) => {
+                    for index in batch_indices {
+                        results[index] = Some(Err(batch_failure(&e)));
+                    }
+                }
+            }
+        }
+
+        results.into_iter().flatten().collect()
+    }
+
+    /// Hold an award to the trust policy, returning proofs an earlier attempt minted for it
+    ///
+    /// Loot already minted counted against the daily ceiling when it was minted, so
+    /// only an award that still has to be minted reserves its amount.
+    fn admit(&self, award: &LootAward) -> Result<Option<MintedLoot>, GameEngineError> {
+        let minted = self.saved_mint(award)?;
+        if minted.is_none() {
+            self.spend_limiter
+                .reserve(award.amount)
+                .map_err(GameEngineError::MintPolicyViolation)?;
+        }
+        Ok(minted)
+    }
+
+    /// Mint loot for each award and lock it to the paired key
+    ///
+    /// The total is minted to engine-held secrets against one NUT-04 quote, then
+    /// swapped (NUT-03) into NUT-11 outputs only each winner can spend. Minted proofs
+    /// are saved to the payout ledger before the swap, so a retry resumes from them.
+    /// Reservations are handed back only if the loot was never minted.
+    async fn mint_loot_batch(
+        &self,
+        awards: &[BatchAward<'_>],
+    ) -> Result<Vec<LootTokenResult>, GameEngineError> {
+        let total: u64 = awards.iter().map(|batch| batch.award.amount).sum();
+        for batch in awards {
+            info!(
+                "🏆 Creating loot token: {} for winner {} (match {})",
+                batch.award.amount, batch.award.winner_npub, batch.award.match_id
+            );
+        }
+
+        let keyset = self
+            .get_active_keyset(LOOT_UNIT)
+            .await
+            .inspect_err(|_| self.release_unminted(awards))?;
+        let minted = self.mint_or_resume(&keyset, awards).await?;
+        let engine_proofs: Vec<Proof> = minted
+            .iter()
+            .flat_map(|minted| minted.proofs.iter().cloned())
+            .collect();
+        self.require_checkstate_before_payout(&engine_proofs)
+            .await?;
+
+        // Swap into outputs locked to the winners, in award order
+        let mut winner_outputs = Vec::new();
+        let mut output_counts = Vec::new();
+        for batch in awards {
+            let amounts = split_amount(batch.award.amount);
+            winner_outputs.extend(blind_outputs(
+                &keyset.id,
+                &amounts,
+                amounts.iter().map(|_| p2pk_secret(&batch.key)).collect(),
+            )?);
+            output_counts.push(amounts.len());
+        }
+        let swap_request = SwapRequest {
+            inputs: engine_proofs,
+            outputs: winner_outputs.iter().map(|o| o.message.clone()).collect(),
+        };
+        let swapped: SwapResponse = self.post_mint_json("/v1/swap", &swap_request).await?;
+        let mut winner_proofs =
+            unblind_signatures(winner_outputs, swapped.signatures, &keyset)?.into_iter();
+
+        let mut results = Vec::new();
+        for ((batch, count), minted) in awards.iter().zip(output_counts).zip(minted) {
+            let award = batch.award;
+            // The engine proofs are spent, so a retry must not resume from them
+            if let Some(ledger) = &self.payout_ledger {
+                ledger.clear_minted(&award.match_id, &award.winner_npub)?;
+            }
+            let proofs: Vec<Proof> = winner_proofs.by_ref().take(count).collect();
+            results.push(LootTokenResult {
+                quote: minted.quote,
+                amount: award.amount,
+                winner_npub: award.winner_npub.clone(),
+                match_id: award.match_id.clone(),
+                token: encode_token(
+                    &self.mint_url,
+                    LOOT_UNIT,
+                    &format!("Manastr loot for match {}", award.match_id),
+                    &proofs,
+                ),
+            });
+        }
+
         info!(
-            "🏆 Creating loot token: {} for winner {} (match {})",
-            amount, winner_npub, match_id
+            "🎯 Loot minted: {} (amount: {}, {} winners locked)",
+            results
+                .iter()
+                .map(|result| result.quote.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            total,
+            results.len()
         );
+        Ok(results)
+    }
 
-        // In a real implementation, this would be a special authenticated endpoint
-        // For now, we simulate the loot token creation
-        let quote_request = MintQuoteRequest {
-            amount,
-            currency: Some("loot".to_string()),
+    /// Engine proofs for each award, in award order
+    ///
+    /// Awards an earlier attempt already minted reuse the proofs saved in the payout
+    /// ledger; the rest are minted together against one quote and saved per award.
+    async fn mint_or_resume(
+        &self,
+        keyset: &MintKeyset,
+        awards: &[BatchAward<'_>],
+    ) -> Result<Vec<MintedLoot>, GameEngineError> {
+        let unminted: Vec<&LootAward> = awards
+            .iter()
+            .filter(|batch| batch.minted.is_none())
+            .map(|batch| batch.award)
+            .collect();
+        let mut fresh = Vec::new();
+        if !unminted.is_empty() {
+            let amounts: Vec<Vec<u64>> = unminted
+                .iter()
+                .map(|award| split_amount(award.amount))
+                .collect();
+            let (quote, proofs) = self
+                .mint_engine_proofs(keyset, amounts.concat())
+                .await
+                .inspect_err(|_| self.release_unminted(awards))?;
+
+            let mut proofs = proofs.into_iter();
+            for (award, amounts) in unminted.iter().zip(amounts) {
+                let award_mint = MintedLoot {
+                    quote: quote.clone(),
+                    amount: award.amount,
+                    proofs: proofs.by_ref().take(amounts.len()).collect(),
+                };
+                if let Some(ledger) = &self.payout_ledger {
+                    ledger.record_minted(
+                        &award.match_id,
+                        &award.winner_npub,
+                        award_mint.clone(),
+                    )?;
+                }
+                fresh.push(award_mint);
+            }
+        }
+
+        let mut fresh = fresh.into_iter();
+        Ok(awards
+            .iter()
+            .filter_map(|batch| match &batch.minted {
+                Some(saved) => {
+                    info!(
+                        "♻️ Resuming loot for {} (match {}) from proofs already minted",
+                        batch.award.winner_npub, batch.award.match_id
+                    );
+                    Some(saved.clone())
+                }
+                None => fresh.next(),
+            })
+            .collect())
+    }
+
+    /// Hand back the reservations of awards whose loot was never minted
+    fn release_unminted(&self, awards: &[BatchAward<'_>]) {
+        self.spend_limiter.release(
+            awards
+                .iter()
+                .filter(|batch| batch.minted.is_none())
+                .map(|batch| batch.award.amount)
+                .sum(),
+        );
+    }
+
+    /// Proofs an earlier attempt minted for this award but never swapped to its winner
+    fn saved_mint(&self, award: &LootAward) -> Result<Option<MintedLoot>, GameEngineError> {
+        let Some(ledger) = &self.payout_ledger else {
+            return Ok(None);
         };
+        Ok(ledger
+            .minted_loot(&award.match_id, &award.winner_npub)?
+            .filter(|minted| minted.amount == award.amount))
+    }
 
-        let url = format!("{}/v1/mint/quote/bolt11", self.mint_url);
+    /// Refuse to spend proofs the mint does not confirm as unspent, if required
+    async fn require_checkstate_before_payout(
+        &self,
+        proofs: &[Proof],
+    ) -> Result<(), GameEngineError> {
+        if !self.spend_limiter.policy().require_checkstate_before_payout {
+            return Ok(());
+        }
 
-        let response = self.client.post(&url).json(&quote_request).send().await?;
+        let secrets: Vec<String> = proofs.iter().map(|proof| proof.secret.clone()).collect();
+        let ys = hash_secrets(&secrets)?;
+        let states = self.check_proof_states(ys.clone()).await.map_err(|e| {
+            GameEngineError::MintPolicyViolation(format!(
+                "mint checkstate required before payout failed: {e}"
+            ))
+        })?;
 
-        if !response.status().is_success() {
-            return Err(GameEngineError::CashuError(format!(
-                "Failed to create loot quote: {}",
-                response.status()
+        let unspendable = unconfirmed_unspent(&ys, &states);
+        if !unspendable.is_empty() {
+            return Err(GameEngineError::MintPolicyViolation(format!(
+                "mint does not confirm {} of {} payout proofs as unspent",
+                unspendable.len(),
+                ys.len()
             )));
         }
+        Ok(())
+    }
+
+    /// Mint loot to secrets only the engine knows, returning the quote id and proofs
+    ///
+    /// One proof is minted per entry of `amounts`, in order, against a single quote.
+    async fn mint_engine_proofs(
+        &self,
+        keyset: &MintKeyset,
+        amounts: Vec<u64>,
+    ) -> Result<(String, Vec<Proof>), GameEngineError> {
+        let total: u64 = amounts.iter().sum();
+        let quote = self.request_mint_quote(total).await?;
+        self.wait_for_quote_paid(&quote.quote).await?;
+
+        let engine_outputs = blind_outputs(
+            &keyset.id,
+            &amounts,
+            amounts.iter().map(|_| random_secret()).collect(),
+        )?;
+        let mint_request = MintRequest {
+            quote: quote.quote.clone(),
+            outputs: engine_outputs.iter().map(|o| o.message.clone()).collect(),
+        };
+        let minted: MintResponse = self
+            .post_mint_json("/v1/mint/bolt11", &mint_request)
+            .await?;
+        let proofs = unblind_signatures(engine_outputs, minted.signatures, keyset)?;
+
+        // The loot exists now; its reservation stays, so this must not fail the mint
+        if let Some(ledger) = &self.payout_ledger {
+            if let Err(e) = ledger.record_daily_mint(&self.mint_url, Utc::now().date_naive(), total)
+            {
+                warn!(
+                    "⚠️ Failed to record {} loot minted at {}: {}",
+                    total, self.mint_url, e
+                );
+            }
+        }
 
-        let quote_response: MintQuoteResponse = response.json().await?;
+        Ok((quote.quote, proofs))
+    }
+
+    /// Pay a match fee to the operator wallet, bounded by the mint trust policy
+    pub async fn pay_match_fee(
+        &self,
+        recipient: &FeeRecipient,
+        amount: u64,
+        match_id: &str,
+    ) -> Result<FeePayout, GameEngineError> {
+        match recipient {
+            FeeRecipient::Npub(npub) => self
+                .create_loot_token(npub, amount, match_id)
+                .await
+                .map(FeePayout::Token),
+            FeeRecipient::LightningAddress(address) => self
+                .melt_to_lightning_address(address, amount, match_id)
+                .await
+                .map(FeePayout::Lightning),
+        }
+    }
+
+    /// Pay loot out over Lightning to a LUD-16 address
+    ///
+    /// The address's invoice is quoted for melting (NUT-05), then the engine mints
+    /// the quoted amount plus the mint's fee reserve to its own secrets and melts them.
+    async fn melt_to_lightning_address(
+        &self,
+        address: &str,
+        amount: u64,
+        match_id: &str,
+    ) -> Result<MeltResponse, GameEngineError> {
+        let invoice = self.request_lightning_invoice(address, amount).await?;
+        let quote: MeltQuoteResponse = self
+            .post_mint_json(
+                "/v1/melt/quote/bolt11",
+                &MeltQuoteRequest {
+                    request: invoice,
+                    unit: LOOT_UNIT.to_string(),
+                },
+            )
+            .await?;
+
+        // The routing fee reserve is minted on top of the payment and counts against the ceiling
+        let total = quote.amount + quote.fee_reserve;
+        self.spend_limiter
+            .reserve(total)
+            .map_err(GameEngineError::MintPolicyViolation)?;
+        let melted = self.melt_quote(&quote, total).await?;
 
-        // In a real implementation, the game engine would have authority to mint
-        // the loot token directly without requiring Lightning payment
         info!(
-            "🎯 Loot token quote created: {} (amount: {})",
-            quote_response.quote, amount
+            "⚡ Melted {} loot to {} for match {} ({})",
+            quote.amount, address, match_id, melted.state
         );
+        Ok(melted)
+    }
+
+    /// Mint `total` loot to the engine and spend it on a melt quote
+    ///
+    /// The reservation of `total` is handed back only if the loot was never minted.
+    async fn melt_quote(
+        &self,
+        quote: &MeltQuoteResponse,
+        total: u64,
+    ) -> Result<MeltResponse, GameEngineError> {
+        let release = |_: &GameEngineError| self.spend_limiter.release(total);
+        let keyset = self
+            .get_active_keyset(LOOT_UNIT)
+            .await
+            .inspect_err(release)?;
+        let (_, inputs) = self
+            .mint_engine_proofs(&keyset, split_amount(total))
+            .await
+            .inspect_err(release)?;
+        self.require_checkstate_before_payout(&inputs).await?;
+        let melt_request = MeltRequest {
+            quote: quote.quote.clone(),
+            inputs,
+        };
+        let melted: MeltResponse = self
+            .post_mint_json("/v1/melt/bolt11", &melt_request)
+            .await?;
 
-        Ok(LootTokenResult {
-            quote: quote_response.quote,
+        if melted.state == "UNPAID" {
+            return Err(GameEngineError::CashuError(format!(
+                "Mint did not pay melt quote {}",
+                quote.quote
+            )));
+        }
+        Ok(melted)
+    }
+
+    /// Fetch an invoice for `amount` sats from a Lightning address (LUD-16)
+    async fn request_lightning_invoice(
+        &self,
+        address: &str,
+        amount: u64,
+    ) -> Result<String, GameEngineError> {
+        let (user, domain) = address.split_once('@').ok_or_else(|| {
+            GameEngineError::CashuError(format!("Invalid Lightning address {address}"))
+        })?;
+        let pay_request: LnurlPayRequest = self
+            .client
+            .get(format!("https://{domain}/.well-known/lnurlp/{user}"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let millisats = amount.saturating_mul(1000);
+        if millisats < pay_request.min_sendable || millisats > pay_request.max_sendable {
+            return Err(GameEngineError::CashuError(format!(
+                "{address} accepts {}-{} msat, not {millisats}",
+                pay_request.min_sendable, pay_request.max_sendable
+            )));
+        }
+
+        let invoice: LnurlInvoice = self
+            .client
+            .get(&pay_request.callback)
+            .query(&[("amount", millisats)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        invoice.pr.ok_or_else(|| {
+            GameEngineError::CashuError(format!(
+                "{address} refused to issue an invoice: {}",
+                invoice.reason.unwrap_or_default()
+            ))
+        })
+    }
+
+    /// Active keyset for a currency unit (NUT-01)
+    async fn get_active_keyset(&self, unit: &str) -> Result<MintKeyset, GameEngineError> {
+        let url = format!("{}/v1/keys", self.mint_url);
+
+        let response = self.send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(mint_status_error(
+                response.status(),
+                format!("Failed to fetch mint keys: {}", response.status()),
+            ));
+        }
+
+        let keys: KeysResponse = response.json().await?;
+        keys.keysets
+            .into_iter()
+            .find(|keyset| keyset.unit == unit)
+            .ok_or_else(|| GameEngineError::CashuError(format!("Mint has no active {unit} keyset")))
+    }
+
+    /// Request a loot mint quote (NUT-04)
+    async fn request_mint_quote(&self, amount: u64) -> Result<MintQuoteResponse, GameEngineError> {
+        let quote_request = MintQuoteRequest {
             amount,
-            winner_npub: winner_npub.to_string(),
+            unit: LOOT_UNIT.to_string(),
+        };
+
+        self.post_mint_json("/v1/mint/quote/bolt11", &quote_request)
+            .await
+    }
+
+    /// Poll a mint quote until the mint reports it paid
+    async fn wait_for_quote_paid(&self, quote_id: &str) -> Result<(), GameEngineError> {
+        let url = format!("{}/v1/mint/quote/bolt11/{}", self.mint_url, quote_id);
+
+        for _ in 0..QUOTE_POLL_ATTEMPTS {
+            let quote: MintQuoteResponse = self.send(self.client.get(&url)).await?.json().await?;
+            match quote.state.as_str() {
+                "PAID" => return Ok(()),
+                "ISSUED" => {
+                    return Err(GameEngineError::CashuError(format!(
+                        "Loot quote {quote_id} was already issued"
+                    )))
+                }
+                state => debug!("⏳ Loot quote {} is {}", quote_id, state),
+            }
+            tokio::time::sleep(QUOTE_POLL_INTERVAL).await;
+        }
+
+        Err(GameEngineError::transient(format!(
+            "Loot quote {quote_id} was not paid in time"
+        )))
+    }
+
+    /// Burn both players' wagered mana once a match is validated
+    ///
+    /// Only the game engine may burn mana, so the request carries a NIP-98
+    /// signature the mint checks against the engine's pubkey. Expects the mint to
+    /// serve `POST /game-engine/burn`, which no mint in this tree does yet.
+    pub async fn burn_mana(
+        &self,
+        match_id: &str,
+        token_secrets: &[String],
+    ) -> Result<ManaBurnResponse, GameEngineError> {
+        let request = ManaBurnRequest {
             match_id: match_id.to_string(),
-        })
+            ys: hash_secrets(token_secrets)?,
+        };
+        let burn: ManaBurnResponse = self
+            .post_engine_json("/game-engine/burn", "Mana burn", match_id, &request)
+            .await?;
+        info!("🔥 Burned {} mana for match {}", burn.burned, match_id);
+        Ok(burn)
+    }
+
+    /// Hold the revealed wagers at the mint so players cannot spend them mid-match
+    ///
+    /// The mint keeps the proofs pending until the engine burns or refunds them.
+    /// Cashu has no escrow NUT: this needs the game mint to serve
+    /// `POST /game-engine/escrow` (see `post_engine_json`), so the engine only
+    /// escrows with `[cashu] escrow_wagers` enabled.
+    pub async fn escrow_mana(
+        &self,
+        match_id: &str,
+        token_secrets: &[String],
+    ) -> Result<ManaEscrowResponse, GameEngineError> {
+        let request = ManaEscrowRequest {
+            match_id: match_id.to_string(),
+            ys: hash_secrets(token_secrets)?,
+        };
+        let escrow: ManaEscrowResponse = self
+            .post_engine_json("/game-engine/escrow", "Mana escrow", match_id, &request)
+            .await?;
+        info!(
+            "🔒 Escrowed {} mana for match {}",
+            escrow.escrowed, match_id
+        );
+        Ok(escrow)
+    }
+
+    /// Mana the mint signed for the revealed token secrets
+    ///
+    /// A proof counts once however often it is revealed; unknown proofs count as zero.
+    /// NUT-07 checkstate reports no amounts, so this relies on the game mint serving
+    /// `POST /game-engine/proof-amounts` (see `post_engine_json`); stock mints do not.
+    pub async fn committed_amount(
+        &self,
+        match_id: &str,
+        token_secrets: &[String],
+    ) -> Result<u64, GameEngineError> {
+        let (ys, amounts) = self.proof_amounts(match_id, token_secrets).await?;
+        Ok(sum_proof_amounts(&ys, amounts))
+    }
+
+    /// Y values of revealed token secrets the mint never signed
+    ///
+    /// Armies derive from the revealed secrets, so a made-up secret next to a real
+    /// proof would let a player pick their army while still matching the wager.
+    /// Uses the same proof amount lookup as `committed_amount`, so until the game
+    /// mint implements it every wagered reveal is held for want of an answer.
+    pub async fn unsigned_secrets(
+        &self,
+        match_id: &str,
+        token_secrets: &[String],
+    ) -> Result<Vec<String>, GameEngineError> {
+        let (ys, amounts) = self.proof_amounts(match_id, token_secrets).await?;
+        Ok(unsigned_ys(&ys, &amounts))
+    }
+
+    /// Deduplicated Y values of the secrets with the amounts the mint signed for them
+    async fn proof_amounts(
+        &self,
+        match_id: &str,
+        token_secrets: &[String],
+    ) -> Result<(Vec<String>, Vec<ProofAmount>), GameEngineError> {
+        let mut ys = hash_secrets(token_secrets)?;
+        ys.sort_unstable();
+        ys.dedup();
+        if ys.is_empty() {
+            return Ok((ys, Vec::new()));
+        }
+
+        let request = ProofAmountsRequest {
+            match_id: match_id.to_string(),
+            ys: ys.clone(),
+        };
+        let response: ProofAmountsResponse = self
+            .post_engine_json(
+                "/game-engine/proof-amounts",
+                "Proof amount lookup",
+                match_id,
+                &request,
+            )
+            .await?;
+        Ok((ys, response.amounts))
+    }
+
+    /// Swap the mana wagered on a drawn match back to the players who revealed it
+    pub async fn refund_wagers(
+        &self,
+        match_id: &str,
+        secrets_by_player: &[(String, Vec<String>)],
+    ) -> Result<ManaRefundResponse, GameEngineError> {
+        let refunds = secrets_by_player
+            .iter()
+            .map(|(npub, secrets)| Ok((npub.clone(), hash_secrets(secrets)?)))
+            .collect::<Result<Vec<_>, GameEngineError>>()?;
+        self.release_escrow(match_id, refunds).await
+    }
+
+    /// Swap escrowed wagers, given by Y value, back to the players who staked them
+    ///
+    /// Sent to `POST /game-engine/refund`, part of the game mint contract described at
+    /// `post_engine_json`; the mint has to implement it before draws can be refunded.
+    pub async fn release_escrow(
+        &self,
+        match_id: &str,
+        ys_by_player: Vec<(String, Vec<String>)>,
+    ) -> Result<ManaRefundResponse, GameEngineError> {
+        let refunds = ys_by_player
+            .into_iter()
+            .map(|(npub, ys)| ManaRefund { npub, ys })
+            .collect();
+        let request = ManaRefundRequest {
+            match_id: match_id.to_string(),
+            refunds,
+        };
+        let refund: ManaRefundResponse = self
+            .post_engine_json("/game-engine/refund", "Wager refund", match_id, &request)
+            .await?;
+        info!(
+            "↩️ Refunded {} mana for match {}",
+            refund.refunded, match_id
+        );
+        Ok(refund)
+    }
+
+    /// POST a request signed with the engine's keys to a mint game-engine endpoint
+    ///
+    /// The `/game-engine/*` routes are not Cashu NUTs and the CDK mint does not serve
+    /// them: they are the contract a game mint has to implement for the engine's
+    /// authority-only operations. Against a stock mint they fail with a client error.
+    async fn post_engine_json<Req: Serialize, Resp: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        operation: &str,
+        match_id: &str,
+        body: &Req,
+    ) -> Result<Resp, GameEngineError> {
+        let keys = self.signing_keys.as_ref().ok_or_else(|| {
+            GameEngineError::CashuError(format!("{operation} requires engine signing keys"))
+        })?;
+
+        let url = format!("{}{}", self.mint_url, path);
+        let body = serde_json::to_vec(body).map_err(|e| {
+            GameEngineError::CashuError(format!("Failed to encode {operation} request: {e}"))
+        })?;
+
+        let request = self
+            .client
+            .post(&url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                authorization_header(keys, &url, "POST", &body)?,
+            )
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(mint_status_error(
+                status,
+                format!("{operation} for match {match_id} failed: {status} {detail}"),
+            ));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    async fn post_mint_json<Req: Serialize, Resp: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &Req,
+    ) -> Result<Resp, GameEngineError> {
+        let url = format!("{}{}", self.mint_url, path);
+
+        let response = self.send(self.client.post(&url).json(body)).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(mint_status_error(
+                status,
+                format!("Mint request to {path} failed: {status} {detail}"),
+            ));
+        }
+
+        Ok(response.json().await?)
     }
 
     /// Verify a mana token (not implemented in pure CDK mint)
@@ -150,9 +1276,7 @@ impl CashuClient {
         let url = format!("{}/v1/keysets", self.mint_url);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .send(self.client.get(&url))
             .await?
             .json::<serde_json::Value>()
             .await?;
@@ -182,21 +1306,28 @@ impl CashuClient {
 
         // For demo purposes, simulate the swap process
         let swap_request = SwapRequest {
-            inputs: vec![serde_json::json!({
-                "amount": new_tokens_count,
-                "id": loot_token_quote,
-                "secret": format!("loot_token_{}_{}", winner_npub, loot_token_quote),
-                "C": format!("02{}", winner_npub.chars().take(64).collect::<String>()) // Simulated pubkey
-            })],
-            outputs: vec![serde_json::json!({
-                "amount": new_tokens_count,
-                "B_": format!("03{}", hex::encode(format!("new_token_{winner_npub}").as_bytes()).chars().take(62).collect::<String>()) // Simulated blind message
-            })],
+            inputs: vec![Proof {
+                amount: new_tokens_count,
+                id: loot_token_quote.to_string(),
+                secret: format!("loot_token_{}_{}", winner_npub, loot_token_quote),
+                signature: format!("02{}", winner_npub.chars().take(64).collect::<String>()), // Simulated pubkey
+            }],
+            outputs: vec![BlindedMessage {
+                amount: new_tokens_count,
+                id: loot_token_quote.to_string(),
+                blinded_secret: format!(
+                    "03{}",
+                    hex::encode(format!("new_token_{winner_npub}").as_bytes())
+                        .chars()
+                        .take(62)
+                        .collect::<String>()
+                ), // Simulated blind message
+            }],
         };
 
         let url = format!("{}/v1/swap", self.mint_url);
 
-        match self.client.post(&url).json(&swap_request).send().await {
+        match self.send(self.client.post(&url).json(&swap_request)).await {
             Ok(response) => {
                 if response.status().is_success() {
                     match response.json::<SwapResponse>().await {
@@ -256,6 +1387,106 @@ impl CashuClient {
     }
 }
 
+/// Y values (hash_to_curve) the mint indexes proofs by
+pub(crate) fn hash_secrets(secrets: &[String]) -> Result<Vec<String>, GameEngineError> {
+    secrets
+        .iter()
+        .map(|secret| hash_to_curve(secret.as_bytes()).map(|y| y.to_string()))
+        .collect()
+}
+
+/// Ys the mint did not report as UNSPENT, including any it left out of its answer
+fn unconfirmed_unspent(ys: &[String], states: &[ProofState]) -> Vec<String> {
+    ys.iter()
+        .filter(|y| {
+            !states
+                .iter()
+                .any(|proof| &proof.y == *y && proof.state == "UNSPENT")
+        })
+        .cloned()
+        .collect()
+}
+
+/// Total of the requested proofs, ignoring any the mint reported that were not asked about
+fn sum_proof_amounts(ys: &[String], amounts: Vec<ProofAmount>) -> u64 {
+    let amounts: HashMap<String, u64> = amounts
+        .into_iter()
+        .filter(|proof| ys.contains(&proof.y))
+        .filter_map(|proof| Some((proof.y, proof.amount?)))
+        .collect();
+    amounts.values().sum()
+}
+
+/// Requested proofs the mint reported no signed amount for, or left out of its answer
+fn unsigned_ys(ys: &[String], amounts: &[ProofAmount]) -> Vec<String> {
+    ys.iter()
+        .filter(|y| {
+            !amounts
+                .iter()
+                .any(|proof| &proof.y == *y && proof.amount.is_some())
+        })
+        .cloned()
+        .collect()
+}
+
+/// NUT-11 lock key for a winner's npub
+fn locking_key(winner_npub: &str) -> Result<String, GameEngineError> {
+    let winner_pubkey = PublicKey::parse(winner_npub).map_err(|e| {
+        GameEngineError::CashuError(format!("Invalid winner pubkey {winner_npub}: {e}"))
+    })?;
+    Ok(format!("02{}", winner_pubkey.to_hex()))
+}
+
+/// Per-award copy of a failed batch mint that keeps whether it is worth retrying
+fn batch_failure(error: &GameEngineError) -> GameEngineError {
+    if error.is_retryable() {
+        GameEngineError::transient(error.to_string())
+    } else {
+        GameEngineError::CashuError(error.to_string())
+    }
+}
+
+/// Pooled HTTP client with the policy's timeouts
+fn http_client(http: &MintHttpConfig) -> Client {
+    Client::builder()
+        .connect_timeout(Duration::from_millis(http.connect_timeout_ms))
+        .timeout(Duration::from_millis(http.request_timeout_ms))
+        .pool_idle_timeout(Duration::from_secs(http.pool_idle_timeout_seconds))
+        .build()
+        .unwrap_or_else(|e| {
+            warn!("⚠️ Failed to build mint HTTP client, using defaults: {}", e);
+            Client::new()
+        })
+}
+
+fn circuit_breaker(mint_url: &str, http: &MintHttpConfig) -> CircuitBreaker {
+    CircuitBreaker::new(
+        mint_url,
+        http.failure_threshold,
+        Duration::from_secs(http.open_seconds),
+    )
+}
+
+/// Delay before a retry, doubling per attempt up to the cap plus up to half again as jitter
+fn retry_backoff(http: &MintHttpConfig, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    let delay = http
+        .base_backoff_ms
+        .saturating_mul(factor)
+        .min(http.max_backoff_ms);
+    let jitter = rand::thread_rng().gen_range(0..=delay / 2);
+    Duration::from_millis(delay + jitter)
+}
+
+/// Error for a mint response; outages and rate limiting may clear up on retry
+fn mint_status_error(status: reqwest::StatusCode, message: String) -> GameEngineError {
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        GameEngineError::transient(message)
+    } else {
+        GameEngineError::CashuError(message)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +1497,187 @@ mod tests {
         assert_eq!(client.mint_url, "http://localhost:3333");
     }
 
+    #[tokio::test]
+    async fn test_loot_over_ceiling_never_reaches_mint() {
+        let policy = MintTrustPolicy {
+            max_loot_per_day: 1000,
+            max_single_payout: 100,
+            require_checkstate_before_payout: false,
+        };
+        // Unroutable mint: a refused payout must fail on policy, not on the network
+        let client = CashuClient::with_trust_policy("http://127.0.0.1:1".to_string(), policy);
+
+        let result = client.create_loot_token("npub1winner", 101, "match_1").await;
+        assert!(matches!(result, Err(GameEngineError::MintPolicyViolation(_))));
+        assert_eq!(client.loot_minted_today(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batched_loot_is_refused_per_award() {
+        let policy = MintTrustPolicy {
+            max_loot_per_day: 1000,
+            max_single_payout: 100,
+            require_checkstate_before_payout: false,
+        };
+        let client = CashuClient::with_trust_policy("http://127.0.0.1:1".to_string(), policy);
+        let award = |winner_npub: &str, amount| LootAward {
+            winner_npub: winner_npub.to_string(),
+            amount,
+            match_id: "match_1".to_string(),
+        };
+        let winner = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+        let results = client
+            .create_loot_tokens(&[award(winner, 101), award("npub1bad", 10), award(winner, 50)])
+            .await;
+        assert!(matches!(
+            results[0],
+            Err(GameEngineError::MintPolicyViolation(_))
+        ));
+        assert!(matches!(results[1], Err(GameEngineError::CashuError(_))));
+        // The admitted award reached the unroutable mint and can be retried alone
+        assert!(results[2].as_ref().unwrap_err().is_retryable());
+        assert_eq!(client.loot_minted_today(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_mint_opens_circuit() {
+        let http = MintHttpConfig {
+            max_retries: 1,
+            base_backoff_ms: 1,
+            failure_threshold: 3,
+            ..MintHttpConfig::default()
+        };
+        let client = CashuClient::new("http://127.0.0.1:1".to_string()).with_http_policy(&http);
+
+        // Each call fails twice on the network; the second call trips the breaker
+        assert!(matches!(
+            client.get_mint_info().await,
+            Err(GameEngineError::Http(_))
+        ));
+        assert!(client.is_available());
+        assert!(client.get_mint_info().await.unwrap_err().is_retryable());
+        assert!(!client.is_available());
+        assert!(matches!(
+            client.get_mint_info().await,
+            Err(GameEngineError::Transient { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_empty_reveal_skips_checkstate() {
+        // Unroutable mint: nothing to check means no request is made
+        let client = CashuClient::new("http://127.0.0.1:1".to_string());
+        assert!(client.find_spent_secrets(&[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_burn_without_signing_keys_never_reaches_mint() {
+        let client = CashuClient::new("http://127.0.0.1:1".to_string());
+
+        let result = client.burn_mana("match_1", &["secret".to_string()]).await;
+        assert!(matches!(result, Err(GameEngineError::CashuError(_))));
+
+        let refunds = [("npub1alice".to_string(), vec!["secret".to_string()])];
+        let result = client.refund_wagers("match_1", &refunds).await;
+        assert!(matches!(result, Err(GameEngineError::CashuError(_))));
+
+        let result = client.escrow_mana("match_1", &["secret".to_string()]).await;
+        assert!(matches!(result, Err(GameEngineError::CashuError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refused_proof_amount_lookup_is_not_retryable() {
+        // A mint that does not serve the game mint routes answers them with a 404
+        let app = axum::Router::new().fallback(|| async { axum::http::StatusCode::NOT_FOUND });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mint_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = CashuClient::new(mint_url).with_signing_keys(Keys::generate());
+        let error = client
+            .committed_amount("match_1", &["secret".to_string()])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, GameEngineError::CashuError(_)));
+        assert!(!error.is_retryable());
+        assert!(client.is_available());
+    }
+
+    #[test]
+    fn test_repeated_proofs_count_once() {
+        let ys = vec!["y1".to_string(), "y2".to_string(), "y3".to_string()];
+        let proof = |y: &str, amount: Option<u64>| ProofAmount {
+            y: y.to_string(),
+            amount,
+        };
+
+        let amounts = vec![
+            proof("y1", Some(64)),
+            proof("y1", Some(64)),
+            proof("y2", None),
+            proof("y3", Some(32)),
+            proof("y_unrequested", Some(1000)),
+        ];
+        // Unknown and unanswered proofs are the ones the mint never signed
+        let requested = [ys.clone(), vec!["y4".to_string()]].concat();
+        assert_eq!(unsigned_ys(&requested, &amounts), ["y2", "y4"]);
+        assert_eq!(sum_proof_amounts(&ys, amounts), 96);
+    }
+
+    #[test]
+    fn test_payout_requires_every_proof_unspent() {
+        let ys = vec!["y1".to_string(), "y2".to_string(), "y3".to_string()];
+        let state = |y: &str, state: &str| ProofState {
+            y: y.to_string(),
+            state: state.to_string(),
+            witness: None,
+        };
+
+        let states = vec![state("y1", "UNSPENT"), state("y2", "PENDING")];
+        assert_eq!(unconfirmed_unspent(&ys, &states), ["y2", "y3"]);
+        assert!(unconfirmed_unspent(&ys[..1], &states).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_lightning_address_never_reaches_mint() {
+        let client = CashuClient::new("http://127.0.0.1:1".to_string());
+        let recipient = FeeRecipient::LightningAddress("operator".to_string());
+
+        let result = client.pay_match_fee(&recipient, 10, "match_1").await;
+        assert!(matches!(result, Err(GameEngineError::CashuError(_))));
+        assert_eq!(client.loot_minted_today(), 0);
+    }
+
+    #[test]
+    fn test_league_mints_route_by_league() {
+        let test_mint = LeagueMintConfig {
+            league_ids: vec![7, 8],
+            mint_url: "http://localhost:3334".to_string(),
+            trust: Some(MintTrustPolicy {
+                max_loot_per_day: 50,
+                ..MintTrustPolicy::default()
+            }),
+        };
+        let client = CashuClient::new("http://localhost:3333".to_string())
+            .with_league_mints(&[test_mint], &MintTrustPolicy::default());
+
+        assert_eq!(
+            client.for_league(Some(7)).mint_url(),
+            "http://localhost:3334"
+        );
+        assert_eq!(
+            client.for_league(Some(8)).mint_url(),
+            "http://localhost:3334"
+        );
+        assert_eq!(
+            client.for_league(Some(0)).mint_url(),
+            "http://localhost:3333"
+        );
+        assert_eq!(client.for_league(None).mint_url(), "http://localhost:3333");
+        assert_eq!(client.all_mints().len(), 2);
+    }
+
     // Note: Integration tests would require a running mint
     // These are unit tests for the client structure
 }
@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>, // Set while open, and while half-open after it passes
+    trial_in_flight: bool,       // A half-open circuit lets one request through
+}
+
+/// Circuit breaker guarding the requests to one mint
+///
+/// After `failure_threshold` failed requests in a row the circuit opens and
+/// requests are refused without contacting the mint. Once `open_for` has passed
+/// a single trial request is let through: success closes the circuit, failure
+/// opens it again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    open_for: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            name: name.into(),
+            failure_threshold: failure_threshold.max(1),
+            open_for,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a request may be sent now
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.open_until {
+            None => true,
+            Some(until) if Instant::now() < until => false,
+            Some(_) if state.trial_in_flight => false,
+            Some(_) => {
+                state.trial_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// Whether requests are currently refused or waiting on a trial request
+    pub fn is_open(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .open_until
+            .is_some_and(|until| Instant::now() < until || state.trial_in_flight)
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.open_until.is_some() {
+            info!("✅ Circuit to {} closed, the mint answers again", self.name);
+        }
+        *state = BreakerState::default();
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        let half_open = state.trial_in_flight;
+        state.trial_in_flight = false;
+
+        if half_open || state.consecutive_failures >= self.failure_threshold {
+            if !half_open {
+                warn!(
+                    "🔌 Circuit to {} opened after {} failed requests, deferring payouts for {:?}",
+                    self.name, state.consecutive_failures, self.open_for
+                );
+            }
+            state.open_until = Some(Instant::now() + self.open_for);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_opens_and_recovers_through_one_trial() {
+        let breaker = CircuitBreaker::new("mint", 2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        assert!(!breaker.allow());
+
+        // Once the open period is over only one trial request goes through
+        let breaker = CircuitBreaker::new("mint", 1, Duration::ZERO);
+        breaker.record_failure();
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        assert!(breaker.is_open());
+
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        assert!(breaker.allow() && breaker.allow());
+    }
+}
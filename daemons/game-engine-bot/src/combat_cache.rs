@@ -0,0 +1,172 @@
+use nostr::util::hex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::match_events::{PlayerReveals, RoundCombat};
+use crate::match_state_machine::MatchData;
+use shared_game_logic::game_state::Unit;
+
+/// Resolved rounds kept by the process-wide cache
+pub const COMBAT_CACHE_CAPACITY: usize = 4096;
+
+/// Match, round and hashes of both players' reveals up to that round
+///
+/// Combat is deterministic in these, so equal keys always resolve to the same totals.
+/// The armies are hashed with the reveals, as the verifier resolves rounds from them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CombatKey {
+    match_event_id: String,
+    round: u32,
+    reveal_hashes: [String; 2],
+}
+
+impl CombatKey {
+    pub fn new(match_data: &MatchData, round: u32) -> Self {
+        Self {
+            match_event_id: match_data.match_event_id.clone(),
+            round,
+            reveal_hashes: [
+                reveal_hash(&match_data.player1_reveals, &match_data.player1_army, round),
+                reveal_hash(&match_data.player2_reveals, &match_data.player2_army, round),
+            ],
+        }
+    }
+}
+
+/// Hash of the revealed tokens, the army and the moves of every round up to `round`
+fn reveal_hash(reveals: &PlayerReveals, army: &Option<[Unit; 8]>, round: u32) -> String {
+    let moves: Vec<_> = (1..=round)
+        .map(|resolved| reveals.moves_by_round.get(&resolved))
+        .collect();
+    let encoded = serde_json::to_vec(&(&reveals.cashu_tokens, army, moves)).unwrap_or_default();
+    hex::encode(Sha256::digest(encoded))
+}
+
+/// Combat totals already resolved, so re-verifying the same reveals is a lookup
+///
+/// Both players submit a result for the same games and the engine summarizes each
+/// round, so the same rounds are otherwise re-executed several times per match. Only
+/// resolved rounds are cached; a round still missing a move is resolved again.
+#[derive(Debug)]
+pub struct CombatCache {
+    entries: Mutex<(HashMap<CombatKey, RoundCombat>, VecDeque<CombatKey>)>, // Entries, oldest first
+    capacity: usize,
+    pub hits: AtomicU64,
+    pub misses: AtomicU64,
+}
+
+impl CombatCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new((HashMap::new(), VecDeque::new())),
+            capacity: capacity.max(1),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Cached totals for the match's round, or those `resolve` computes from scratch
+    pub fn resolve(
+        &self,
+        match_data: &MatchData,
+        round: u32,
+        resolve: impl FnOnce() -> Option<RoundCombat>,
+    ) -> Option<RoundCombat> {
+        let key = CombatKey::new(match_data, round);
+        if let Some(combat) = self.entries.lock().unwrap().0.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(combat.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // Resolved outside the lock; a concurrent miss on the same key computes the same totals
+        let combat = resolve()?;
+        let mut entries = self.entries.lock().unwrap();
+        let (cached, order) = &mut *entries;
+        if cached.insert(key.clone(), combat.clone()).is_none() {
+            order.push_back(key);
+            while order.len() > self.capacity {
+                if let Some(oldest) = order.pop_front() {
+                    cached.remove(&oldest);
+                }
+            }
+        }
+        Some(combat)
+    }
+}
+
+/// Cache shared by every match the engine verifies
+pub fn combat_cache() -> &'static CombatCache {
+    static CACHE: OnceLock<CombatCache> = OnceLock::new();
+    CACHE.get_or_init(|| CombatCache::new(COMBAT_CACHE_CAPACITY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_events::{test_support, MatchChallenge, MatchFormat};
+
+    fn match_data(match_event_id: &str) -> MatchData {
+        let challenge = MatchChallenge {
+            match_format: MatchFormat::BestOf3,
+            ..test_support::challenge(match_event_id)
+        };
+        let acceptance = test_support::acceptance(match_event_id);
+        let mut match_data = MatchData::new(&challenge, &acceptance);
+        for reveals in [
+            &mut match_data.player1_reveals,
+            &mut match_data.player2_reveals,
+        ] {
+            reveals
+                .moves_by_round
+                .insert(1, (vec![0], Vec::new(), "nonce".to_string()));
+        }
+        match_data
+    }
+
+    fn combat(score: [u32; 2]) -> RoundCombat {
+        RoundCombat {
+            damage_taken: [0, 0],
+            units_lost: [0, 0],
+            score,
+            round_winner: None,
+        }
+    }
+
+    #[test]
+    fn test_identical_reveals_hit_the_cache() {
+        let cache = CombatCache::new(1);
+        let mut data = match_data("match_1");
+
+        assert_eq!(
+            cache.resolve(&data, 1, || Some(combat([1, 0]))),
+            Some(combat([1, 0]))
+        );
+        // Second submission of the same games: served without re-executing
+        assert_eq!(
+            cache.resolve(&data, 1, || unreachable!()),
+            Some(combat([1, 0]))
+        );
+        assert_eq!(cache.hits.load(Ordering::Relaxed), 1);
+
+        // Different moves are a different key, and unresolved rounds are not cached
+        data.player2_reveals
+            .moves_by_round
+            .insert(1, (vec![3], Vec::new(), "nonce".to_string()));
+        assert_eq!(cache.resolve(&data, 1, || None), None);
+        assert_eq!(
+            cache.resolve(&data, 1, || Some(combat([0, 1]))),
+            Some(combat([0, 1]))
+        );
+        assert_eq!(cache.misses.load(Ordering::Relaxed), 3);
+
+        // The oldest entry made room for the newest
+        assert_eq!(
+            cache.resolve(&match_data("match_1"), 1, || Some(combat([1, 0]))),
+            Some(combat([1, 0]))
+        );
+        assert_eq!(cache.misses.load(Ordering::Relaxed), 4);
+    }
+}
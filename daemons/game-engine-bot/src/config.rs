@@ -2,37 +2,522 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::match_events::MATCH_FEE_PERCENT;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameEngineConfig {
     pub server: ServerConfig,
     pub nostr: NostrConfig,
     pub cashu: CashuConfig,
     pub game: GameConfig,
+    #[serde(default)]
+    pub reconciliation: ReconciliationConfig,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    #[serde(default)]
+    pub hot_reload: HotReloadConfig,
+    #[serde(default)]
+    pub loot_batch: LootBatchConfig,
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    #[serde(default)]
+    pub reputation: ReputationConfig,
+    #[serde(default)]
+    pub queues: QueueConfig,
+    #[serde(default)]
+    pub relay_discovery: RelayDiscoveryConfig,
+    #[serde(default)]
+    pub matchmaking: MatchmakingConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NostrConfig {
     pub relay_url: String,
+    /// Additional relays subscribed alongside `relay_url`, tried in order when publishing fails
+    #[serde(default)]
+    pub relay_urls: Vec<String>,
     pub private_key: String,
+    /// Relays that require NIP-42 AUTH; the engine answers their challenges with its key
+    #[serde(default)]
+    pub auth_relay_urls: Vec<String>,
+}
+
+impl NostrConfig {
+    /// Primary relay followed by the additional relays, without duplicates
+    pub fn all_relay_urls(&self) -> Vec<String> {
+        let mut urls = vec![self.relay_url.clone()];
+        for url in &self.relay_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+
+    /// Whether the engine should authenticate to the given relay
+    pub fn auth_enabled(&self, relay_url: &str) -> bool {
+        let relay_url = relay_url.trim_end_matches('/');
+        self.auth_relay_urls
+            .iter()
+            .any(|url| url.trim_end_matches('/') == relay_url)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CashuConfig {
     pub mint_url: String,
+    #[serde(default)]
+    pub trust: MintTrustPolicy,
+    /// Mints serving specific leagues instead of `mint_url`
+    #[serde(default)]
+    pub league_mints: Vec<LeagueMintConfig>,
+    #[serde(default)]
+    pub http: MintHttpConfig,
+    /// Hold revealed wagers at the mint until settlement; needs a game mint serving
+    /// `POST /game-engine/escrow`, so it stays off against a stock Cashu mint
+    #[serde(default)]
+    pub escrow_wagers: bool,
+}
+
+/// Mint holding the wagers and loot of the listed leagues, e.g. a test mint for practice leagues
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeagueMintConfig {
+    pub league_ids: Vec<u8>,
+    pub mint_url: String,
+    /// Trust policy for this mint; the `[cashu.trust]` policy applies when omitted
+    #[serde(default)]
+    pub trust: Option<MintTrustPolicy>,
+}
+
+/// Trust policy applied to interactions with the configured mint
+///
+/// Ceilings cap how much loot the engine will mint, so a compromised or buggy
+/// mint interaction cannot drain beyond them without operator intervention.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MintTrustPolicy {
+    pub max_loot_per_day: u64,
+    pub max_single_payout: u64,
+    pub require_checkstate_before_payout: bool,
+}
+
+impl Default for MintTrustPolicy {
+    fn default() -> Self {
+        Self {
+            max_loot_per_day: 100_000,
+            max_single_payout: 10_000,
+            require_checkstate_before_payout: false,
+        }
+    }
+}
+
+/// Timeouts, retries and circuit breaker for requests to every configured mint
+///
+/// Once `failure_threshold` requests in a row fail, the mint is left alone for
+/// `open_seconds` and loot actions wait in the retry queue instead of failing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MintHttpConfig {
+    pub connect_timeout_ms: u64,
+    pub request_timeout_ms: u64,
+    pub pool_idle_timeout_seconds: u64,
+    pub max_retries: u32, // Extra attempts after a connection error, 5xx or 429
+    pub base_backoff_ms: u64, // Doubled per attempt, with up to half of it as jitter
+    pub max_backoff_ms: u64,
+    pub failure_threshold: u32, // Consecutive failed requests that open the circuit
+    pub open_seconds: u64,      // How long the circuit stays open before a trial request
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for MintHttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 2_000,
+            request_timeout_ms: 10_000,
+            pool_idle_timeout_seconds: 90,
+            max_retries: 2,
+            base_backoff_ms: 200,
+            max_backoff_ms: 2_000,
+            failure_threshold: 5,
+            open_seconds: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GameConfig {
     pub max_concurrent_matches: u32,
     pub round_timeout_seconds: u64,
     pub match_timeout_seconds: u64,
     pub loot_reward_per_match: u64,
+    /// League ruleset file (TOML, or JSON by extension); the built-in leagues apply when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset_path: Option<String>,
+    /// Game modes verifying the listed leagues; standard combat applies to the rest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub league_modes: Vec<LeagueModeConfig>,
+    #[serde(default)]
+    pub draw_policy: DrawPolicy,
+    /// Share of the total wager withheld as the match fee
+    #[serde(default = "default_match_fee_percent")]
+    pub match_fee_percent: u64,
+    /// Operator wallet collected fees are paid to; fees are only reported when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<FeeRecipient>,
+}
+
+fn default_match_fee_percent() -> u64 {
+    MATCH_FEE_PERCENT
+}
+
+/// Game mode whose verifier decides the games of the listed leagues
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeagueModeConfig {
+    pub league_ids: Vec<u8>,
+    pub mode: GameMode,
+}
+
+/// Game modes the engine can re-execute, see `match_verifier`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    /// One unit per round, resolved by shared-game-logic combat
+    StandardCombat,
+}
+
+/// Operator wallet the match fee is paid to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRecipient {
+    /// Loot locked to the operator's npub, delivered with the loot award
+    Npub(String),
+    /// Loot melted by the mint to the operator's Lightning address (LUD-16)
+    LightningAddress(String),
+}
+
+/// How a wagered match that ends without a winner is settled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrawPolicy {
+    /// Return both wagers through the mint
+    #[default]
+    RefundWagers,
+    /// Burn the wagers and mint half the loot reward to each player
+    SplitLoot,
+    /// Play extra deciding games, refunding if they are still drawn
+    SuddenDeath,
+}
+
+/// Scheduled cross-check of engine payout records against the mint ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconciliationConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub auto_repair: bool,
+    pub repair_grace_seconds: u64, // Payouts validated more recently may still be in flight
+    pub settled_retention_seconds: u64, // How long settled payout records are kept
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 600, // 10 minutes
+            auto_repair: false,
+            repair_grace_seconds: 900,            // 15 minutes
+            settled_retention_seconds: 7 * 86400, // 1 week
+        }
+    }
+}
+
+/// Durable storage of match state machines for crash recovery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistenceConfig {
+    pub enabled: bool,
+    pub database_path: String,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            database_path: "data/match-tracker.sqlite".to_string(),
+        }
+    }
+}
+
+/// Retry policy for loot and invalidation actions that fail transiently
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub enabled: bool,
+    pub poll_interval_seconds: u64,
+    pub base_delay_seconds: u64,
+    pub max_delay_seconds: u64,
+    pub max_attempts: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_seconds: 5,
+            base_delay_seconds: 5,
+            max_delay_seconds: 900, // 15 minutes
+            max_attempts: 12,
+        }
+    }
+}
+
+/// Per-pubkey token buckets guarding the engine against event floods
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub burst: u32,             // Events a pubkey may send back to back
+    pub refill_per_second: f64, // Sustained event rate per pubkey
+    pub challenge_burst: u32,   // Challenges a pubkey may post back to back
+    pub challenges_per_minute: f64,
+    pub strikes_before_ban: u32, // Throttled events before a temporary ban
+    pub ban_seconds: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            burst: 30,
+            refill_per_second: 5.0,
+            challenge_burst: 3,
+            challenges_per_minute: 6.0,
+            strikes_before_ban: 20,
+            ban_seconds: 900, // 15 minutes
+        }
+    }
+}
+
+/// JSON lines audit trail of every state-machine transition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub max_file_bytes: u64, // Rotate once the current file would exceed this
+    pub max_files: u32,      // Rotated files kept beside the current one
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "data/audit.jsonl".to_string(),
+            max_file_bytes: 10 * 1024 * 1024, // 10 MiB
+            max_files: 5,
+        }
+    }
+}
+
+/// Optional Prometheus scrape endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:9464".to_string(),
+        }
+    }
+}
+
+/// Optional authenticated WebSocket endpoint for operators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdminConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub token: String, // Bearer token operators must present; required when enabled
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1:9465".to_string(),
+            token: String::new(),
+        }
+    }
+}
+
+/// Elo ratings from validated wagered matches, published as a leaderboard
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    pub enabled: bool,
+    pub initial_rating: f64,
+    pub k_factor: f64, // Most rating points one match can move
+    pub publish_interval_seconds: u64,
+    pub leaderboard_size: u32,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_rating: 1200.0,
+            k_factor: 32.0,
+            publish_interval_seconds: 300, // 5 minutes
+            leaderboard_size: 100,
+        }
+    }
+}
+
+/// Reloading game-engine.toml while the engine runs, on SIGHUP or when the file changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotReloadConfig {
+    pub enabled: bool,
+    pub watch_interval_seconds: u64, // How often the file's modification time is checked
+}
+
+impl Default for HotReloadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            watch_interval_seconds: 5,
+        }
+    }
+}
+
+/// Minting winners' loot together when many matches finish at once
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LootBatchConfig {
+    pub enabled: bool,
+    pub window_ms: u64, // How long the first payout waits for others to join it
+    pub max_batch_size: usize,
+}
+
+impl Default for LootBatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: 500,
+            max_batch_size: 25,
+        }
+    }
+}
+
+/// Signed liveness event so operators can tell the engine is up without an HTTP endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 60,
+        }
+    }
+}
+
+/// Cheat record per player; blacklisted players cannot start or join matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReputationConfig {
+    pub enabled: bool,
+    pub strikes_before_blacklist: u32, // Matches a player must be caught cheating in
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strikes_before_blacklist: 1,
+        }
+    }
+}
+
+/// Capacity of the engine's internal queues; a full queue sheds new challenges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConfig {
+    pub match_event_capacity: usize, // Player events received but not yet validated
+    pub action_capacity: usize,      // State machine actions awaiting loot, publish, etc.
+    pub held_reveal_capacity: usize, // Reveals held until their mint can check or escrow them
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            match_event_capacity: 1024,
+            action_capacity: 1024,
+            held_reveal_capacity: 256,
+        }
+    }
+}
+
+/// Follow players to the relays they publish to, as listed in their NIP-65 relay lists
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayDiscoveryConfig {
+    pub enabled: bool,
+    pub max_relays_per_player: usize, // Extra relays subscribed to for one player
+    pub fetch_timeout_ms: u64,        // Time given to relays to return a relay list
+}
+
+impl Default for RelayDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_relays_per_player: 3,
+            fetch_timeout_ms: 3000,
+        }
+    }
+}
+
+/// Optional matchmaker pairing players who post a seek with compatible open challenges
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchmakingConfig {
+    pub enabled: bool,
+    pub max_rating_gap: f64, // Largest Elo difference between paired players
+}
+
+impl Default for MatchmakingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_rating_gap: 200.0,
+        }
+    }
 }
 
 impl Default for GameEngineConfig {
@@ -44,24 +529,53 @@ impl Default for GameEngineConfig {
             },
             nostr: NostrConfig {
                 relay_url: "ws://localhost:7777".to_string(),
+                relay_urls: Vec::new(),
                 private_key: "game_engine_bot_private_key_hex".to_string(),
+                auth_relay_urls: Vec::new(),
             },
             cashu: CashuConfig {
                 mint_url: "http://localhost:3333".to_string(),
+                trust: MintTrustPolicy::default(),
+                league_mints: Vec::new(),
+                http: MintHttpConfig::default(),
+                escrow_wagers: false,
             },
             game: GameConfig {
                 max_concurrent_matches: 100,
                 round_timeout_seconds: 300,  // 5 minutes
                 match_timeout_seconds: 1800, // 30 minutes
                 loot_reward_per_match: 1000,
+                ruleset_path: None,
+                league_modes: Vec::new(),
+                draw_policy: DrawPolicy::default(),
+                match_fee_percent: MATCH_FEE_PERCENT,
+                fee_recipient: None,
             },
+            reconciliation: ReconciliationConfig::default(),
+            persistence: PersistenceConfig::default(),
+            retry: RetryConfig::default(),
+            metrics: MetricsConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            audit: AuditConfig::default(),
+            admin: AdminConfig::default(),
+            ranking: RankingConfig::default(),
+            hot_reload: HotReloadConfig::default(),
+            loot_batch: LootBatchConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            reputation: ReputationConfig::default(),
+            queues: QueueConfig::default(),
+            relay_discovery: RelayDiscoveryConfig::default(),
+            matchmaking: MatchmakingConfig::default(),
         }
     }
 }
 
+/// Configuration file read at startup and watched for reloads
+pub const CONFIG_PATH: &str = "game-engine.toml";
+
 impl GameEngineConfig {
     pub fn load() -> Result<Self> {
-        let config_path = "game-engine.toml";
+        let config_path = CONFIG_PATH;
 
         if !std::path::Path::new(config_path).exists() {
             // Create default config file
@@ -71,7 +585,12 @@ impl GameEngineConfig {
             tracing::info!("📋 Created default {} configuration file", config_path);
         }
 
-        let config_str = fs::read_to_string(config_path)?;
+        Self::from_file(config_path)
+    }
+
+    /// Parse an existing configuration file
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let config_str = fs::read_to_string(path)?;
         let config: Self = toml::from_str(&config_str)?;
 
         Ok(config)
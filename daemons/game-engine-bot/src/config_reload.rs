@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use tracing::{debug, info, warn};
+
+use crate::config::{GameConfig, GameEngineConfig};
+use crate::errors::GameEngineError;
+use crate::match_tracker::MatchTracker;
+use crate::nostr_client::NostrClient;
+
+/// Configuration in force, updated in place when the config file is reloaded
+pub struct LiveConfig {
+    path: PathBuf,
+    config: RwLock<GameEngineConfig>,
+}
+
+/// Settings a reload changed, by dotted name
+#[derive(Debug, Default, PartialEq)]
+pub struct ReloadOutcome {
+    pub applied: Vec<&'static str>,
+    pub needs_restart: Vec<&'static str>, // Changed in the file but left as they were
+}
+
+impl LiveConfig {
+    pub fn new(path: impl Into<PathBuf>, config: GameEngineConfig) -> Self {
+        Self {
+            path: path.into(),
+            config: RwLock::new(config),
+        }
+    }
+
+    /// Game settings currently in force
+    pub fn game(&self) -> GameConfig {
+        self.config.read().unwrap().game.clone()
+    }
+
+    /// Re-read the config file and apply the settings that are safe to change at runtime
+    ///
+    /// Nothing is applied if the file changes match terms while matches are in flight.
+    pub fn reload(&self, matches_in_flight: usize) -> Result<ReloadOutcome, GameEngineError> {
+        let next = GameEngineConfig::from_file(&self.path).map_err(|e| {
+            GameEngineError::ConfigReloadRejected(format!(
+                "Failed to read {}: {e}",
+                self.path.display()
+            ))
+        })?;
+        apply_reload(&mut self.config.write().unwrap(), next, matches_in_flight)
+    }
+}
+
+/// Fold the runtime-safe parts of `next` into the running config
+fn apply_reload(
+    current: &mut GameEngineConfig,
+    next: GameEngineConfig,
+    matches_in_flight: usize,
+) -> Result<ReloadOutcome, GameEngineError> {
+    // Players in flight accepted these terms from the published ruleset
+    let changed_terms: Vec<&str> = [
+        (
+            "game.match_fee_percent",
+            current.game.match_fee_percent != next.game.match_fee_percent,
+        ),
+        (
+            "game.draw_policy",
+            current.game.draw_policy != next.game.draw_policy,
+        ),
+        (
+            "game.loot_reward_per_match",
+            current.game.loot_reward_per_match != next.game.loot_reward_per_match,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, changed)| changed.then_some(name))
+    .collect();
+    if !changed_terms.is_empty() && matches_in_flight > 0 {
+        return Err(GameEngineError::ConfigReloadRejected(format!(
+            "{} cannot change while {} matches are in flight",
+            changed_terms.join(", "),
+            matches_in_flight
+        )));
+    }
+
+    let applied = [
+        (
+            "game.max_concurrent_matches",
+            current.game.max_concurrent_matches != next.game.max_concurrent_matches,
+        ),
+        (
+            "game.round_timeout_seconds",
+            current.game.round_timeout_seconds != next.game.round_timeout_seconds,
+        ),
+        (
+            "game.match_timeout_seconds",
+            current.game.match_timeout_seconds != next.game.match_timeout_seconds,
+        ),
+        (
+            "game.fee_recipient",
+            current.game.fee_recipient != next.game.fee_recipient,
+        ),
+    ];
+
+    // Connections, storage and background tasks are only set up at startup
+    let needs_restart = [
+        (
+            "game.ruleset_path",
+            current.game.ruleset_path != next.game.ruleset_path,
+        ),
+        (
+            "game.league_modes",
+            current.game.league_modes != next.game.league_modes,
+        ),
+        ("server", current.server != next.server),
+        ("nostr", current.nostr != next.nostr),
+        ("cashu", current.cashu != next.cashu),
+        (
+            "reconciliation",
+            current.reconciliation != next.reconciliation,
+        ),
+        ("persistence", current.persistence != next.persistence),
+        ("retry", current.retry != next.retry),
+        ("metrics", current.metrics != next.metrics),
+        ("rate_limit", current.rate_limit != next.rate_limit),
+        ("audit", current.audit != next.audit),
+        ("admin", current.admin != next.admin),
+        ("ranking", current.ranking != next.ranking),
+        ("hot_reload", current.hot_reload != next.hot_reload),
+        ("loot_batch", current.loot_batch != next.loot_batch),
+        ("heartbeat", current.heartbeat != next.heartbeat),
+        ("reputation", current.reputation != next.reputation),
+        ("queues", current.queues != next.queues),
+        (
+            "relay_discovery",
+            current.relay_discovery != next.relay_discovery,
+        ),
+        ("matchmaking", current.matchmaking != next.matchmaking),
+    ];
+
+    let outcome = ReloadOutcome {
+        applied: changed_terms
+            .into_iter()
+            .chain(
+                applied
+                    .into_iter()
+                    .filter_map(|(name, changed)| changed.then_some(name)),
+            )
+            .collect(),
+        needs_restart: needs_restart
+            .into_iter()
+            .filter_map(|(name, changed)| changed.then_some(name))
+            .collect(),
+    };
+
+    current.game = GameConfig {
+        ruleset_path: current.game.ruleset_path.take(),
+        league_modes: std::mem::take(&mut current.game.league_modes),
+        ..next.game
+    };
+    Ok(outcome)
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Wait for SIGHUP; never resolves where the signal does not exist
+#[cfg(unix)]
+async fn hangup(signal: &mut Option<tokio::signal::unix::Signal>) {
+    match signal {
+        Some(signal) => {
+            signal.recv().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn hangup(_signal: &mut Option<()>) {
+    std::future::pending().await
+}
+
+/// Background task reloading the config file on SIGHUP or when it is modified
+pub async fn run_config_watcher(
+    live_config: Arc<LiveConfig>,
+    tracker: Arc<MatchTracker>,
+    nostr_client: Arc<NostrClient>,
+    watch_interval_seconds: u64,
+) {
+    #[cfg(unix)]
+    let mut hangup_signal =
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                warn!("⚠️ SIGHUP reloads unavailable: {}", e);
+                None
+            }
+        };
+    #[cfg(not(unix))]
+    let mut hangup_signal = None;
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        watch_interval_seconds.max(1),
+    ));
+    let mut last_modified = modified_at(&live_config.path);
+
+    loop {
+        tokio::select! {
+            _ = hangup(&mut hangup_signal) => {
+                info!("🔄 SIGHUP received, reloading {}", live_config.path.display());
+            }
+            _ = interval.tick() => {
+                let modified = modified_at(&live_config.path);
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+                info!("🔄 {} changed, reloading", live_config.path.display());
+            }
+        }
+
+        let matches_in_flight = tracker.get_statistics().await.active_matches();
+        let outcome = match live_config.reload(matches_in_flight) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                warn!("🚫 Keeping the running configuration: {}", e);
+                continue;
+            }
+        };
+
+        for name in &outcome.needs_restart {
+            warn!("⚠️ {} changed but only takes effect after a restart", name);
+        }
+        if outcome.applied.is_empty() {
+            debug!("No runtime settings changed");
+            continue;
+        }
+
+        let game = live_config.game();
+        tracker.set_limits(
+            game.max_concurrent_matches as usize,
+            game.round_timeout_seconds / 60,
+        );
+        info!("✅ Applied config changes: {}", outcome.applied.join(", "));
+
+        // Clients read fees and timeouts from the ruleset, so republish it
+        if let Err(e) = nostr_client.publish_ruleset(&game).await {
+            warn!("⚠️ Failed to republish ruleset after reload: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DrawPolicy;
+
+    #[test]
+    fn test_reload_applies_safe_settings_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game-engine.toml");
+        let live_config = LiveConfig::new(&path, GameEngineConfig::default());
+
+        let mut edited = GameEngineConfig::default();
+        edited.game.loot_reward_per_match = 2500;
+        edited.game.draw_policy = DrawPolicy::SplitLoot;
+        edited.server.port = 5555;
+        std::fs::write(&path, toml::to_string_pretty(&edited).unwrap()).unwrap();
+
+        // A new draw policy or loot reward would change the terms of matches being played
+        let rejected = live_config.reload(1).unwrap_err().to_string();
+        assert!(rejected.contains("game.draw_policy, game.loot_reward_per_match"));
+        assert_eq!(live_config.game().loot_reward_per_match, 1000);
+
+        let outcome = live_config.reload(0).unwrap();
+        assert_eq!(
+            outcome.applied,
+            ["game.draw_policy", "game.loot_reward_per_match"]
+        );
+        assert_eq!(outcome.needs_restart, ["server"]);
+        assert_eq!(live_config.game().loot_reward_per_match, 2500);
+        assert_eq!(live_config.config.read().unwrap().server.port, 4444);
+    }
+}
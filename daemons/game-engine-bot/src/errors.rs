@@ -2,8 +2,23 @@ use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum GameEngineError {
-    #[error("Nostr connection failed: {0}")]
-    NostrConnectionError(String),
+    /// Failure that may clear up on its own: relay or mint outages, timeouts, busy storage
+    #[error("Transient failure: {source}")]
+    Transient {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// Request the engine refuses on its merits; retrying cannot change the answer
+    #[error("Validation failed: {reason}")]
+    Validation { reason: String },
+
+    /// Player event that breaks the protocol for its Nostr kind
+    #[error("Protocol violation in kind {kind} event: {reason}")]
+    Protocol { kind: u16, reason: String },
+
+    /// A player provably cheated, e.g. revealed tokens that do not match their commitment
+    #[error("Cheating detected: {evidence}")]
+    Cheat { evidence: String },
 
     #[error("Nostr error: {0}")]
     NostrError(String),
@@ -11,30 +26,74 @@ pub enum GameEngineError {
     #[error("Cashu mint communication failed: {0}")]
     CashuError(String),
 
-    #[error("Invalid event format: {0}")]
-    EventParsingError(String),
+    #[error("Mint trust policy violation: {0}")]
+    MintPolicyViolation(String),
 
     #[error("Match not found: {0}")]
     MatchNotFound(String),
 
-    #[error("Invalid game state transition")]
-    InvalidStateTransition,
-
-    #[error("Combat resolution failed: {0}")]
-    CombatError(String),
-
     #[error("Configuration error: {0}")]
     Config(#[from] config::ConfigError),
 
+    #[error("Config reload rejected: {0}")]
+    ConfigReloadRejected(String),
+
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("Match persistence failed: {0}")]
+    Persistence(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+impl GameEngineError {
+    pub fn transient(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        GameEngineError::Transient {
+            source: source.into(),
+        }
+    }
+
+    /// Whether the failed operation may succeed if it is attempted again later
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GameEngineError::Transient { .. } | GameEngineError::Persistence(_) => true,
+            // Connection failures and timeouts never got a verdict from the server
+            GameEngineError::Http(e) => !e.status().is_some_and(|status| status.is_client_error()),
+            _ => false,
+        }
+    }
+}
+
 impl From<String> for GameEngineError {
     fn from(err: String) -> Self {
         GameEngineError::Internal(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_transient_failures_are_retryable() {
+        assert!(GameEngineError::transient("relay timed out").is_retryable());
+        assert!(GameEngineError::Persistence("database is locked".to_string()).is_retryable());
+
+        assert!(!GameEngineError::Validation {
+            reason: "wager exceeds league cap".to_string()
+        }
+        .is_retryable());
+        assert!(!GameEngineError::Protocol {
+            kind: 21000,
+            reason: "missing challenger".to_string()
+        }
+        .is_retryable());
+        assert!(!GameEngineError::Cheat {
+            evidence: "token commitment mismatch".to_string()
+        }
+        .is_retryable());
+        assert!(!GameEngineError::MatchNotFound("match_1".to_string()).is_retryable());
+    }
+}
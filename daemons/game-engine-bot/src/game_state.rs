@@ -358,9 +358,9 @@ impl MatchValidationManager {
             })?;
 
         if !verify_cashu_commitment(p1_commitment, p1_tokens, p1_nonce) {
-            return Err(GameEngineError::Internal(
-                "Player 1 token commitment verification failed".to_string(),
-            ));
+            return Err(GameEngineError::Cheat {
+                evidence: "Player 1 token commitment verification failed".to_string(),
+            });
         }
 
         let p2_tokens = player_match.player2_reveals.cashu_tokens.as_ref().unwrap();
@@ -378,9 +378,9 @@ impl MatchValidationManager {
             })?;
 
         if !verify_cashu_commitment(p2_commitment, p2_tokens, p2_nonce) {
-            return Err(GameEngineError::Internal(
-                "Player 2 token commitment verification failed".to_string(),
-            ));
+            return Err(GameEngineError::Cheat {
+                evidence: "Player 2 token commitment verification failed".to_string(),
+            });
         }
 
         info!("✅ All token commitments verified successfully");
@@ -431,9 +431,11 @@ impl MatchValidationManager {
                 &p1_move_data.1, // abilities
                 &p1_move_data.2, // nonce
             ) {
-                return Err(GameEngineError::Internal(format!(
-                    "Player 1 move commitment verification failed for round {round}"
-                )));
+                return Err(GameEngineError::Cheat {
+                    evidence: format!(
+                        "Player 1 move commitment verification failed for round {round}"
+                    ),
+                });
             }
 
             // Player 2 move validation
@@ -460,9 +462,11 @@ impl MatchValidationManager {
                 &p2_move_data.1, // abilities
                 &p2_move_data.2, // nonce
             ) {
-                return Err(GameEngineError::Internal(format!(
-                    "Player 2 move commitment verification failed for round {round}"
-                )));
+                return Err(GameEngineError::Cheat {
+                    evidence: format!(
+                        "Player 2 move commitment verification failed for round {round}"
+                    ),
+                });
             }
 
             debug!("✅ Round {} move commitments verified", round);
@@ -483,7 +487,7 @@ impl MatchValidationManager {
         ),
         GameEngineError,
     > {
-        use shared_game_logic::combat::generate_units_from_token_secret;
+        use shared_game_logic::combat::generate_army_for_version;
         use tracing::{debug, info};
 
         // Get token secrets (first token used for army generation)
@@ -516,9 +520,21 @@ impl MatchValidationManager {
             p2_tokens.len()
         );
 
-        // Generate armies deterministically from first token
-        let player1_army = generate_units_from_token_secret(&p1_tokens[0], player_match.league_id);
-        let player2_army = generate_units_from_token_secret(&p2_tokens[0], player_match.league_id);
+        // Generate armies deterministically from first token, with the challenge's generator
+        let generate = |secret: &str| {
+            generate_army_for_version(
+                secret,
+                player_match.league_id,
+                player_match.generator_version,
+            )
+            .map_err(|e| GameEngineError::Internal(e.to_string()))
+        };
+        let mut player1_army = generate(&p1_tokens[0])?;
+        let mut player2_army = generate(&p2_tokens[0])?;
+
+        // Stats of the season the challenge was created in
+        shared_game_logic::league::apply_season(&mut player1_army, player_match.created_at);
+        shared_game_logic::league::apply_season(&mut player2_army, player_match.created_at);
 
         // Log army details for debugging
         debug!("🎪 Player 1 Army Generated:");
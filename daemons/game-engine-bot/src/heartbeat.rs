@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::cashu_client::CashuClient;
+use crate::config::HeartbeatConfig;
+use crate::match_events::MintConnectivity;
+use crate::match_tracker::MatchTracker;
+use crate::nostr_client::NostrClient;
+
+/// Health check every mint the engine pays out from
+pub async fn mint_connectivity(cashu_client: &CashuClient) -> Vec<MintConnectivity> {
+    let mut mints = Vec::new();
+    for mint in cashu_client.all_mints() {
+        mints.push(MintConnectivity {
+            mint_url: mint.mint_url().to_string(),
+            reachable: mint.health_check().await.unwrap_or(false),
+        });
+    }
+    mints
+}
+
+/// Background task publishing the engine heartbeat every interval
+pub async fn run_heartbeat_task(
+    nostr_client: Arc<NostrClient>,
+    tracker: Arc<MatchTracker>,
+    cashu_client: Arc<CashuClient>,
+    config: HeartbeatConfig,
+) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        config.interval_seconds.max(1),
+    ));
+
+    loop {
+        interval.tick().await;
+
+        let active_matches = tracker.get_statistics().await.active_matches();
+        let mints = mint_connectivity(&cashu_client).await;
+        for mint in mints.iter().filter(|mint| !mint.reachable) {
+            warn!("⚠️ Heartbeat: mint {} is unreachable", mint.mint_url);
+        }
+
+        if let Err(e) = nostr_client.publish_heartbeat(active_matches, mints).await {
+            warn!("⚠️ Failed to publish heartbeat: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreachable_mint_is_reported() {
+        // Unroutable mint: the health check fails on the network
+        let cashu_client = CashuClient::new("http://127.0.0.1:1".to_string());
+
+        let mints = mint_connectivity(&cashu_client).await;
+        assert_eq!(
+            mints,
+            [MintConnectivity {
+                mint_url: "http://127.0.0.1:1".to_string(),
+                reachable: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Number of latency samples kept per player per match
+const MAX_SAMPLES_PER_PLAYER: usize = 32;
+
+/// Upper bound on the reveal delay the engine will ever recommend
+pub const MAX_RECOMMENDED_REVEAL_DELAY_MS: u64 = 10_000;
+
+/// Tracks observed Nostr event propagation latency per match and player
+///
+/// Latency is measured as the delta between an event's `created_at` and the time
+/// the engine received it from the relay. Players on slow relays reveal into a
+/// window their opponent may already have seen, so the engine recommends a
+/// reveal delay covering the slowest observed propagation in each match.
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    samples: Mutex<HashMap<String, HashMap<String, VecDeque<u64>>>>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a propagation latency sample for a player in a match
+    pub fn record(&self, match_id: &str, player_npub: &str, latency_ms: u64) {
+        let mut samples = self.samples.lock().unwrap();
+        let player_samples = samples
+            .entry(match_id.to_string())
+            .or_default()
+            .entry(player_npub.to_string())
+            .or_default();
+
+        if player_samples.len() >= MAX_SAMPLES_PER_PLAYER {
+            player_samples.pop_front();
+        }
+        player_samples.push_back(latency_ms);
+
+        debug!(
+            "⏱️ Latency sample for {} in match {}: {}ms",
+            player_npub, match_id, latency_ms
+        );
+    }
+
+    /// Median observed latency for a player in a match
+    pub fn median_latency_ms(&self, match_id: &str, player_npub: &str) -> Option<u64> {
+        let samples = self.samples.lock().unwrap();
+        samples
+            .get(match_id)
+            .and_then(|players| players.get(player_npub))
+            .and_then(|player_samples| median(player_samples.iter().copied().collect()))
+    }
+
+    /// Recommended delay before revealing, covering the slowest player's median latency
+    pub fn recommended_reveal_delay_ms(&self, match_id: &str) -> u64 {
+        let samples = self.samples.lock().unwrap();
+        samples
+            .get(match_id)
+            .map(|players| {
+                players
+                    .values()
+                    .filter_map(|player_samples| median(player_samples.iter().copied().collect()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+            .min(MAX_RECOMMENDED_REVEAL_DELAY_MS)
+    }
+
+    /// Drop all samples for a finished match
+    pub fn forget_match(&self, match_id: &str) {
+        self.samples.lock().unwrap().remove(match_id);
+    }
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    Some(values[values.len() / 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_samples_recommends_no_delay() {
+        let tracker = LatencyTracker::new();
+        assert_eq!(tracker.recommended_reveal_delay_ms("match_1"), 0);
+    }
+
+    #[test]
+    fn test_recommendation_covers_slowest_player() {
+        let tracker = LatencyTracker::new();
+        for latency in [100, 120, 110] {
+            tracker.record("match_1", "npub1alice", latency);
+        }
+        for latency in [900, 1100, 1000] {
+            tracker.record("match_1", "npub1bob", latency);
+        }
+
+        assert_eq!(tracker.median_latency_ms("match_1", "npub1alice"), Some(110));
+        assert_eq!(tracker.recommended_reveal_delay_ms("match_1"), 1000);
+    }
+
+    #[test]
+    fn test_recommendation_is_capped() {
+        let tracker = LatencyTracker::new();
+        tracker.record("match_1", "npub1alice", 60_000);
+        assert_eq!(
+            tracker.recommended_reveal_delay_ms("match_1"),
+            MAX_RECOMMENDED_REVEAL_DELAY_MS
+        );
+    }
+
+    #[test]
+    fn test_sample_window_is_bounded() {
+        let tracker = LatencyTracker::new();
+        for _ in 0..MAX_SAMPLES_PER_PLAYER {
+            tracker.record("match_1", "npub1alice", 5_000);
+        }
+        for _ in 0..MAX_SAMPLES_PER_PLAYER {
+            tracker.record("match_1", "npub1alice", 50);
+        }
+        assert_eq!(tracker.median_latency_ms("match_1", "npub1alice"), Some(50));
+
+        tracker.forget_match("match_1");
+        assert_eq!(tracker.median_latency_ms("match_1", "npub1alice"), None);
+    }
+}
@@ -4,25 +4,79 @@
 //! for the Manastr decentralized gaming engine.
 
 // Re-export all the modules for external use
+pub mod action_queue;
+pub mod admin_api;
+pub mod audit;
 pub mod cashu_client;
+pub mod circuit_breaker;
+pub mod combat_cache;
 pub mod config;
+pub mod config_reload;
 pub mod errors;
 pub mod game_state;
+pub mod heartbeat;
+pub mod latency;
+pub mod loot_token;
+pub mod match_archive;
 pub mod match_events;
 pub mod match_state_machine;
+pub mod match_store;
 pub mod match_tracker;
+pub mod match_verifier;
+pub mod match_workers;
+pub mod matchmaking;
+pub mod metrics;
+pub mod mint_auth;
+pub mod mint_policy;
 pub mod nostr_client;
+pub mod player_event_log;
+pub mod proof_bundle;
+pub mod ranking;
+pub mod rate_limiter;
+pub mod reconciliation;
+pub mod relay_discovery;
+pub mod replay_guard;
+pub mod reputation;
 
 // Re-export the main types for easy access
+pub use action_queue::ActionRetryQueue;
+pub use admin_api::run_admin_server;
+pub use audit::{AuditLog, AuditRecord};
 pub use cashu_client::CashuClient;
+pub use combat_cache::CombatCache;
 pub use config::GameEngineConfig;
+pub use config_reload::{run_config_watcher, LiveConfig};
 pub use errors::GameEngineError;
+pub use heartbeat::run_heartbeat_task;
+pub use match_archive::{ArchivedMatch, MatchArchive};
 pub use match_state_machine::{GameEngineAction, MatchState};
+pub use match_store::{MatchStore, MemoryMatchStore, SqliteMatchStore};
 pub use match_tracker::{run_cleanup_task, MatchTracker, TrackedAction};
+pub use match_verifier::{install_verifiers, MatchVerifier, VerifierRegistry};
+pub use match_workers::MatchWorkers;
+pub use matchmaking::MatchmakingPool;
+pub use metrics::{run_metrics_server, EngineMetrics};
 pub use nostr_client::{NostrClient, PlayerMatchEvent};
+pub use player_event_log::PlayerEventLog;
+pub use proof_bundle::{replay_match, verify_bundle, ProofBundle};
+pub use ranking::{run_leaderboard_task, RankingLedger};
+pub use rate_limiter::{RateDecision, RateLimiter};
+pub use reconciliation::{
+    run_reconciliation_task, CollectedFee, LootShare, PayoutLedger, ReconciliationMetrics,
+};
+pub use replay_guard::ReplayGuard;
+pub use reputation::ReputationStore;
 
 // Copy the GameEngineBot struct and its implementation from main.rs
 use anyhow::Result;
+use cashu_client::{hash_secrets, FeePayout, LootAward, LootTokenResult};
+use config::{DrawPolicy, CONFIG_PATH};
+use match_events::{FeeSplit, MatchSeek, TokenReveal};
+use match_state_machine::Invalidation;
+use matchmaking::Pairing;
+use reputation::PlayerReputation;
+use shared_game_logic::league::{self, LeagueRegistry};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
@@ -30,36 +84,151 @@ use tracing::{debug, error, info, warn};
 /// Now operates purely through state machine transitions
 pub struct GameEngineBot {
     config: GameEngineConfig,
+    live_config: Arc<LiveConfig>, // Game settings as reloaded at runtime
     match_tracker: Arc<MatchTracker>,
     cashu_client: Arc<CashuClient>,
     nostr_client: Arc<NostrClient>,
-    match_event_receiver:
-        Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<PlayerMatchEvent>>>,
-    action_receiver: Arc<tokio::sync::Mutex<tokio::sync::mpsc::UnboundedReceiver<TrackedAction>>>,
+    payout_ledger: Arc<PayoutLedger>,
+    retry_queue: Arc<ActionRetryQueue>,
+    match_archive: Arc<MatchArchive>,
+    ranking: Option<Arc<RankingLedger>>, // None when rankings are disabled
+    reputation: Option<Arc<ReputationStore>>, // None when the blacklist is disabled
+    reconciliation_metrics: Arc<ReconciliationMetrics>,
+    metrics: Arc<EngineMetrics>,
+    matchmaker: Option<std::sync::Mutex<MatchmakingPool>>, // None unless matchmaking is enabled
+    held_reveals: std::sync::Mutex<Vec<TokenReveal>>, // Reveals waiting for the mint to check them
+    match_event_sender: tokio::sync::mpsc::Sender<PlayerMatchEvent>, // Feeds held reveals back to their match
+    match_event_receiver: Arc<tokio::sync::Mutex<tokio::sync::mpsc::Receiver<PlayerMatchEvent>>>,
+    action_receiver: Arc<tokio::sync::Mutex<tokio::sync::mpsc::Receiver<TrackedAction>>>,
 }
 
+/// Loot payouts owed by one mint, each with the action to retry if it fails
+type MintBatch<'a> = (&'a CashuClient, Vec<(TrackedAction, LootAward)>);
+
 impl GameEngineBot {
     pub async fn new(config: GameEngineConfig) -> Result<Self, GameEngineError> {
-        // Initialize Cashu client
-        let cashu_client = Arc::new(CashuClient::new(config.cashu.mint_url.clone()));
+        // League rules come from the configured ruleset file, if any
+        if let Some(path) = &config.game.ruleset_path {
+            let registry = LeagueRegistry::load(path).map_err(|e| {
+                GameEngineError::Internal(format!("Failed to load league ruleset {path}: {e}"))
+            })?;
+            info!("📜 Loaded {} leagues from {}", registry.len(), path);
+            league::install_registry(registry)
+                .map_err(|e| GameEngineError::Internal(e.to_string()))?;
+        }
+        install_verifiers(VerifierRegistry::from_config(&config.game.league_modes))?;
 
-        // Test connection to mint
-        if !cashu_client.health_check().await? {
-            warn!("⚠️ Cashu mint not available at {}", config.cashu.mint_url);
+        // Payout stages are recorded as they complete, including loot minted but not yet swapped
+        let payout_ledger = Arc::new(if config.persistence.enabled {
+            PayoutLedger::open(&config.persistence.database_path)?
         } else {
-            info!("✅ Connected to Cashu mint at {}", config.cashu.mint_url);
+            PayoutLedger::in_memory()?
+        });
+
+        // Initialize Cashu client, signing authority-only requests as the engine
+        let engine_keys = nostr::Keys::parse(&config.nostr.private_key)
+            .map_err(|e| GameEngineError::NostrError(format!("Invalid private key: {e}")))?;
+        let metrics = Arc::new(EngineMetrics::default());
+        let cashu_client = Arc::new(
+            CashuClient::with_trust_policy(
+                config.cashu.mint_url.clone(),
+                config.cashu.trust.clone(),
+            )
+            .with_signing_keys(engine_keys)
+            .with_metrics(Arc::clone(&metrics))
+            .with_payout_ledger(Arc::clone(&payout_ledger))
+            .with_http_policy(&config.cashu.http)
+            .with_league_mints(&config.cashu.league_mints, &config.cashu.trust),
+        );
+
+        // Loot minted earlier today still counts against the daily ceilings
+        cashu_client.restore_daily_spend()?;
+
+        // Test connection to every mint
+        for mint in cashu_client.all_mints() {
+            if !mint.health_check().await? {
+                warn!("⚠️ Cashu mint not available at {}", mint.mint_url());
+            } else {
+                info!("✅ Connected to Cashu mint at {}", mint.mint_url());
+            }
         }
 
         // Initialize match tracker with state machine
-        let (match_tracker, action_receiver) = MatchTracker::new(
+        let match_store: Arc<dyn MatchStore> = if config.persistence.enabled {
+            Arc::new(SqliteMatchStore::open(&config.persistence.database_path)?)
+        } else {
+            Arc::new(MemoryMatchStore::new())
+        };
+        let retry_queue = Arc::new(if config.persistence.enabled {
+            ActionRetryQueue::open(&config.persistence.database_path, config.retry.clone())?
+        } else {
+            ActionRetryQueue::in_memory(config.retry.clone())?
+        });
+        let match_archive = Arc::new(if config.persistence.enabled {
+            MatchArchive::open(&config.persistence.database_path)?
+        } else {
+            MatchArchive::in_memory()?
+        });
+        let ranking = if !config.ranking.enabled {
+            None
+        } else if config.persistence.enabled {
+            Some(Arc::new(RankingLedger::open(
+                &config.persistence.database_path,
+                config.ranking.clone(),
+            )?))
+        } else {
+            Some(Arc::new(RankingLedger::in_memory(config.ranking.clone())?))
+        };
+        let reputation = if !config.reputation.enabled {
+            None
+        } else if config.persistence.enabled {
+            Some(Arc::new(ReputationStore::open(
+                &config.persistence.database_path,
+                config.reputation.clone(),
+            )?))
+        } else {
+            Some(Arc::new(ReputationStore::in_memory(
+                config.reputation.clone(),
+            )?))
+        };
+        let (match_tracker, action_receiver) = MatchTracker::with_store(
             config.game.max_concurrent_matches as usize,
             config.game.round_timeout_seconds / 60, // convert to minutes
-        );
+            config.queues.action_capacity,
+            match_store,
+        )?;
+        let mut match_tracker = match_tracker.with_archive(Arc::clone(&match_archive));
+        if config.audit.enabled {
+            info!("📝 Auditing state transitions to {}", config.audit.path);
+            match_tracker =
+                match_tracker.with_audit_log(Arc::new(AuditLog::open(config.audit.clone())?));
+        }
+        if let Some(reputation) = &reputation {
+            match_tracker = match_tracker.with_reputation(Arc::clone(reputation));
+        }
+        match_tracker.watch_action_queue(&metrics);
         let match_tracker = Arc::new(match_tracker);
 
         // Initialize Nostr client
-        let (match_event_sender, match_event_receiver) = tokio::sync::mpsc::unbounded_channel();
-        let nostr_client = Arc::new(NostrClient::new(&config.nostr, match_event_sender).await?);
+        let (match_event_sender, match_event_receiver) =
+            tokio::sync::mpsc::channel(config.queues.match_event_capacity.max(1));
+        metrics.watch_queue("match_events", &match_event_sender);
+        let mut nostr_client = NostrClient::new(&config.nostr, match_event_sender.clone())
+            .await?
+            .with_relay_discovery(config.relay_discovery.clone())
+            .with_rate_limit(config.rate_limit.clone());
+        if config.persistence.enabled {
+            nostr_client = nostr_client
+                .with_replay_guard(ReplayGuard::open(&config.persistence.database_path)?)
+                .with_player_event_log(PlayerEventLog::open(&config.persistence.database_path)?);
+        }
+        // Private matches restored from the store keep their engine events off the public feed
+        for (match_id, tracked) in match_tracker.tracked_matches().await {
+            if tracked.state.is_private() {
+                nostr_client.register_private_match(&match_id, tracked.state.players());
+            }
+        }
+        let nostr_client = Arc::new(nostr_client);
 
         info!("🎮 Initialized Game Engine Bot with State Machine Architecture");
         info!(
@@ -77,11 +246,31 @@ impl GameEngineBot {
         info!("🔑 Bot pubkey: {}", nostr_client.public_key());
         info!("🤖 Operating purely via Nostr events (no HTTP endpoints)");
 
+        let matchmaker = config.matchmaking.enabled.then(|| {
+            info!(
+                "🤝 Matchmaking enabled (max rating gap {})",
+                config.matchmaking.max_rating_gap
+            );
+            std::sync::Mutex::new(MatchmakingPool::new(config.matchmaking.max_rating_gap))
+        });
+        let live_config = Arc::new(LiveConfig::new(CONFIG_PATH, config.clone()));
+
         Ok(Self {
             config,
+            live_config,
             match_tracker,
             cashu_client,
             nostr_client,
+            payout_ledger,
+            retry_queue,
+            match_archive,
+            ranking,
+            reputation,
+            reconciliation_metrics: Arc::new(ReconciliationMetrics::default()),
+            metrics,
+            matchmaker,
+            held_reveals: std::sync::Mutex::new(Vec::new()),
+            match_event_sender,
             match_event_receiver: Arc::new(tokio::sync::Mutex::new(match_event_receiver)),
             action_receiver: Arc::new(tokio::sync::Mutex::new(action_receiver)),
         })
@@ -94,6 +283,11 @@ impl GameEngineBot {
         // Start listening for Nostr events
         self.nostr_client.start_event_listener().await?;
 
+        // Advertise the ruleset so clients can configure themselves against this engine
+        if let Err(e) = self.nostr_client.publish_ruleset(&self.config.game).await {
+            warn!("⚠️ Failed to publish engine ruleset: {}", e);
+        }
+
         // Start match event processing loop
         let bot_clone = Arc::clone(&self);
         tokio::spawn(async move {
@@ -106,16 +300,103 @@ impl GameEngineBot {
             bot_clone.process_state_actions().await;
         });
 
+        // Retry loot and invalidation actions that failed on transient errors
+        if self.config.retry.enabled {
+            let bot_clone = Arc::clone(&self);
+            tokio::spawn(async move {
+                bot_clone.process_retry_queue().await;
+            });
+        }
+
         // Start periodic cleanup task
         let tracker_clone = Arc::clone(&self.match_tracker);
         tokio::spawn(async move {
             run_cleanup_task(tracker_clone).await;
         });
 
+        // Start periodic reconciliation against the mint ledger
+        if self.config.reconciliation.enabled {
+            let ledger_clone = Arc::clone(&self.payout_ledger);
+            let cashu_clone = Arc::clone(&self.cashu_client);
+            let metrics_clone = Arc::clone(&self.reconciliation_metrics);
+            let reconciliation_config = self.config.reconciliation.clone();
+            tokio::spawn(async move {
+                run_reconciliation_task(
+                    ledger_clone,
+                    cashu_clone,
+                    metrics_clone,
+                    reconciliation_config,
+                )
+                .await;
+            });
+        }
+
+        // Signed liveness signal in place of an HTTP health endpoint
+        if self.config.heartbeat.enabled {
+            let nostr_clone = Arc::clone(&self.nostr_client);
+            let tracker_clone = Arc::clone(&self.match_tracker);
+            let cashu_clone = Arc::clone(&self.cashu_client);
+            let heartbeat_config = self.config.heartbeat.clone();
+            tokio::spawn(async move {
+                run_heartbeat_task(nostr_clone, tracker_clone, cashu_clone, heartbeat_config).await;
+            });
+        }
+
+        // Publish standings whenever validated results move the ratings
+        if let Some(ranking) = &self.ranking {
+            let ranking_clone = Arc::clone(ranking);
+            let nostr_clone = Arc::clone(&self.nostr_client);
+            tokio::spawn(async move {
+                run_leaderboard_task(ranking_clone, nostr_clone).await;
+            });
+        }
+
+        // Expose counters and latencies for Prometheus scrapes
+        if self.config.metrics.enabled {
+            let metrics_clone = Arc::clone(&self.metrics);
+            let metrics_config = self.config.metrics.clone();
+            tokio::spawn(async move {
+                if let Err(e) = run_metrics_server(metrics_clone, metrics_config).await {
+                    error!("❌ Metrics endpoint stopped: {}", e);
+                }
+            });
+        }
+
+        // Let operators watch transitions and intervene in live matches
+        if self.config.admin.enabled {
+            let tracker_clone = Arc::clone(&self.match_tracker);
+            let retry_queue_clone = Arc::clone(&self.retry_queue);
+            let admin_config = self.config.admin.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    run_admin_server(tracker_clone, retry_queue_clone, admin_config).await
+                {
+                    error!("❌ Admin API stopped: {}", e);
+                }
+            });
+        }
+
+        // Pick up edits to game-engine.toml without dropping matches
+        if self.config.hot_reload.enabled {
+            let live_config_clone = Arc::clone(&self.live_config);
+            let tracker_clone = Arc::clone(&self.match_tracker);
+            let nostr_clone = Arc::clone(&self.nostr_client);
+            let watch_interval_seconds = self.config.hot_reload.watch_interval_seconds;
+            tokio::spawn(async move {
+                run_config_watcher(
+                    live_config_clone,
+                    tracker_clone,
+                    nostr_clone,
+                    watch_interval_seconds,
+                )
+                .await;
+            });
+        }
+
         info!("🎮 Game Engine Bot fully operational");
         info!(
             "📡 Listening for Nostr events on: {}",
-            self.config.nostr.relay_url
+            self.config.nostr.all_relay_urls().join(", ")
         );
         info!("🤖 Operating in pure state machine mode (no HTTP endpoints)");
 
@@ -123,19 +404,831 @@ impl GameEngineBot {
     }
 
     /// Process incoming player-driven match events from Nostr via state machine
-    async fn process_match_events(&self) {
+    async fn process_match_events(self: &Arc<Self>) {
         let mut receiver = self.match_event_receiver.lock().await;
+        let bot = Arc::clone(self);
+        let workers = MatchWorkers::new(
+            self.config.game.max_concurrent_matches as usize,
+            self.config.queues.match_event_capacity,
+            move |(event, started): (PlayerMatchEvent, std::time::Instant)| {
+                let bot = Arc::clone(&bot);
+                async move { bot.handle_match_event(event, started).await }
+            },
+        );
 
         info!("🎮 Started Nostr match event processing loop");
 
         while let Some(event) = receiver.recv().await {
             debug!("📨 Received Nostr match event: {:?}", event);
+            let started = std::time::Instant::now();
+
+            let match_id = event.match_event_id().to_string();
+            workers.dispatch(&match_id, (event, started)).await;
+        }
+    }
+
+    /// Validate one match event and apply it to the match's state machine
+    ///
+    /// Runs on the match's own worker, after every earlier event of the match.
+    async fn handle_match_event(&self, event: PlayerMatchEvent, started: std::time::Instant) {
+        match &event {
+            PlayerMatchEvent::TokenReveal(reveal)
+                if self.reject_spent_reveal(reveal).await
+                    || self.reject_unsigned_reveal(reveal).await
+                    || self.reject_mismatched_wager(reveal).await
+                    || self.reject_unescrowed_reveal(reveal).await =>
+            {
+                self.metrics.nostr_event_latency.observe(started.elapsed());
+                return;
+            }
+            PlayerMatchEvent::Seek(seek) => {
+                self.handle_seek(seek.clone()).await;
+                self.metrics.nostr_event_latency.observe(started.elapsed());
+                return;
+            }
+            _ => {}
+        }
+
+        // Public challenges the tracker takes on are offered to the matchmaker until accepted
+        let pooled_challenge = match &event {
+            PlayerMatchEvent::Challenge(challenge) if !challenge.private => Some(challenge.clone()),
+            _ => None,
+        };
+        let accepted_match = matches!(event, PlayerMatchEvent::Acceptance(_))
+            .then(|| event.match_event_id().to_string());
+
+        if let Err(e) = self.match_tracker.process_event(event).await {
+            error!(
+                "❌ Failed to process match event through state machine: {}",
+                e
+            );
+        } else if let Some(matchmaker) = &self.matchmaker {
+            if let Some(challenge) = pooled_challenge {
+                let rating = self.matchmaking_rating(&challenge.challenger_npub);
+                let now = chrono::Utc::now().timestamp() as u64;
+                let pairing = matchmaker
+                    .lock()
+                    .unwrap()
+                    .add_challenge(&challenge, rating, now);
+                self.publish_pairing(pairing).await;
+            } else if let Some(match_id) = accepted_match {
+                matchmaker.lock().unwrap().remove_challenge(&match_id);
+            }
+        }
+        self.metrics.nostr_event_latency.observe(started.elapsed());
+    }
+
+    /// Pair a seeking player with an open challenge, or leave them waiting in the pool
+    async fn handle_seek(&self, seek: MatchSeek) {
+        let Some(matchmaker) = &self.matchmaker else {
+            debug!(
+                "Ignoring seek from {}: matchmaking is disabled",
+                seek.player_npub
+            );
+            return;
+        };
+        if let Some(reputation) = &self.reputation {
+            if reputation.is_blacklisted(&seek.player_npub) {
+                warn!("⛔ Ignoring seek from blacklisted {}", seek.player_npub);
+                return;
+            }
+        }
+
+        let rating = self.matchmaking_rating(&seek.player_npub);
+        let now = chrono::Utc::now().timestamp() as u64;
+        let pairing = matchmaker.lock().unwrap().add_seek(seek, rating, now);
+        self.publish_pairing(pairing).await;
+    }
+
+    /// Elo the matchmaker compares, the initial rating when the player is not rated
+    fn matchmaking_rating(&self, npub: &str) -> f64 {
+        self.ranking
+            .as_ref()
+            .and_then(|ranking| ranking.rating(npub).ok())
+            .map_or(self.config.ranking.initial_rating, |player| player.rating)
+    }
+
+    async fn publish_pairing(&self, pairing: Option<Pairing>) {
+        let Some(pairing) = pairing else {
+            return;
+        };
+        if let Err(e) = self.nostr_client.publish_match_pairing(&pairing).await {
+            warn!(
+                "⚠️ Failed to publish pairing for challenge {}: {}",
+                pairing.match_event_id, e
+            );
+        }
+    }
+
+    /// Archived matches a player took part in, most recent first
+    pub fn get_match_history(
+        &self,
+        player_npub: &str,
+    ) -> Result<Vec<ArchivedMatch>, GameEngineError> {
+        self.match_archive.get_match_history(player_npub)
+    }
+
+    /// Archived summary of a finished match
+    pub fn get_match(&self, match_id: &str) -> Result<Option<ArchivedMatch>, GameEngineError> {
+        self.match_archive.get_match(match_id)
+    }
+
+    /// Signed proof bundle of a completed match that third parties can verify offline
+    ///
+    /// Available while the engine still tracks the match, for a few minutes after completion.
+    pub async fn export_proof_bundle(&self, match_id: &str) -> Result<String, GameEngineError> {
+        let state = self
+            .match_tracker
+            .get_match_state(match_id)
+            .await
+            .ok_or_else(|| GameEngineError::MatchNotFound(match_id.to_string()))?;
+        let keys = nostr::Keys::parse(&self.config.nostr.private_key)
+            .map_err(|e| GameEngineError::NostrError(format!("Invalid private key: {e}")))?;
+        let player_events = self.nostr_client.player_events(match_id)?;
+        let sealed = ProofBundle::from_state(&state, player_events)?.seal(&keys)?;
+
+        // Hand out only bundles the offline check accepts, e.g. not ones missing a player event
+        verify_bundle(&sealed, &keys.public_key().to_hex())?;
+        Ok(sealed)
+    }
+
+    /// Reference the loot payout from the match's archived summary
+    fn archive_loot(&self, match_id: &str, loot_quote: &str) {
+        if let Err(e) = self.match_archive.record_loot(match_id, loot_quote) {
+            warn!("⚠️ Failed to archive loot for match {}: {}", match_id, e);
+        }
+    }
+
+    /// Burn both players' revealed mana before any loot is minted for the match
+    async fn burn_wagered_mana(&self, match_id: &str) -> Result<(), GameEngineError> {
+        let Some(state) = self.match_tracker.get_match_state(match_id).await else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+
+        let token_secrets = state.revealed_mana_tokens();
+        if state.is_practice() || token_secrets.is_empty() {
+            debug!("No wagered mana to burn for match {}", match_id);
+            return Ok(());
+        }
+
+        self.cashu_client
+            .for_league(state.league_id())
+            .burn_mana(match_id, &token_secrets)
+            .await
+            .map(|_| ())
+    }
+
+    /// Have the mint hold a player's revealed wager until the match is settled
+    ///
+    /// Runs as soon as the reveal passed its checks, so neither player can spend
+    /// their wager while the other has yet to reveal. Only a successful escrow is
+    /// recorded, keyed by player, so the engine can tell its own hold apart from a
+    /// double spend and release it if the match ends without a payout.
+    async fn escrow_wagered_mana(&self, reveal: &TokenReveal) -> Result<(), GameEngineError> {
+        let Some(state) = self
+            .revealing_match(reveal)
+            .await
+            .filter(|state| !state.is_practice())
+        else {
+            return Ok(());
+        };
+        if !self.config.cashu.escrow_wagers || reveal.cashu_tokens.is_empty() {
+            return Ok(());
+        }
+
+        let ys = hash_secrets(&reveal.cashu_tokens)?;
+        self.cashu_client
+            .for_league(state.league_id())
+            .escrow_mana(&reveal.match_event_id, &reveal.cashu_tokens)
+            .await?;
+        self.payout_ledger.record_escrowed(
+            &reveal.match_event_id,
+            state.league_id(),
+            &reveal.player_npub,
+            ys,
+        )
+    }
+
+    /// Return the escrowed wagers of a match that ends without a payout
+    ///
+    /// Every invalidation, whether for cheating, a timeout or by the operator,
+    /// ends here; wagers already burned or refunded are left alone.
+    async fn release_escrow(&self, match_id: &str) -> Result<(), GameEngineError> {
+        let Some(record) = self.payout_ledger.record(match_id)? else {
+            return Ok(());
+        };
+        if record.wagers_settled || record.escrowed_ys.is_empty() {
+            return Ok(());
+        }
+
+        let ys_by_player = record.escrowed_ys.into_iter().collect();
+        self.cashu_client
+            .for_league(record.league_id)
+            .release_escrow(match_id, ys_by_player)
+            .await?;
+        self.payout_ledger.record_wagers_settled(match_id)?;
+        info!("🔓 Released escrowed wagers of match {}", match_id);
+        Ok(())
+    }
+
+    /// Settle a wagered match that ended without a winner per the configured draw policy
+    async fn settle_draw(
+        &self,
+        match_id: &str,
+        player_npubs: &[String],
+    ) -> Result<(), GameEngineError> {
+        let policy = self.live_config.game().draw_policy;
+        if policy == DrawPolicy::SuddenDeath {
+            if self.match_tracker.order_sudden_death(match_id).await? {
+                info!("⚡ Match {} drawn, playing a sudden-death game", match_id);
+                return Ok(());
+            }
+            info!(
+                "🤝 Match {} still drawn after sudden death, refunding wagers",
+                match_id
+            );
+        }
+
+        // Drawn matches are settled without a match fee
+        self.payout_ledger.record_validated(match_id, None, 0)?;
+        let wagers_settled = self
+            .payout_ledger
+            .record(match_id)?
+            .is_some_and(|record| record.wagers_settled);
+
+        let award = if policy == DrawPolicy::SplitLoot {
+            if !wagers_settled {
+                self.burn_wagered_mana(match_id).await?;
+                self.payout_ledger.record_wagers_settled(match_id)?;
+            }
+            let shares = self.pay_split_loot(match_id, player_npubs).await?;
+            self.nostr_client
+                .publish_split_loot_awards(match_id, &shares)
+                .await?
+        } else {
+            if !wagers_settled {
+                self.refund_wagered_mana(match_id).await?;
+                self.payout_ledger.record_wagers_settled(match_id)?;
+            }
+            info!("🤝 Match {} was a draw, wagers refunded", match_id);
+            let award = self
+                .nostr_client
+                .publish_loot_award(match_id, None, None, 0, 0, None)
+                .await?;
+            Some(award)
+        };
+        if let Some(award) = award {
+            self.match_tracker.complete_match(match_id, award).await?;
+        }
+
+        self.rate_match(match_id, None).await;
+        self.metrics.record_match_validated();
+        Ok(())
+    }
+
+    /// Mint each drawn player's half of the loot, recording every share once paid
+    ///
+    /// A retry after a partial payout only mints the shares still owed.
+    async fn pay_split_loot(
+        &self,
+        match_id: &str,
+        player_npubs: &[String],
+    ) -> Result<Vec<(String, LootShare)>, GameEngineError> {
+        let mut paid = self
+            .payout_ledger
+            .record(match_id)?
+            .map(|record| record.split_shares)
+            .unwrap_or_default();
+        let share_amount = self.live_config.game().loot_reward_per_match / 2;
+
+        let mut shares = Vec::new();
+        for player in player_npubs {
+            if let Some(share) = paid.remove(player) {
+                debug!(
+                    "Split loot for drawn match {} already paid to {}",
+                    match_id, player
+                );
+                shares.push((player.clone(), share));
+                continue;
+            }
+            let loot_result = self
+                .mint_for_match(match_id)
+                .await
+                .create_loot_token(player, share_amount, match_id)
+                .await?;
+            let share = LootShare {
+                quote: loot_result.quote,
+                token: loot_result.token,
+                amount: loot_result.amount,
+            };
+            self.payout_ledger
+                .record_share_paid(match_id, player, share.clone())?;
+            self.archive_loot(match_id, &share.quote);
+            self.metrics.record_loot_distributed(share.amount);
+            info!(
+                "🤝 Split loot for drawn match {} paid to {}",
+                match_id, player
+            );
+            shares.push((player.clone(), share));
+        }
+        Ok(shares)
+    }
+
+    /// Return both players' revealed mana through the mint instead of burning it
+    async fn refund_wagered_mana(&self, match_id: &str) -> Result<(), GameEngineError> {
+        let Some(state) = self.match_tracker.get_match_state(match_id).await else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+
+        let secrets_by_player = state.revealed_mana_tokens_by_player();
+        if state.is_practice()
+            || secrets_by_player
+                .iter()
+                .all(|(_, secrets)| secrets.is_empty())
+        {
+            debug!("No wagered mana to refund for match {}", match_id);
+            return Ok(());
+        }
+
+        self.cashu_client
+            .for_league(state.league_id())
+            .refund_wagers(match_id, &secrets_by_player)
+            .await
+            .map(|_| ())
+    }
+
+    /// Mint serving the league the match is played in
+    async fn mint_for_match(&self, match_id: &str) -> &CashuClient {
+        let league_id = self
+            .match_tracker
+            .get_match_state(match_id)
+            .await
+            .and_then(|state| state.league_id());
+        self.cashu_client.for_league(league_id)
+    }
+
+    /// Configured share of a decided match's wager withheld as the match fee
+    async fn match_fee(&self, match_id: &str) -> u64 {
+        let wager_amount = self
+            .match_tracker
+            .get_match_state(match_id)
+            .await
+            .map_or(0, |state| state.wager_amount());
+        FeeSplit::for_wager(wager_amount, self.live_config.game().match_fee_percent).match_fee
+    }
+
+    /// Pay a decided match's fee to the operator wallet
+    ///
+    /// Returns the fee and, when paid as locked loot, its token for the loot award.
+    async fn collect_match_fee(
+        &self,
+        match_id: &str,
+        fee: u64,
+    ) -> Result<CollectedFee, GameEngineError> {
+        let game_config = self.live_config.game();
+        let Some(recipient) = game_config.fee_recipient.as_ref().filter(|_| fee > 0) else {
+            return Ok(CollectedFee {
+                amount: fee,
+                token: None,
+            });
+        };
+
+        let payout = self
+            .mint_for_match(match_id)
+            .await
+            .pay_match_fee(recipient, fee, match_id)
+            .await?;
+        let token = match payout {
+            FeePayout::Token(fee_result) => {
+                info!(
+                    "💸 Match fee of {} for {} locked to the operator: {}",
+                    fee, match_id, fee_result.quote
+                );
+                Some(fee_result.token)
+            }
+            FeePayout::Lightning(_) => {
+                info!(
+                    "💸 Match fee of {} for {} paid to the operator over Lightning",
+                    fee, match_id
+                );
+                None
+            }
+        };
+        Ok(CollectedFee { amount: fee, token })
+    }
+
+    /// Pay a match fee that failed after the winner's award went out
+    ///
+    /// The ledger keeps the fee once paid, so a fee is never taken twice.
+    async fn retry_match_fee(&self, match_id: &str, fee: u64) -> Result<(), GameEngineError> {
+        let collected = self
+            .payout_ledger
+            .record(match_id)?
+            .is_some_and(|record| record.match_fee.is_some());
+        if collected {
+            return Ok(());
+        }
+
+        let fee = self.collect_match_fee(match_id, fee).await?;
+        self.payout_ledger
+            .record_fee(match_id, fee.amount, fee.token)?;
+        info!("💸 Match fee for {} collected on retry", match_id);
+        Ok(())
+    }
+
+    /// Invalidate the match when the revealed mana is not worth the advertised wager
+    ///
+    /// Returns true if the reveal was rejected or held and must not reach the state machine.
+    async fn reject_mismatched_wager(&self, reveal: &TokenReveal) -> bool {
+        let Some(state) = self
+            .revealing_match(reveal)
+            .await
+            .filter(|state| !state.is_practice())
+        else {
+            return false;
+        };
+        let wager_amount = state.wager_amount();
+
+        let committed = match self
+            .cashu_client
+            .for_league(state.league_id())
+            .committed_amount(&reveal.match_event_id, &reveal.cashu_tokens)
+            .await
+        {
+            Ok(committed) => committed,
+            Err(e) => {
+                self.hold_or_drop_reveal(reveal, "check mana token amounts", &e);
+                return true;
+            }
+        };
+
+        if committed == wager_amount {
+            return false;
+        }
+
+        warn!(
+            "🚫 {} revealed {} mana against an advertised wager of {} in match {}",
+            reveal.player_npub, committed, wager_amount, reveal.match_event_id
+        );
+
+        let invalidation = Invalidation {
+            reason: format!(
+                "Revealed mana is worth {committed}, not the advertised wager of {wager_amount}"
+            ),
+            offending_npub: Some(reveal.player_npub.clone()),
+            evidence_hashes: hash_secrets(&reveal.cashu_tokens).unwrap_or_default(),
+        };
+        if let Err(e) = self
+            .match_tracker
+            .invalidate_match(&reveal.match_event_id, invalidation)
+            .await
+        {
+            error!("❌ Failed to invalidate mismatched wager match: {}", e);
+        }
+        true
+    }
+
+    /// Invalidate the match when a revealed token secret was never signed by the mint
+    ///
+    /// Returns true if the reveal was rejected or held and must not reach the state machine.
+    async fn reject_unsigned_reveal(&self, reveal: &TokenReveal) -> bool {
+        let Some(state) = self
+            .revealing_match(reveal)
+            .await
+            .filter(|state| !state.is_practice())
+        else {
+            return false;
+        };
+
+        let unsigned = match self
+            .cashu_client
+            .for_league(state.league_id())
+            .unsigned_secrets(&reveal.match_event_id, &reveal.cashu_tokens)
+            .await
+        {
+            Ok(unsigned) => unsigned,
+            Err(e) => {
+                self.hold_or_drop_reveal(reveal, "check mana token signatures", &e);
+                return true;
+            }
+        };
+
+        if unsigned.is_empty() {
+            return false;
+        }
+
+        warn!(
+            "🚫 {} revealed {} mana tokens the mint never signed in match {}",
+            reveal.player_npub,
+            unsigned.len(),
+            reveal.match_event_id
+        );
+
+        let invalidation = Invalidation {
+            reason: "Revealed mana tokens were not signed by the mint".to_string(),
+            offending_npub: Some(reveal.player_npub.clone()),
+            evidence_hashes: unsigned,
+        };
+        if let Err(e) = self
+            .match_tracker
+            .invalidate_match(&reveal.match_event_id, invalidation)
+            .await
+        {
+            error!("❌ Failed to invalidate unsigned reveal match: {}", e);
+        }
+        true
+    }
+
+    /// Keep a reveal out of the match until the mint holds its wager in escrow
+    ///
+    /// A mint that is unreachable gets the reveal held for another try; one that
+    /// refuses the escrow outright gets it dropped, so combat never starts on a
+    /// wager the player could still spend.
+    ///
+    /// Returns true if the reveal was rejected or held and must not reach the state machine.
+    async fn reject_unescrowed_reveal(&self, reveal: &TokenReveal) -> bool {
+        let Err(e) = self.escrow_wagered_mana(reveal).await else {
+            return false;
+        };
+        self.hold_or_drop_reveal(reveal, "escrow the wager", &e);
+        true
+    }
+
+    /// Invalidate the match when a revealed mana token was already spent at the mint
+    ///
+    /// Returns true if the reveal was rejected or held and must not reach the state machine.
+    async fn reject_spent_reveal(&self, reveal: &TokenReveal) -> bool {
+        let Some(state) = self.revealing_match(reveal).await else {
+            return false;
+        };
+
+        let mut spent = match self
+            .cashu_client
+            .for_league(state.league_id())
+            .find_spent_secrets(&reveal.cashu_tokens)
+            .await
+        {
+            Ok(spent) => spent,
+            Err(e) => {
+                warn!(
+                    "⚠️ Could not check mana token states for match {}: {}",
+                    reveal.match_event_id, e
+                );
+                self.hold_reveal(reveal);
+                return true;
+            }
+        };
+
+        // The engine's own escrow of this player's wager leaves those proofs pending
+        let escrowed = match self.payout_ledger.record(&reveal.match_event_id) {
+            Ok(record) => record
+                .and_then(|record| record.escrowed_ys.get(&reveal.player_npub).cloned())
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    "⚠️ Could not read the escrow of match {}: {}",
+                    reveal.match_event_id, e
+                );
+                self.hold_reveal(reveal);
+                return true;
+            }
+        };
+        spent.retain(|y| !escrowed.contains(y));
+
+        if spent.is_empty() {
+            return false;
+        }
+
+        warn!(
+            "🚫 {} revealed {} already-spent mana tokens in match {}",
+            reveal.player_npub,
+            spent.len(),
+            reveal.match_event_id
+        );
+
+        let invalidation = Invalidation {
+            reason: "Revealed mana tokens were already spent".to_string(),
+            offending_npub: Some(reveal.player_npub.clone()),
+            evidence_hashes: spent,
+        };
+        if let Err(e) = self
+            .match_tracker
+            .invalidate_match(&reveal.match_event_id, invalidation)
+            .await
+        {
+            error!("❌ Failed to invalidate double-spend match: {}", e);
+        }
+        true
+    }
+
+    /// Match a reveal is checked against, if it comes from one of the match's two
+    /// players while the match is waiting on reveals
+    ///
+    /// A reveal from anyone else never reaches the match, so it must not void it either.
+    async fn revealing_match(&self, reveal: &TokenReveal) -> Option<MatchState> {
+        self.match_tracker
+            .get_match_state(&reveal.match_event_id)
+            .await
+            .filter(|state| state.awaits_reveal_from(&reveal.player_npub))
+    }
+
+    /// Hold a reveal the mint could not check for another try, or drop it when the mint
+    /// refused the check outright, e.g. with a 404 from a mint not serving the route
+    ///
+    /// A refusal is no breaker failure, so a held reveal would be re-queued until the match timed out.
+    fn hold_or_drop_reveal(&self, reveal: &TokenReveal, check: &str, e: &GameEngineError) {
+        if e.is_retryable() {
+            warn!(
+                "⚠️ Could not {} for match {}: {}",
+                check, reveal.match_event_id, e
+            );
+            self.hold_reveal(reveal);
+        } else {
+            warn!(
+                "🚫 Mint refused to {} for match {}, dropping the reveal of {}: {}",
+                check, reveal.match_event_id, reveal.player_npub, e
+            );
+        }
+    }
+
+    /// Keep a reveal the mint could not check or escrow until the mint answers again
+    ///
+    /// At most `queues.held_reveal_capacity` reveals are held; once full, further
+    /// reveals are dropped and the players have to publish them again.
+    fn hold_reveal(&self, reveal: &TokenReveal) {
+        let mut held = self.held_reveals.lock().unwrap();
+        // A republished reveal replaces the one already held for the player
+        held.retain(|held| {
+            held.match_event_id != reveal.match_event_id || held.player_npub != reveal.player_npub
+        });
+        if held.len() >= self.config.queues.held_reveal_capacity {
+            warn!(
+                "⚠️ {} reveals already held, dropping reveal of {} for match {}",
+                held.len(),
+                reveal.player_npub,
+                reveal.match_event_id
+            );
+            return;
+        }
+
+        info!(
+            "⏸️ Holding reveal of {} for match {} until the mint answers again",
+            reveal.player_npub, reveal.match_event_id
+        );
+        held.push(reveal.clone());
+    }
+
+    /// Send held reveals back to their match once their mint is available
+    ///
+    /// A reveal goes through the match event queue like a fresh one, so its match's
+    /// worker handles it after, never alongside, the events already queued for it.
+    async fn recheck_held_reveals(&self) {
+        let held = std::mem::take(&mut *self.held_reveals.lock().unwrap());
+        for reveal in held {
+            if !self
+                .mint_for_match(&reveal.match_event_id)
+                .await
+                .is_available()
+            {
+                self.held_reveals.lock().unwrap().push(reveal);
+                continue;
+            }
+            // A full queue leaves the reveal held until the next pass
+            if let Err(e) = self
+                .match_event_sender
+                .try_send(PlayerMatchEvent::TokenReveal(reveal))
+            {
+                if let PlayerMatchEvent::TokenReveal(reveal) = e.into_inner() {
+                    self.held_reveals.lock().unwrap().push(reveal);
+                }
+            }
+        }
+    }
+
+    /// Count a proven cheat against the offender before the invalidation is announced
+    ///
+    /// Returns the offender's updated reputation, or None when no player is at fault.
+    fn record_cheat(
+        &self,
+        match_id: &str,
+        reason: &str,
+        offending_npub: &Option<String>,
+        evidence_hashes: &[String],
+    ) -> Result<Option<PlayerReputation>, GameEngineError> {
+        let (Some(reputation), Some(npub)) = (&self.reputation, offending_npub) else {
+            return Ok(None);
+        };
+        reputation
+            .record_cheat(npub, match_id, reason, evidence_hashes)
+            .map(Some)
+    }
+
+    /// Share the cheat with other engines; the match is already invalidated, so failures are only logged
+    async fn publish_cheat_evidence(
+        &self,
+        match_id: &str,
+        reason: &str,
+        evidence_hashes: Vec<String>,
+        offender: &PlayerReputation,
+    ) {
+        if let Err(e) = self
+            .nostr_client
+            .publish_cheat_evidence(match_id, reason, evidence_hashes, offender)
+            .await
+        {
+            warn!(
+                "⚠️ Failed to publish cheat evidence against {}: {}",
+                offender.npub, e
+            );
+        }
+    }
+
+    /// Fold a validated wagered result into both players' ratings; a None winner is a draw
+    async fn rate_match(&self, match_id: &str, winner_npub: Option<&str>) {
+        let Some(ranking) = &self.ranking else {
+            return;
+        };
+        let Some(state) = self.match_tracker.get_match_state(match_id).await else {
+            return;
+        };
+        if state.is_practice() {
+            return;
+        }
+
+        let players = state.players();
+        let [player1, player2] = players.as_slice() else {
+            return;
+        };
+        if let Err(e) = ranking.record_result(match_id, player1, player2, winner_npub) {
+            warn!("⚠️ Failed to rate match {}: {}", match_id, e);
+        }
+    }
+
+    /// Hand an action that failed transiently to the retry queue instead of dropping it
+    fn queue_for_retry(&self, action: Option<TrackedAction>, error: &GameEngineError) {
+        let Some(action) = action.filter(|_| self.config.retry.enabled) else {
+            return;
+        };
+        if !error.is_retryable() {
+            warn!(
+                "🚫 Not retrying action for match {}: {}",
+                action.match_id, error
+            );
+            return;
+        }
+        if let Err(e) = self.retry_queue.enqueue(&action, &error.to_string()) {
+            error!("❌ Failed to queue action for retry, it is lost: {}", e);
+        }
+    }
+
+    /// Periodically re-run queued actions until they succeed or become dead letters
+    async fn process_retry_queue(&self) {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            self.config.retry.poll_interval_seconds.max(1),
+        ));
+
+        info!("🔁 Started action retry loop");
+
+        loop {
+            interval.tick().await;
+            self.recheck_held_reveals().await;
+
+            let due = match self.retry_queue.due(chrono::Utc::now()) {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("❌ Failed to read retry queue: {}", e);
+                    continue;
+                }
+            };
+
+            for pending in due {
+                // Payouts wait while their mint's circuit is open, without using up an attempt
+                if is_mint_payout(&pending.action)
+                    && !self
+                        .mint_for_match(&pending.action.match_id)
+                        .await
+                        .is_available()
+                {
+                    debug!(
+                        "⏸️ Deferring payout for match {} until its mint is available",
+                        pending.action.match_id
+                    );
+                    continue;
+                }
 
-            if let Err(e) = self.match_tracker.process_event(event).await {
-                error!(
-                    "❌ Failed to process match event through state machine: {}",
-                    e
+                info!(
+                    "🔁 Retrying action for match {} (attempt {})",
+                    pending.action.match_id,
+                    pending.attempts + 1
                 );
+
+                let outcome = match self.handle_action(pending.action.clone()).await {
+                    Ok(()) => self.retry_queue.complete(pending.id),
+                    Err(e) => self.retry_queue.reschedule(&pending, &e).map(|_| ()),
+                };
+                if let Err(e) = outcome {
+                    error!("❌ Failed to update retry queue: {}", e);
+                }
             }
         }
     }
@@ -146,49 +1239,410 @@ impl GameEngineBot {
 
         info!("🎰 Started state machine action processing loop");
 
-        while let Some(action) = receiver.recv().await {
-            debug!("⚡ Processing state machine action: {:?}", action);
+        let batch_config = self.config.loot_batch.clone();
+        // Actions that arrived while a loot batch was filling, run once it is paid
+        let mut deferred = VecDeque::new();
+        loop {
+            let action = match deferred.pop_front() {
+                Some(action) => action,
+                None => match receiver.recv().await {
+                    Some(action) => action,
+                    None => break,
+                },
+            };
+
+            if !batch_config.enabled || !is_winner_payout(&action) {
+                self.run_action(action).await;
+                continue;
+            }
+
+            let deadline = tokio::time::Instant::now()
+                + tokio::time::Duration::from_millis(batch_config.window_ms);
+            let mut batch = vec![action];
+            while batch.len() < batch_config.max_batch_size {
+                match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(Some(next)) if is_winner_payout(&next) => batch.push(next),
+                    Ok(Some(next)) => deferred.push_back(next),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+            self.distribute_loot_batch(batch).await;
+        }
+    }
+
+    /// Run one state machine action, queueing it for retry if it fails transiently
+    async fn run_action(&self, action: TrackedAction) {
+        debug!("⚡ Processing state machine action: {:?}", action);
+
+        let retry = ActionRetryQueue::is_retryable(&action.action).then(|| action.clone());
+        if let Err(e) = self.handle_action(action).await {
+            error!("❌ Failed to process state machine action: {}", e);
+            self.queue_for_retry(retry, &e);
+        }
+    }
+
+    /// Pay several winners at once, with one mint round trip per mint
+    ///
+    /// Each payout succeeds or is queued for retry on its own.
+    async fn distribute_loot_batch(&self, actions: Vec<TrackedAction>) {
+        let loot_reward = self.live_config.game().loot_reward_per_match;
+        let mut by_mint: HashMap<&str, MintBatch> = HashMap::new();
+
+        for action in actions {
+            let award = match &action.action {
+                GameEngineAction::DistributeLoot {
+                    match_id,
+                    winner_npub: Some(winner),
+                } => LootAward {
+                    winner_npub: winner.clone(),
+                    amount: loot_reward,
+                    match_id: match_id.clone(),
+                },
+                _ => {
+                    self.run_action(action).await;
+                    continue;
+                }
+            };
+            match self.prepare_loot(&award.match_id, &award.winner_npub).await {
+                Ok(None) => {}
+                Ok(Some(token)) => {
+                    let outcome = self
+                        .announce_loot(&award.match_id, award.winner_npub, token)
+                        .await;
+                    if let Err(e) = outcome {
+                        error!(
+                            "❌ Failed to republish loot for match {}: {}",
+                            award.match_id, e
+                        );
+                        self.queue_for_retry(Some(action), &e);
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    error!(
+                        "❌ Failed to prepare loot for match {}: {}",
+                        award.match_id, e
+                    );
+                    self.queue_for_retry(Some(action), &e);
+                    continue;
+                }
+            }
+            let mint = self.mint_for_match(&award.match_id).await;
+            by_mint
+                .entry(mint.mint_url())
+                .or_insert_with(|| (mint, Vec::new()))
+                .1
+                .push((action, award));
+        }
+
+        for (mint, batch) in by_mint.into_values() {
+            let (actions, awards): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+            info!(
+                "🏆 Minting loot for {} winners in one batch from {}",
+                awards.len(),
+                mint.mint_url()
+            );
+            let results = mint.create_loot_tokens(&awards).await;
+            for (action, result) in actions.into_iter().zip(results) {
+                let outcome = match result {
+                    Ok(loot_result) => self.publish_loot(loot_result).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = outcome {
+                    error!("❌ Failed to distribute batched loot: {}", e);
+                    self.queue_for_retry(Some(action), &e);
+                }
+            }
+        }
+    }
+
+    /// Record the validated result and burn the wagers before the winner is paid
+    ///
+    /// Returns the loot token when an earlier attempt already minted it, so a
+    /// retried payout neither burns nor mints a second time.
+    async fn prepare_loot(
+        &self,
+        match_id: &str,
+        winner_npub: &str,
+    ) -> Result<Option<String>, GameEngineError> {
+        let record = self.payout_ledger.record(match_id)?;
+        if let Some(token) = record.as_ref().and_then(|record| record.loot_token.clone()) {
+            info!(
+                "🔁 Loot for match {} already minted, republishing the award",
+                match_id
+            );
+            return Ok(Some(token));
+        }
+
+        self.payout_ledger.record_validated(
+            match_id,
+            Some(winner_npub),
+            self.live_config.game().loot_reward_per_match,
+        )?;
+        if !record.is_some_and(|record| record.wagers_settled) {
+            self.burn_wagered_mana(match_id).await?;
+            self.payout_ledger.record_wagers_settled(match_id)?;
+        }
+        Ok(None)
+    }
+
+    /// Settle the books for minted loot and publish the loot award
+    async fn publish_loot(&self, loot_result: LootTokenResult) -> Result<(), GameEngineError> {
+        let LootTokenResult {
+            quote,
+            amount,
+            winner_npub,
+            match_id,
+            token,
+        } = loot_result;
+        self.payout_ledger.record_paid(&match_id, &quote, &token)?;
+        self.archive_loot(&match_id, &quote);
+        self.metrics.record_loot_distributed(amount);
+        info!("💰 Loot token created for {}: {}", winner_npub, quote);
 
-            if let Err(e) = self.handle_action(action).await {
-                error!("❌ Failed to process state machine action: {}", e);
+        self.rate_match(&match_id, Some(&winner_npub)).await;
+        self.announce_loot(&match_id, winner_npub, token).await
+    }
+
+    /// Take the match fee once and publish the winner's loot award
+    ///
+    /// A retried payout whose loot was already minted resumes here. A fee the mint
+    /// fails to pay is queued for retry rather than holding back the award.
+    async fn announce_loot(
+        &self,
+        match_id: &str,
+        winner_npub: String,
+        token: String,
+    ) -> Result<(), GameEngineError> {
+        let record = self.payout_ledger.record(match_id)?;
+        let loot_amount = record.as_ref().map_or(0, |record| record.amount);
+        let fee = match record.and_then(|record| record.match_fee) {
+            Some(fee) => fee,
+            None => {
+                let amount = self.match_fee(match_id).await;
+                match self.collect_match_fee(match_id, amount).await {
+                    Ok(fee) => {
+                        self.payout_ledger
+                            .record_fee(match_id, fee.amount, fee.token.clone())?;
+                        fee
+                    }
+                    Err(e) => {
+                        // The winner is paid, so the award goes out and the fee follows later
+                        warn!("⚠️ Failed to pay match fee for {}: {}", match_id, e);
+                        let retry = TrackedAction {
+                            match_id: match_id.to_string(),
+                            action: GameEngineAction::CollectMatchFee {
+                                match_id: match_id.to_string(),
+                                amount,
+                            },
+                            triggered_at: chrono::Utc::now(),
+                        };
+                        self.queue_for_retry(Some(retry), &e);
+                        CollectedFee {
+                            amount,
+                            token: None,
+                        }
+                    }
+                }
             }
+        };
+        let award = self
+            .nostr_client
+            .publish_loot_award(
+                match_id,
+                Some(winner_npub),
+                Some(token),
+                loot_amount,
+                fee.amount,
+                fee.token,
+            )
+            .await?;
+        self.match_tracker.complete_match(match_id, award).await?;
+        self.metrics.record_match_validated();
+        Ok(())
+    }
+
+    /// Re-verify a submitted result by replaying the match, then queue its payout
+    ///
+    /// The replay trusts nothing the players submitted beyond their committed reveals
+    /// and moves. A result it contradicts voids the match and blames the submitter; a
+    /// confirmed one pays the winner or settles the draw.
+    async fn validate_complete_match(&self, match_id: &str) -> Result<(), GameEngineError> {
+        let Some(state) = self.match_tracker.get_match_state(match_id).await else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+        // Practice results complete on submission and pay nothing
+        let MatchState::AwaitingValidation {
+            match_data, result, ..
+        } = &state
+        else {
+            debug!(
+                "No payout to validate for match {} in {}",
+                match_id,
+                state.phase_name()
+            );
+            return Ok(());
+        };
+
+        let replayed_winner = match replay_match(match_data) {
+            Ok((replayed, _)) => replayed.format_winner(),
+            Err(e) => {
+                warn!("🚨 Match {} does not replay: {}", match_id, e);
+                let invalidation = Invalidation::new(format!("Match does not replay: {e}"));
+                return self
+                    .match_tracker
+                    .invalidate_match(match_id, invalidation)
+                    .await;
+            }
+        };
+        if result.calculated_winner != replayed_winner {
+            let reason = format!(
+                "Submitted winner {:?} contradicts the replayed games (winner {:?})",
+                result.calculated_winner, replayed_winner
+            );
+            warn!("🚨 {}", reason);
+            let invalidation = Invalidation {
+                reason,
+                offending_npub: Some(result.player_npub.clone()),
+                evidence_hashes: Vec::new(),
+            };
+            return self
+                .match_tracker
+                .invalidate_match(match_id, invalidation)
+                .await;
         }
+
+        info!(
+            "✅ Replay confirms the result of match {}, winner: {:?}",
+            match_id, replayed_winner
+        );
+        let payout = match replayed_winner {
+            Some(winner) => GameEngineAction::DistributeLoot {
+                match_id: match_id.to_string(),
+                winner_npub: Some(winner),
+            },
+            None => GameEngineAction::RefundWagers {
+                match_id: match_id.to_string(),
+                player_npubs: state.players(),
+            },
+        };
+        self.match_tracker.queue_engine_action(match_id, payout);
+        Ok(())
     }
 
     /// Handle actions generated by the state machine (like loot distribution)
     async fn handle_action(&self, action: TrackedAction) -> Result<(), GameEngineError> {
         match action.action {
-            GameEngineAction::DistributeLoot { match_id, winner_npub } => {
-                if let Some(winner) = winner_npub {
-                    info!(
-                        "🏆 Distributing loot for match {} to winner {}",
-                        match_id, winner
-                    );
-
-                    // Create loot token for the winner
-                    let loot_result = self
-                        .cashu_client
-                        .create_loot_token(
-                            &winner,
-                            self.config.game.loot_reward_per_match,
-                            &match_id,
-                        )
-                        .await?;
-
-                    info!(
-                        "💰 Loot token created for {}: {}",
-                        winner, loot_result.quote
-                    );
-                } else {
-                    warn!("🤷 No winner determined for match {}", match_id);
+            GameEngineAction::DistributeLoot {
+                match_id,
+                winner_npub: None,
+            } => {
+                let players = self
+                    .match_tracker
+                    .get_match_state(&match_id)
+                    .await
+                    .map(|state| state.players())
+                    .unwrap_or_default();
+                self.settle_draw(&match_id, &players).await?;
+            }
+            GameEngineAction::RefundWagers {
+                match_id,
+                player_npubs,
+            } => {
+                self.settle_draw(&match_id, &player_npubs).await?;
+            }
+            GameEngineAction::DistributeLoot {
+                match_id,
+                winner_npub: Some(winner),
+            } => {
+                if let Some(token) = self.prepare_loot(&match_id, &winner).await? {
+                    return self.announce_loot(&match_id, winner, token).await;
                 }
+                info!(
+                    "🏆 Distributing loot for match {} to winner {}",
+                    match_id, winner
+                );
+
+                // Mint a loot token locked to the winner
+                let loot_result = self
+                    .mint_for_match(&match_id)
+                    .await
+                    .create_loot_token(
+                        &winner,
+                        self.live_config.game().loot_reward_per_match,
+                        &match_id,
+                    )
+                    .await?;
+                self.publish_loot(loot_result).await?;
+            }
+            GameEngineAction::CollectMatchFee { match_id, amount } => {
+                self.retry_match_fee(&match_id, amount).await?;
             }
-            GameEngineAction::InvalidateMatch { match_id, reason } => {
+            GameEngineAction::GenerateArmies { .. } => {
+                self.metrics.record_match_started();
+            }
+            GameEngineAction::RecordPracticeResult {
+                match_id,
+                winner_npub,
+            } => {
+                info!(
+                    "📝 Practice match {} recorded (ratings off), winner: {:?}",
+                    match_id, winner_npub
+                );
+                self.metrics.record_match_validated();
+            }
+            GameEngineAction::InvalidateMatch {
+                match_id,
+                reason,
+                offending_npub,
+                evidence_hashes,
+            } => {
                 warn!(
                     "❌ Invalidating match {}: {}",
                     match_id, reason
                 );
-                // TODO: Publish match invalidation event to Nostr when needed
+                self.release_escrow(&match_id).await?;
+                let offender =
+                    self.record_cheat(&match_id, &reason, &offending_npub, &evidence_hashes)?;
+                let cheating = offending_npub.is_some();
+                self.nostr_client
+                    .publish_match_invalidation(
+                        &match_id,
+                        &reason,
+                        offending_npub,
+                        evidence_hashes.clone(),
+                    )
+                    .await?;
+                if cheating {
+                    self.metrics.record_cheating_detected();
+                }
+                if let Some(offender) = offender {
+                    self.publish_cheat_evidence(&match_id, &reason, evidence_hashes, &offender)
+                        .await;
+                }
+            }
+            GameEngineAction::CancelChallenge {
+                match_id,
+                challenger_npub,
+                reason,
+                expired_at,
+            } => {
+                self.nostr_client
+                    .publish_challenge_cancelled(&match_id, &challenger_npub, &reason, expired_at)
+                    .await?;
+            }
+            GameEngineAction::ValidateMatchResult { match_id } => {
+                self.validate_complete_match(&match_id).await?;
+            }
+            GameEngineAction::ExecuteCombatRound { match_id, round } => {
+                let combat = self
+                    .match_tracker
+                    .get_match_state(&match_id)
+                    .await
+                    .and_then(|state| state.round_combat(round));
+                self.nostr_client
+                    .publish_round_summary(&match_id, round, combat)
+                    .await?;
             }
             _ => {
                 debug!("🔧 Handling other game engine action: {:?}", action.action);
@@ -198,4 +1652,25 @@ impl GameEngineBot {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Whether the action pays a decided match's winner, the only payouts that are batched
+fn is_winner_payout(action: &TrackedAction) -> bool {
+    matches!(
+        action.action,
+        GameEngineAction::DistributeLoot {
+            winner_npub: Some(_),
+            ..
+        }
+    )
+}
+
+/// Whether the action pays out through a mint, which defers it while the mint is down
+fn is_mint_payout(action: &TrackedAction) -> bool {
+    matches!(
+        action.action,
+        GameEngineAction::DistributeLoot { .. }
+            | GameEngineAction::RefundWagers { .. }
+            | GameEngineAction::CollectMatchFee { .. }
+    )
+}
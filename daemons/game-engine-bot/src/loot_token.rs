@@ -0,0 +1,300 @@
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use nostr::secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use nostr::util::hex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::errors::GameEngineError;
+
+/// NUT-00 domain separator for hashing secrets onto the curve
+const DOMAIN_SEPARATOR: &[u8] = b"Secp256k1_HashToCurve_Cashu_";
+
+/// Blinded output sent to the mint (NUT-00)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlindedMessage {
+    pub amount: u64,
+    pub id: String,
+    #[serde(rename = "B_")]
+    pub blinded_secret: String,
+}
+
+/// Mint signature over a blinded output (NUT-00)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlindSignature {
+    pub amount: u64,
+    pub id: String,
+    #[serde(rename = "C_")]
+    pub blinded_signature: String,
+}
+
+/// Spendable ecash proof (NUT-00)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Proof {
+    pub amount: u64,
+    pub id: String,
+    pub secret: String,
+    #[serde(rename = "C")]
+    pub signature: String,
+}
+
+/// Active keyset advertised by the mint (NUT-01)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintKeyset {
+    pub id: String,
+    pub unit: String,
+    pub keys: HashMap<String, String>, // amount -> mint pubkey
+}
+
+/// Blinded output together with what the engine needs to unblind its signature
+#[derive(Debug, Clone)]
+pub struct PendingOutput {
+    pub message: BlindedMessage,
+    pub secret: String,
+    blinding_factor: SecretKey,
+}
+
+/// Map a secret onto a secp256k1 point (NUT-00 hash_to_curve)
+pub fn hash_to_curve(message: &[u8]) -> Result<PublicKey, GameEngineError> {
+    let msg_hash = Sha256::new()
+        .chain_update(DOMAIN_SEPARATOR)
+        .chain_update(message)
+        .finalize();
+
+    for counter in 0..=u16::MAX as u32 {
+        let hash = Sha256::new()
+            .chain_update(msg_hash)
+            .chain_update(counter.to_le_bytes())
+            .finalize();
+
+        let mut candidate = [0x02; 33];
+        candidate[1..].copy_from_slice(&hash);
+        if let Ok(point) = PublicKey::from_slice(&candidate) {
+            return Ok(point);
+        }
+    }
+
+    Err(GameEngineError::CashuError(
+        "No valid curve point found for secret".to_string(),
+    ))
+}
+
+/// Split an amount into the power-of-two denominations mint keysets sign
+pub fn split_amount(amount: u64) -> Vec<u64> {
+    (0..u64::BITS)
+        .map(|bit| 1u64 << bit)
+        .filter(|denomination| amount & denomination != 0)
+        .collect()
+}
+
+/// Random hex secret for proofs the engine holds itself
+pub fn random_secret() -> String {
+    hex::encode(rand::thread_rng().gen::<[u8; 32]>())
+}
+
+/// NUT-11 secret locking a proof to the given compressed pubkey
+pub fn p2pk_secret(locking_pubkey: &str) -> String {
+    serde_json::json!([
+        "P2PK",
+        {
+            "nonce": random_secret(),
+            "data": locking_pubkey,
+        }
+    ])
+    .to_string()
+}
+
+/// Blind one output per secret, denominated by the matching amount
+pub fn blind_outputs(
+    keyset_id: &str,
+    amounts: &[u64],
+    secrets: Vec<String>,
+) -> Result<Vec<PendingOutput>, GameEngineError> {
+    let secp = Secp256k1::new();
+
+    amounts
+        .iter()
+        .zip(secrets)
+        .map(|(&amount, secret)| {
+            let blinding_factor = random_blinding_factor();
+            let blinded = blind(&secp, &secret, &blinding_factor)?;
+            Ok(PendingOutput {
+                message: BlindedMessage {
+                    amount,
+                    id: keyset_id.to_string(),
+                    blinded_secret: blinded.to_string(),
+                },
+                secret,
+                blinding_factor,
+            })
+        })
+        .collect()
+}
+
+/// Unblind the mint's signatures into proofs, in output order
+pub fn unblind_signatures(
+    outputs: Vec<PendingOutput>,
+    signatures: Vec<BlindSignature>,
+    keyset: &MintKeyset,
+) -> Result<Vec<Proof>, GameEngineError> {
+    if outputs.len() != signatures.len() {
+        return Err(GameEngineError::CashuError(format!(
+            "Mint returned {} signatures for {} outputs",
+            signatures.len(),
+            outputs.len()
+        )));
+    }
+
+    let secp = Secp256k1::new();
+
+    outputs
+        .into_iter()
+        .zip(signatures)
+        .map(|(output, signature)| {
+            let mint_key = keyset
+                .keys
+                .get(&signature.amount.to_string())
+                .ok_or_else(|| {
+                    GameEngineError::CashuError(format!(
+                        "Keyset {} has no key for amount {}",
+                        keyset.id, signature.amount
+                    ))
+                })?;
+
+            let unblinded = unblind(
+                &secp,
+                &parse_point(&signature.blinded_signature)?,
+                &output.blinding_factor,
+                &parse_point(mint_key)?,
+            )?;
+
+            Ok(Proof {
+                amount: signature.amount,
+                id: signature.id,
+                secret: output.secret,
+                signature: unblinded.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Serialize proofs as a V3 `cashuA` token
+pub fn encode_token(mint_url: &str, unit: &str, memo: &str, proofs: &[Proof]) -> String {
+    let token = serde_json::json!({
+        "token": [{ "mint": mint_url, "proofs": proofs }],
+        "unit": unit,
+        "memo": memo,
+    });
+    format!("cashuA{}", URL_SAFE.encode(token.to_string()))
+}
+
+/// B_ = Y + rG
+fn blind<C: nostr::secp256k1::Signing>(
+    secp: &Secp256k1<C>,
+    secret: &str,
+    blinding_factor: &SecretKey,
+) -> Result<PublicKey, GameEngineError> {
+    let y = hash_to_curve(secret.as_bytes())?;
+    y.combine(&PublicKey::from_secret_key(secp, blinding_factor))
+        .map_err(|e| GameEngineError::CashuError(format!("Failed to blind secret: {e}")))
+}
+
+/// C = C_ - rK
+fn unblind<C: nostr::secp256k1::Verification>(
+    secp: &Secp256k1<C>,
+    blinded_signature: &PublicKey,
+    blinding_factor: &SecretKey,
+    mint_key: &PublicKey,
+) -> Result<PublicKey, GameEngineError> {
+    let r_k = mint_key
+        .mul_tweak(secp, &Scalar::from(*blinding_factor))
+        .map_err(|e| GameEngineError::CashuError(format!("Failed to unblind: {e}")))?;
+    blinded_signature
+        .combine(&r_k.negate(secp))
+        .map_err(|e| GameEngineError::CashuError(format!("Failed to unblind: {e}")))
+}
+
+fn random_blinding_factor() -> SecretKey {
+    loop {
+        if let Ok(key) = SecretKey::from_slice(&rand::thread_rng().gen::<[u8; 32]>()) {
+            return key;
+        }
+    }
+}
+
+fn parse_point(point: &str) -> Result<PublicKey, GameEngineError> {
+    point
+        .parse()
+        .map_err(|e| GameEngineError::CashuError(format!("Invalid curve point {point}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_to_curve_vectors() {
+        // NUT-00 reference vectors
+        let zero = [0u8; 32];
+        let mut one = [0u8; 32];
+        one[31] = 1;
+
+        assert_eq!(
+            hash_to_curve(&zero).unwrap().to_string(),
+            "024cce997d3b518f739663b757deaec95bcd9473c30a14ac2fd04023a739d1a725"
+        );
+        assert_eq!(
+            hash_to_curve(&one).unwrap().to_string(),
+            "022e7158e11c9506f1aa4248bf531298daa7febd6194f003edcd9b93ade6253acf"
+        );
+    }
+
+    #[test]
+    fn test_split_amount() {
+        assert_eq!(split_amount(0), Vec::<u64>::new());
+        assert_eq!(split_amount(100), vec![4, 32, 64]);
+        assert_eq!(split_amount(100).iter().sum::<u64>(), 100);
+    }
+
+    #[test]
+    fn test_blind_sign_unblind_round_trip() {
+        let secp = Secp256k1::new();
+        let mint_secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let keyset = MintKeyset {
+            id: "00loot".to_string(),
+            unit: "loot".to_string(),
+            keys: HashMap::from([(
+                "4".to_string(),
+                PublicKey::from_secret_key(&secp, &mint_secret).to_string(),
+            )]),
+        };
+
+        let secret = p2pk_secret(&keyset.keys["4"]);
+        let outputs = blind_outputs(&keyset.id, &[4], vec![secret.clone()]).unwrap();
+
+        // Mint signs C_ = kB_
+        let blinded = parse_point(&outputs[0].message.blinded_secret).unwrap();
+        let signature = BlindSignature {
+            amount: 4,
+            id: keyset.id.clone(),
+            blinded_signature: blinded
+                .mul_tweak(&secp, &Scalar::from(mint_secret))
+                .unwrap()
+                .to_string(),
+        };
+
+        let proofs = unblind_signatures(outputs, vec![signature], &keyset).unwrap();
+        let expected = hash_to_curve(secret.as_bytes())
+            .unwrap()
+            .mul_tweak(&secp, &Scalar::from(mint_secret))
+            .unwrap();
+
+        assert_eq!(proofs[0].secret, secret);
+        assert_eq!(proofs[0].signature, expected.to_string());
+
+        let token = encode_token("http://mint", "loot", "match_1", &proofs);
+        assert!(token.starts_with("cashuA"));
+    }
+}
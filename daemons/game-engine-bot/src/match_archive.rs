@@ -0,0 +1,258 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::errors::GameEngineError;
+use crate::match_events::ValidationSummary;
+use crate::match_state_machine::MatchState;
+use crate::match_store::persistence_error;
+
+/// Summary of a finished match, kept after the tracker forgets it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedMatch {
+    pub match_id: String,
+    pub player1_npub: Option<String>,
+    pub player2_npub: Option<String>,
+    pub outcome: String, // Completed or Invalid
+    pub winner_npub: Option<String>,
+    pub wager_amount: u64,
+    pub practice: bool,
+    pub validation: Option<ValidationSummary>,
+    pub invalid_reason: Option<String>,
+    pub loot_quote: Option<String>, // Mint quote the winner's loot token was issued against
+    pub created_at: DateTime<Utc>,
+    pub archived_at: DateTime<Utc>,
+}
+
+impl ArchivedMatch {
+    /// Summarize a match that just reached a terminal state
+    ///
+    /// `previous` is the state before the final transition; an invalid match
+    /// no longer carries its players, so they are taken from there.
+    pub fn from_terminal(
+        match_id: &str,
+        previous: &MatchState,
+        terminal: &MatchState,
+        created_at: DateTime<Utc>,
+    ) -> Option<Self> {
+        let mut archived = Self {
+            match_id: match_id.to_string(),
+            player1_npub: None,
+            player2_npub: None,
+            outcome: terminal.phase_name().to_string(),
+            winner_npub: None,
+            wager_amount: previous.wager_amount(),
+            practice: previous.is_practice(),
+            validation: None,
+            invalid_reason: None,
+            loot_quote: None,
+            created_at,
+            archived_at: Utc::now(),
+        };
+
+        let players = match terminal {
+            MatchState::Completed {
+                result,
+                loot_distribution,
+                ..
+            } => {
+                archived.winner_npub = result.calculated_winner.clone();
+                archived.validation = loot_distribution
+                    .as_ref()
+                    .map(|loot| loot.validation_summary.clone());
+                terminal.players()
+            }
+            MatchState::Invalid { reason, .. } => {
+                archived.invalid_reason = Some(reason.clone());
+                previous.players()
+            }
+            _ => return None,
+        };
+
+        let mut players = players.into_iter();
+        archived.player1_npub = players.next();
+        archived.player2_npub = players.next();
+        Some(archived)
+    }
+}
+
+/// Queryable history of completed and invalidated matches
+pub struct MatchArchive {
+    connection: Mutex<Connection>,
+}
+
+impl MatchArchive {
+    /// Open (or create) the archive in the given SQLite database
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Archive that only lives as long as the process, for when persistence is disabled
+    pub fn in_memory() -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS match_archive (
+                    match_id TEXT PRIMARY KEY,
+                    player1_npub TEXT,
+                    player2_npub TEXT,
+                    summary TEXT NOT NULL,
+                    archived_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS match_archive_player1 ON match_archive (player1_npub);
+                CREATE INDEX IF NOT EXISTS match_archive_player2 ON match_archive (player2_npub);",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Store a match summary, replacing any earlier summary of the same match
+    pub fn archive(&self, archived: &ArchivedMatch) -> Result<(), GameEngineError> {
+        let summary = serde_json::to_string(archived).map_err(|e| {
+            GameEngineError::Persistence(format!(
+                "Failed to encode archived match {}: {e}",
+                archived.match_id
+            ))
+        })?;
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO match_archive (match_id, player1_npub, player2_npub, summary, archived_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(match_id) DO UPDATE SET
+                     player1_npub = excluded.player1_npub,
+                     player2_npub = excluded.player2_npub,
+                     summary = excluded.summary,
+                     archived_at = excluded.archived_at",
+                params![
+                    archived.match_id,
+                    archived.player1_npub,
+                    archived.player2_npub,
+                    summary,
+                    archived.archived_at.timestamp()
+                ],
+            )
+            .map_err(persistence_error)?;
+
+        info!(
+            "📦 Archived match {} ({})",
+            archived.match_id, archived.outcome
+        );
+        Ok(())
+    }
+
+    /// Attach the loot payout reference to an archived match
+    pub fn record_loot(&self, match_id: &str, loot_quote: &str) -> Result<(), GameEngineError> {
+        let Some(mut archived) = self.get_match(match_id)? else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+        archived.loot_quote = Some(loot_quote.to_string());
+        self.archive(&archived)
+    }
+
+    /// Summary of a single archived match
+    pub fn get_match(&self, match_id: &str) -> Result<Option<ArchivedMatch>, GameEngineError> {
+        let summary: Option<String> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT summary FROM match_archive WHERE match_id = ?1",
+                params![match_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(persistence_error)?;
+
+        summary.map(|json| decode_summary(&json)).transpose()
+    }
+
+    /// Every archived match a player took part in, most recent first
+    pub fn get_match_history(
+        &self,
+        player_npub: &str,
+    ) -> Result<Vec<ArchivedMatch>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(
+                "SELECT summary FROM match_archive
+                 WHERE player1_npub = ?1 OR player2_npub = ?1
+                 ORDER BY archived_at DESC",
+            )
+            .map_err(persistence_error)?;
+
+        let rows = statement
+            .query_map(params![player_npub], |row| row.get::<_, String>(0))
+            .map_err(persistence_error)?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            history.push(decode_summary(&row.map_err(persistence_error)?)?);
+        }
+        Ok(history)
+    }
+}
+
+fn decode_summary(summary: &str) -> Result<ArchivedMatch, GameEngineError> {
+    serde_json::from_str(summary)
+        .map_err(|e| GameEngineError::Persistence(format!("Corrupt archived match: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archived(match_id: &str, player1: &str, player2: &str) -> ArchivedMatch {
+        ArchivedMatch {
+            match_id: match_id.to_string(),
+            player1_npub: Some(player1.to_string()),
+            player2_npub: Some(player2.to_string()),
+            outcome: "Completed".to_string(),
+            winner_npub: Some(player1.to_string()),
+            wager_amount: 100,
+            practice: false,
+            validation: None,
+            invalid_reason: None,
+            loot_quote: None,
+            created_at: Utc::now(),
+            archived_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_history_lists_matches_for_either_seat() {
+        let archive = MatchArchive::in_memory().unwrap();
+        archive
+            .archive(&archived("match_1", "npub1alice", "npub1bob"))
+            .unwrap();
+        archive
+            .archive(&archived("match_2", "npub1carol", "npub1alice"))
+            .unwrap();
+        archive
+            .archive(&archived("match_3", "npub1bob", "npub1carol"))
+            .unwrap();
+
+        let history = archive.get_match_history("npub1alice").unwrap();
+        let mut ids: Vec<_> = history.iter().map(|m| m.match_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["match_1", "match_2"]);
+
+        archive.record_loot("match_1", "quote_1").unwrap();
+        let stored = archive.get_match("match_1").unwrap().unwrap();
+        assert_eq!(stored.loot_quote.as_deref(), Some("quote_1"));
+        assert!(archive.get_match("missing").unwrap().is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use nostr::{Event, EventBuilder, Keys, Kind, Tag};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::{debug, error, info};
 
 /// Player-driven match events for commitment/reveal scheme
@@ -15,6 +15,39 @@ pub const KIND_TOKEN_REVEAL: Kind = Kind::Custom(21002);
 pub const KIND_COMBAT_MOVE: Kind = Kind::Custom(21003);
 pub const KIND_MATCH_RESULT: Kind = Kind::Custom(21004);
 pub const KIND_LOOT_DISTRIBUTION: Kind = Kind::Custom(21005);
+pub const KIND_MATCH_INVALIDATION: Kind = Kind::Custom(21006);
+pub const KIND_CHALLENGE_CANCELLED: Kind = Kind::Custom(21007);
+pub const KIND_CHALLENGE_REJECTED: Kind = Kind::Custom(21008);
+pub const KIND_MATCH_SEEK: Kind = Kind::Custom(21009);
+pub const KIND_MODERATION_LOG: Kind = Kind::Custom(21010);
+pub const KIND_CHEAT_EVIDENCE: Kind = Kind::Custom(21011);
+pub const KIND_PROOF_BUNDLE: Kind = Kind::Custom(21012); // Signed by the engine, exported rather than published
+pub const KIND_MATCH_PAIRING: Kind = Kind::Custom(21013);
+
+// Player-published draft-phase events (NIP-33 range, one per player and match)
+pub const KIND_DRAFT_BAN_COMMITMENT: Kind = Kind::Custom(31008);
+pub const KIND_DRAFT_BAN_REVEAL: Kind = Kind::Custom(31009);
+
+// Engine-published parameterized replaceable events (NIP-33 range)
+pub const KIND_ROUND_SUMMARY: Kind = Kind::Custom(31010);
+pub const KIND_ENGINE_RULESET: Kind = Kind::Custom(31011);
+pub const KIND_LEADERBOARD: Kind = Kind::Custom(31012);
+pub const KIND_ENGINE_HEARTBEAT: Kind = Kind::Custom(31099);
+
+/// Version of the player-driven match protocol implemented by this engine
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Default system fee taken from the total wager before loot is issued
+pub const MATCH_FEE_PERCENT: u64 = 5;
+
+/// Replaceable identifier of the engine ruleset event
+pub const ENGINE_RULESET_IDENTIFIER: &str = "manastr-ruleset";
+
+/// Replaceable identifier of the engine leaderboard event
+pub const LEADERBOARD_IDENTIFIER: &str = "manastr-leaderboard";
+
+/// Replaceable identifier of the engine heartbeat event
+pub const HEARTBEAT_IDENTIFIER: &str = "manastr-heartbeat";
 
 /// Match challenge created by Player 1
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,6 +60,60 @@ pub struct MatchChallenge {
     pub expires_at: u64,                // Unix timestamp
     pub created_at: u64,
     pub match_event_id: String, // EventId as hex string for JSON serialization
+    #[serde(default)]
+    pub practice: bool, // Zero-wager practice match: no escrow, no loot, ratings off
+    #[serde(default)]
+    pub match_format: MatchFormat,
+    #[serde(default)]
+    pub private: bool, // Arrived in an encrypted DM: only the loot event is published
+    #[serde(default = "default_generator_version")]
+    pub generator_version: u8, // Army generator; challenges from before v2 omit it and get v1
+    #[serde(default)]
+    pub bans_per_player: u8, // Opponent units each player bans in the draft phase; 0 skips it
+}
+
+/// Most of the opponent's units a challenge may let each player ban
+pub const MAX_BANS_PER_PLAYER: u8 = 3;
+
+/// Generator of challenges and matches recorded before `generator_version` existed
+pub fn default_generator_version() -> u8 {
+    shared_game_logic::combat::ARMY_GENERATOR_V1
+}
+
+/// Number of games a match is played over; each combat round is one game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchFormat {
+    #[serde(rename = "single_round")]
+    SingleRound,
+    #[serde(rename = "best_of_3")]
+    BestOf3,
+    #[default]
+    #[serde(rename = "best_of_5")]
+    BestOf5,
+}
+
+impl MatchFormat {
+    /// Most games the match can take
+    pub fn max_games(self) -> u32 {
+        match self {
+            MatchFormat::SingleRound => 1,
+            MatchFormat::BestOf3 => 3,
+            MatchFormat::BestOf5 => 5,
+        }
+    }
+
+    /// Game wins that decide the match
+    pub fn wins_needed(self) -> u32 {
+        self.max_games() / 2 + 1
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MatchFormat::SingleRound => "single_round",
+            MatchFormat::BestOf3 => "best_of_3",
+            MatchFormat::BestOf5 => "best_of_5",
+        }
+    }
 }
 
 /// Match acceptance by Player 2
@@ -49,6 +136,25 @@ pub struct TokenReveal {
     pub revealed_at: u64,
 }
 
+/// Draft-phase commitment to the opponent's units a player bans
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftBanCommitment {
+    pub player_npub: String,
+    pub match_event_id: String, // References the challenge EventId
+    pub ban_commitment: String, // commit_to_bans(banned_units, ban_nonce)
+    pub committed_at: u64,
+}
+
+/// Draft-phase reveal, published once both players have committed to their bans
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftBanReveal {
+    pub player_npub: String,
+    pub match_event_id: String, // References the challenge EventId
+    pub banned_units: Vec<u8>,  // Indices into the opponent's army
+    pub ban_nonce: String,      // Nonce used in commitment
+    pub revealed_at: u64,
+}
+
 /// Combat move for turn-based gameplay
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombatMove {
@@ -70,6 +176,33 @@ pub struct MatchResult {
     pub all_round_results: Vec<Value>, // Results from all combat rounds
     pub calculated_winner: Option<String>, // Winner npub or None for draw
     pub match_completed_at: u64,
+    /// Rules hash of the client that computed the result, checked against the engine's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules_hash: Option<String>,
+}
+
+impl MatchResult {
+    /// Why the engine can't accept the result, if it was computed under other rules
+    pub fn rules_error(&self) -> Option<String> {
+        let engine_hash = shared_game_logic::rules_hash();
+        match &self.rules_hash {
+            Some(hash) if hash != engine_hash => Some(format!(
+                "Result was computed under rules {hash}, engine runs {engine_hash}"
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Player asking the engine's matchmaker for an opponent instead of picking a challenge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchSeek {
+    pub player_npub: String,
+    pub league_id: u8,
+    pub min_wager: u64, // Wager range the player will accept a challenge for
+    pub max_wager: u64,
+    pub expires_at: u64, // Unix timestamp
+    pub created_at: u64,
 }
 
 /// Loot distribution by Game Engine Bot (ONLY authoritative event from bot)
@@ -79,9 +212,197 @@ pub struct LootDistribution {
     pub match_event_id: String,      // References the challenge EventId
     pub winner_npub: Option<String>, // None for draw
     pub loot_cashu_token: Option<String>, // Loot token for winner (None for draw)
-    pub match_fee: u64,              // Fee taken (5% of wager)
+    #[serde(default)]
+    pub loot_amount: u64, // Mana the loot token is worth (0 for draw)
+    pub match_fee: u64,              // Fee withheld from the total wager
+    /// Fee loot locked to the operator's npub, when the fee is paid that way
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_cashu_token: Option<String>,
     pub loot_issued_at: u64,
     pub validation_summary: ValidationSummary,
+    #[serde(default)]
+    pub rules_hash: String, // Rules the engine validated the match under
+}
+
+/// Split of a match's total wager between the operator fee and the players
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub total_wager: u64,
+    pub match_fee: u64,
+    pub player_share: u64,
+}
+
+impl FeeSplit {
+    /// Withhold `match_fee_percent` of the total wager, capped at the whole wager
+    pub fn new(total_wager: u64, match_fee_percent: u64) -> Self {
+        let match_fee = total_wager.saturating_mul(match_fee_percent.min(100)) / 100;
+        Self {
+            total_wager,
+            match_fee,
+            player_share: total_wager - match_fee,
+        }
+    }
+
+    /// Split of a match in which both players wagered `wager_amount`
+    pub fn for_wager(wager_amount: u64, match_fee_percent: u64) -> Self {
+        Self::new(wager_amount.saturating_mul(2), match_fee_percent)
+    }
+}
+
+/// Match invalidation by Game Engine Bot - the match is void and wagers are reclaimable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchInvalidation {
+    pub game_engine_npub: String,
+    pub match_event_id: String,
+    pub reason: String,
+    pub offending_npub: Option<String>, // None when no player is at fault (e.g. timeout)
+    pub evidence_hashes: Vec<String>,   // Hashes of the events proving the fault
+    pub invalidated_at: u64,
+    #[serde(default)]
+    pub rules_hash: String, // Rules the engine validated the match under
+}
+
+/// Challenge withdrawn by Game Engine Bot - nobody accepted before it expired,
+/// so the challenger's committed tokens are free to unlock
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeCancelled {
+    pub game_engine_npub: String,
+    pub match_event_id: String, // The expired challenge event
+    pub challenger_npub: String,
+    pub reason: String,
+    pub expired_at: u64,
+    pub cancelled_at: u64,
+}
+
+/// Challenge turned away by Game Engine Bot without being tracked, e.g. while the
+/// engine is shedding load; nothing was locked, so the challenger may simply retry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeRejected {
+    pub game_engine_npub: String,
+    pub match_event_id: String, // The rejected challenge event
+    pub challenger_npub: String,
+    pub reason: String, // "engine_busy"
+    pub rejected_at: u64,
+}
+
+/// Open challenge Game Engine Bot matched to a seeking player, who may now accept it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchPairing {
+    pub game_engine_npub: String,
+    pub match_event_id: String, // The challenge the seeker is invited to accept
+    pub challenger_npub: String,
+    pub acceptor_npub: String,
+    pub league_id: u8,
+    pub wager_amount: u64,
+    pub paired_at: u64,
+}
+
+/// Moderation action taken by Game Engine Bot against a pubkey, e.g. a temporary ban for flooding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModerationLog {
+    pub game_engine_npub: String,
+    pub target_npub: String,
+    pub action: String, // "temporary_ban"
+    pub reason: String,
+    pub expires_at: Option<u64>, // None for permanent actions
+    pub issued_at: u64,
+}
+
+/// Cheat detected by Game Engine Bot, published so other engines can screen the offender
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheatEvidence {
+    pub game_engine_npub: String,
+    pub offender_npub: String,
+    pub match_event_id: String,
+    pub reason: String,
+    pub evidence_hashes: Vec<String>, // Hashes of the events proving the cheat
+    pub detections: u32,              // Cheats this engine has recorded for the offender
+    pub blacklisted: bool,
+    pub detected_at: u64,
+}
+
+/// Per-round summary published by the Game Engine Bot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundSummary {
+    pub game_engine_npub: String,
+    pub match_event_id: String,
+    pub round_number: u32,
+    pub recommended_reveal_delay_ms: u64, // Covers the slowest observed relay propagation
+    #[serde(default)]
+    pub combat: Option<RoundCombat>, // None until both armies and moves are known
+    pub published_at: u64,
+}
+
+/// Combat outcome of a round as spectators see it, indexed [player1, player2]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundCombat {
+    pub damage_taken: [u8; 2],
+    pub units_lost: [u32; 2], // Units lost so far in the match
+    pub score: [u32; 2],      // Rounds won so far in the match
+    pub round_winner: Option<String>,
+}
+
+/// Machine-readable ruleset published by the Game Engine Bot
+/// Clients configure themselves against the engine they are playing on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineRuleset {
+    pub game_engine_npub: String,
+    pub protocol_version: u32,
+    pub event_kinds: BTreeMap<String, u16>, // Event name -> Nostr kind
+    pub league_registry_hash: String,       // hash(all league modifiers)
+    #[serde(default)]
+    pub class_matrix_version: u32, // Class matchup matrix combat resolves with
+    #[serde(default)]
+    pub rules_hash: String, // shared_game_logic::rules_hash() of the engine build
+    pub fee_schedule: FeeSchedule,
+    pub timeouts: TimeoutParameters,
+    pub published_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub match_fee_percent: u64,
+    pub loot_reward_per_match: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeoutParameters {
+    pub round_timeout_seconds: u64,
+    pub match_timeout_seconds: u64,
+}
+
+/// Elo standings derived from engine-validated matches, replaced on every publish
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub game_engine_npub: String,
+    pub entries: Vec<LeaderboardEntry>, // Highest rating first
+    pub published_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub rank: u32,
+    pub npub: String,
+    pub rating: i64, // Elo rating rounded to the nearest point
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// Periodic liveness signal from the engine, replaced on every publish
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineHeartbeat {
+    pub game_engine_npub: String,
+    pub version: String, // Engine release, not the protocol version
+    pub active_matches: u32,
+    pub mints: Vec<MintConnectivity>,
+    pub published_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintConnectivity {
+    pub mint_url: String,
+    pub reachable: bool,
 }
 
 /// Summary of game engine validation
@@ -103,6 +424,8 @@ pub struct PlayerMatch {
     pub player2_npub: String,
     pub wager_amount: u64,
     pub league_id: u8,
+    pub generator_version: u8,
+    pub created_at: u64,
 
     // Commitment tracking
     pub player1_commitments: PlayerCommitments,
@@ -151,6 +474,8 @@ impl PlayerMatch {
             player2_npub: String::new(), // Set when accepted
             wager_amount: challenge.wager_amount,
             league_id: challenge.league_id,
+            generator_version: challenge.generator_version,
+            created_at: challenge.created_at,
             player1_commitments: PlayerCommitments {
                 cashu_tokens: Some(challenge.cashu_token_commitment.clone()),
                 army: Some(challenge.army_commitment.clone()),
@@ -283,7 +608,7 @@ impl PlayerMatch {
 impl MatchChallenge {
     pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
         let content = serde_json::to_string(self)?;
-        let tags = vec![
+        let mut tags = vec![
             Tag::custom(
                 nostr::TagKind::Custom("d".into()),
                 vec![self.challenger_npub.clone()],
@@ -300,7 +625,17 @@ impl MatchChallenge {
                 nostr::TagKind::Custom("expires".into()),
                 vec![self.expires_at.to_string()],
             ),
+            Tag::custom(
+                nostr::TagKind::Custom("format".into()),
+                vec![self.match_format.as_str().to_string()],
+            ),
         ];
+        if self.practice {
+            tags.push(Tag::custom(
+                nostr::TagKind::Custom("practice".into()),
+                vec!["true".to_string()],
+            ));
+        }
 
         let event = EventBuilder::new(KIND_MATCH_CHALLENGE, content, tags).to_event(keys)?;
         Ok(event)
@@ -347,6 +682,40 @@ impl TokenReveal {
     }
 }
 
+impl DraftBanCommitment {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let tags = vec![
+            Tag::identifier(self.match_event_id.clone()),
+            Tag::event(nostr::EventId::from_hex(&self.match_event_id)?),
+            Tag::custom(
+                nostr::TagKind::Custom("phase".into()),
+                vec!["draft_ban_commitment".to_string()],
+            ),
+        ];
+
+        let event = EventBuilder::new(KIND_DRAFT_BAN_COMMITMENT, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl DraftBanReveal {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let tags = vec![
+            Tag::identifier(self.match_event_id.clone()),
+            Tag::event(nostr::EventId::from_hex(&self.match_event_id)?),
+            Tag::custom(
+                nostr::TagKind::Custom("phase".into()),
+                vec!["draft_ban_reveal".to_string()],
+            ),
+        ];
+
+        let event = EventBuilder::new(KIND_DRAFT_BAN_REVEAL, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
 impl CombatMove {
     pub fn to_nostr_event(
         &self,
@@ -404,23 +773,6 @@ impl MatchResult {
 }
 
 impl LootDistribution {
-    /// Calculate optimized loot amount (95% of total wager, 5% system fee)
-    pub fn calculate_optimized_loot_amount(&self) -> u64 {
-        // Get total mana wagered from both players
-        let total_wager = self.total_mana_wagered();
-
-        // Return 95% to winner as loot tokens
-        (total_wager * 95) / 100
-    }
-
-    /// Get total mana wagered by both players  
-    pub fn total_mana_wagered(&self) -> u64 {
-        // For current implementation, wager_amount represents per-player wager
-        // So total is wager_amount * 2 (both players)
-        // TODO: Get actual wager amounts from match data
-        200 // Placeholder - should be calculated from actual match data
-    }
-
     pub fn to_nostr_event(
         &self,
         keys: &Keys,
@@ -438,7 +790,7 @@ impl LootDistribution {
             Tag::custom(nostr::TagKind::Custom("winner".into()), vec![winner_tag]),
             Tag::custom(
                 nostr::TagKind::Custom("loot_amount".into()),
-                vec![self.calculate_optimized_loot_amount().to_string()],
+                vec![self.loot_amount.to_string()],
             ),
             Tag::custom(
                 nostr::TagKind::Custom("match_event_id".into()),
@@ -451,23 +803,590 @@ impl LootDistribution {
     }
 }
 
+impl MatchInvalidation {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("match_event_id".into()),
+                vec![self.match_event_id.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("reason".into()),
+                vec![self.reason.clone()],
+            ),
+        ];
+
+        // Matches voided before acceptance have no challenge event to reference yet
+        if let Ok(event_id) = nostr::EventId::from_hex(&self.match_event_id) {
+            tags.push(Tag::event(event_id));
+        }
+        if let Some(offending_npub) = &self.offending_npub {
+            tags.push(Tag::custom(
+                nostr::TagKind::Custom("offender".into()),
+                vec![offending_npub.clone()],
+            ));
+        }
+
+        let event = EventBuilder::new(KIND_MATCH_INVALIDATION, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl ChallengeCancelled {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("match_event_id".into()),
+                vec![self.match_event_id.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("challenger".into()),
+                vec![self.challenger_npub.clone()],
+            ),
+        ];
+
+        if let Ok(event_id) = nostr::EventId::from_hex(&self.match_event_id) {
+            tags.push(Tag::event(event_id));
+        }
+
+        let event = EventBuilder::new(KIND_CHALLENGE_CANCELLED, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl ChallengeRejected {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("match_event_id".into()),
+                vec![self.match_event_id.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("challenger".into()),
+                vec![self.challenger_npub.clone()],
+            ),
+        ];
+
+        if let Ok(event_id) = nostr::EventId::from_hex(&self.match_event_id) {
+            tags.push(Tag::event(event_id));
+        }
+
+        let event = EventBuilder::new(KIND_CHALLENGE_REJECTED, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl MatchPairing {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("match_event_id".into()),
+                vec![self.match_event_id.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("challenger".into()),
+                vec![self.challenger_npub.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("acceptor".into()),
+                vec![self.acceptor_npub.clone()],
+            ),
+        ];
+
+        if let Ok(event_id) = nostr::EventId::from_hex(&self.match_event_id) {
+            tags.push(Tag::event(event_id));
+        }
+
+        let event = EventBuilder::new(KIND_MATCH_PAIRING, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl ModerationLog {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("target".into()),
+                vec![self.target_npub.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("action".into()),
+                vec![self.action.clone()],
+            ),
+        ];
+
+        if let Some(expires_at) = self.expires_at {
+            tags.push(Tag::expiration(nostr::Timestamp::from(expires_at)));
+        }
+
+        let event = EventBuilder::new(KIND_MODERATION_LOG, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl CheatEvidence {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::custom(
+                nostr::TagKind::Custom("offender".into()),
+                vec![self.offender_npub.clone()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("match_event_id".into()),
+                vec![self.match_event_id.clone()],
+            ),
+        ];
+
+        if let Ok(event_id) = nostr::EventId::from_hex(&self.match_event_id) {
+            tags.push(Tag::event(event_id));
+        }
+
+        let event = EventBuilder::new(KIND_CHEAT_EVIDENCE, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl RoundSummary {
+    /// Replaceable identifier so each round has exactly one current summary
+    pub fn identifier(&self) -> String {
+        format!("{}:{}", self.match_event_id, self.round_number)
+    }
+
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let mut tags = vec![
+            Tag::identifier(self.identifier()),
+            Tag::event(nostr::EventId::from_hex(&self.match_event_id)?),
+            Tag::custom(
+                nostr::TagKind::Custom("round".into()),
+                vec![self.round_number.to_string()],
+            ),
+            Tag::custom(
+                nostr::TagKind::Custom("reveal_delay_ms".into()),
+                vec![self.recommended_reveal_delay_ms.to_string()],
+            ),
+        ];
+        if let Some(combat) = &self.combat {
+            tags.push(Tag::custom(
+                nostr::TagKind::Custom("score".into()),
+                combat
+                    .score
+                    .iter()
+                    .map(|rounds| rounds.to_string())
+                    .collect::<Vec<_>>(),
+            ));
+        }
+
+        let event = EventBuilder::new(KIND_ROUND_SUMMARY, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl Leaderboard {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let tags = vec![
+            Tag::identifier(LEADERBOARD_IDENTIFIER),
+            Tag::custom(
+                nostr::TagKind::Custom("players".into()),
+                vec![self.entries.len().to_string()],
+            ),
+        ];
+
+        let event = EventBuilder::new(KIND_LEADERBOARD, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl EngineHeartbeat {
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let tags = vec![
+            Tag::identifier(HEARTBEAT_IDENTIFIER),
+            Tag::custom(
+                nostr::TagKind::Custom("version".into()),
+                vec![self.version.clone()],
+            ),
+        ];
+
+        let event = EventBuilder::new(KIND_ENGINE_HEARTBEAT, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+impl EngineRuleset {
+    /// Event kinds clients need to speak this engine's protocol
+    pub fn protocol_event_kinds() -> BTreeMap<String, u16> {
+        [
+            ("match_challenge", KIND_MATCH_CHALLENGE),
+            ("match_acceptance", KIND_MATCH_ACCEPTANCE),
+            ("token_reveal", KIND_TOKEN_REVEAL),
+            ("draft_ban_commitment", KIND_DRAFT_BAN_COMMITMENT),
+            ("draft_ban_reveal", KIND_DRAFT_BAN_REVEAL),
+            ("combat_move", KIND_COMBAT_MOVE),
+            ("match_result", KIND_MATCH_RESULT),
+            ("loot_distribution", KIND_LOOT_DISTRIBUTION),
+            ("match_invalidation", KIND_MATCH_INVALIDATION),
+            ("challenge_cancelled", KIND_CHALLENGE_CANCELLED),
+            ("challenge_rejected", KIND_CHALLENGE_REJECTED),
+            ("match_seek", KIND_MATCH_SEEK),
+            ("moderation_log", KIND_MODERATION_LOG),
+            ("cheat_evidence", KIND_CHEAT_EVIDENCE),
+            ("proof_bundle", KIND_PROOF_BUNDLE),
+            ("match_pairing", KIND_MATCH_PAIRING),
+            ("round_summary", KIND_ROUND_SUMMARY),
+            ("engine_ruleset", KIND_ENGINE_RULESET),
+            ("leaderboard", KIND_LEADERBOARD),
+            ("engine_heartbeat", KIND_ENGINE_HEARTBEAT),
+        ]
+        .into_iter()
+        .map(|(name, kind)| (name.to_string(), kind.as_u16()))
+        .collect()
+    }
+
+    /// Hash of the league registry, so clients can detect modifier drift
+    pub fn league_registry_hash() -> String {
+        let leagues = match shared_game_logic::league::installed_registry() {
+            Some(registry) => serde_json::to_string(&registry.leagues().collect::<Vec<_>>()),
+            None => serde_json::to_string(&shared_game_logic::league::get_all_league_modifiers()),
+        };
+        shared_game_logic::commitment::hash_data(&leagues.unwrap())
+    }
+
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event, Box<dyn std::error::Error>> {
+        let content = serde_json::to_string(self)?;
+        let tags = vec![
+            Tag::identifier(ENGINE_RULESET_IDENTIFIER),
+            Tag::custom(
+                nostr::TagKind::Custom("protocol_version".into()),
+                vec![self.protocol_version.to_string()],
+            ),
+        ];
+
+        let event = EventBuilder::new(KIND_ENGINE_RULESET, content, tags).to_event(keys)?;
+        Ok(event)
+    }
+}
+
+/// Challenge and acceptance fixtures shared by the crate's tests
 #[cfg(test)]
-mod tests {
+pub(crate) mod test_support {
     use super::*;
-    
+    use shared_game_logic::combat::ARMY_GENERATOR_V1;
 
-    #[test]
-    fn test_match_creation_and_acceptance() {
-        let challenge = MatchChallenge {
+    /// Wagered challenge from npub1alice; tests override the fields they care about
+    pub(crate) fn challenge(match_event_id: &str) -> MatchChallenge {
+        MatchChallenge {
             challenger_npub: "npub1alice".to_string(),
             wager_amount: 100,
             league_id: 0,
-            cashu_token_commitment: "commitment_hash_123".to_string(),
-            army_commitment: "army_hash_456".to_string(),
-            expires_at: 1690000000,
-            created_at: 1689900000,
-            match_event_id: "match_event_123".to_string(),
-        };
+            cashu_token_commitment: "alice_token_commitment".to_string(),
+            army_commitment: "alice_army_commitment".to_string(),
+            expires_at: 1690003600,
+            created_at: 1690000000,
+            match_event_id: match_event_id.to_string(),
+            practice: false,
+            match_format: MatchFormat::default(),
+            private: false,
+            generator_version: ARMY_GENERATOR_V1,
+            bans_per_player: 0,
+        }
+    }
+
+    /// npub1bob accepting the challenge
+    pub(crate) fn acceptance(match_event_id: &str) -> MatchAcceptance {
+        MatchAcceptance {
+            acceptor_npub: "npub1bob".to_string(),
+            match_event_id: match_event_id.to_string(),
+            cashu_token_commitment: "bob_token_commitment".to_string(),
+            army_commitment: "bob_army_commitment".to_string(),
+            accepted_at: 1690000100,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_validation_summary() -> ValidationSummary {
+        ValidationSummary {
+            commitments_valid: true,
+            combat_verified: true,
+            signatures_valid: true,
+            winner_confirmed: true,
+            error_details: None,
+        }
+    }
+
+    #[test]
+    fn test_protocol_event_schema_snapshots() {
+        insta::assert_json_snapshot!(
+            "match_challenge",
+            MatchChallenge {
+                league_id: 2,
+                match_format: MatchFormat::BestOf3,
+                bans_per_player: 2,
+                ..test_support::challenge("challenge_event_id")
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "match_acceptance",
+            test_support::acceptance("challenge_event_id")
+        );
+
+        insta::assert_json_snapshot!(
+            "token_reveal",
+            TokenReveal {
+                player_npub: "npub1alice".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                cashu_tokens: vec!["secret_1".to_string(), "secret_2".to_string()],
+                token_secrets_nonce: "alice_nonce".to_string(),
+                revealed_at: 1690000200,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "draft_ban_commitment",
+            DraftBanCommitment {
+                player_npub: "npub1alice".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                ban_commitment: "v2:alice_ban_commitment".to_string(),
+                committed_at: 1690000250,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "draft_ban_reveal",
+            DraftBanReveal {
+                player_npub: "npub1alice".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                banned_units: vec![2, 5],
+                ban_nonce: "alice_ban_nonce".to_string(),
+                revealed_at: 1690000280,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "combat_move",
+            CombatMove {
+                player_npub: "npub1bob".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                previous_event_hash: Some("alice_move_event_id".to_string()),
+                round_number: 1,
+                unit_positions: vec![0, 3],
+                unit_abilities: vec!["boost".to_string()],
+                move_timestamp: 1690000300,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "match_result",
+            MatchResult {
+                player_npub: "npub1alice".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                final_army_state: serde_json::json!({"units": []}),
+                all_round_results: vec![serde_json::json!({"round": 1, "winner": "npub1alice"})],
+                calculated_winner: Some("npub1alice".to_string()),
+                match_completed_at: 1690000400,
+                rules_hash: Some(shared_game_logic::determinism::RULES_HASH.to_string()),
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "loot_distribution",
+            LootDistribution {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                winner_npub: Some("npub1alice".to_string()),
+                loot_cashu_token: None,
+                loot_amount: 100,
+                match_fee: 10,
+                fee_cashu_token: None,
+                loot_issued_at: 1690000500,
+                validation_summary: sample_validation_summary(),
+                rules_hash: shared_game_logic::determinism::RULES_HASH.to_string(),
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "match_invalidation",
+            MatchInvalidation {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                reason: "Token commitment mismatch".to_string(),
+                offending_npub: Some("npub1bob".to_string()),
+                evidence_hashes: vec!["bob_reveal_event_hash".to_string()],
+                invalidated_at: 1690000450,
+                rules_hash: shared_game_logic::determinism::RULES_HASH.to_string(),
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "challenge_cancelled",
+            ChallengeCancelled {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                challenger_npub: "npub1alice".to_string(),
+                reason: "Challenge expired without acceptance".to_string(),
+                expired_at: 1690003600,
+                cancelled_at: 1690003900,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "challenge_rejected",
+            ChallengeRejected {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                challenger_npub: "npub1alice".to_string(),
+                reason: "engine_busy".to_string(),
+                rejected_at: 1690000010,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "match_seek",
+            MatchSeek {
+                player_npub: "npub1bob".to_string(),
+                league_id: 0,
+                min_wager: 50,
+                max_wager: 200,
+                expires_at: 1690003600,
+                created_at: 1690000000,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "match_pairing",
+            MatchPairing {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                challenger_npub: "npub1alice".to_string(),
+                acceptor_npub: "npub1bob".to_string(),
+                league_id: 0,
+                wager_amount: 100,
+                paired_at: 1690000020,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "moderation_log",
+            ModerationLog {
+                game_engine_npub: "npub1engine".to_string(),
+                target_npub: "npub1spam".to_string(),
+                action: "temporary_ban".to_string(),
+                reason: "Exceeded the event rate limit 20 times".to_string(),
+                expires_at: Some(1690000900),
+                issued_at: 1690000000,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "cheat_evidence",
+            CheatEvidence {
+                game_engine_npub: "npub1engine".to_string(),
+                offender_npub: "npub1bob".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                reason: "Revealed tokens do not match the commitment".to_string(),
+                evidence_hashes: vec!["reveal_event_hash".to_string()],
+                detections: 1,
+                blacklisted: true,
+                detected_at: 1690000450,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "round_summary",
+            RoundSummary {
+                game_engine_npub: "npub1engine".to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                round_number: 1,
+                recommended_reveal_delay_ms: 1200,
+                combat: Some(RoundCombat {
+                    damage_taken: [4, 11],
+                    units_lost: [0, 1],
+                    score: [1, 0],
+                    round_winner: Some("npub1alice".to_string()),
+                }),
+                published_at: 1690000350,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "engine_ruleset",
+            EngineRuleset {
+                game_engine_npub: "npub1engine".to_string(),
+                protocol_version: PROTOCOL_VERSION,
+                event_kinds: EngineRuleset::protocol_event_kinds(),
+                league_registry_hash: EngineRuleset::league_registry_hash(),
+                class_matrix_version: shared_game_logic::classes::CLASS_MATRIX_VERSION,
+                rules_hash: shared_game_logic::determinism::RULES_HASH.to_string(),
+                fee_schedule: FeeSchedule {
+                    match_fee_percent: MATCH_FEE_PERCENT,
+                    loot_reward_per_match: 100,
+                },
+                timeouts: TimeoutParameters {
+                    round_timeout_seconds: 30,
+                    match_timeout_seconds: 300,
+                },
+                published_at: 1690000000,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "leaderboard",
+            Leaderboard {
+                game_engine_npub: "npub1engine".to_string(),
+                entries: vec![LeaderboardEntry {
+                    rank: 1,
+                    npub: "npub1alice".to_string(),
+                    rating: 1216,
+                    wins: 1,
+                    losses: 0,
+                    draws: 0,
+                }],
+                published_at: 1690000600,
+            }
+        );
+
+        insta::assert_json_snapshot!(
+            "engine_heartbeat",
+            EngineHeartbeat {
+                game_engine_npub: "npub1engine".to_string(),
+                version: "0.1.0".to_string(),
+                active_matches: 3,
+                mints: vec![MintConnectivity {
+                    mint_url: "http://localhost:3333".to_string(),
+                    reachable: true,
+                }],
+                published_at: 1690000060,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_split() {
+        let split = FeeSplit::for_wager(100, MATCH_FEE_PERCENT);
+        assert_eq!(split.total_wager, 200);
+        assert_eq!(split.match_fee, 10);
+        assert_eq!(split.player_share, 190);
+
+        // A misconfigured percentage never withholds more than the wager
+        assert_eq!(FeeSplit::new(200, 150).player_share, 0);
+        assert_eq!(FeeSplit::new(200, 0).match_fee, 0);
+    }
+
+    #[test]
+    fn test_match_creation_and_acceptance() {
+        let challenge = test_support::challenge("match_event_123");
 
         let match_id = "match_123".to_string();
         let mut player_match = PlayerMatch::new(&challenge, match_id.clone());
@@ -475,13 +1394,7 @@ mod tests {
         assert_eq!(player_match.player1_npub, "npub1alice");
         assert!(matches!(player_match.phase, MatchPhase::Created));
 
-        let acceptance = MatchAcceptance {
-            acceptor_npub: "npub1bob".to_string(),
-            match_event_id: match_id.clone(),
-            cashu_token_commitment: "bob_token_commitment".to_string(),
-            army_commitment: "bob_army_commitment".to_string(),
-            accepted_at: 1689910000,
-        };
+        let acceptance = test_support::acceptance(&match_id);
 
         player_match.accept(&acceptance).unwrap();
         assert_eq!(player_match.player2_npub, "npub1bob");
@@ -490,26 +1403,11 @@ mod tests {
 
     #[test]
     fn test_token_reveal_flow() {
-        let challenge = MatchChallenge {
-            challenger_npub: "npub1alice".to_string(),
-            wager_amount: 100,
-            league_id: 0,
-            cashu_token_commitment: "alice_commitment".to_string(),
-            army_commitment: "alice_army".to_string(),
-            expires_at: 1690000000,
-            created_at: 1689900000,
-            match_event_id: "match_event_123".to_string(),
-        };
+        let challenge = test_support::challenge("match_event_123");
 
         let mut player_match = PlayerMatch::new(&challenge, "match_123".to_string());
 
-        let acceptance = MatchAcceptance {
-            acceptor_npub: "npub1bob".to_string(),
-            match_event_id: "match_123".to_string(),
-            cashu_token_commitment: "bob_commitment".to_string(),
-            army_commitment: "bob_army".to_string(),
-            accepted_at: 1689910000,
-        };
+        let acceptance = test_support::acceptance("match_123");
         player_match.accept(&acceptance).unwrap();
 
         // Alice reveals tokens
@@ -542,16 +1440,7 @@ mod tests {
 
     #[test]
     fn test_combat_move_cycle() {
-        let challenge = MatchChallenge {
-            challenger_npub: "npub1alice".to_string(),
-            wager_amount: 100,
-            league_id: 0,
-            cashu_token_commitment: "alice_commitment".to_string(),
-            army_commitment: "alice_army".to_string(),
-            expires_at: 1690000000,
-            created_at: 1689900000,
-            match_event_id: "match_event_123".to_string(),
-        };
+        let challenge = test_support::challenge("match_event_123");
 
         let mut player_match = PlayerMatch::new(&challenge, "match_123".to_string());
 
@@ -3,8 +3,18 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{info, warn};
 
+use crate::combat_cache::combat_cache;
 use crate::match_events::*;
+use crate::match_verifier::{fielded_unit, verifier_for, MatchVerifier};
+use shared_game_logic::abilities::AbilityLedger;
+use shared_game_logic::army::{validate_army_for, ArmyValidationError};
+use shared_game_logic::combat::supports_army_generator;
+use shared_game_logic::commitment::verify_bans_commitment;
 use shared_game_logic::game_state::Unit;
+use shared_game_logic::league::{self, LeagueDefinition};
+
+/// Deciding games a drawn match may add before the wagers are refunded instead
+pub const MAX_SUDDEN_DEATH_GAMES: u32 = 3;
 
 /// State machine for tracking match progression through Nostr events
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,6 +30,16 @@ pub enum MatchState {
         acceptance: MatchAcceptance,
         player1_revealed: bool,
         player2_revealed: bool,
+        #[serde(default)]
+        player1_reveals: PlayerReveals,
+        #[serde(default)]
+        player2_reveals: PlayerReveals,
+    },
+    /// Both tokens revealed, players banning each other's units before round 1
+    Drafting {
+        match_data: MatchData,
+        ban_commitments: [Option<String>; 2], // Indexed [player1, player2]
+        bans_revealed: [bool; 2],
     },
     /// Both tokens revealed, combat rounds in progress
     InCombat {
@@ -37,11 +57,11 @@ pub enum MatchState {
         result: MatchResult,
         submitted_at: DateTime<Utc>,
     },
-    /// Match validated, loot distributed
+    /// Match validated, loot distributed (no loot for practice matches)
     Completed {
         match_data: MatchData,
         result: MatchResult,
-        loot_distribution: LootDistribution,
+        loot_distribution: Option<LootDistribution>,
         completed_at: DateTime<Utc>,
     },
     /// Match invalid due to cheating or errors
@@ -59,6 +79,24 @@ pub struct MatchData {
     pub player2_npub: String,
     pub league_id: u32,
     pub wager_amount: u64,
+    #[serde(default)]
+    pub practice: bool,
+    #[serde(default)]
+    pub match_format: MatchFormat,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub game_wins: [u32; 2], // Games won so far, indexed [player1, player2]
+    #[serde(default)]
+    pub sudden_death_games: u32, // Extra games granted to break a draw
+    #[serde(default = "default_generator_version")]
+    pub generator_version: u8, // Army generator the challenge asked for
+    #[serde(default)]
+    pub created_at: u64, // Challenge creation time; picks the league season armies get
+    #[serde(default)]
+    pub bans_per_player: u8, // Opponent units each player bans before round 1
+    #[serde(default)]
+    pub banned_units: [Vec<u8>; 2], // Units banned from each army, indexed [player1, player2]
 
     // Commitment/reveal data
     pub player1_commitments: PlayerCommitments,
@@ -77,13 +115,35 @@ pub enum MatchEvent {
     ChallengePosted(MatchChallenge),
     ChallengeAccepted(MatchAcceptance),
     TokenRevealed(TokenReveal),
+    BanCommitted(DraftBanCommitment),
+    BansRevealed(DraftBanReveal),
     CombatMoveSubmitted(CombatMove),
     ResultSubmitted(MatchResult),
     LootDistributed(LootDistribution),
-    InvalidationTriggered(String), // reason
+    InvalidationTriggered(Invalidation),
+    SuddenDeathOrdered,
     TimeoutExpired,
 }
 
+/// Why a match was voided, and which player is at fault if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invalidation {
+    pub reason: String,
+    pub offending_npub: Option<String>,
+    pub evidence_hashes: Vec<String>, // Hashes of the events proving the fault
+}
+
+impl Invalidation {
+    /// Invalidation not attributed to either player
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            offending_npub: None,
+            evidence_hashes: Vec::new(),
+        }
+    }
+}
+
 /// Result of a state transition
 #[derive(Debug)]
 pub struct TransitionResult {
@@ -93,7 +153,7 @@ pub struct TransitionResult {
 }
 
 /// Actions the game engine should take after state transitions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GameEngineAction {
     ValidateTokenCommitment {
         match_id: String,
@@ -118,22 +178,84 @@ pub enum GameEngineAction {
         match_id: String,
         winner_npub: Option<String>,
     },
-    PublishLootEvent {
+    ArchiveMatch {
         match_id: String,
-        loot_distribution: LootDistribution,
     },
-    ArchiveMatch {
+    RecordPracticeResult {
         match_id: String,
+        winner_npub: Option<String>,
     },
     InvalidateMatch {
         match_id: String,
         reason: String,
+        offending_npub: Option<String>,
+        evidence_hashes: Vec<String>,
+    },
+    CancelChallenge {
+        match_id: String,
+        challenger_npub: String,
+        reason: String,
+        expired_at: u64,
+    },
+    /// Wagered match ended without a winner; settle both wagers per the draw policy
+    RefundWagers {
+        match_id: String,
+        player_npubs: Vec<String>,
+    },
+    /// Match fee the mint failed to pay after the winner's award was published
+    CollectMatchFee {
+        match_id: String,
+        amount: u64,
     },
 }
 
 impl MatchState {
     /// Create initial challenge state
     pub fn new_challenge(challenge: MatchChallenge) -> Self {
+        if challenge.practice && challenge.wager_amount > 0 {
+            return MatchState::Invalid {
+                reason: format!(
+                    "Practice match cannot carry a wager ({} mana offered)",
+                    challenge.wager_amount
+                ),
+                failed_at: Utc::now(),
+            };
+        }
+
+        if !supports_army_generator(challenge.generator_version) {
+            return MatchState::Invalid {
+                reason: format!(
+                    "Unknown army generator version {}",
+                    challenge.generator_version
+                ),
+                failed_at: Utc::now(),
+            };
+        }
+
+        if challenge.bans_per_player > MAX_BANS_PER_PLAYER {
+            return MatchState::Invalid {
+                reason: format!(
+                    "Challenge asks for {} bans per player (at most {MAX_BANS_PER_PLAYER})",
+                    challenge.bans_per_player
+                ),
+                failed_at: Utc::now(),
+            };
+        }
+
+        if let Some(league) = league::active_registry().get(challenge.league_id) {
+            if !league.allows_wager(challenge.wager_amount) {
+                return MatchState::Invalid {
+                    reason: format!(
+                        "{} caps wagers at {} mana ({} offered)",
+                        league.name,
+                        league.max_wager.unwrap_or_default(),
+                        challenge.wager_amount
+                    ),
+                    failed_at: Utc::now(),
+                };
+            }
+        }
+
         let expires_at = DateTime::from_timestamp(challenge.expires_at as i64, 0)
             .unwrap_or_else(|| Utc::now() + chrono::Duration::minutes(30));
 
@@ -153,12 +275,13 @@ impl MatchState {
             ) => {
                 info!("🤝 Challenge accepted, waiting for token reveals");
 
-                let _match_data = MatchData::new(&challenge, &acceptance);
                 let new_state = MatchState::Accepted {
                     challenge,
                     acceptance,
                     player1_revealed: false,
                     player2_revealed: false,
+                    player1_reveals: PlayerReveals::default(),
+                    player2_reveals: PlayerReveals::default(),
                 };
 
                 TransitionResult {
@@ -175,6 +298,8 @@ impl MatchState {
                     acceptance,
                     mut player1_revealed,
                     mut player2_revealed,
+                    mut player1_reveals,
+                    mut player2_reveals,
                 },
                 MatchEvent::TokenRevealed(reveal),
             ) => {
@@ -183,26 +308,68 @@ impl MatchState {
                     player_npub: reveal.player_npub.clone(),
                 }];
 
-                // Update reveal status
+                // Update reveal status, keeping the tokens for army generation and burning
                 if reveal.player_npub == challenge.challenger_npub {
                     player1_revealed = true;
+                    player1_reveals.cashu_tokens = Some(reveal.cashu_tokens.clone());
+                    player1_reveals.token_nonce = Some(reveal.token_secrets_nonce.clone());
                 } else if reveal.player_npub == acceptance.acceptor_npub {
                     player2_revealed = true;
+                    player2_reveals.cashu_tokens = Some(reveal.cashu_tokens.clone());
+                    player2_reveals.token_nonce = Some(reveal.token_secrets_nonce.clone());
                 }
 
                 // If both revealed, transition to combat
                 if player1_revealed && player2_revealed {
                     info!("🎪 Both players revealed tokens, transitioning to combat");
 
-                    let match_data = MatchData::new(&challenge, &acceptance);
-                    let new_state = MatchState::InCombat {
-                        match_data,
-                        current_round: 1,
-                        completed_rounds: vec![],
-                        player1_committed: vec![],
-                        player2_committed: vec![],
-                        player1_revealed: vec![],
-                        player2_revealed: vec![],
+                    let mut match_data = MatchData::new(&challenge, &acceptance);
+                    match_data.player1_reveals = player1_reveals;
+                    match_data.player2_reveals = player2_reveals;
+                    match_data.generate_armies();
+
+                    // An army outside the league's rules voids the match, blaming its player
+                    if let Some((npub, error)) = match_data.army_violation() {
+                        let reason = format!("Army of {npub} is not allowed: {error}");
+                        warn!("🚨 {}", reason);
+                        actions.push(GameEngineAction::InvalidateMatch {
+                            match_id: reveal.match_event_id.clone(),
+                            reason: reason.clone(),
+                            offending_npub: Some(npub),
+                            evidence_hashes: Vec::new(),
+                        });
+
+                        return TransitionResult {
+                            new_state: MatchState::Invalid {
+                                reason,
+                                failed_at: Utc::now(),
+                            },
+                            actions,
+                            errors: vec![],
+                        };
+                    }
+
+                    // Challenges with bans hold combat until both players banned their units
+                    let new_state = if match_data.bans_per_player > 0 {
+                        info!(
+                            "🚫 Draft phase: {} bans per player",
+                            match_data.bans_per_player
+                        );
+                        MatchState::Drafting {
+                            match_data,
+                            ban_commitments: [None, None],
+                            bans_revealed: [false, false],
+                        }
+                    } else {
+                        MatchState::InCombat {
+                            match_data,
+                            current_round: 1,
+                            completed_rounds: vec![],
+                            player1_committed: vec![],
+                            player2_committed: vec![],
+                            player1_revealed: vec![],
+                            player2_revealed: vec![],
+                        }
                     };
 
                     actions.push(GameEngineAction::GenerateArmies {
@@ -220,6 +387,8 @@ impl MatchState {
                         acceptance,
                         player1_revealed,
                         player2_revealed,
+                        player1_reveals,
+                        player2_reveals,
                     };
 
                     TransitionResult {
@@ -230,35 +399,263 @@ impl MatchState {
                 }
             }
 
-            // Move committed during combat
+            // Ban commitment in the draft phase; the first one per player counts
             (
-                MatchState::InCombat {
+                MatchState::Drafting {
                     match_data,
+                    mut ban_commitments,
+                    bans_revealed,
+                },
+                MatchEvent::BanCommitted(commitment),
+            ) => {
+                let mut errors = vec![];
+                match match_data.player_index(&commitment.player_npub) {
+                    Some(index) if ban_commitments[index].is_none() => {
+                        ban_commitments[index] = Some(commitment.ban_commitment);
+                    }
+                    Some(_) => errors.push(format!(
+                        "{} already committed to their bans",
+                        commitment.player_npub
+                    )),
+                    None => errors.push(format!(
+                        "Ban commitment from {}, who is not playing",
+                        commitment.player_npub
+                    )),
+                }
+
+                TransitionResult {
+                    new_state: MatchState::Drafting {
+                        match_data,
+                        ban_commitments,
+                        bans_revealed,
+                    },
+                    actions: vec![],
+                    errors,
+                }
+            }
+
+            // Ban reveal before both players committed - ignore it, it would leak the bans
+            (
+                state @ MatchState::Drafting {
+                    ban_commitments: [None, _] | [_, None],
+                    ..
+                },
+                MatchEvent::BansRevealed(reveal),
+            ) => {
+                let error = format!(
+                    "{} revealed bans before both players committed",
+                    reveal.player_npub
+                );
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
+
+            // Ban reveal - a mismatch with the commitment or an illegal ban voids the match
+            (
+                MatchState::Drafting {
+                    mut match_data,
+                    ban_commitments,
+                    mut bans_revealed,
+                },
+                MatchEvent::BansRevealed(reveal),
+            ) => {
+                let Some(index) = match_data.player_index(&reveal.player_npub) else {
+                    let error =
+                        format!("Ban reveal from {}, who is not playing", reveal.player_npub);
+                    return TransitionResult {
+                        new_state: MatchState::Drafting {
+                            match_data,
+                            ban_commitments,
+                            bans_revealed,
+                        },
+                        actions: vec![],
+                        errors: vec![error],
+                    };
+                };
+
+                let commitment = ban_commitments[index].as_deref().unwrap_or_default();
+                let committed =
+                    verify_bans_commitment(commitment, &reveal.banned_units, &reveal.ban_nonce);
+                let violation = if committed {
+                    match_data.ban_violation(&reveal.banned_units)
+                } else {
+                    Some("ban reveal does not match its commitment".to_string())
+                };
+                if let Some(violation) = violation {
+                    let reason = format!(
+                        "Bans of {} are not allowed: {violation}",
+                        reveal.player_npub
+                    );
+                    warn!("🚨 {}", reason);
+                    return TransitionResult {
+                        new_state: MatchState::Invalid {
+                            reason: reason.clone(),
+                            failed_at: Utc::now(),
+                        },
+                        actions: vec![GameEngineAction::InvalidateMatch {
+                            match_id: reveal.match_event_id.clone(),
+                            reason,
+                            offending_npub: Some(reveal.player_npub.clone()),
+                            evidence_hashes: vec![commitment.to_string()],
+                        }],
+                        errors: vec![],
+                    };
+                }
+
+                // A player bans units from the opponent's army
+                if !bans_revealed[index] {
+                    bans_revealed[index] = true;
+                    match_data.banned_units[1 - index] = reveal.banned_units;
+                }
+
+                let new_state = if bans_revealed == [true, true] {
+                    info!(
+                        "🚫 Bans revealed ({:?}), transitioning to combat",
+                        match_data.banned_units
+                    );
+                    MatchState::InCombat {
+                        match_data,
+                        current_round: 1,
+                        completed_rounds: vec![],
+                        player1_committed: vec![],
+                        player2_committed: vec![],
+                        player1_revealed: vec![],
+                        player2_revealed: vec![],
+                    }
+                } else {
+                    MatchState::Drafting {
+                        match_data,
+                        ban_commitments,
+                        bans_revealed,
+                    }
+                };
+
+                TransitionResult {
+                    new_state,
+                    actions: vec![],
+                    errors: vec![],
+                }
+            }
+
+            // Combat move past the league's last round - ignore it
+            (state @ MatchState::InCombat { .. }, MatchEvent::CombatMoveSubmitted(combat_move))
+                if state
+                    .league()
+                    .is_some_and(|league| !league.allows_round(combat_move.round_number)) =>
+            {
+                let error = format!(
+                    "Round {} is beyond the league's round limit",
+                    combat_move.round_number
+                );
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
+
+            // Combat move after the match format is already decided - ignore it
+            (state @ MatchState::InCombat { .. }, MatchEvent::CombatMoveSubmitted(combat_move))
+                if state.format_decided() =>
+            {
+                let error = format!(
+                    "Round {} submitted after the match was decided",
+                    combat_move.round_number
+                );
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
+
+            // Combat move submitted (turn-based, the move is its own reveal)
+            (state @ MatchState::InCombat { .. }, MatchEvent::CombatMoveSubmitted(combat_move)) => {
+                // Reject a move with an unknown, unaffordable or cooling down ability,
+                // or one fielding a unit banned in the draft phase
+                let rejected = state
+                    .ability_error(&combat_move)
+                    .or_else(|| state.ban_error(&combat_move));
+                if let Some(error) = rejected {
+                    return TransitionResult {
+                        new_state: state,
+                        actions: vec![],
+                        errors: vec![error],
+                    };
+                }
+
+                let MatchState::InCombat {
+                    mut match_data,
                     current_round,
-                    completed_rounds,
+                    mut completed_rounds,
                     mut player1_committed,
                     mut player2_committed,
-                    player1_revealed,
-                    player2_revealed,
-                },
-                MatchEvent::CombatMoveSubmitted(combat_move),
-            ) => {
+                    mut player1_revealed,
+                    mut player2_revealed,
+                } = state
+                else {
+                    unreachable!()
+                };
+
                 let round = combat_move.round_number;
-                let actions = vec![GameEngineAction::ValidateCombatMove {
+                let mut actions = vec![GameEngineAction::ValidateCombatMove {
                     match_id: combat_move.match_event_id.clone(),
                     player_npub: combat_move.player_npub.clone(),
                     round,
                 }];
 
-                // Track combat move (turn-based, no commitment needed)
+                // Track combat move and keep it for round resolution
+                let moves = (
+                    combat_move.unit_positions.clone(),
+                    combat_move.unit_abilities.clone(),
+                    String::new(), // Turn-based moves carry no nonce
+                );
                 if combat_move.player_npub == match_data.player1_npub {
                     if !player1_committed.contains(&round) {
                         player1_committed.push(round);
+                        player1_revealed.push(round);
+                        match_data
+                            .player1_reveals
+                            .moves_by_round
+                            .insert(round, moves);
                     }
                 } else if combat_move.player_npub == match_data.player2_npub
-                    && !player2_committed.contains(&round) {
-                        player2_committed.push(round);
+                    && !player2_committed.contains(&round)
+                {
+                    player2_committed.push(round);
+                    player2_revealed.push(round);
+                    match_data
+                        .player2_reveals
+                        .moves_by_round
+                        .insert(round, moves);
+                }
+
+                // Check if round is complete (both players moved), resolving it once
+                if player1_revealed.contains(&round)
+                    && player2_revealed.contains(&round)
+                    && !completed_rounds.contains(&round)
+                {
+                    completed_rounds.push(round);
+                    actions.push(GameEngineAction::ExecuteCombatRound {
+                        match_id: combat_move.match_event_id.clone(),
+                        round,
+                    });
+
+                    if let Some(combat) = match_data.round_combat(round) {
+                        match_data.game_wins = combat.score;
+                    }
+                    if match_data.format_decided(completed_rounds.len() as u32) {
+                        info!(
+                            "🏅 Match decided after {} games ({:?}), winner: {:?}",
+                            completed_rounds.len(),
+                            match_data.game_wins,
+                            match_data.format_winner()
+                        );
                     }
+                }
 
                 let new_state = MatchState::InCombat {
                     match_data,
@@ -277,48 +674,84 @@ impl MatchState {
                 }
             }
 
-            // Move revealed during combat
-            (
-                MatchState::InCombat {
-                    match_data,
-                    current_round,
-                    completed_rounds,
-                    player1_committed,
-                    player2_committed,
-                    mut player1_revealed,
-                    mut player2_revealed,
-                },
-                MatchEvent::CombatMoveSubmitted(combat_move),
-            ) => {
-                let round = combat_move.round_number;
-                let mut actions = vec![];
+            // Result computed by a client built against other rules - reject it
+            (state @ MatchState::InCombat { .. }, MatchEvent::ResultSubmitted(result))
+                if result.rules_error().is_some() =>
+            {
+                let error = result.rules_error().unwrap_or_default();
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
 
-                // Track combat move (turn-based)
-                if combat_move.player_npub == match_data.player1_npub {
-                    if !player1_revealed.contains(&round) {
-                        player1_revealed.push(round);
-                    }
-                } else if combat_move.player_npub == match_data.player2_npub
-                    && !player2_revealed.contains(&round) {
-                        player2_revealed.push(round);
-                    }
+            // Result submitted before the engine-tracked games decided the match
+            (state @ MatchState::InCombat { .. }, MatchEvent::ResultSubmitted(_))
+                if state.games_played() > 0 && !state.format_decided() =>
+            {
+                let error = format!(
+                    "Result submitted after {} games, before the match format was decided",
+                    state.games_played()
+                );
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
 
-                // Check if round is complete (both players revealed)
-                if player1_revealed.contains(&round) && player2_revealed.contains(&round) {
-                    actions.push(GameEngineAction::ExecuteCombatRound {
-                        match_id: combat_move.match_event_id.clone(),
-                        round,
-                    });
+            // Result claims a winner the decided games do not support
+            (state @ MatchState::InCombat { .. }, MatchEvent::ResultSubmitted(result))
+                if state.format_decided() && result.calculated_winner != state.format_winner() =>
+            {
+                let reason = format!(
+                    "Submitted winner {:?} contradicts the decided games (winner {:?})",
+                    result.calculated_winner,
+                    state.format_winner()
+                );
+                warn!("🚨 {}", reason);
+
+                let actions = vec![GameEngineAction::InvalidateMatch {
+                    match_id: result.match_event_id.clone(),
+                    reason: reason.clone(),
+                    offending_npub: Some(result.player_npub.clone()),
+                    evidence_hashes: Vec::new(),
+                }];
+
+                TransitionResult {
+                    new_state: MatchState::Invalid {
+                        reason,
+                        failed_at: Utc::now(),
+                    },
+                    actions,
+                    errors: vec![],
                 }
+            }
 
-                let new_state = MatchState::InCombat {
+            // Practice match result submitted - validate and record, no escrow or loot
+            (MatchState::InCombat { match_data, .. }, MatchEvent::ResultSubmitted(result))
+                if match_data.practice =>
+            {
+                info!("🏁 Practice match result submitted, recording without loot");
+
+                let match_id = result.match_event_id.clone();
+                let actions = vec![
+                    GameEngineAction::ValidateMatchResult {
+                        match_id: match_id.clone(),
+                    },
+                    GameEngineAction::RecordPracticeResult {
+                        match_id: match_id.clone(),
+                        winner_npub: result.calculated_winner.clone(),
+                    },
+                    GameEngineAction::ArchiveMatch { match_id },
+                ];
+
+                let new_state = MatchState::Completed {
                     match_data,
-                    current_round,
-                    completed_rounds,
-                    player1_committed,
-                    player2_committed,
-                    player1_revealed,
-                    player2_revealed,
+                    result,
+                    loot_distribution: None,
+                    completed_at: Utc::now(),
                 };
 
                 TransitionResult {
@@ -328,20 +761,20 @@ impl MatchState {
                 }
             }
 
-            // Match result submitted
+            // Match result submitted; the engine replays it before paying out or refunding
             (MatchState::InCombat { match_data, .. }, MatchEvent::ResultSubmitted(result)) => {
                 info!("🏁 Match result submitted, transitioning to validation");
 
+                let actions = vec![GameEngineAction::ValidateMatchResult {
+                    match_id: result.match_event_id.clone(),
+                }];
+
                 let new_state = MatchState::AwaitingValidation {
                     match_data,
-                    result: result.clone(),
+                    result,
                     submitted_at: Utc::now(),
                 };
 
-                let actions = vec![GameEngineAction::ValidateMatchResult {
-                    match_id: result.match_event_id.clone(),
-                }];
-
                 TransitionResult {
                     new_state,
                     actions,
@@ -349,7 +782,7 @@ impl MatchState {
                 }
             }
 
-            // Loot distributed - final state
+            // Loot award published by the payout - final state
             (
                 MatchState::AwaitingValidation {
                     match_data, result, ..
@@ -359,22 +792,14 @@ impl MatchState {
                 info!("🏆 Loot distributed, match completed");
 
                 let match_id = loot_distribution.match_event_id.clone();
-                let loot_distribution_clone = loot_distribution.clone();
-
                 let new_state = MatchState::Completed {
                     match_data,
                     result,
-                    loot_distribution: loot_distribution_clone,
+                    loot_distribution: Some(loot_distribution),
                     completed_at: Utc::now(),
                 };
 
-                let actions = vec![
-                    GameEngineAction::PublishLootEvent {
-                        match_id: match_id.clone(),
-                        loot_distribution,
-                    },
-                    GameEngineAction::ArchiveMatch { match_id },
-                ];
+                let actions = vec![GameEngineAction::ArchiveMatch { match_id }];
 
                 TransitionResult {
                     new_state,
@@ -383,26 +808,85 @@ impl MatchState {
                 }
             }
 
+            // Drawn match already went to sudden death as often as allowed
+            (state @ MatchState::AwaitingValidation { .. }, MatchEvent::SuddenDeathOrdered)
+                if state.sudden_death_games() >= MAX_SUDDEN_DEATH_GAMES =>
+            {
+                let error =
+                    format!("Match already played {MAX_SUDDEN_DEATH_GAMES} sudden-death games");
+                TransitionResult {
+                    new_state: state,
+                    actions: vec![],
+                    errors: vec![error],
+                }
+            }
+
+            // Drawn wagered match reopens combat for one more deciding game
+            (
+                MatchState::AwaitingValidation {
+                    mut match_data,
+                    result,
+                    ..
+                },
+                MatchEvent::SuddenDeathOrdered,
+            ) if !match_data.practice && result.calculated_winner.is_none() => {
+                match_data.sudden_death_games += 1;
+                let player1_moved = sorted_rounds(&match_data.player1_reveals);
+                let player2_moved = sorted_rounds(&match_data.player2_reveals);
+                let completed_rounds: Vec<u32> = player1_moved
+                    .iter()
+                    .copied()
+                    .filter(|round| player2_moved.contains(round))
+                    .collect();
+                let current_round = completed_rounds.iter().max().copied().unwrap_or(0) + 1;
+                info!(
+                    "⚡ Sudden death for match {}, deciding game is round {}",
+                    match_data.match_event_id, current_round
+                );
+
+                let new_state = MatchState::InCombat {
+                    match_data,
+                    current_round,
+                    completed_rounds,
+                    player1_committed: player1_moved.clone(),
+                    player2_committed: player2_moved.clone(),
+                    player1_revealed: player1_moved,
+                    player2_revealed: player2_moved,
+                };
+
+                TransitionResult {
+                    new_state,
+                    actions: vec![],
+                    errors: vec![],
+                }
+            }
+
             // Invalidation at any point
-            (state, MatchEvent::InvalidationTriggered(reason)) => {
-                warn!("🚨 Match invalidated: {}", reason);
+            (state, MatchEvent::InvalidationTriggered(invalidation)) => {
+                warn!("🚨 Match invalidated: {}", invalidation.reason);
 
                 let new_state = MatchState::Invalid {
-                    reason: reason.clone(),
+                    reason: invalidation.reason.clone(),
                     failed_at: Utc::now(),
                 };
 
                 let match_id = match state {
                     MatchState::Challenged { challenge, .. } => challenge.challenger_npub.clone(),
                     MatchState::Accepted { acceptance, .. } => acceptance.match_event_id.clone(),
-                    MatchState::InCombat { match_data, .. } => match_data.match_event_id.clone(),
+                    MatchState::Drafting { match_data, .. }
+                    | MatchState::InCombat { match_data, .. } => match_data.match_event_id.clone(),
                     MatchState::AwaitingValidation { match_data, .. } => {
                         match_data.match_event_id.clone()
                     }
                     _ => "unknown".to_string(),
                 };
 
-                let actions = vec![GameEngineAction::InvalidateMatch { match_id, reason }];
+                let actions = vec![GameEngineAction::InvalidateMatch {
+                    match_id,
+                    reason: invalidation.reason,
+                    offending_npub: invalidation.offending_npub,
+                    evidence_hashes: invalidation.evidence_hashes,
+                }];
 
                 TransitionResult {
                     new_state,
@@ -440,7 +924,9 @@ impl MatchState {
                 Some(format!("challenge_{}", challenge.challenger_npub))
             }
             MatchState::Accepted { acceptance, .. } => Some(acceptance.match_event_id.clone()),
-            MatchState::InCombat { match_data, .. } => Some(match_data.match_event_id.clone()),
+            MatchState::Drafting { match_data, .. } | MatchState::InCombat { match_data, .. } => {
+                Some(match_data.match_event_id.clone())
+            }
             MatchState::AwaitingValidation { match_data, .. } => {
                 Some(match_data.match_event_id.clone())
             }
@@ -449,11 +935,243 @@ impl MatchState {
         }
     }
 
+    /// Check if this is a zero-wager practice match
+    pub fn is_practice(&self) -> bool {
+        match self {
+            MatchState::Challenged { challenge, .. } | MatchState::Accepted { challenge, .. } => {
+                challenge.practice
+            }
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.practice,
+            MatchState::Invalid { .. } => false,
+        }
+    }
+
+    /// Whether the match was negotiated over encrypted direct messages
+    pub fn is_private(&self) -> bool {
+        match self {
+            MatchState::Challenged { challenge, .. } | MatchState::Accepted { challenge, .. } => {
+                challenge.private
+            }
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.private,
+            MatchState::Invalid { .. } => false,
+        }
+    }
+
+    /// Mana each player wagered on the match
+    pub fn wager_amount(&self) -> u64 {
+        match self {
+            MatchState::Challenged { challenge, .. } | MatchState::Accepted { challenge, .. } => {
+                challenge.wager_amount
+            }
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.wager_amount,
+            MatchState::Invalid { .. } => 0,
+        }
+    }
+
+    /// League the match is played in (None once invalidated)
+    pub fn league_id(&self) -> Option<u8> {
+        match self {
+            MatchState::Challenged { challenge, .. } | MatchState::Accepted { challenge, .. } => {
+                Some(challenge.league_id)
+            }
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => Some(match_data.league_id as u8),
+            MatchState::Invalid { .. } => None,
+        }
+    }
+
+    /// Rules of the league the match is played in, if the active registry defines it
+    pub fn league(&self) -> Option<&'static LeagueDefinition> {
+        league::active_registry().get(self.league_id()?)
+    }
+
+    /// Combat rounds (games) resolved so far
+    pub fn games_played(&self) -> u32 {
+        match self {
+            MatchState::InCombat {
+                completed_rounds, ..
+            } => completed_rounds.len() as u32,
+            _ => 0,
+        }
+    }
+
+    /// Whether the games played so far meet the match format's win condition
+    pub fn format_decided(&self) -> bool {
+        match self {
+            MatchState::InCombat { match_data, .. } => {
+                match_data.format_decided(self.games_played())
+            }
+            _ => false,
+        }
+    }
+
+    /// Challenge nobody accepted before its expiry, if this state is one
+    pub fn expired_challenge(&self, now: DateTime<Utc>) -> Option<&MatchChallenge> {
+        match self {
+            MatchState::Challenged {
+                challenge,
+                expires_at,
+            } if *expires_at <= now => Some(challenge),
+            _ => None,
+        }
+    }
+
+    /// Extra games granted so far to break a draw
+    pub fn sudden_death_games(&self) -> u32 {
+        match self {
+            MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.sudden_death_games,
+            _ => 0,
+        }
+    }
+
+    /// Leader on game wins, or None while tied
+    pub fn format_winner(&self) -> Option<String> {
+        match self {
+            MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.format_winner(),
+            _ => None,
+        }
+    }
+
+    /// Npubs of the players known so far (challenger first)
+    pub fn players(&self) -> Vec<String> {
+        match self {
+            MatchState::Challenged { challenge, .. } => vec![challenge.challenger_npub.clone()],
+            MatchState::Accepted {
+                challenge,
+                acceptance,
+                ..
+            } => vec![
+                challenge.challenger_npub.clone(),
+                acceptance.acceptor_npub.clone(),
+            ],
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => vec![
+                match_data.player1_npub.clone(),
+                match_data.player2_npub.clone(),
+            ],
+            MatchState::Invalid { .. } => Vec::new(),
+        }
+    }
+
+    /// Engine actions a match in this state still waits on
+    ///
+    /// Re-derived for matches restored after a restart, whose queued actions were lost.
+    /// Only a submitted result awaits the engine; every other live state awaits players.
+    pub fn pending_actions(&self) -> Vec<GameEngineAction> {
+        match self {
+            MatchState::AwaitingValidation { match_data, .. } => {
+                vec![GameEngineAction::ValidateMatchResult {
+                    match_id: match_data.match_event_id.clone(),
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the match is waiting on token reveals and the npub is one of its players
+    pub fn awaits_reveal_from(&self, player_npub: &str) -> bool {
+        matches!(self, MatchState::Accepted { .. })
+            && self.players().iter().any(|npub| npub == player_npub)
+    }
+
+    /// Mana token secrets both players revealed for this match
+    pub fn revealed_mana_tokens(&self) -> Vec<String> {
+        match self {
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => [
+                &match_data.player1_reveals.cashu_tokens,
+                &match_data.player2_reveals.cashu_tokens,
+            ]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .cloned()
+            .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mana token secrets each player revealed, keyed by the player's npub
+    pub fn revealed_mana_tokens_by_player(&self) -> Vec<(String, Vec<String>)> {
+        match self {
+            MatchState::Drafting { match_data, .. }
+            | MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => [
+                (&match_data.player1_npub, &match_data.player1_reveals),
+                (&match_data.player2_npub, &match_data.player2_reveals),
+            ]
+            .into_iter()
+            .map(|(npub, reveals)| {
+                (
+                    npub.clone(),
+                    reveals.cashu_tokens.clone().unwrap_or_default(),
+                )
+            })
+            .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Why the move's abilities are not allowed, given the player's earlier rounds
+    pub fn ability_error(&self, combat_move: &CombatMove) -> Option<String> {
+        match self {
+            MatchState::InCombat { match_data, .. } => match_data
+                .check_abilities(
+                    &combat_move.player_npub,
+                    combat_move.round_number,
+                    &combat_move.unit_abilities,
+                )
+                .err(),
+            _ => None,
+        }
+    }
+
+    /// Why the move is not allowed, given the units banned from the player's army
+    pub fn ban_error(&self, combat_move: &CombatMove) -> Option<String> {
+        match self {
+            MatchState::InCombat { match_data, .. } => match_data
+                .check_bans(&combat_move.player_npub, &combat_move.unit_positions)
+                .err(),
+            _ => None,
+        }
+    }
+
+    /// Spectator view of a resolved combat round
+    pub fn round_combat(&self, round: u32) -> Option<RoundCombat> {
+        match self {
+            MatchState::InCombat { match_data, .. }
+            | MatchState::AwaitingValidation { match_data, .. }
+            | MatchState::Completed { match_data, .. } => match_data.round_combat(round),
+            _ => None,
+        }
+    }
+
     /// Get current phase as string for logging
     pub fn phase_name(&self) -> &str {
         match self {
             MatchState::Challenged { .. } => "Challenged",
             MatchState::Accepted { .. } => "Accepted",
+            MatchState::Drafting { .. } => "Drafting",
             MatchState::InCombat { .. } => "InCombat",
             MatchState::AwaitingValidation { .. } => "AwaitingValidation",
             MatchState::Completed { .. } => "Completed",
@@ -471,6 +1189,15 @@ impl MatchData {
             player2_npub: acceptance.acceptor_npub.clone(),
             league_id: challenge.league_id as u32,
             wager_amount: challenge.wager_amount,
+            practice: challenge.practice,
+            match_format: challenge.match_format,
+            private: challenge.private,
+            game_wins: [0, 0],
+            sudden_death_games: 0,
+            generator_version: challenge.generator_version,
+            created_at: challenge.created_at,
+            bans_per_player: challenge.bans_per_player,
+            banned_units: [Vec::new(), Vec::new()],
 
             player1_commitments: PlayerCommitments {
                 cashu_tokens: Some(challenge.cashu_token_commitment.clone()),
@@ -489,4 +1216,595 @@ impl MatchData {
             player2_army: None,
         }
     }
+
+    /// A player reached the format's win threshold, or every game has been played
+    pub fn format_decided(&self, games_played: u32) -> bool {
+        let wins_needed = self.match_format.wins_needed();
+        self.game_wins.iter().any(|wins| *wins >= wins_needed)
+            || games_played >= self.match_format.max_games() + self.sudden_death_games
+    }
+
+    /// Leader on game wins, or None while tied
+    pub fn format_winner(&self) -> Option<String> {
+        match self.game_wins[0].cmp(&self.game_wins[1]) {
+            std::cmp::Ordering::Greater => Some(self.player1_npub.clone()),
+            std::cmp::Ordering::Less => Some(self.player2_npub.clone()),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Verifier for the game mode played in this match's league
+    pub fn verifier(&self) -> &'static dyn MatchVerifier {
+        verifier_for(self.league_id as u8)
+    }
+
+    /// Derive both armies from the players' revealed tokens
+    pub fn generate_armies(&mut self) {
+        let [player1_army, player2_army] = self.verifier().generate_armies(self);
+        self.player1_army = player1_army;
+        self.player2_army = player2_army;
+    }
+
+    /// First player whose army breaks the league's size, ability or point budget rules
+    pub fn army_violation(&self) -> Option<(String, ArmyValidationError)> {
+        let league = league::active_registry().get(self.league_id as u8)?;
+        [
+            (&self.player1_npub, &self.player1_army),
+            (&self.player2_npub, &self.player2_army),
+        ]
+        .into_iter()
+        .find_map(|(npub, army)| {
+            let army = army.as_ref()?;
+            validate_army_for(army, league)
+                .err()
+                .map(|error| (npub.clone(), error))
+        })
+    }
+
+    /// Index of the player in this match's [player1, player2] arrays
+    pub fn player_index(&self, player_npub: &str) -> Option<usize> {
+        if player_npub == self.player1_npub {
+            Some(0)
+        } else if player_npub == self.player2_npub {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Why a player's revealed bans break the challenge's draft rules, if they do
+    pub fn ban_violation(&self, banned_units: &[u8]) -> Option<String> {
+        let mut unique = banned_units.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+        if banned_units.len() != self.bans_per_player as usize {
+            return Some(format!(
+                "{} units banned, the challenge allows {}",
+                banned_units.len(),
+                self.bans_per_player
+            ));
+        }
+        if unique.len() != banned_units.len() {
+            return Some("the same unit is banned twice".to_string());
+        }
+        banned_units
+            .iter()
+            .find(|unit| **unit >= 8)
+            .map(|unit| format!("unit {unit} is outside the 8-unit army"))
+    }
+
+    /// Whether the unit at the index of the player's army was banned in the draft phase
+    pub fn is_banned(&self, player_index: usize, unit_index: usize) -> bool {
+        self.banned_units[player_index]
+            .iter()
+            .any(|unit| *unit as usize == unit_index)
+    }
+
+    /// Check that a player's move does not field a unit banned from their army
+    pub fn check_bans(&self, player_npub: &str, positions: &[u8]) -> Result<(), String> {
+        let Some(index) = self.player_index(player_npub) else {
+            return Ok(());
+        };
+        let unit = fielded_unit(positions);
+        if self.is_banned(index, unit) {
+            return Err(format!("Unit {unit} was banned in the draft phase"));
+        }
+        Ok(())
+    }
+
+    /// Check a player's abilities for the round against the rounds they already moved in
+    pub fn check_abilities(
+        &self,
+        player_npub: &str,
+        round: u32,
+        abilities: &[String],
+    ) -> Result<(), String> {
+        let reveals = if player_npub == self.player1_npub {
+            &self.player1_reveals
+        } else if player_npub == self.player2_npub {
+            &self.player2_reveals
+        } else {
+            return Ok(());
+        };
+
+        let mut ledger = AbilityLedger::default();
+        for earlier in sorted_rounds(reveals).into_iter().filter(|r| *r < round) {
+            ledger
+                .use_abilities(earlier, &reveals.moves_by_round[&earlier].1)
+                .map_err(|e| e.to_string())?;
+        }
+        ledger
+            .use_abilities(round, abilities)
+            .map(|_| ())
+            .map_err(|e| format!("Round {round} abilities rejected: {e}"))
+    }
+
+    /// Mana each player has banked after the rounds they moved in, indexed [player1, player2]
+    pub fn mana(&self) -> [u8; 2] {
+        [&self.player1_reveals, &self.player2_reveals].map(|reveals| {
+            let mut ledger = AbilityLedger::default();
+            for round in sorted_rounds(reveals) {
+                // Recorded moves already passed check_abilities
+                if ledger
+                    .use_abilities(round, &reveals.moves_by_round[&round].1)
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            ledger.mana()
+        })
+    }
+
+    /// Resolve combat up to the given round for spectators
+    ///
+    /// Rounds are re-executed deterministically by the league's verifier from the
+    /// cached armies and revealed moves, so the totals match what validation will compute.
+    /// Reveals already resolved are served from the combat cache.
+    pub fn round_combat(&self, round: u32) -> Option<RoundCombat> {
+        combat_cache().resolve(self, round, || self.verifier().round_combat(self, round))
+    }
+}
+
+/// Rounds a player has moved in, in order
+fn sorted_rounds(reveals: &PlayerReveals) -> Vec<u32> {
+    let mut rounds: Vec<u32> = reveals.moves_by_round.keys().copied().collect();
+    rounds.sort_unstable();
+    rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_events::test_support;
+    use shared_game_logic::commitment::commit_to_bans;
+
+    fn challenge(wager_amount: u64, practice: bool) -> MatchChallenge {
+        MatchChallenge {
+            wager_amount,
+            practice,
+            ..test_support::challenge("match_1")
+        }
+    }
+
+    fn acceptance() -> MatchAcceptance {
+        test_support::acceptance("match_1")
+    }
+
+    fn in_combat(challenge: MatchChallenge) -> MatchState {
+        MatchState::InCombat {
+            match_data: MatchData::new(&challenge, &acceptance()),
+            current_round: 1,
+            completed_rounds: vec![],
+            player1_committed: vec![],
+            player2_committed: vec![],
+            player1_revealed: vec![],
+            player2_revealed: vec![],
+        }
+    }
+
+    fn result() -> MatchResult {
+        MatchResult {
+            player_npub: "npub1alice".to_string(),
+            match_event_id: "match_1".to_string(),
+            final_army_state: serde_json::json!({}),
+            all_round_results: vec![],
+            calculated_winner: Some("npub1alice".to_string()),
+            match_completed_at: 1690000400,
+            rules_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_practice_match_completes_without_loot() {
+        let transition =
+            in_combat(challenge(0, true)).transition(MatchEvent::ResultSubmitted(result()));
+
+        assert!(transition.new_state.is_practice());
+        assert!(matches!(
+            transition.new_state,
+            MatchState::Completed {
+                loot_distribution: None,
+                ..
+            }
+        ));
+        assert!(transition
+            .actions
+            .iter()
+            .any(|action| matches!(action, GameEngineAction::ValidateMatchResult { .. })));
+        assert!(transition
+            .actions
+            .iter()
+            .any(|action| matches!(action, GameEngineAction::RecordPracticeResult { .. })));
+    }
+
+    #[test]
+    fn test_wagered_match_awaits_validation() {
+        let transition =
+            in_combat(challenge(100, false)).transition(MatchEvent::ResultSubmitted(result()));
+
+        assert!(!transition.new_state.is_practice());
+        assert_eq!(transition.new_state.phase_name(), "AwaitingValidation");
+        // A restart before validation ran queues it again
+        assert!(matches!(
+            transition.new_state.pending_actions().as_slice(),
+            [GameEngineAction::ValidateMatchResult { .. }]
+        ));
+        assert!(in_combat(challenge(100, false))
+            .pending_actions()
+            .is_empty());
+    }
+
+    fn reveal(player_npub: &str, secret: &str) -> MatchEvent {
+        MatchEvent::TokenRevealed(TokenReveal {
+            player_npub: player_npub.to_string(),
+            match_event_id: "match_1".to_string(),
+            cashu_tokens: vec![secret.to_string()],
+            token_secrets_nonce: "nonce".to_string(),
+            revealed_at: 1690000200,
+        })
+    }
+
+    fn combat_move(player_npub: &str, round_number: u32) -> MatchEvent {
+        combat_move_using(player_npub, round_number, &[])
+    }
+
+    fn combat_move_using(player_npub: &str, round_number: u32, abilities: &[&str]) -> MatchEvent {
+        MatchEvent::CombatMoveSubmitted(CombatMove {
+            player_npub: player_npub.to_string(),
+            match_event_id: "match_1".to_string(),
+            previous_event_hash: None,
+            round_number,
+            unit_positions: vec![round_number as u8],
+            unit_abilities: abilities.iter().map(|a| a.to_string()).collect(),
+            move_timestamp: 1690000300,
+        })
+    }
+
+    #[test]
+    fn test_only_players_reveal_while_reveals_are_open() {
+        let state = MatchState::new_challenge(challenge(100, false));
+        assert!(!state.awaits_reveal_from("npub1alice"));
+
+        let state = state
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state;
+        assert!(state.awaits_reveal_from("npub1alice"));
+        assert!(state.awaits_reveal_from("npub1bob"));
+        assert!(!state.awaits_reveal_from("npub1mallory"));
+
+        let state = state
+            .transition(reveal("npub1alice", "alice_secret"))
+            .new_state
+            .transition(reveal("npub1bob", "bob_secret"))
+            .new_state;
+        assert!(!state.awaits_reveal_from("npub1alice"));
+    }
+
+    #[test]
+    fn test_completed_round_is_resolved_once() {
+        let state = MatchState::new_challenge(challenge(100, false))
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state
+            .transition(reveal("npub1alice", "alice_secret"))
+            .new_state
+            .transition(reveal("npub1bob", "bob_secret"))
+            .new_state;
+        assert_eq!(
+            state.revealed_mana_tokens(),
+            vec!["alice_secret".to_string(), "bob_secret".to_string()]
+        );
+
+        let state = state.transition(combat_move("npub1alice", 1)).new_state;
+        assert!(state.round_combat(1).is_none());
+
+        let transition = state.transition(combat_move("npub1bob", 1));
+        assert!(transition.actions.iter().any(|action| matches!(
+            action,
+            GameEngineAction::ExecuteCombatRound { round: 1, .. }
+        )));
+
+        let combat = transition.new_state.round_combat(1).unwrap();
+        assert_eq!(
+            combat.score.iter().sum::<u32>(),
+            u32::from(combat.round_winner.is_some())
+        );
+
+        let replay = transition.new_state.transition(combat_move("npub1bob", 1));
+        assert!(!replay
+            .actions
+            .iter()
+            .any(|action| matches!(action, GameEngineAction::ExecuteCombatRound { .. })));
+    }
+
+    fn combat_after_reveals(match_format: MatchFormat) -> MatchState {
+        let challenge = MatchChallenge {
+            match_format,
+            ..challenge(100, false)
+        };
+        MatchState::new_challenge(challenge)
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state
+            .transition(reveal("npub1alice", "alice_secret"))
+            .new_state
+            .transition(reveal("npub1bob", "bob_secret"))
+            .new_state
+    }
+
+    #[test]
+    fn test_result_waits_for_best_of_five_decision() {
+        let state = combat_after_reveals(MatchFormat::BestOf5)
+            .transition(combat_move("npub1alice", 1))
+            .new_state
+            .transition(combat_move("npub1bob", 1))
+            .new_state;
+        assert_eq!(state.games_played(), 1);
+        assert!(!state.format_decided());
+
+        let transition = state.transition(MatchEvent::ResultSubmitted(result()));
+        assert_eq!(transition.new_state.phase_name(), "InCombat");
+        assert_eq!(transition.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_single_round_format_is_decided_after_one_game() {
+        let state = combat_after_reveals(MatchFormat::SingleRound)
+            .transition(combat_move("npub1alice", 1))
+            .new_state
+            .transition(combat_move("npub1bob", 1))
+            .new_state;
+        assert!(state.format_decided());
+
+        let extra = state.clone().transition(combat_move("npub1alice", 2));
+        assert_eq!(extra.errors.len(), 1);
+        assert_eq!(extra.new_state, state);
+
+        let false_claim = MatchResult {
+            calculated_winner: Some("npub1mallory".to_string()),
+            ..result()
+        };
+        let transition = state.transition(MatchEvent::ResultSubmitted(false_claim));
+        assert_eq!(transition.new_state.phase_name(), "Invalid");
+        assert!(transition.actions.iter().any(|action| matches!(
+            action,
+            GameEngineAction::InvalidateMatch {
+                offending_npub: Some(npub),
+                ..
+            } if npub == "npub1alice"
+        )));
+    }
+
+    #[test]
+    fn test_ability_on_cooldown_is_rejected() {
+        let boost = |player_npub: &str, round_number: u32| {
+            combat_move_using(player_npub, round_number, &["boost"])
+        };
+        let state = combat_after_reveals(MatchFormat::BestOf5)
+            .transition(boost("npub1alice", 1))
+            .new_state
+            .transition(combat_move("npub1bob", 1))
+            .new_state;
+
+        // Both gained 2 mana in round 1, Alice spent hers on Boost
+        let MatchState::InCombat { match_data, .. } = &state else {
+            panic!("expected combat, got {}", state.phase_name());
+        };
+        assert_eq!(match_data.mana(), [0, 2]);
+
+        // Boost's cooldown runs through round 2, so that move is not recorded
+        let rejected = state.clone().transition(boost("npub1alice", 2));
+        assert_eq!(rejected.errors.len(), 1);
+        assert!(rejected.errors[0].contains("cooldown"));
+        assert_eq!(rejected.new_state, state);
+
+        let accepted = state.transition(boost("npub1alice", 3));
+        assert!(accepted.errors.is_empty());
+    }
+
+    #[test]
+    fn test_result_under_other_rules_is_rejected() {
+        let state = in_combat(challenge(100, false));
+        let stale = MatchResult {
+            rules_hash: Some("v1:stale".to_string()),
+            ..result()
+        };
+        let rejected = state.clone().transition(MatchEvent::ResultSubmitted(stale));
+        assert_eq!(rejected.errors.len(), 1);
+        assert_eq!(rejected.new_state, state);
+
+        let current = MatchResult {
+            rules_hash: Some(shared_game_logic::rules_hash().to_string()),
+            ..result()
+        };
+        let accepted = state.transition(MatchEvent::ResultSubmitted(current));
+        assert_eq!(accepted.new_state.phase_name(), "AwaitingValidation");
+    }
+
+    #[test]
+    fn test_format_winner_follows_game_wins() {
+        let mut match_data = MatchData::new(&challenge(100, false), &acceptance());
+        match_data.match_format = MatchFormat::BestOf3;
+        match_data.game_wins = [1, 1];
+        assert!(!match_data.format_decided(2));
+        assert_eq!(match_data.format_winner(), None);
+
+        match_data.game_wins = [1, 2];
+        assert!(match_data.format_decided(3));
+        assert_eq!(match_data.format_winner().as_deref(), Some("npub1bob"));
+    }
+
+    #[test]
+    fn test_draw_refunds_wagers_or_goes_to_sudden_death() {
+        let draw = MatchResult {
+            calculated_winner: None,
+            ..result()
+        };
+        let transition =
+            in_combat(challenge(100, false)).transition(MatchEvent::ResultSubmitted(draw));
+        assert_eq!(transition.new_state.phase_name(), "AwaitingValidation");
+        // Wagers are refunded only once the engine's replay confirms the draw
+        assert!(!transition
+            .actions
+            .iter()
+            .any(|action| matches!(action, GameEngineAction::RefundWagers { .. })));
+
+        let sudden_death = transition
+            .new_state
+            .clone()
+            .transition(MatchEvent::SuddenDeathOrdered);
+        assert!(sudden_death.errors.is_empty());
+        assert_eq!(sudden_death.new_state.phase_name(), "InCombat");
+        assert_eq!(sudden_death.new_state.sudden_death_games(), 1);
+        assert!(!sudden_death.new_state.format_decided());
+
+        let MatchState::AwaitingValidation {
+            mut match_data,
+            result,
+            submitted_at,
+        } = transition.new_state
+        else {
+            unreachable!()
+        };
+        match_data.sudden_death_games = MAX_SUDDEN_DEATH_GAMES;
+        let exhausted = MatchState::AwaitingValidation {
+            match_data,
+            result,
+            submitted_at,
+        }
+        .transition(MatchEvent::SuddenDeathOrdered);
+        assert_eq!(exhausted.new_state.phase_name(), "AwaitingValidation");
+        assert_eq!(exhausted.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_expired_challenge_is_reported_until_accepted() {
+        let state = MatchState::new_challenge(challenge(100, false));
+        let expiry = DateTime::from_timestamp(1690003600, 0).unwrap();
+        assert!(state
+            .expired_challenge(expiry - chrono::Duration::seconds(1))
+            .is_none());
+        assert_eq!(
+            state
+                .expired_challenge(expiry)
+                .map(|challenge| challenge.challenger_npub.as_str()),
+            Some("npub1alice")
+        );
+
+        let accepted = state
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state;
+        assert!(accepted.expired_challenge(expiry).is_none());
+    }
+
+    #[test]
+    fn test_practice_challenge_rejects_wager() {
+        let state = MatchState::new_challenge(challenge(100, true));
+        assert_eq!(state.phase_name(), "Invalid");
+    }
+
+    #[test]
+    fn test_private_challenge_stays_private() {
+        let private_challenge = MatchChallenge {
+            private: true,
+            ..challenge(100, false)
+        };
+        let state = MatchState::new_challenge(private_challenge.clone());
+        assert!(state.is_private());
+
+        let accepted = state
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state;
+        assert!(accepted.is_private());
+        assert!(in_combat(private_challenge).is_private());
+        assert!(!in_combat(challenge(100, false)).is_private());
+    }
+
+    fn ban_commitment(player_npub: &str, banned_units: &[u8]) -> MatchEvent {
+        MatchEvent::BanCommitted(DraftBanCommitment {
+            player_npub: player_npub.to_string(),
+            match_event_id: "match_1".to_string(),
+            ban_commitment: commit_to_bans(banned_units, "ban_nonce"),
+            committed_at: 1690000250,
+        })
+    }
+
+    fn ban_reveal(player_npub: &str, banned_units: &[u8]) -> MatchEvent {
+        MatchEvent::BansRevealed(DraftBanReveal {
+            player_npub: player_npub.to_string(),
+            match_event_id: "match_1".to_string(),
+            banned_units: banned_units.to_vec(),
+            ban_nonce: "ban_nonce".to_string(),
+            revealed_at: 1690000280,
+        })
+    }
+
+    #[test]
+    fn test_draft_bans_gate_combat_and_block_banned_units() {
+        let challenge = MatchChallenge {
+            bans_per_player: 1,
+            ..challenge(100, false)
+        };
+        let drafting = MatchState::new_challenge(challenge)
+            .transition(MatchEvent::ChallengeAccepted(acceptance()))
+            .new_state
+            .transition(reveal("npub1alice", "alice_secret"))
+            .new_state
+            .transition(reveal("npub1bob", "bob_secret"))
+            .new_state;
+        assert_eq!(drafting.phase_name(), "Drafting");
+
+        // Reveals wait until both players committed
+        let drafting = drafting
+            .transition(ban_commitment("npub1alice", &[1]))
+            .new_state;
+        let early = drafting.clone().transition(ban_reveal("npub1alice", &[1]));
+        assert_eq!(early.new_state.phase_name(), "Drafting");
+        assert_eq!(early.errors.len(), 1);
+
+        let committed = drafting
+            .transition(ban_commitment("npub1bob", &[6]))
+            .new_state;
+
+        // A reveal differing from its commitment voids the match, blaming its player
+        let cheated = committed.clone().transition(ban_reveal("npub1bob", &[5]));
+        assert_eq!(cheated.new_state.phase_name(), "Invalid");
+        assert!(matches!(
+            &cheated.actions[..],
+            [GameEngineAction::InvalidateMatch { offending_npub: Some(npub), .. }] if npub == "npub1bob"
+        ));
+
+        let combat = committed
+            .transition(ban_reveal("npub1alice", &[1]))
+            .new_state
+            .transition(ban_reveal("npub1bob", &[6]))
+            .new_state;
+        assert_eq!(combat.phase_name(), "InCombat");
+
+        // Alice banned Bob's unit 1, which his round 1 move fields
+        let banned = combat.clone().transition(combat_move("npub1bob", 1));
+        assert_eq!(banned.errors.len(), 1);
+        assert!(banned.errors[0].contains("banned"), "{:?}", banned.errors);
+        let allowed = combat.transition(combat_move("npub1alice", 1));
+        assert!(allowed.errors.is_empty());
+    }
 }
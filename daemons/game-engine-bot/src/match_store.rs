@@ -0,0 +1,224 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::errors::GameEngineError;
+use crate::match_tracker::TrackedMatch;
+
+/// Durable storage for tracked match state machines
+///
+/// The tracker snapshots every transition through the store and reloads the
+/// snapshots on startup, so validation resumes mid-match after a crash.
+pub trait MatchStore: Send + Sync {
+    /// Persist the latest snapshot of a match, replacing any previous one
+    fn save(&self, match_id: &str, tracked_match: &TrackedMatch) -> Result<(), GameEngineError>;
+
+    /// Forget a match once it no longer needs tracking
+    fn remove(&self, match_id: &str) -> Result<(), GameEngineError>;
+
+    /// Load every persisted match snapshot
+    fn load_all(&self) -> Result<Vec<(String, TrackedMatch)>, GameEngineError>;
+}
+
+/// Non-durable store used when persistence is disabled and in tests
+#[derive(Debug, Default)]
+pub struct MemoryMatchStore {
+    matches: Mutex<HashMap<String, TrackedMatch>>,
+}
+
+impl MemoryMatchStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MatchStore for MemoryMatchStore {
+    fn save(&self, match_id: &str, tracked_match: &TrackedMatch) -> Result<(), GameEngineError> {
+        self.matches
+            .lock()
+            .unwrap()
+            .insert(match_id.to_string(), tracked_match.clone());
+        Ok(())
+    }
+
+    fn remove(&self, match_id: &str) -> Result<(), GameEngineError> {
+        self.matches.lock().unwrap().remove(match_id);
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<Vec<(String, TrackedMatch)>, GameEngineError> {
+        Ok(self
+            .matches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, tm)| (id.clone(), tm.clone()))
+            .collect())
+    }
+}
+
+/// SQLite-backed store keeping one JSON snapshot per match
+pub struct SqliteMatchStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteMatchStore {
+    /// Open (or create) the match database at the given path
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GameEngineError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                GameEngineError::Persistence(format!(
+                    "Failed to create {}: {}",
+                    parent.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let connection = Connection::open(path).map_err(persistence_error)?;
+        info!("💾 Match store opened at {}", path.display());
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS tracked_matches (
+                    match_id TEXT PRIMARY KEY,
+                    snapshot TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Load the snapshot of a single match
+    pub fn load(&self, match_id: &str) -> Result<Option<TrackedMatch>, GameEngineError> {
+        let snapshot: Option<String> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT snapshot FROM tracked_matches WHERE match_id = ?1",
+                params![match_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(persistence_error)?;
+
+        snapshot.map(|json| decode_snapshot(&json)).transpose()
+    }
+}
+
+impl MatchStore for SqliteMatchStore {
+    fn save(&self, match_id: &str, tracked_match: &TrackedMatch) -> Result<(), GameEngineError> {
+        let snapshot = serde_json::to_string(tracked_match).map_err(|e| {
+            GameEngineError::Persistence(format!("Failed to encode match {match_id}: {e}"))
+        })?;
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO tracked_matches (match_id, snapshot, updated_at)
+                 VALUES (?1, ?2, ?3)
+                 ON CONFLICT(match_id) DO UPDATE SET
+                     snapshot = excluded.snapshot,
+                     updated_at = excluded.updated_at",
+                params![match_id, snapshot, tracked_match.last_updated.to_rfc3339()],
+            )
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, match_id: &str) -> Result<(), GameEngineError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM tracked_matches WHERE match_id = ?1",
+                params![match_id],
+            )
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+
+    fn load_all(&self) -> Result<Vec<(String, TrackedMatch)>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT match_id, snapshot FROM tracked_matches")
+            .map_err(persistence_error)?;
+
+        let rows = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(persistence_error)?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            let (match_id, snapshot) = row.map_err(persistence_error)?;
+            matches.push((match_id, decode_snapshot(&snapshot)?));
+        }
+        Ok(matches)
+    }
+}
+
+fn decode_snapshot(snapshot: &str) -> Result<TrackedMatch, GameEngineError> {
+    serde_json::from_str(snapshot)
+        .map_err(|e| GameEngineError::Persistence(format!("Corrupt match snapshot: {e}")))
+}
+
+pub(crate) fn persistence_error(e: rusqlite::Error) -> GameEngineError {
+    GameEngineError::Persistence(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_state_machine::MatchState;
+    use chrono::Utc;
+
+    fn tracked_invalid(reason: &str) -> TrackedMatch {
+        TrackedMatch {
+            state: MatchState::Invalid {
+                reason: reason.to_string(),
+                failed_at: Utc::now(),
+            },
+            created_at: Utc::now(),
+            last_updated: Utc::now(),
+            action_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_sqlite_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("matches").join("tracker.sqlite");
+
+        {
+            let store = SqliteMatchStore::open(&path).unwrap();
+            store.save("match_1", &tracked_invalid("first")).unwrap();
+            store.save("match_1", &tracked_invalid("second")).unwrap();
+            store.save("match_2", &tracked_invalid("other")).unwrap();
+            store.remove("match_2").unwrap();
+        }
+
+        let store = SqliteMatchStore::open(&path).unwrap();
+        let matches = store.load_all().unwrap();
+        assert_eq!(matches.len(), 1);
+
+        match &store.load("match_1").unwrap().unwrap().state {
+            MatchState::Invalid { reason, .. } => assert_eq!(reason, "second"),
+            other => panic!("unexpected state {}", other.phase_name()),
+        }
+        assert!(store.load("match_2").unwrap().is_none());
+    }
+}
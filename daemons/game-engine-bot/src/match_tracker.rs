@@ -1,26 +1,50 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
+use crate::audit::{AuditLog, AuditRecord};
+use crate::config::QueueConfig;
 use crate::errors::GameEngineError;
-use crate::match_state_machine::{GameEngineAction, MatchEvent, MatchState};
+use crate::match_archive::{ArchivedMatch, MatchArchive};
+use crate::match_events::{LootDistribution, MatchChallenge};
+use crate::match_state_machine::{
+    GameEngineAction, Invalidation, MatchEvent, MatchState, TransitionResult,
+};
+use crate::match_store::{MatchStore, MemoryMatchStore};
+use crate::metrics::EngineMetrics;
 use crate::nostr_client::PlayerMatchEvent;
+use crate::reputation::ReputationStore;
+
+/// Transitions buffered for slow admin subscribers before they start lagging
+const TRANSITION_BUFFER: usize = 256;
 
 /// Concurrent match tracker using state machines
 pub struct MatchTracker {
     /// Active matches tracked by match_event_id
     matches: Arc<RwLock<HashMap<String, TrackedMatch>>>,
     /// Action queue for processing state transitions
-    action_sender: mpsc::UnboundedSender<TrackedAction>,
-    /// Configuration
-    max_concurrent_matches: usize,
-    match_timeout_minutes: u64,
+    action_sender: mpsc::Sender<TrackedAction>,
+    /// Durable snapshots of every tracked match
+    store: Arc<dyn MatchStore>,
+    /// History of finished matches, kept after they leave the tracker
+    archive: Option<Arc<MatchArchive>>,
+    /// Append-only trail of every transition for operators
+    audit: Option<Arc<AuditLog>>,
+    /// Cheat record used to turn away blacklisted players
+    reputation: Option<Arc<ReputationStore>>,
+    /// Live feed of transitions for admin API subscribers
+    transitions: broadcast::Sender<AuditRecord>,
+    /// Configuration, adjustable when the config file is reloaded
+    max_concurrent_matches: AtomicUsize,
+    match_timeout_minutes: AtomicU64,
 }
 
 /// A match being tracked with its state machine
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedMatch {
     pub state: MatchState,
     pub created_at: DateTime<Utc>,
@@ -29,7 +53,7 @@ pub struct TrackedMatch {
 }
 
 /// Action to be processed with context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedAction {
     pub match_id: String,
     pub action: GameEngineAction,
@@ -41,37 +65,160 @@ impl MatchTracker {
     pub fn new(
         max_concurrent_matches: usize,
         match_timeout_minutes: u64,
-    ) -> (Self, mpsc::UnboundedReceiver<TrackedAction>) {
-        let (action_sender, action_receiver) = mpsc::unbounded_channel();
+    ) -> (Self, mpsc::Receiver<TrackedAction>) {
+        let (action_sender, action_receiver) =
+            mpsc::channel(QueueConfig::default().action_capacity);
 
         let tracker = Self {
             matches: Arc::new(RwLock::new(HashMap::new())),
             action_sender,
-            max_concurrent_matches,
-            match_timeout_minutes,
+            store: Arc::new(MemoryMatchStore::new()),
+            archive: None,
+            audit: None,
+            reputation: None,
+            transitions: broadcast::channel(TRANSITION_BUFFER).0,
+            max_concurrent_matches: AtomicUsize::new(max_concurrent_matches),
+            match_timeout_minutes: AtomicU64::new(match_timeout_minutes),
         };
 
         (tracker, action_receiver)
     }
 
+    /// Create a match tracker backed by a persistent store, resuming any
+    /// in-flight matches it holds
+    ///
+    /// Actions a restored match was still waiting on are queued again, since the
+    /// queue itself did not survive the restart.
+    pub fn with_store(
+        max_concurrent_matches: usize,
+        match_timeout_minutes: u64,
+        action_capacity: usize,
+        store: Arc<dyn MatchStore>,
+    ) -> Result<(Self, mpsc::Receiver<TrackedAction>), GameEngineError> {
+        let (action_sender, action_receiver) = mpsc::channel(action_capacity.max(1));
+
+        let mut matches = HashMap::new();
+        for (match_id, tracked_match) in store.load_all()? {
+            if tracked_match.state.is_terminal() {
+                // Terminal matches were already settled before the restart
+                store.remove(&match_id)?;
+                continue;
+            }
+
+            info!(
+                "♻️ Resuming match {} in phase {}",
+                match_id,
+                tracked_match.state.phase_name()
+            );
+            matches.insert(match_id, tracked_match);
+        }
+
+        if !matches.is_empty() {
+            info!("♻️ Restored {} in-flight matches from store", matches.len());
+        }
+
+        let pending: Vec<(String, Vec<GameEngineAction>)> = matches
+            .iter()
+            .map(|(match_id, tracked_match)| {
+                (match_id.clone(), tracked_match.state.pending_actions())
+            })
+            .filter(|(_, actions)| !actions.is_empty())
+            .collect();
+
+        let tracker = Self {
+            matches: Arc::new(RwLock::new(matches)),
+            action_sender,
+            store,
+            archive: None,
+            audit: None,
+            reputation: None,
+            transitions: broadcast::channel(TRANSITION_BUFFER).0,
+            max_concurrent_matches: AtomicUsize::new(max_concurrent_matches),
+            match_timeout_minutes: AtomicU64::new(match_timeout_minutes),
+        };
+
+        for (match_id, actions) in pending {
+            info!(
+                "♻️ Requeueing {} pending actions for match {}",
+                actions.len(),
+                match_id
+            );
+            tracker.queue_detached(&match_id, actions);
+        }
+
+        Ok((tracker, action_receiver))
+    }
+
+    /// Archive every match that reaches a terminal state
+    pub fn with_archive(mut self, archive: Arc<MatchArchive>) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Write every state transition to the audit log
+    pub fn with_audit_log(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Reject challenges and acceptances from players blacklisted for cheating
+    pub fn with_reputation(mut self, reputation: Arc<ReputationStore>) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+
+    /// Apply reloaded limits; matches already tracked are never evicted
+    pub fn set_limits(&self, max_concurrent_matches: usize, match_timeout_minutes: u64) {
+        self.max_concurrent_matches
+            .store(max_concurrent_matches, Ordering::Relaxed);
+        self.match_timeout_minutes
+            .store(match_timeout_minutes, Ordering::Relaxed);
+    }
+
+    /// Report the depth of the action queue with the engine metrics
+    pub fn watch_action_queue(&self, metrics: &EngineMetrics) {
+        metrics.watch_queue("actions", &self.action_sender);
+    }
+
+    /// Receive every state transition as it happens
+    pub fn subscribe_transitions(&self) -> broadcast::Receiver<AuditRecord> {
+        self.transitions.subscribe()
+    }
+
     /// Process a Nostr match event through the state machine
     pub async fn process_event(&self, event: PlayerMatchEvent) -> Result<(), GameEngineError> {
         let (match_id, match_event) = self.convert_to_match_event(event).await?;
 
         debug!("🔄 Processing event for match {}", match_id);
 
+        // Blacklisted players may not start or join matches
+        let joining_npub = match &match_event {
+            MatchEvent::ChallengePosted(challenge) => Some(&challenge.challenger_npub),
+            MatchEvent::ChallengeAccepted(acceptance) => Some(&acceptance.acceptor_npub),
+            _ => None,
+        };
+        if let (Some(reputation), Some(npub)) = (&self.reputation, joining_npub) {
+            if reputation.is_blacklisted(npub) {
+                warn!("⛔ Rejected match {} from blacklisted {}", match_id, npub);
+                return Err(GameEngineError::Validation {
+                    reason: format!("{npub} is blacklisted for cheating"),
+                });
+            }
+        }
+
         // Get or create match state
         let mut matches = self.matches.write().await;
 
         // Check concurrent match limit
-        if matches.len() >= self.max_concurrent_matches && !matches.contains_key(&match_id) {
+        let max_concurrent_matches = self.max_concurrent_matches.load(Ordering::Relaxed);
+        if matches.len() >= max_concurrent_matches && !matches.contains_key(&match_id) {
             warn!(
                 "🚫 Maximum concurrent matches ({}) reached",
-                self.max_concurrent_matches
+                max_concurrent_matches
             );
-            return Err(GameEngineError::Internal(
-                "Too many concurrent matches".to_string(),
-            ));
+            return Err(GameEngineError::Validation {
+                reason: "Too many concurrent matches".to_string(),
+            });
         }
 
         let current_state = matches
@@ -97,7 +244,12 @@ impl MatchTracker {
             });
 
         // Process state transition
+        let previous_state = current_state.clone();
+        let audited_event = self.observing_transitions().then(|| match_event.clone());
         let transition_result = current_state.transition(match_event);
+        if let Some(event) = &audited_event {
+            self.audit_transition(&match_id, event, &previous_state, &transition_result);
+        }
 
         // Update match state
         let tracked_match = TrackedMatch {
@@ -113,7 +265,12 @@ impl MatchTracker {
                 .unwrap_or(transition_result.actions.len() as u64),
         };
 
+        persist_snapshot(self.store.as_ref(), &match_id, &tracked_match);
+        if !previous_state.is_terminal() {
+            self.archive_terminal(&match_id, &previous_state, &tracked_match);
+        }
         matches.insert(match_id.clone(), tracked_match);
+        drop(matches);
 
         // Log state transition
         info!(
@@ -123,17 +280,16 @@ impl MatchTracker {
         );
 
         // Queue actions for processing
-        for action in transition_result.actions {
-            let tracked_action = TrackedAction {
+        let actions = transition_result
+            .actions
+            .into_iter()
+            .map(|action| TrackedAction {
                 match_id: match_id.clone(),
                 action,
                 triggered_at: Utc::now(),
-            };
-
-            if let Err(e) = self.action_sender.send(tracked_action) {
-                error!("Failed to queue action: {}", e);
-            }
-        }
+            })
+            .collect();
+        self.queue_actions(actions).await;
 
         // Log any errors
         for error in transition_result.errors {
@@ -143,6 +299,7 @@ impl MatchTracker {
         // Clean up terminal matches after delay
         if transition_result.new_state.is_terminal() {
             let matches_clone = Arc::clone(&self.matches);
+            let store_clone = Arc::clone(&self.store);
             let match_id_clone = match_id.clone();
 
             tokio::spawn(async move {
@@ -153,6 +310,7 @@ impl MatchTracker {
                 if let Some(tracked_match) = matches.get(&match_id_clone) {
                     if tracked_match.state.is_terminal() {
                         matches.remove(&match_id_clone);
+                        forget_snapshot(store_clone.as_ref(), &match_id_clone);
                         info!("🧹 Cleaned up terminal match: {}", match_id_clone);
                     }
                 }
@@ -180,6 +338,14 @@ impl MatchTracker {
                 let match_id = reveal.match_event_id.clone();
                 Ok((match_id, MatchEvent::TokenRevealed(reveal)))
             }
+            PlayerMatchEvent::BanCommitment(commitment) => {
+                let match_id = commitment.match_event_id.clone();
+                Ok((match_id, MatchEvent::BanCommitted(commitment)))
+            }
+            PlayerMatchEvent::BanReveal(reveal) => {
+                let match_id = reveal.match_event_id.clone();
+                Ok((match_id, MatchEvent::BansRevealed(reveal)))
+            }
             PlayerMatchEvent::CombatMove(combat_move) => {
                 let match_id = combat_move.match_event_id.clone();
                 Ok((match_id, MatchEvent::CombatMoveSubmitted(combat_move)))
@@ -188,6 +354,12 @@ impl MatchTracker {
                 let match_id = result.match_event_id.clone();
                 Ok((match_id, MatchEvent::ResultSubmitted(result)))
             }
+            PlayerMatchEvent::Seek(seek) => Err(GameEngineError::Validation {
+                reason: format!(
+                    "Seek from {} belongs to the matchmaker, not a match",
+                    seek.player_npub
+                ),
+            }),
         }
     }
 
@@ -197,6 +369,23 @@ impl MatchTracker {
         matches.get(match_id).map(|tm| tm.state.clone())
     }
 
+    /// Get a tracked match with its bookkeeping
+    pub async fn get_tracked_match(&self, match_id: &str) -> Option<TrackedMatch> {
+        let matches = self.matches.read().await;
+        matches.get(match_id).cloned()
+    }
+
+    /// Get every tracked match, oldest first
+    pub async fn tracked_matches(&self) -> Vec<(String, TrackedMatch)> {
+        let matches = self.matches.read().await;
+        let mut tracked: Vec<_> = matches
+            .iter()
+            .map(|(id, tm)| (id.clone(), tm.clone()))
+            .collect();
+        tracked.sort_by_key(|(_, tm)| tm.created_at);
+        tracked
+    }
+
     /// Get match statistics
     pub async fn get_statistics(&self) -> MatchStatistics {
         let matches = self.matches.read().await;
@@ -205,6 +394,7 @@ impl MatchTracker {
             total_matches: matches.len(),
             challenged: 0,
             accepted: 0,
+            drafting: 0,
             in_combat: 0,
             awaiting_validation: 0,
             completed: 0,
@@ -224,6 +414,7 @@ impl MatchTracker {
             match tracked_match.state {
                 MatchState::Challenged { .. } => stats.challenged += 1,
                 MatchState::Accepted { .. } => stats.accepted += 1,
+                MatchState::Drafting { .. } => stats.drafting += 1,
                 MatchState::InCombat { .. } => stats.in_combat += 1,
                 MatchState::AwaitingValidation { .. } => stats.awaiting_validation += 1,
                 MatchState::Completed { .. } => stats.completed += 1,
@@ -238,10 +429,12 @@ impl MatchTracker {
     /// Clean up expired matches
     pub async fn cleanup_expired_matches(&self) {
         let now = Utc::now();
-        let timeout_duration = chrono::Duration::minutes(self.match_timeout_minutes as i64);
+        let timeout_minutes = self.match_timeout_minutes.load(Ordering::Relaxed);
+        let timeout_duration = chrono::Duration::minutes(timeout_minutes as i64);
 
         let mut matches = self.matches.write().await;
         let mut expired_matches = Vec::new();
+        let mut invalidations = Vec::new();
 
         for (match_id, tracked_match) in matches.iter() {
             if now.signed_duration_since(tracked_match.last_updated) > timeout_duration {
@@ -251,59 +444,132 @@ impl MatchTracker {
 
         for match_id in expired_matches {
             if let Some(tracked_match) = matches.remove(&match_id) {
+                forget_snapshot(self.store.as_ref(), &match_id);
+                let finished = tracked_match.state.is_terminal();
+                if !finished {
+                    let expired = TrackedMatch {
+                        state: MatchState::Invalid {
+                            reason: "Match timeout expired".to_string(),
+                            failed_at: now,
+                        },
+                        last_updated: now,
+                        ..tracked_match.clone()
+                    };
+                    self.archive_terminal(&match_id, &tracked_match.state, &expired);
+                    self.record_transition(
+                        &match_id,
+                        &MatchEvent::TimeoutExpired,
+                        &tracked_match.state,
+                        &expired.state,
+                        &[],
+                    );
+                }
                 warn!(
                     "⏰ Expired match removed: {} (last updated: {})",
                     match_id, tracked_match.last_updated
                 );
 
-                // Queue invalidation action
-                let action = TrackedAction {
+                // Matches that already finished have nothing left to invalidate
+                if finished {
+                    continue;
+                }
+                invalidations.push(TrackedAction {
                     match_id: match_id.clone(),
                     action: GameEngineAction::InvalidateMatch {
                         match_id,
                         reason: "Match timeout expired".to_string(),
+                        offending_npub: None,
+                        evidence_hashes: Vec::new(),
                     },
                     triggered_at: now,
-                };
+                });
+            }
+        }
 
-                if let Err(e) = self.action_sender.send(action) {
-                    error!("Failed to queue timeout invalidation: {}", e);
-                }
+        drop(matches);
+        self.queue_actions(invalidations).await;
+    }
+
+    /// Drop challenges nobody accepted before they expired and queue their cancellation
+    pub async fn sweep_expired_challenges(&self) -> usize {
+        let now = Utc::now();
+        let mut matches = self.matches.write().await;
+
+        let expired: Vec<(String, MatchChallenge)> = matches
+            .iter()
+            .filter_map(|(match_id, tracked_match)| {
+                tracked_match
+                    .state
+                    .expired_challenge(now)
+                    .map(|challenge| (match_id.clone(), challenge.clone()))
+            })
+            .collect();
+
+        let mut cancellations = Vec::new();
+        for (match_id, challenge) in &expired {
+            if let Some(tracked_match) = matches.remove(match_id) {
+                let cancelled = MatchState::Invalid {
+                    reason: "Challenge expired without acceptance".to_string(),
+                    failed_at: now,
+                };
+                self.record_transition(
+                    match_id,
+                    &MatchEvent::TimeoutExpired,
+                    &tracked_match.state,
+                    &cancelled,
+                    &[],
+                );
             }
+            forget_snapshot(self.store.as_ref(), match_id);
+            info!(
+                "⌛ Challenge {} from {} expired without acceptance",
+                challenge.match_event_id, challenge.challenger_npub
+            );
+
+            cancellations.push(TrackedAction {
+                match_id: match_id.clone(),
+                action: GameEngineAction::CancelChallenge {
+                    match_id: challenge.match_event_id.clone(),
+                    challenger_npub: challenge.challenger_npub.clone(),
+                    reason: "Challenge expired without acceptance".to_string(),
+                    expired_at: challenge.expires_at,
+                },
+                triggered_at: now,
+            });
         }
+
+        drop(matches);
+        self.queue_actions(cancellations).await;
+        expired.len()
     }
 
     /// Trigger manual match invalidation
     pub async fn invalidate_match(
         &self,
         match_id: &str,
-        reason: String,
+        invalidation: Invalidation,
     ) -> Result<(), GameEngineError> {
         let mut matches = self.matches.write().await;
 
         if let Some(tracked_match) = matches.get_mut(match_id) {
-            let transition_result = tracked_match
-                .state
-                .clone()
-                .transition(MatchEvent::InvalidationTriggered(reason.clone()));
+            let reason = invalidation.reason.clone();
+            let previous_state = tracked_match.state.clone();
+            let event = MatchEvent::InvalidationTriggered(invalidation);
+            let transition_result = previous_state.clone().transition(event.clone());
+            self.audit_transition(match_id, &event, &previous_state, &transition_result);
 
             tracked_match.state = transition_result.new_state;
             tracked_match.last_updated = Utc::now();
+            persist_snapshot(self.store.as_ref(), match_id, tracked_match);
+            if !previous_state.is_terminal() {
+                self.archive_terminal(match_id, &previous_state, tracked_match);
+            }
 
             info!("🚨 Manually invalidated match {}: {}", match_id, reason);
+            drop(matches);
 
-            // Queue invalidation actions
-            for action in transition_result.actions {
-                let tracked_action = TrackedAction {
-                    match_id: match_id.to_string(),
-                    action,
-                    triggered_at: Utc::now(),
-                };
-
-                if let Err(e) = self.action_sender.send(tracked_action) {
-                    error!("Failed to queue invalidation action: {}", e);
-                }
-            }
+            // The engine also invalidates from its action loop, so never wait on the queue
+            self.queue_detached(match_id, transition_result.actions);
 
             Ok(())
         } else {
@@ -311,6 +577,184 @@ impl MatchTracker {
         }
     }
 
+    /// Reopen a drawn match for another deciding game
+    ///
+    /// Returns false if the state machine refused, e.g. once the sudden-death limit is reached.
+    pub async fn order_sudden_death(&self, match_id: &str) -> Result<bool, GameEngineError> {
+        let mut matches = self.matches.write().await;
+        let Some(tracked_match) = matches.get_mut(match_id) else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+
+        let transition_result = tracked_match
+            .state
+            .clone()
+            .transition(MatchEvent::SuddenDeathOrdered);
+        self.audit_transition(
+            match_id,
+            &MatchEvent::SuddenDeathOrdered,
+            &tracked_match.state,
+            &transition_result,
+        );
+        if !transition_result.errors.is_empty() {
+            warn!(
+                "⚠️ Sudden death refused for match {}: {:?}",
+                match_id, transition_result.errors
+            );
+            return Ok(false);
+        }
+
+        tracked_match.state = transition_result.new_state;
+        tracked_match.last_updated = Utc::now();
+        persist_snapshot(self.store.as_ref(), match_id, tracked_match);
+        Ok(true)
+    }
+
+    /// Queue an action the engine decided on outside a state transition, like a payout
+    /// for a result it re-verified
+    pub fn queue_engine_action(&self, match_id: &str, action: GameEngineAction) {
+        self.queue_detached(match_id, vec![action]);
+    }
+
+    /// Complete a validated match once its loot award is published
+    ///
+    /// A match that already completed is left as is, so a retried payout can
+    /// republish its award without failing here.
+    pub async fn complete_match(
+        &self,
+        match_id: &str,
+        loot_distribution: LootDistribution,
+    ) -> Result<(), GameEngineError> {
+        let mut matches = self.matches.write().await;
+        let Some(tracked_match) = matches.get_mut(match_id) else {
+            return Err(GameEngineError::MatchNotFound(match_id.to_string()));
+        };
+        if matches!(tracked_match.state, MatchState::Completed { .. }) {
+            return Ok(());
+        }
+
+        let previous_state = tracked_match.state.clone();
+        let event = MatchEvent::LootDistributed(loot_distribution);
+        let transition_result = previous_state.clone().transition(event.clone());
+        self.audit_transition(match_id, &event, &previous_state, &transition_result);
+        if !transition_result.errors.is_empty() {
+            return Err(GameEngineError::Validation {
+                reason: transition_result.errors.join("; "),
+            });
+        }
+
+        tracked_match.state = transition_result.new_state;
+        tracked_match.last_updated = Utc::now();
+        persist_snapshot(self.store.as_ref(), match_id, tracked_match);
+        self.archive_terminal(match_id, &previous_state, tracked_match);
+        info!("🏁 Match {} completed", match_id);
+        drop(matches);
+
+        self.queue_detached(match_id, transition_result.actions);
+        Ok(())
+    }
+
+    /// Hand actions to the engine from a separate task
+    ///
+    /// For actions produced while the engine works through its action loop: waiting
+    /// there for room in the queue it drains itself would never finish.
+    fn queue_detached(&self, match_id: &str, actions: Vec<GameEngineAction>) {
+        let sender = self.action_sender.clone();
+        let actions: Vec<TrackedAction> = actions
+            .into_iter()
+            .map(|action| TrackedAction {
+                match_id: match_id.to_string(),
+                action,
+                triggered_at: Utc::now(),
+            })
+            .collect();
+        tokio::spawn(async move {
+            for action in actions {
+                if let Err(e) = sender.send(action).await {
+                    error!("Failed to queue action: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Hand actions to the engine, waiting for room while the queue is full
+    ///
+    /// The matches lock must be released first: the action loop reads it while draining.
+    async fn queue_actions(&self, actions: Vec<TrackedAction>) {
+        for action in actions {
+            if let Err(e) = self.action_sender.send(action).await {
+                error!("Failed to queue action: {}", e);
+            }
+        }
+    }
+
+    /// Report a transition to the audit log and admin subscribers
+    fn audit_transition(
+        &self,
+        match_id: &str,
+        event: &MatchEvent,
+        previous: &MatchState,
+        transition_result: &TransitionResult,
+    ) {
+        self.record_transition(
+            match_id,
+            event,
+            previous,
+            &transition_result.new_state,
+            &transition_result.errors,
+        );
+    }
+
+    fn record_transition(
+        &self,
+        match_id: &str,
+        event: &MatchEvent,
+        previous: &MatchState,
+        new_state: &MatchState,
+        errors: &[String],
+    ) {
+        if !self.observing_transitions() {
+            return;
+        }
+
+        let record = AuditRecord::from_transition(match_id, event, previous, new_state, errors);
+        if let Some(audit) = &self.audit {
+            if let Err(e) = audit.record(&record) {
+                error!("📝 Failed to audit transition of match {}: {}", match_id, e);
+            }
+        }
+        // No subscribers is fine, nobody is watching
+        let _ = self.transitions.send(record);
+    }
+
+    /// Whether anyone consumes transition records, to skip building them otherwise
+    fn observing_transitions(&self) -> bool {
+        self.audit.is_some() || self.transitions.receiver_count() > 0
+    }
+
+    /// Record a match that just reached a terminal state in the archive
+    fn archive_terminal(
+        &self,
+        match_id: &str,
+        previous: &MatchState,
+        tracked_match: &TrackedMatch,
+    ) {
+        let Some(archive) = &self.archive else {
+            return;
+        };
+        let Some(archived) = ArchivedMatch::from_terminal(
+            match_id,
+            previous,
+            &tracked_match.state,
+            tracked_match.created_at,
+        ) else {
+            return;
+        };
+        if let Err(e) = archive.archive(&archived) {
+            error!("📦 Failed to archive match {}: {}", match_id, e);
+        }
+    }
+
     /// Get all matches in a specific state
     pub async fn get_matches_in_state(&self, target_state: &str) -> Vec<(String, TrackedMatch)> {
         let matches = self.matches.read().await;
@@ -323,12 +767,26 @@ impl MatchTracker {
     }
 }
 
+/// Snapshot a match transition, keeping the in-memory tracker authoritative on failure
+fn persist_snapshot(store: &dyn MatchStore, match_id: &str, tracked_match: &TrackedMatch) {
+    if let Err(e) = store.save(match_id, tracked_match) {
+        error!("💾 Failed to persist match {}: {}", match_id, e);
+    }
+}
+
+fn forget_snapshot(store: &dyn MatchStore, match_id: &str) {
+    if let Err(e) = store.remove(match_id) {
+        error!("💾 Failed to remove persisted match {}: {}", match_id, e);
+    }
+}
+
 /// Statistics about current matches
 #[derive(Debug, Clone)]
 pub struct MatchStatistics {
     pub total_matches: usize,
     pub challenged: usize,
     pub accepted: usize,
+    pub drafting: usize,
     pub in_combat: usize,
     pub awaiting_validation: usize,
     pub completed: usize,
@@ -339,7 +797,7 @@ pub struct MatchStatistics {
 impl MatchStatistics {
     /// Get active (non-terminal) match count
     pub fn active_matches(&self) -> usize {
-        self.challenged + self.accepted + self.in_combat + self.awaiting_validation
+        self.challenged + self.accepted + self.drafting + self.in_combat + self.awaiting_validation
     }
 }
 
@@ -349,6 +807,10 @@ pub async fn run_cleanup_task(tracker: Arc<MatchTracker>) {
 
     loop {
         interval.tick().await;
+        let cancelled = tracker.sweep_expired_challenges().await;
+        if cancelled > 0 {
+            info!("⌛ Cancelled {} expired challenges", cancelled);
+        }
         tracker.cleanup_expired_matches().await;
 
         let stats = tracker.get_statistics().await;
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::{GameMode, LeagueModeConfig};
+use crate::errors::GameEngineError;
+use crate::match_events::{PlayerReveals, RoundCombat};
+use crate::match_state_machine::MatchData;
+use shared_game_logic::abilities::AbilityLedger;
+use shared_game_logic::combat::{
+    break_tie, generate_army_for_version, is_double_knockout, process_combat_with_abilities,
+};
+use shared_game_logic::game_state::Unit;
+use shared_game_logic::league;
+use shared_game_logic::rng::CombatRng;
+
+/// Deterministic re-execution of a game mode, used to decide who won each game
+///
+/// The engine never trusts the result players submit: it replays their revealed
+/// tokens and moves through the verifier registered for the match's league.
+pub trait MatchVerifier: Send + Sync {
+    /// Name of the game mode, for logs
+    fn name(&self) -> &'static str;
+
+    /// Armies for both players, indexed [player1, player2], once their tokens are revealed
+    fn generate_armies(&self, match_data: &MatchData) -> [Option<[Unit; 8]>; 2];
+
+    /// Combat totals after the given round, or None while a move is missing
+    fn round_combat(&self, match_data: &MatchData, round: u32) -> Option<RoundCombat>;
+}
+
+/// Standard one-unit-per-round combat from shared-game-logic
+pub struct StandardCombatVerifier;
+
+impl MatchVerifier for StandardCombatVerifier {
+    fn name(&self) -> &'static str {
+        "standard_combat"
+    }
+
+    /// Derive each army from the first revealed token secret of its player, with the
+    /// generator the challenge asked for and the season it was created in
+    fn generate_armies(&self, match_data: &MatchData) -> [Option<[Unit; 8]>; 2] {
+        let league_id = match_data.league_id as u8;
+        let army = |reveals: &PlayerReveals| {
+            reveals
+                .cashu_tokens
+                .as_ref()
+                .and_then(|tokens| tokens.first())
+                .and_then(|secret| {
+                    generate_army_for_version(secret, league_id, match_data.generator_version).ok()
+                })
+                .map(|mut army| {
+                    league::apply_season(&mut army, match_data.created_at);
+                    army
+                })
+        };
+
+        [
+            army(&match_data.player1_reveals),
+            army(&match_data.player2_reveals),
+        ]
+    }
+
+    fn round_combat(&self, match_data: &MatchData, round: u32) -> Option<RoundCombat> {
+        let (army1, army2) = (match_data.player1_army?, match_data.player2_army?);
+
+        let mut combat = RoundCombat {
+            damage_taken: [0, 0],
+            units_lost: [0, 0],
+            score: [0, 0],
+            round_winner: None,
+        };
+
+        // Abilities are replayed through each player's ledger; a move breaking it never resolves
+        let mut ledgers = [AbilityLedger::default(), AbilityLedger::default()];
+        let mut fallen = [[false; 8]; 2];
+
+        for resolved in 1..=round {
+            let p1_moves = match_data.player1_reveals.moves_by_round.get(&resolved)?;
+            let p2_moves = match_data.player2_reveals.moves_by_round.get(&resolved)?;
+            let p1_abilities = ledgers[0].use_abilities(resolved, &p1_moves.1).ok()?;
+            let p2_abilities = ledgers[1].use_abilities(resolved, &p2_moves.1).ok()?;
+            let rolls = CombatRng::new([&p1_moves.2, &p2_moves.2], resolved).attack_rolls();
+
+            // A unit banned in the draft phase never takes the field
+            let units = [fielded_unit(&p1_moves.0), fielded_unit(&p2_moves.0)];
+            if match_data.is_banned(0, units[0]) || match_data.is_banned(1, units[1]) {
+                return None;
+            }
+
+            let result = process_combat_with_abilities(
+                army1[units[0]],
+                army2[units[1]],
+                [&p1_abilities, &p2_abilities],
+                rolls,
+                &match_data.player1_npub,
+                &match_data.player2_npub,
+            )
+            .ok()?;
+
+            combat.damage_taken = [result.damage_dealt[1], result.damage_dealt[0]];
+            combat.units_lost[0] += u32::from(!result.player1_unit.is_alive());
+            combat.units_lost[1] += u32::from(!result.player2_unit.is_alive());
+            fallen[0][units[0]] |= !result.player1_unit.is_alive();
+            fallen[1][units[1]] |= !result.player2_unit.is_alive();
+
+            // Same tie-breaker the clients' replay applies to a double knockout
+            let winner = if is_double_knockout(&result) {
+                let nonces = [p1_moves.2.as_str(), p2_moves.2.as_str()];
+                let players = [&match_data.player1_npub, &match_data.player2_npub];
+                let winner = break_tie([&army1, &army2], fallen, nonces, resolved);
+                Some(players[winner as usize].clone())
+            } else {
+                result.winner
+            };
+            match winner.as_deref() {
+                Some(winner) if winner == match_data.player1_npub => combat.score[0] += 1,
+                Some(winner) if winner == match_data.player2_npub => combat.score[1] += 1,
+                _ => {}
+            }
+            combat.round_winner = winner;
+        }
+
+        Some(combat)
+    }
+}
+
+/// Army index of the unit a move fields in standard combat: its first position
+pub fn fielded_unit(positions: &[u8]) -> usize {
+    positions.first().copied().unwrap_or(0) as usize % 8
+}
+
+/// Verifier for each league, falling back to standard combat
+pub struct VerifierRegistry {
+    default: Box<dyn MatchVerifier>,
+    by_league: HashMap<u8, Box<dyn MatchVerifier>>,
+}
+
+impl Default for VerifierRegistry {
+    fn default() -> Self {
+        Self {
+            default: Box::new(StandardCombatVerifier),
+            by_league: HashMap::new(),
+        }
+    }
+}
+
+impl VerifierRegistry {
+    /// Verify the league's matches with a game mode of its own
+    pub fn register(mut self, league_id: u8, verifier: impl MatchVerifier + 'static) -> Self {
+        self.by_league.insert(league_id, Box::new(verifier));
+        self
+    }
+
+    /// Registry verifying each configured league with its game mode
+    pub fn from_config(league_modes: &[LeagueModeConfig]) -> Self {
+        league_modes
+            .iter()
+            .flat_map(|entry| entry.league_ids.iter().map(move |id| (*id, entry.mode)))
+            .fold(Self::default(), |registry, (league_id, mode)| match mode {
+                GameMode::StandardCombat => registry.register(league_id, StandardCombatVerifier),
+            })
+    }
+
+    pub fn get(&self, league_id: u8) -> &dyn MatchVerifier {
+        self.by_league
+            .get(&league_id)
+            .map_or(self.default.as_ref(), |verifier| verifier.as_ref())
+    }
+}
+
+static DEFAULT_VERIFIERS: OnceLock<VerifierRegistry> = OnceLock::new();
+static INSTALLED_VERIFIERS: OnceLock<VerifierRegistry> = OnceLock::new();
+
+/// Replace the verifier registry for the rest of the process
+///
+/// Must run before any match is tracked; fails if a registry was already installed.
+pub fn install_verifiers(registry: VerifierRegistry) -> Result<(), GameEngineError> {
+    INSTALLED_VERIFIERS.set(registry).map_err(|_| {
+        GameEngineError::Internal("A match verifier registry is already installed".to_string())
+    })
+}
+
+/// Verifier that decides games in the given league
+pub fn verifier_for(league_id: u8) -> &'static dyn MatchVerifier {
+    INSTALLED_VERIFIERS
+        .get()
+        .unwrap_or_else(|| DEFAULT_VERIFIERS.get_or_init(VerifierRegistry::default))
+        .get(league_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Game mode where player 1 wins every round, standing in for e.g. capture the flag
+    struct FirstPlayerWins;
+
+    impl MatchVerifier for FirstPlayerWins {
+        fn name(&self) -> &'static str {
+            "first_player_wins"
+        }
+
+        fn generate_armies(&self, _match_data: &MatchData) -> [Option<[Unit; 8]>; 2] {
+            [None, None]
+        }
+
+        fn round_combat(&self, match_data: &MatchData, round: u32) -> Option<RoundCombat> {
+            Some(RoundCombat {
+                damage_taken: [0, 0],
+                units_lost: [0, 0],
+                score: [round, 0],
+                round_winner: Some(match_data.player1_npub.clone()),
+            })
+        }
+    }
+
+    #[test]
+    fn test_registered_league_uses_its_own_verifier() {
+        let registry = VerifierRegistry::default().register(7, FirstPlayerWins);
+
+        assert_eq!(registry.get(7).name(), "first_player_wins");
+        assert_eq!(registry.get(0).name(), "standard_combat");
+        assert_eq!(verifier_for(7).name(), "standard_combat");
+    }
+
+    #[test]
+    fn test_configured_leagues_get_their_game_mode() {
+        let registry = VerifierRegistry::from_config(&[LeagueModeConfig {
+            league_ids: vec![3, 4],
+            mode: GameMode::StandardCombat,
+        }]);
+
+        assert_eq!(registry.by_league.len(), 2);
+        assert_eq!(registry.get(3).name(), "standard_combat");
+    }
+
+    #[test]
+    fn test_double_knockout_matches_client_replay() {
+        use crate::match_events::{test_support, MatchChallenge, MatchFormat};
+        use shared_game_logic::game_state::Ability;
+        use shared_game_logic::replay::{replay_match, CombatEvent, MoveReveal};
+
+        let challenge = MatchChallenge {
+            match_format: MatchFormat::SingleRound,
+            ..test_support::challenge("match_1")
+        };
+        let acceptance = test_support::acceptance("match_1");
+
+        // Glass cannons knock each other out every round
+        let army = [Unit::new(40, 0, 10, 10, Ability::None); 8];
+        let mut match_data = MatchData::new(&challenge, &acceptance);
+        match_data.player1_army = Some(army);
+        match_data.player2_army = Some(army);
+        let mut reveals = Vec::new();
+        for (player, nonce) in [(0u8, "nonce_a"), (1, "nonce_b")] {
+            let moves = if player == 0 {
+                &mut match_data.player1_reveals
+            } else {
+                &mut match_data.player2_reveals
+            };
+            for round in 1..=3 {
+                moves
+                    .moves_by_round
+                    .insert(round, (vec![round as u8], Vec::new(), nonce.to_string()));
+                reveals.push(MoveReveal {
+                    player,
+                    round,
+                    unit_positions: vec![round as u8],
+                    unit_abilities: Vec::new(),
+                    moves_nonce: nonce.to_string(),
+                });
+            }
+        }
+
+        let combat = StandardCombatVerifier.round_combat(&match_data, 3).unwrap();
+        assert_eq!(combat.units_lost, [3, 3]);
+        assert_eq!(combat.score[0] + combat.score[1], 3);
+
+        let replayed: Vec<Option<u8>> = replay_match([&army, &army], &reveals)
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                CombatEvent::RoundWon { winner, .. } => Some(winner),
+                _ => None,
+            })
+            .collect();
+        let players = ["npub1alice", "npub1bob"];
+        assert_eq!(
+            combat.round_winner.as_deref(),
+            replayed[2].map(|winner| players[winner as usize])
+        );
+        let replayed_score = [0, 1].map(|p| replayed.iter().filter(|w| **w == Some(p)).count());
+        assert_eq!(combat.score, replayed_score.map(|wins| wins as u32));
+    }
+}
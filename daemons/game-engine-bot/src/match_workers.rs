@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tracing::{debug, warn};
+
+type EventHandler<T> = Arc<dyn Fn(T) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+type Mailboxes<T> = Arc<Mutex<HashMap<String, mpsc::UnboundedSender<(T, OwnedSemaphorePermit)>>>>;
+
+/// One worker task per active match, each draining its own mailbox in order
+///
+/// Events for different matches are handled concurrently, so a slow validation
+/// only delays its own match. At most `max_workers` matches are handled at once and
+/// at most `max_queued` events wait across all mailboxes; further matches queue until
+/// a worker finishes, further events until one is handled.
+pub struct MatchWorkers<T> {
+    mailboxes: Mailboxes<T>,
+    workers: Arc<Semaphore>,
+    queued: Arc<Semaphore>,
+    handler: EventHandler<T>,
+}
+
+impl<T: Send + 'static> MatchWorkers<T> {
+    pub fn new<F, Fut>(max_workers: usize, max_queued: usize, handler: F) -> Self
+    where
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            mailboxes: Arc::new(Mutex::new(HashMap::new())),
+            workers: Arc::new(Semaphore::new(max_workers.max(1))),
+            queued: Arc::new(Semaphore::new(max_queued.max(1))),
+            handler: Arc::new(move |event| Box::pin(handler(event))),
+        }
+    }
+
+    /// Queue an event behind any earlier events of the same match
+    ///
+    /// Waits while `max_queued` events are pending, so a caller draining a bounded
+    /// channel stops draining it and the backpressure reaches the producer.
+    pub async fn dispatch(&self, match_id: &str, event: T) {
+        let permit = Arc::clone(&self.queued)
+            .acquire_owned()
+            .await
+            .expect("the queue semaphore is never closed");
+        let mut mailboxes = self.mailboxes.lock().unwrap();
+
+        let queued = match mailboxes.get(match_id) {
+            Some(mailbox) => match mailbox.send((event, permit)) {
+                Ok(()) => return,
+                // The worker died mid-event; start a fresh one
+                Err(mpsc::error::SendError(queued)) => {
+                    warn!("⚠️ Worker for match {} stopped unexpectedly", match_id);
+                    queued
+                }
+            },
+            None => (event, permit),
+        };
+
+        let (mailbox, receiver) = mpsc::unbounded_channel();
+        mailbox
+            .send(queued)
+            .expect("receiver is held until the worker starts");
+        mailboxes.insert(match_id.to_string(), mailbox);
+        tokio::spawn(run_worker(
+            match_id.to_string(),
+            receiver,
+            Arc::clone(&self.mailboxes),
+            Arc::clone(&self.workers),
+            Arc::clone(&self.handler),
+        ));
+    }
+
+    /// Matches with a worker queued or running
+    pub fn active_workers(&self) -> usize {
+        self.mailboxes.lock().unwrap().len()
+    }
+}
+
+async fn run_worker<T>(
+    match_id: String,
+    mut receiver: mpsc::UnboundedReceiver<(T, OwnedSemaphorePermit)>,
+    mailboxes: Mailboxes<T>,
+    workers: Arc<Semaphore>,
+    handler: EventHandler<T>,
+) {
+    let Ok(_worker) = workers.acquire_owned().await else {
+        return;
+    };
+    debug!("🧵 Worker started for match {}", match_id);
+
+    loop {
+        // The queue permit is released once the event is handled
+        while let Ok((event, _queued)) = receiver.try_recv() {
+            handler(event).await;
+        }
+
+        // Dispatch sends under this lock, so nothing can arrive between the
+        // last check and the mailbox being removed
+        let mut mailboxes = mailboxes.lock().unwrap();
+        if receiver.is_empty() {
+            mailboxes.remove(&match_id);
+            break;
+        }
+    }
+
+    debug!("🧵 Worker finished for match {}", match_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    // Paused time only advances once every worker is idle, so the sleeps below are deterministic
+    #[tokio::test(start_paused = true)]
+    async fn test_events_stay_ordered_within_a_match() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let workers = MatchWorkers::new(4, 16, move |(match_id, step): (&'static str, u64)| {
+            let recorded = Arc::clone(&recorded);
+            async move {
+                // The slow match must not hold up the fast one
+                if match_id == "slow" {
+                    tokio::time::sleep(Duration::from_millis(20 - step * 5)).await;
+                }
+                recorded.lock().unwrap().push((match_id, step));
+            }
+        });
+
+        for step in 0..3 {
+            workers.dispatch("slow", ("slow", step)).await;
+            workers.dispatch("fast", ("fast", step)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let seen = seen.lock().unwrap().clone();
+        let steps = |id| {
+            seen.iter()
+                .filter(|(match_id, _)| *match_id == id)
+                .map(|(_, step)| *step)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(steps("slow"), [0, 1, 2]);
+        assert_eq!(steps("fast"), [0, 1, 2]);
+        assert_eq!(seen[0].0, "fast");
+        assert_eq!(workers.active_workers(), 0);
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use crate::match_events::{MatchChallenge, MatchSeek};
+
+/// Open challenge waiting in the pool for an opponent
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenChallenge {
+    pub match_event_id: String,
+    pub challenger_npub: String,
+    pub league_id: u8,
+    pub wager_amount: u64,
+    pub expires_at: u64,
+    pub rating: f64, // Challenger's Elo when the challenge was pooled
+}
+
+/// Challenger and seeker the matchmaker put together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pairing {
+    pub match_event_id: String,
+    pub challenger_npub: String,
+    pub acceptor_npub: String,
+    pub league_id: u8,
+    pub wager_amount: u64,
+}
+
+/// Pool of open challenges and players seeking a match
+///
+/// A seeker is paired with the open challenge of their league and wager range whose
+/// challenger is closest in Elo, within `max_rating_gap`. Each challenge and seek is
+/// paired at most once; an unanswered pairing leaves the challenge open on the relays
+/// for anyone to accept.
+#[derive(Debug)]
+pub struct MatchmakingPool {
+    challenges: HashMap<String, OpenChallenge>,
+    seekers: HashMap<String, (MatchSeek, f64)>, // Latest seek per player, with their Elo
+    max_rating_gap: f64,
+}
+
+impl MatchmakingPool {
+    pub fn new(max_rating_gap: f64) -> Self {
+        Self {
+            challenges: HashMap::new(),
+            seekers: HashMap::new(),
+            max_rating_gap,
+        }
+    }
+
+    /// Pool a newly tracked challenge, or pair it straight away with a waiting seeker
+    pub fn add_challenge(
+        &mut self,
+        challenge: &MatchChallenge,
+        rating: f64,
+        now: u64,
+    ) -> Option<Pairing> {
+        self.prune(now);
+        let open = OpenChallenge {
+            match_event_id: challenge.match_event_id.clone(),
+            challenger_npub: challenge.challenger_npub.clone(),
+            league_id: challenge.league_id,
+            wager_amount: challenge.wager_amount,
+            expires_at: challenge.expires_at,
+            rating,
+        };
+        if open.expires_at <= now {
+            return None;
+        }
+
+        let seeker = self
+            .seekers
+            .values()
+            .filter(|(seek, seeker_rating)| self.compatible(&open, seek, *seeker_rating))
+            .min_by(|(a, a_rating), (b, b_rating)| {
+                (a_rating - rating)
+                    .abs()
+                    .total_cmp(&(b_rating - rating).abs())
+                    .then(a.created_at.cmp(&b.created_at))
+            })
+            .map(|(seek, _)| seek.player_npub.clone());
+
+        match seeker {
+            Some(npub) => {
+                self.seekers.remove(&npub);
+                Some(pairing(&open, npub))
+            }
+            None => {
+                self.challenges.insert(open.match_event_id.clone(), open);
+                None
+            }
+        }
+    }
+
+    /// Pool a player's seek, replacing any earlier one, or pair it with an open challenge
+    pub fn add_seek(&mut self, seek: MatchSeek, rating: f64, now: u64) -> Option<Pairing> {
+        self.prune(now);
+        self.seekers.remove(&seek.player_npub);
+        if seek.expires_at <= now {
+            return None;
+        }
+
+        let challenge = self
+            .open_challenges(seek.league_id, seek.min_wager, seek.max_wager)
+            .into_iter()
+            .filter(|open| self.compatible(open, &seek, rating))
+            .min_by(|a, b| {
+                (a.rating - rating)
+                    .abs()
+                    .total_cmp(&(b.rating - rating).abs())
+                    .then(a.expires_at.cmp(&b.expires_at))
+            })
+            .map(|open| open.match_event_id.clone());
+
+        match challenge.and_then(|id| self.challenges.remove(&id)) {
+            Some(open) => Some(pairing(&open, seek.player_npub)),
+            None => {
+                self.seekers
+                    .insert(seek.player_npub.clone(), (seek, rating));
+                None
+            }
+        }
+    }
+
+    /// Take a challenge out of the pool, e.g. once someone accepted it
+    pub fn remove_challenge(&mut self, match_event_id: &str) {
+        self.challenges.remove(match_event_id);
+    }
+
+    /// Pooled challenges of a league with a wager in the given range
+    pub fn open_challenges(
+        &self,
+        league_id: u8,
+        min_wager: u64,
+        max_wager: u64,
+    ) -> Vec<&OpenChallenge> {
+        self.challenges
+            .values()
+            .filter(|open| {
+                open.league_id == league_id && (min_wager..=max_wager).contains(&open.wager_amount)
+            })
+            .collect()
+    }
+
+    /// Seekers waiting for a challenge
+    pub fn waiting_seekers(&self) -> usize {
+        self.seekers.len()
+    }
+
+    fn compatible(&self, open: &OpenChallenge, seek: &MatchSeek, seeker_rating: f64) -> bool {
+        open.league_id == seek.league_id
+            && (seek.min_wager..=seek.max_wager).contains(&open.wager_amount)
+            && open.challenger_npub != seek.player_npub
+            && (open.rating - seeker_rating).abs() <= self.max_rating_gap
+    }
+
+    fn prune(&mut self, now: u64) {
+        self.challenges.retain(|_, open| open.expires_at > now);
+        self.seekers.retain(|_, (seek, _)| seek.expires_at > now);
+    }
+}
+
+fn pairing(open: &OpenChallenge, acceptor_npub: String) -> Pairing {
+    Pairing {
+        match_event_id: open.match_event_id.clone(),
+        challenger_npub: open.challenger_npub.clone(),
+        acceptor_npub,
+        league_id: open.league_id,
+        wager_amount: open.wager_amount,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_events::test_support;
+
+    fn challenge(id: &str, challenger: &str, wager_amount: u64) -> MatchChallenge {
+        MatchChallenge {
+            challenger_npub: challenger.to_string(),
+            wager_amount,
+            expires_at: 2000,
+            created_at: 1000,
+            ..test_support::challenge(id)
+        }
+    }
+
+    fn seek(player: &str, min_wager: u64, max_wager: u64) -> MatchSeek {
+        MatchSeek {
+            player_npub: player.to_string(),
+            league_id: 0,
+            min_wager,
+            max_wager,
+            expires_at: 2000,
+            created_at: 1000,
+        }
+    }
+
+    #[test]
+    fn test_seeker_is_paired_with_closest_rated_compatible_challenge() {
+        let mut pool = MatchmakingPool::new(200.0);
+        assert!(pool
+            .add_challenge(&challenge("strong", "npub1strong", 100), 1500.0, 1000)
+            .is_none());
+        assert!(pool
+            .add_challenge(&challenge("close", "npub1close", 100), 1250.0, 1000)
+            .is_none());
+        assert!(pool
+            .add_challenge(&challenge("rich", "npub1rich", 500), 1200.0, 1000)
+            .is_none());
+
+        // The 500 wager is out of range and 1500 too far above; 1250 is the match
+        let paired = pool
+            .add_seek(seek("npub1bob", 50, 200), 1200.0, 1100)
+            .unwrap();
+        assert_eq!(paired.match_event_id, "close");
+        assert_eq!(paired.acceptor_npub, "npub1bob");
+        assert_eq!(pool.open_challenges(0, 0, u64::MAX).len(), 2);
+
+        // Nothing fits carol, so she waits for the next compatible challenge
+        assert!(pool
+            .add_seek(seek("npub1carol", 50, 200), 1000.0, 1100)
+            .is_none());
+        assert_eq!(pool.waiting_seekers(), 1);
+        let paired = pool
+            .add_challenge(&challenge("fresh", "npub1dave", 150), 1100.0, 1200)
+            .unwrap();
+        assert_eq!(paired.acceptor_npub, "npub1carol");
+        assert_eq!(pool.waiting_seekers(), 0);
+
+        // Seeks and challenges lapse at their expiry
+        assert!(pool
+            .add_seek(seek("npub1erin", 0, 1000), 1200.0, 2000)
+            .is_none());
+        assert!(pool.open_challenges(0, 0, u64::MAX).is_empty());
+    }
+}
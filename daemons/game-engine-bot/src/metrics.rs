@@ -0,0 +1,273 @@
+use axum::{http::header, routing::get, Router};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::info;
+
+use crate::combat_cache::combat_cache;
+use crate::config::MetricsConfig;
+use crate::errors::GameEngineError;
+
+/// Latency bucket upper bounds in seconds
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Cumulative latency histogram in the Prometheus layout
+#[derive(Debug, Default)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{le=\"{bound}\"}} {}",
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.count();
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}");
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{name}_sum {sum}");
+        let _ = writeln!(out, "{name}_count {count}");
+    }
+}
+
+/// Bounded queue whose depth is sampled on every scrape
+struct QueueGauge {
+    queue: &'static str,
+    sample: Box<dyn Fn() -> (usize, usize) + Send + Sync>, // (depth, capacity)
+}
+
+impl std::fmt::Debug for QueueGauge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueueGauge")
+            .field("queue", &self.queue)
+            .finish()
+    }
+}
+
+/// Operational counters and latencies for the game engine
+#[derive(Debug, Default)]
+pub struct EngineMetrics {
+    pub matches_started: AtomicU64,
+    pub matches_validated: AtomicU64,
+    pub cheating_detected: AtomicU64,
+    pub loot_distributed: AtomicU64, // Loot units paid out to winners
+    pub nostr_event_latency: LatencyHistogram,
+    pub cashu_rpc_latency: LatencyHistogram,
+    queues: Mutex<Vec<QueueGauge>>,
+}
+
+impl EngineMetrics {
+    pub fn record_match_started(&self) {
+        self.matches_started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_match_validated(&self) {
+        self.matches_validated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cheating_detected(&self) {
+        self.cheating_detected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_loot_distributed(&self, amount: u64) {
+        self.loot_distributed.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Report how full a bounded channel is, without keeping it open
+    pub fn watch_queue<T: Send + 'static>(&self, queue: &'static str, sender: &mpsc::Sender<T>) {
+        let sender = sender.downgrade();
+        let sample = move || {
+            sender.upgrade().map_or((0, 0), |sender| {
+                (
+                    sender.max_capacity() - sender.capacity(),
+                    sender.max_capacity(),
+                )
+            })
+        };
+        self.queues.lock().unwrap().push(QueueGauge {
+            queue,
+            sample: Box::new(sample),
+        });
+    }
+
+    /// Render every metric in the Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "manastr_matches_started_total",
+                "Matches that entered combat after both players revealed",
+                &self.matches_started,
+            ),
+            (
+                "manastr_matches_validated_total",
+                "Matches validated to a result",
+                &self.matches_validated,
+            ),
+            (
+                "manastr_cheating_detected_total",
+                "Matches invalidated with an offending player",
+                &self.cheating_detected,
+            ),
+            (
+                "manastr_loot_distributed_total",
+                "Loot units minted to match winners",
+                &self.loot_distributed,
+            ),
+            (
+                "manastr_combat_cache_hits_total",
+                "Combat rounds served from the cache instead of re-executed",
+                &combat_cache().hits,
+            ),
+            (
+                "manastr_combat_cache_misses_total",
+                "Combat rounds re-executed because the cache held no result",
+                &combat_cache().misses,
+            ),
+        ];
+
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {}", value.load(Ordering::Relaxed));
+        }
+
+        let queues: Vec<_> = self
+            .queues
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|gauge| (gauge.queue, (gauge.sample)()))
+            .collect();
+        let _ = writeln!(
+            out,
+            "# HELP manastr_queue_depth Items waiting in an internal queue"
+        );
+        let _ = writeln!(out, "# TYPE manastr_queue_depth gauge");
+        for (queue, (depth, _)) in &queues {
+            let _ = writeln!(out, "manastr_queue_depth{{queue=\"{queue}\"}} {depth}");
+        }
+        let _ = writeln!(
+            out,
+            "# HELP manastr_queue_capacity Items an internal queue holds before applying backpressure"
+        );
+        let _ = writeln!(out, "# TYPE manastr_queue_capacity gauge");
+        for (queue, (_, capacity)) in &queues {
+            let _ = writeln!(
+                out,
+                "manastr_queue_capacity{{queue=\"{queue}\"}} {capacity}"
+            );
+        }
+
+        self.nostr_event_latency.render(
+            &mut out,
+            "manastr_nostr_event_processing_seconds",
+            "Time spent processing a player match event",
+        );
+        self.cashu_rpc_latency.render(
+            &mut out,
+            "manastr_cashu_rpc_seconds",
+            "Round-trip time of requests to the Cashu mint",
+        );
+        out
+    }
+}
+
+/// Serve `GET /metrics` for Prometheus scrapes until the process exits
+pub async fn run_metrics_server(
+    metrics: Arc<EngineMetrics>,
+    config: MetricsConfig,
+) -> Result<(), GameEngineError> {
+    let app = Router::new().route(
+        "/metrics",
+        get(move || {
+            let metrics = metrics.clone();
+            async move {
+                (
+                    [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                    metrics.render_prometheus(),
+                )
+            }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind(&config.bind_address)
+        .await
+        .map_err(|e| {
+            GameEngineError::Internal(format!(
+                "Failed to bind metrics endpoint {}: {e}",
+                config.bind_address
+            ))
+        })?;
+
+    info!(
+        "📈 Metrics endpoint listening on http://{}/metrics",
+        config.bind_address
+    );
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| GameEngineError::Internal(format!("Metrics endpoint failed: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_exposition() {
+        let metrics = EngineMetrics::default();
+        metrics.record_match_started();
+        metrics.record_loot_distributed(100);
+        metrics.record_loot_distributed(50);
+        metrics.cashu_rpc_latency.observe(Duration::from_millis(20));
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("manastr_matches_started_total 1\n"));
+        assert!(text.contains("manastr_loot_distributed_total 150\n"));
+        assert!(text.contains("manastr_cashu_rpc_seconds_bucket{le=\"0.01\"} 0\n"));
+        assert!(text.contains("manastr_cashu_rpc_seconds_bucket{le=\"0.025\"} 1\n"));
+        assert!(text.contains("manastr_cashu_rpc_seconds_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("manastr_cashu_rpc_seconds_sum 0.02\n"));
+        assert!(text.contains("manastr_nostr_event_processing_seconds_count 0\n"));
+        assert!(text.contains("# TYPE manastr_combat_cache_hits_total counter\n"));
+    }
+
+    #[tokio::test]
+    async fn test_queue_depth_is_sampled_on_render() {
+        let metrics = EngineMetrics::default();
+        let (sender, _receiver) = mpsc::channel(8);
+        metrics.watch_queue("match_events", &sender);
+        sender.send(()).await.unwrap();
+        sender.send(()).await.unwrap();
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("manastr_queue_depth{queue=\"match_events\"} 2\n"));
+        assert!(text.contains("manastr_queue_capacity{queue=\"match_events\"} 8\n"));
+    }
+}
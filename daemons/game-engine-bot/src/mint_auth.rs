@@ -0,0 +1,53 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nostr::util::hex;
+use nostr::{EventBuilder, JsonUtil, Keys, Kind, Tag, TagKind};
+use sha2::{Digest, Sha256};
+
+use crate::errors::GameEngineError;
+
+/// Build a NIP-98 `Authorization` header proving a mint request comes from the engine
+///
+/// The signed event binds the URL, method and body hash, so the mint can reject
+/// replays against other endpoints or with a tampered payload.
+pub fn authorization_header(
+    keys: &Keys,
+    url: &str,
+    method: &str,
+    body: &[u8],
+) -> Result<String, GameEngineError> {
+    let tags = vec![
+        Tag::custom(TagKind::Custom("u".into()), vec![url.to_string()]),
+        Tag::custom(TagKind::Custom("method".into()), vec![method.to_string()]),
+        Tag::custom(
+            TagKind::Custom("payload".into()),
+            vec![hex::encode(Sha256::digest(body))],
+        ),
+    ];
+
+    let event = EventBuilder::new(Kind::HttpAuth, "", tags)
+        .to_event(keys)
+        .map_err(|e| GameEngineError::NostrError(format!("Failed to sign mint request: {e}")))?;
+
+    Ok(format!("Nostr {}", STANDARD.encode(event.as_json())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr::Event;
+
+    #[test]
+    fn test_authorization_header_is_signed_by_engine() {
+        let keys = Keys::generate();
+        let header =
+            authorization_header(&keys, "http://mint/game-engine/burn", "POST", b"{}").unwrap();
+
+        let encoded = header.strip_prefix("Nostr ").unwrap();
+        let event = Event::from_json(STANDARD.decode(encoded).unwrap()).unwrap();
+
+        assert_eq!(event.kind, Kind::HttpAuth);
+        assert_eq!(event.pubkey, keys.public_key());
+        assert!(event.verify().is_ok());
+    }
+}
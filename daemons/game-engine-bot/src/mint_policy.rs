@@ -0,0 +1,162 @@
+use chrono::{NaiveDate, Utc};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::config::MintTrustPolicy;
+
+/// Loot minted so far within the current UTC day
+#[derive(Debug)]
+struct DailySpend {
+    day: NaiveDate,
+    minted: u64,
+}
+
+/// Enforces the spend-limit ceilings of a mint trust policy
+///
+/// Payouts are reserved against the daily ceiling before the mint is contacted
+/// and released again only if the loot was never minted, so concurrent or retried
+/// payouts can never jointly exceed the configured limits.
+#[derive(Debug)]
+pub struct SpendLimiter {
+    policy: MintTrustPolicy,
+    spent: Mutex<DailySpend>,
+}
+
+impl SpendLimiter {
+    pub fn new(policy: MintTrustPolicy) -> Self {
+        Self {
+            policy,
+            spent: Mutex::new(DailySpend {
+                day: Utc::now().date_naive(),
+                minted: 0,
+            }),
+        }
+    }
+
+    pub fn policy(&self) -> &MintTrustPolicy {
+        &self.policy
+    }
+
+    /// Reserve a payout against the ceilings, returning a violation message if refused
+    pub fn reserve(&self, amount: u64) -> Result<(), String> {
+        self.reserve_on(Utc::now().date_naive(), amount)
+    }
+
+    /// Resume the day's tally from loot a previous run minted on `day`
+    pub fn restore(&self, day: NaiveDate, minted: u64) {
+        let mut spent = self.spent.lock().unwrap();
+        if spent.day == day {
+            spent.minted = spent.minted.max(minted);
+        }
+    }
+
+    /// Release a reservation whose loot was never minted
+    pub fn release(&self, amount: u64) {
+        let mut spent = self.spent.lock().unwrap();
+        spent.minted = spent.minted.saturating_sub(amount);
+    }
+
+    /// Loot reserved so far today
+    pub fn minted_today(&self) -> u64 {
+        let spent = self.spent.lock().unwrap();
+        if spent.day == Utc::now().date_naive() {
+            spent.minted
+        } else {
+            0
+        }
+    }
+
+    fn reserve_on(&self, today: NaiveDate, amount: u64) -> Result<(), String> {
+        if amount > self.policy.max_single_payout {
+            warn!(
+                "🛑 Refusing payout of {} (single payout ceiling {})",
+                amount, self.policy.max_single_payout
+            );
+            return Err(format!(
+                "payout of {amount} exceeds single payout ceiling of {}",
+                self.policy.max_single_payout
+            ));
+        }
+
+        let mut spent = self.spent.lock().unwrap();
+        if spent.day != today {
+            info!(
+                "📅 New mint spend window for {} (previous day minted {})",
+                today, spent.minted
+            );
+            spent.day = today;
+            spent.minted = 0;
+        }
+
+        let total = spent.minted.saturating_add(amount);
+        if total > self.policy.max_loot_per_day {
+            warn!(
+                "🛑 Refusing payout of {}: daily ceiling {} reached ({} already minted today)",
+                amount, self.policy.max_loot_per_day, spent.minted
+            );
+            return Err(format!(
+                "payout of {amount} would exceed daily loot ceiling of {} ({} already minted today)",
+                self.policy.max_loot_per_day, spent.minted
+            ));
+        }
+
+        spent.minted = total;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_loot_per_day: u64, max_single_payout: u64) -> MintTrustPolicy {
+        MintTrustPolicy {
+            max_loot_per_day,
+            max_single_payout,
+            require_checkstate_before_payout: false,
+        }
+    }
+
+    #[test]
+    fn test_single_payout_ceiling() {
+        let limiter = SpendLimiter::new(policy(1000, 100));
+        assert!(limiter.reserve(100).is_ok());
+        assert!(limiter.reserve(101).is_err());
+        assert_eq!(limiter.minted_today(), 100);
+    }
+
+    #[test]
+    fn test_daily_ceiling_and_release() {
+        let limiter = SpendLimiter::new(policy(250, 100));
+        assert!(limiter.reserve(100).is_ok());
+        assert!(limiter.reserve(100).is_ok());
+        assert!(limiter.reserve(100).is_err());
+
+        // A failed mint interaction hands its reservation back
+        limiter.release(100);
+        assert!(limiter.reserve(50).is_ok());
+        assert_eq!(limiter.minted_today(), 150);
+    }
+
+    #[test]
+    fn test_restored_tally_counts_against_ceiling() {
+        let limiter = SpendLimiter::new(policy(250, 100));
+        let today = Utc::now().date_naive();
+
+        // Loot minted before a restart still counts; other days do not
+        limiter.restore(today.pred_opt().unwrap(), 250);
+        assert_eq!(limiter.minted_today(), 0);
+        limiter.restore(today, 200);
+        assert!(limiter.reserve(100).is_err());
+        assert!(limiter.reserve(50).is_ok());
+    }
+
+    #[test]
+    fn test_daily_window_resets() {
+        let limiter = SpendLimiter::new(policy(100, 100));
+        let today = Utc::now().date_naive();
+        assert!(limiter.reserve_on(today, 100).is_ok());
+        assert!(limiter.reserve_on(today, 1).is_err());
+        assert!(limiter.reserve_on(today.succ_opt().unwrap(), 100).is_ok());
+    }
+}
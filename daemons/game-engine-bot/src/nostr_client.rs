@@ -1,12 +1,27 @@
 use anyhow::Result;
-use nostr::{Event, Keys};
-use nostr_sdk::{Client, RelayPoolNotification};
-use tokio::sync::mpsc;
+use nostr::nips::nip04;
+use nostr::nips::nip59::UnwrappedGift;
+use nostr::nips::nip65::{self, RelayMetadata};
+use nostr::{
+    ClientMessage, Event, EventBuilder, Filter, JsonUtil, Keys, Kind, PublicKey, RelayMessage, Url,
+};
+use nostr_sdk::{Client, EventSource, Options, RelayPoolNotification};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, error::TrySendError};
 use tracing::{debug, error, info, warn};
 
-use crate::config::NostrConfig;
+use crate::config::{GameConfig, NostrConfig, RateLimitConfig, RelayDiscoveryConfig};
 use crate::errors::GameEngineError;
+use crate::latency::LatencyTracker;
 use crate::match_events::*;
+use crate::matchmaking::Pairing;
+use crate::player_event_log::PlayerEventLog;
+use crate::rate_limiter::{RateDecision, RateLimiter};
+use crate::reconciliation::LootShare;
+use crate::relay_discovery::MatchRelays;
+use crate::replay_guard::{ReplayGuard, RETENTION_SECONDS};
+use crate::reputation::PlayerReputation;
 
 /// Player-driven match event for the game engine to process
 #[derive(Debug, Clone)]
@@ -14,81 +29,196 @@ pub enum PlayerMatchEvent {
     Challenge(MatchChallenge),
     Acceptance(MatchAcceptance),
     TokenReveal(TokenReveal),
+    BanCommitment(DraftBanCommitment),
+    BanReveal(DraftBanReveal),
     CombatMove(CombatMove),
     MatchResult(MatchResult),
+    Seek(MatchSeek),
 }
 
+impl PlayerMatchEvent {
+    /// Match the event refers to (empty for seeks, which belong to no match yet)
+    pub fn match_event_id(&self) -> &str {
+        match self {
+            PlayerMatchEvent::Challenge(challenge) => &challenge.match_event_id,
+            PlayerMatchEvent::Acceptance(acceptance) => &acceptance.match_event_id,
+            PlayerMatchEvent::TokenReveal(reveal) => &reveal.match_event_id,
+            PlayerMatchEvent::BanCommitment(commitment) => &commitment.match_event_id,
+            PlayerMatchEvent::BanReveal(reveal) => &reveal.match_event_id,
+            PlayerMatchEvent::CombatMove(combat_move) => &combat_move.match_event_id,
+            PlayerMatchEvent::MatchResult(result) => &result.match_event_id,
+            PlayerMatchEvent::Seek(_) => "",
+        }
+    }
+
+    /// Player who published the event
+    pub fn player_npub(&self) -> &str {
+        match self {
+            PlayerMatchEvent::Challenge(challenge) => &challenge.challenger_npub,
+            PlayerMatchEvent::Acceptance(acceptance) => &acceptance.acceptor_npub,
+            PlayerMatchEvent::TokenReveal(reveal) => &reveal.player_npub,
+            PlayerMatchEvent::BanCommitment(commitment) => &commitment.player_npub,
+            PlayerMatchEvent::BanReveal(reveal) => &reveal.player_npub,
+            PlayerMatchEvent::CombatMove(combat_move) => &combat_move.player_npub,
+            PlayerMatchEvent::MatchResult(result) => &result.player_npub,
+            PlayerMatchEvent::Seek(seek) => &seek.player_npub,
+        }
+    }
+}
+
+/// Prefix relays use in OK and CLOSED messages when NIP-42 AUTH is required
+const AUTH_REQUIRED_PREFIX: &str = "auth-required:";
+
+/// Time given to the listener to answer an AUTH challenge before a publish is retried
+const AUTH_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How far back to fetch direct messages; NIP-59 gift wraps backdate created_at by up to two days
+const DIRECT_MESSAGE_LOOKBACK_SECONDS: u64 = 2 * 24 * 3600;
+
+// Redelivered direct messages must still be remembered by the replay guard
+const _: () = assert!(DIRECT_MESSAGE_LOOKBACK_SECONDS <= RETENTION_SECONDS);
+
 /// Nostr client for the Game Engine Bot
 pub struct NostrClient {
     client: Client,
     keys: Keys,
-    match_event_sender: mpsc::UnboundedSender<PlayerMatchEvent>,
+    relay_urls: Vec<String>,
+    config: Arc<NostrConfig>,
+    match_event_sender: mpsc::Sender<PlayerMatchEvent>,
+    latency: Arc<LatencyTracker>,
+    replay_guard: Arc<Mutex<ReplayGuard>>,
+    player_events: Arc<PlayerEventLog>, // Signed events of each match, for proof bundles
+    rate_limiter: Arc<RateLimiter>,     // Sheds floods before they reach the event queue
+    private_matches: Arc<Mutex<HashMap<String, Vec<String>>>>, // Match id -> players to DM
+    relay_discovery: RelayDiscoveryConfig,
+    match_relays: Arc<Mutex<MatchRelays>>, // Player relays followed per tracked match
 }
 
 impl NostrClient {
     /// Create a new Nostr client for the game engine bot
     pub async fn new(
         config: &NostrConfig,
-        match_event_sender: mpsc::UnboundedSender<PlayerMatchEvent>,
+        match_event_sender: mpsc::Sender<PlayerMatchEvent>,
     ) -> Result<Self, GameEngineError> {
         // Parse private key
         let keys = Keys::parse(&config.private_key)
             .map_err(|e| GameEngineError::NostrError(format!("Invalid private key: {e}")))?;
 
-        let client = Client::new(&keys);
+        // NIP-42 AUTH is answered per relay in the notification loop, never blanket
+        let client = Client::with_opts(&keys, Options::new().automatic_authentication(false));
 
-        // Connect to relay
-        client
-            .add_relay(&config.relay_url)
-            .await
-            .map_err(|e| GameEngineError::NostrError(format!("Failed to add relay: {e}")))?;
+        // Connect to every configured relay; one bad URL should not take the engine down
+        let mut relay_urls = Vec::new();
+        for relay_url in config.all_relay_urls() {
+            match client.add_relay(&relay_url).await {
+                Ok(_) => relay_urls.push(relay_url),
+                Err(e) => warn!("⚠️ Skipping relay {}: {}", relay_url, e),
+            }
+        }
+
+        if relay_urls.is_empty() {
+            return Err(GameEngineError::NostrError(
+                "Failed to add any configured relay".to_string(),
+            ));
+        }
 
         client.connect().await;
 
-        info!("✅ Connected to Nostr relay: {}", config.relay_url);
+        info!("✅ Connected to Nostr relays: {}", relay_urls.join(", "));
         info!("🔑 Game Engine Bot pubkey: {}", keys.public_key());
 
         Ok(Self {
             client,
             keys,
+            config: Arc::new(config.clone()),
             match_event_sender,
+            latency: Arc::new(LatencyTracker::new()),
+            replay_guard: Arc::new(Mutex::new(ReplayGuard::in_memory()?)),
+            player_events: Arc::new(PlayerEventLog::in_memory()?),
+            rate_limiter: Arc::new(RateLimiter::new(RateLimitConfig {
+                enabled: false,
+                ..RateLimitConfig::default()
+            })),
+            private_matches: Arc::new(Mutex::new(HashMap::new())),
+            relay_discovery: RelayDiscoveryConfig {
+                enabled: false,
+                ..RelayDiscoveryConfig::default()
+            },
+            match_relays: Arc::new(Mutex::new(MatchRelays::new(relay_urls.clone()))),
+            relay_urls,
         })
     }
 
+    /// Remember processed events in the engine database so replays are dropped after restarts
+    pub fn with_replay_guard(mut self, replay_guard: ReplayGuard) -> Self {
+        self.replay_guard = Arc::new(Mutex::new(replay_guard));
+        self
+    }
+
+    /// Keep players' signed match events in the engine database, so proof bundles
+    /// can still carry them after a restart
+    pub fn with_player_event_log(mut self, player_events: PlayerEventLog) -> Self {
+        self.player_events = Arc::new(player_events);
+        self
+    }
+
+    /// Players' signed events received for a match
+    pub fn player_events(&self, match_id: &str) -> Result<Vec<Event>, GameEngineError> {
+        self.player_events.events(match_id)
+    }
+
+    /// Drop events from pubkeys sending faster than their limit, banning persistent floods
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(rate_limit));
+        self
+    }
+
+    /// Also subscribe to the relays players publish to, from their NIP-65 relay lists
+    pub fn with_relay_discovery(mut self, relay_discovery: RelayDiscoveryConfig) -> Self {
+        self.relay_discovery = relay_discovery;
+        self
+    }
+
     /// Start listening for player-driven match events
     pub async fn start_event_listener(&self) -> Result<(), GameEngineError> {
-        // OPTIMIZED FILTERING: Only process game-related Nostr events (KIND 31000-31005)
-        // This prevents wasting computational resources on non-game events
-        let since_timestamp = nostr::Timestamp::now() - 3600; // 1 hour ago for integration testing
-
-        // Single efficient filter for all game event types
-        let game_events_filter = nostr::Filter::new()
-            .kinds(vec![
-                KIND_MATCH_CHALLENGE,  // 21000 - Player creates match
-                KIND_MATCH_ACCEPTANCE, // 21001 - Player accepts challenge
-                KIND_TOKEN_REVEAL,     // 21002 - Player reveals Cashu tokens
-                KIND_COMBAT_MOVE,      // 21003 - Player submits combat move
-                KIND_MATCH_RESULT,     // 21004 - Player submits final match state
-                                       // NOTE: KIND_LOOT_DISTRIBUTION (21005) excluded - game engine publishes this
-            ])
-            .since(since_timestamp);
-
         let _subscription_id = self
             .client
-            .subscribe(vec![game_events_filter], None)
+            .subscribe(self.subscription_filters(), None)
             .await
             .map_err(|e| GameEngineError::NostrError(format!("Failed to subscribe: {e}")))?;
 
-        info!("📡 🎯 OPTIMIZED FILTERING: Subscribed to game events only (KIND 31000-31005)");
+        info!(
+            "📡 🎯 OPTIMIZED FILTERING: Subscribed to game events only (KIND 31000-31005) on {} relays",
+            self.relay_urls.len()
+        );
+        info!("🔒 Accepting private match events by encrypted direct message (NIP-04/NIP-17)");
 
         // Start event processing loop in background task
         let client_clone = self.client.clone();
         let sender_clone = self.match_event_sender.clone();
+        let latency_clone = Arc::clone(&self.latency);
+        let keys_clone = self.keys.clone(); // Signs NIP-42 AUTH responses
+        let config_clone = Arc::clone(&self.config);
+        let replay_guard_clone = Arc::clone(&self.replay_guard);
+        let player_events_clone = Arc::clone(&self.player_events);
+        let rate_limiter_clone = Arc::clone(&self.rate_limiter);
+        let private_matches_clone = Arc::clone(&self.private_matches);
+        let relay_discovery_clone = self.relay_discovery.clone();
+        let match_relays_clone = Arc::clone(&self.match_relays);
         tokio::spawn(async move {
             let temp_client = NostrClient {
                 client: client_clone,
-                keys: Keys::generate(), // Dummy keys for processing
+                keys: keys_clone,
+                relay_urls: Vec::new(),
+                config: config_clone,
                 match_event_sender: sender_clone,
+                latency: latency_clone,
+                replay_guard: replay_guard_clone,
+                player_events: player_events_clone,
+                rate_limiter: rate_limiter_clone,
+                private_matches: private_matches_clone,
+                relay_discovery: relay_discovery_clone,
+                match_relays: match_relays_clone,
             };
             temp_client.process_notifications().await;
         });
@@ -105,7 +235,27 @@ impl NostrClient {
 
         while let Ok(notification) = notifications.recv().await {
             match notification {
-                RelayPoolNotification::Event { event, .. } => {
+                RelayPoolNotification::Event {
+                    event, relay_url, ..
+                } => {
+                    // Check the signature first so a forged copy cannot burn a genuine event's id
+                    if let Err(e) = event.verify() {
+                        warn!(
+                            "🚫 Dropping event {} with an invalid signature: {}",
+                            event.id, e
+                        );
+                        continue;
+                    }
+
+                    // The same event arrives once per relay, and relays or attackers may replay old ones
+                    if self.already_processed(&event) {
+                        debug!(
+                            "🔁 Dropping duplicate or replayed event {} from {}",
+                            event.id, relay_url
+                        );
+                        continue;
+                    }
+
                     processed_events += 1;
 
                     // Only game events (KIND 31000-31005) should reach here due to subscription filter
@@ -114,8 +264,16 @@ impl NostrClient {
                         event.kind, event.id, event.pubkey
                     );
 
-                    if let Err(e) = self.handle_event(&event).await {
-                        error!("Failed to handle event {}: {}", event.id, e);
+                    // Remember it only once handled, so a copy of a throttled event gets another chance
+                    let handled = if is_direct_message(&event) {
+                        self.handle_direct_message(&event).await
+                    } else {
+                        self.handle_event(&event, false).await
+                    };
+                    match handled {
+                        Ok(true) => self.mark_processed(&event),
+                        Ok(false) => {}
+                        Err(e) => error!("Failed to handle event {}: {}", event.id, e),
                     }
 
                     // Periodic efficiency logging
@@ -123,6 +281,28 @@ impl NostrClient {
                         info!("📊 Processed {} game events (filtered subscription working efficiently)", processed_events);
                     }
                 }
+                RelayPoolNotification::Message {
+                    relay_url,
+                    message: RelayMessage::Auth { challenge },
+                } => {
+                    self.authenticate(relay_url, challenge).await;
+                }
+                RelayPoolNotification::Message {
+                    relay_url,
+                    message: RelayMessage::Closed { message, .. },
+                } if message.starts_with(AUTH_REQUIRED_PREFIX)
+                    && self.config.auth_enabled(relay_url.as_str()) =>
+                {
+                    // The relay refused the subscription until we authenticated; ask again
+                    info!("🔐 Resubscribing to {} after AUTH", relay_url);
+                    if let Err(e) = self
+                        .client
+                        .subscribe_to([relay_url.clone()], self.subscription_filters(), None)
+                        .await
+                    {
+                        warn!("⚠️ Failed to resubscribe to {}: {}", relay_url, e);
+                    }
+                }
                 RelayPoolNotification::Message { message, .. } => {
                     debug!("Relay message: {:?}", message);
                 }
@@ -144,8 +324,172 @@ impl NostrClient {
         );
     }
 
+    /// Answer a relay's NIP-42 AUTH challenge if authentication is enabled for it
+    async fn authenticate(&self, relay_url: Url, challenge: String) {
+        if !self.config.auth_enabled(relay_url.as_str()) {
+            debug!(
+                "Ignoring AUTH challenge from {} (auth not enabled)",
+                relay_url
+            );
+            return;
+        }
+
+        let event = match EventBuilder::auth(challenge, relay_url.clone()).to_event(&self.keys) {
+            Ok(event) => event,
+            Err(e) => {
+                error!("❌ Failed to sign AUTH event for {}: {}", relay_url, e);
+                return;
+            }
+        };
+
+        match self
+            .client
+            .send_msg_to([relay_url.clone()], ClientMessage::auth(event))
+            .await
+        {
+            Ok(_) => info!("🔐 Authenticated to {}", relay_url),
+            Err(e) => warn!("⚠️ Failed to send AUTH to {}: {}", relay_url, e),
+        }
+    }
+
+    /// Game events plus the direct messages addressed to the engine
+    fn subscription_filters(&self) -> Vec<Filter> {
+        vec![
+            game_events_filter(),
+            Filter::new()
+                .kinds(vec![Kind::EncryptedDirectMessage, Kind::GiftWrap])
+                .pubkey(self.keys.public_key())
+                .since(nostr::Timestamp::now() - DIRECT_MESSAGE_LOOKBACK_SECONDS),
+        ]
+    }
+
+    /// Handle a player's signed match event delivered privately by NIP-04 or NIP-17 DM
+    ///
+    /// The wrapped event must be signed by the DM's sender; challenges sent this way are private.
+    /// Returns whether the message was dealt with, as `handle_event` does.
+    async fn handle_direct_message(&self, message: &Event) -> Result<bool, GameEngineError> {
+        let (sender, plaintext) = self.decrypt_direct_message(message)?;
+        let event = Event::from_json(&plaintext).map_err(|e| {
+            GameEngineError::NostrError(format!(
+                "Direct message {} does not carry a match event: {e}",
+                message.id
+            ))
+        })?;
+
+        event.verify().map_err(|e| {
+            protocol_error(
+                &event,
+                format!(
+                    "Event {} in direct message {} has an invalid signature: {e}",
+                    event.id, message.id
+                ),
+            )
+        })?;
+        if event.pubkey != sender {
+            return Err(protocol_error(
+                &event,
+                format!(
+                    "Event {} signed by {} was forwarded by {}",
+                    event.id, event.pubkey, sender
+                ),
+            ));
+        }
+
+        // The same event may also have been published, or forwarded in another DM
+        if self.already_processed(&event) {
+            debug!(
+                "🔁 Dropping replayed event {} from direct message",
+                event.id
+            );
+            return Ok(true);
+        }
+
+        let handled = self.handle_event(&event, true).await?;
+        if handled {
+            self.mark_processed(&event);
+        }
+        Ok(handled)
+    }
+
+    /// Whether the event was already handled; a failed lookup lets it through
+    fn already_processed(&self, event: &Event) -> bool {
+        let seen = self
+            .replay_guard
+            .lock()
+            .unwrap()
+            .already_seen(event.id.as_bytes());
+        seen.unwrap_or_else(|e| {
+            error!("🔁 Replay check failed for event {}: {}", event.id, e);
+            false
+        })
+    }
+
+    /// Remember a handled event so later copies and replays are dropped
+    fn mark_processed(&self, event: &Event) {
+        let recorded = self
+            .replay_guard
+            .lock()
+            .unwrap()
+            .record(event.id.as_bytes());
+        if let Err(e) = recorded {
+            error!("🔁 Failed to remember event {}: {}", event.id, e);
+        }
+    }
+
+    /// Decrypt a direct message to the engine, returning its sender and plaintext
+    fn decrypt_direct_message(
+        &self,
+        message: &Event,
+    ) -> Result<(PublicKey, String), GameEngineError> {
+        if message.kind == Kind::EncryptedDirectMessage {
+            let secret_key = self.keys.secret_key();
+            let plaintext =
+                nip04::decrypt(secret_key, &message.pubkey, &message.content).map_err(|e| {
+                    GameEngineError::NostrError(format!(
+                        "Failed to decrypt NIP-04 message {}: {e}",
+                        message.id
+                    ))
+                })?;
+            return Ok((message.pubkey, plaintext));
+        }
+
+        let gift = UnwrappedGift::from_gift_wrap(&self.keys, message).map_err(|e| {
+            GameEngineError::NostrError(format!(
+                "Failed to unwrap NIP-17 message {}: {e}",
+                message.id
+            ))
+        })?;
+        Ok((gift.sender, gift.rumor.content))
+    }
+
+    /// Whether the sender may send this event now; a pubkey that keeps flooding is banned
+    async fn within_rate_limit(&self, player_event: &PlayerMatchEvent) -> bool {
+        let npub = player_event.player_npub();
+        let is_challenge = matches!(player_event, PlayerMatchEvent::Challenge(_));
+        match self.rate_limiter.check(npub, is_challenge) {
+            RateDecision::Allow => true,
+            RateDecision::Throttle | RateDecision::Banned => {
+                debug!("🚦 Dropping event from rate-limited {}", npub);
+                false
+            }
+            RateDecision::Ban(ban) => {
+                let banned_until = ban.banned_until.timestamp() as u64;
+                if let Err(e) = self
+                    .publish_temporary_ban(&ban.npub, &ban.reason, banned_until)
+                    .await
+                {
+                    warn!("⚠️ Failed to publish ban of {}: {}", ban.npub, e);
+                }
+                false
+            }
+        }
+    }
+
     /// Handle incoming player-driven match events
-    async fn handle_event(&self, event: &Event) -> Result<(), GameEngineError> {
+    ///
+    /// `private` is set for events that arrived by direct message rather than from a public feed.
+    /// Returns whether the event was dealt with for good; throttled or shed events were not.
+    async fn handle_event(&self, event: &Event, private: bool) -> Result<bool, GameEngineError> {
         // OPTIMIZED: Game engine only processes game events (31000-31005)
         // All other events are filtered out at subscription level for efficiency
         debug!(
@@ -156,56 +500,218 @@ impl NostrClient {
         // Parse event based on kind - only game events should reach here due to subscription filter
         let player_event = match event.kind {
             kind if kind == KIND_MATCH_CHALLENGE => {
-                let challenge: MatchChallenge =
+                let mut challenge: MatchChallenge =
                     serde_json::from_str(&event.content).map_err(|e| {
-                        GameEngineError::NostrError(format!("Failed to parse challenge: {e}"))
+                        protocol_error(event, format!("Failed to parse challenge: {e}"))
                     })?;
+                // Privacy follows the transport, whatever the content claims
+                challenge.private = private;
                 PlayerMatchEvent::Challenge(challenge)
             }
             kind if kind == KIND_MATCH_ACCEPTANCE => {
                 let acceptance: MatchAcceptance =
                     serde_json::from_str(&event.content).map_err(|e| {
-                        GameEngineError::NostrError(format!("Failed to parse acceptance: {e}"))
+                        protocol_error(event, format!("Failed to parse acceptance: {e}"))
                     })?;
                 PlayerMatchEvent::Acceptance(acceptance)
             }
             kind if kind == KIND_TOKEN_REVEAL => {
                 let reveal: TokenReveal = serde_json::from_str(&event.content).map_err(|e| {
-                    GameEngineError::NostrError(format!("Failed to parse token reveal: {e}"))
+                    protocol_error(event, format!("Failed to parse token reveal: {e}"))
                 })?;
                 PlayerMatchEvent::TokenReveal(reveal)
             }
+            kind if kind == KIND_DRAFT_BAN_COMMITMENT => {
+                let commitment: DraftBanCommitment =
+                    serde_json::from_str(&event.content).map_err(|e| {
+                        protocol_error(event, format!("Failed to parse ban commitment: {e}"))
+                    })?;
+                PlayerMatchEvent::BanCommitment(commitment)
+            }
+            kind if kind == KIND_DRAFT_BAN_REVEAL => {
+                let reveal: DraftBanReveal = serde_json::from_str(&event.content).map_err(|e| {
+                    protocol_error(event, format!("Failed to parse ban reveal: {e}"))
+                })?;
+                PlayerMatchEvent::BanReveal(reveal)
+            }
             kind if kind == KIND_COMBAT_MOVE => {
                 let combat_move: CombatMove =
                     serde_json::from_str(&event.content).map_err(|e| {
-                        GameEngineError::NostrError(format!(
-                            "Failed to parse combat move: {e}"
-                        ))
+                        protocol_error(event, format!("Failed to parse combat move: {e}"))
                     })?;
                 PlayerMatchEvent::CombatMove(combat_move)
             }
             kind if kind == KIND_MATCH_RESULT => {
                 let result: MatchResult = serde_json::from_str(&event.content).map_err(|e| {
-                    GameEngineError::NostrError(format!("Failed to parse match result: {e}"))
+                    protocol_error(event, format!("Failed to parse match result: {e}"))
                 })?;
                 PlayerMatchEvent::MatchResult(result)
             }
+            kind if kind == KIND_MATCH_SEEK => {
+                let seek: MatchSeek = serde_json::from_str(&event.content)
+                    .map_err(|e| protocol_error(event, format!("Failed to parse seek: {e}")))?;
+                PlayerMatchEvent::Seek(seek)
+            }
             _ => {
                 // This should never happen due to subscription filtering, but log for debugging
                 warn!(
                     "⚠️ Unexpected event kind received: {} (subscription filter may need update)",
                     event.kind
                 );
-                return Ok(());
+                return Ok(true);
             }
         };
 
-        // Send to game engine for processing
-        self.match_event_sender.send(player_event).map_err(|e| {
-            GameEngineError::NostrError(format!("Failed to send match event: {e}"))
-        })?;
+        verify_signer(event, &player_event)?;
 
-        Ok(())
+        // Floods are shed here, before they take up room in the engine's queue
+        if !self.within_rate_limit(&player_event).await {
+            return Ok(false);
+        }
+
+        let match_event_id = player_event.match_event_id();
+        if !match_event_id.is_empty() {
+            if let Err(e) = self.player_events.record(match_event_id, event) {
+                warn!("⚠️ Failed to keep signed event {}: {}", event.id, e);
+            }
+        }
+
+        self.remember_private_player(&player_event, private);
+
+        // Record relay propagation latency for reveal pacing recommendations
+        if !match_event_id.is_empty() {
+            let received_at_ms = chrono::Utc::now().timestamp_millis().max(0) as u64;
+            let created_at_ms = event.created_at.as_u64() * 1000;
+            self.latency.record(
+                match_event_id,
+                player_event.player_npub(),
+                received_at_ms.saturating_sub(created_at_ms),
+            );
+        }
+
+        // A player joining a match may publish to relays the engine does not watch
+        let joined_match = matches!(
+            player_event,
+            PlayerMatchEvent::Challenge(_) | PlayerMatchEvent::Acceptance(_)
+        )
+        .then(|| player_event.match_event_id().to_string());
+
+        // Send to game engine for processing. While the queue is full new challenges
+        // are shed, but events of matches already under way wait for room
+        match self.match_event_sender.try_send(player_event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(PlayerMatchEvent::Challenge(challenge))) => {
+                warn!(
+                    "🚧 Event queue full, rejecting challenge {} from {}",
+                    challenge.match_event_id, challenge.challenger_npub
+                );
+                return self
+                    .publish_challenge_rejected(&challenge, "engine_busy")
+                    .await
+                    .map(|()| true);
+            }
+            Err(TrySendError::Full(PlayerMatchEvent::Seek(seek))) => {
+                // Seeks lapse anyway; the player posts another
+                debug!(
+                    "🚧 Event queue full, dropping seek from {}",
+                    seek.player_npub
+                );
+                return Ok(false);
+            }
+            Err(TrySendError::Full(player_event)) => {
+                self.match_event_sender
+                    .send(player_event)
+                    .await
+                    .map_err(|e| {
+                        GameEngineError::NostrError(format!("Failed to send match event: {e}"))
+                    })?;
+            }
+            Err(TrySendError::Closed(_)) => {
+                return Err(GameEngineError::NostrError(
+                    "Failed to send match event: engine stopped".to_string(),
+                ));
+            }
+        }
+
+        if let Some(match_event_id) = joined_match {
+            self.follow_player_relays(match_event_id, event.pubkey);
+        }
+
+        Ok(true)
+    }
+
+    /// Subscribe to the relays in the player's NIP-65 relay list for the rest of the match
+    ///
+    /// Runs in the background so a slow relay list lookup does not hold up other events.
+    fn follow_player_relays(&self, match_event_id: String, player: PublicKey) {
+        if !self.relay_discovery.enabled {
+            return;
+        }
+
+        let client = self.client.clone();
+        let match_relays = Arc::clone(&self.match_relays);
+        let relay_discovery = self.relay_discovery.clone();
+        tokio::spawn(async move {
+            let filter = Filter::new().kind(Kind::RelayList).author(player).limit(1);
+            let timeout = std::time::Duration::from_millis(relay_discovery.fetch_timeout_ms);
+            let relay_list = match client
+                .get_events_of(vec![filter], EventSource::relays(Some(timeout)))
+                .await
+            {
+                Ok(events) => events.into_iter().max_by_key(|event| event.created_at),
+                Err(e) => {
+                    debug!("No relay list for {}: {}", player, e);
+                    return;
+                }
+            };
+            let Some(relay_list) = relay_list else {
+                debug!("{} has not published a relay list", player);
+                return;
+            };
+
+            // Write relays (or unmarked ones) are where the player publishes
+            let write_relays = nip65::extract_relay_list(&relay_list)
+                .filter(|(_, metadata)| !matches!(metadata, Some(RelayMetadata::Read)))
+                .map(|(url, _)| url.to_string())
+                .take(relay_discovery.max_relays_per_player);
+            let added = match_relays
+                .lock()
+                .unwrap()
+                .follow(&match_event_id, write_relays);
+
+            for relay_url in added {
+                if let Err(e) = client.add_relay(&relay_url).await {
+                    warn!("⚠️ Skipping relay {} of {}: {}", relay_url, player, e);
+                    continue;
+                }
+                if let Err(e) = client.connect_relay(&relay_url).await {
+                    debug!("Connect to {} failed: {}", relay_url, e);
+                }
+                match client
+                    .subscribe_to([relay_url.clone()], vec![game_events_filter()], None)
+                    .await
+                {
+                    Ok(_) => info!(
+                        "🛰️ Following {}'s relay {} for match {}",
+                        player, relay_url, match_event_id
+                    ),
+                    Err(e) => warn!("⚠️ Failed to subscribe to {}: {}", relay_url, e),
+                }
+            }
+        });
+    }
+
+    /// Forget a settled match: stop DMing its players and drop relays only it needed
+    async fn finish_match(&self, match_event_id: &str) {
+        self.private_matches.lock().unwrap().remove(match_event_id);
+
+        let released = self.match_relays.lock().unwrap().release(match_event_id);
+        for relay_url in released {
+            match self.client.remove_relay(&relay_url).await {
+                Ok(()) => debug!("Stopped following relay {}", relay_url),
+                Err(e) => debug!("Failed to remove relay {}: {}", relay_url, e),
+            }
+        }
     }
 
     /// Publish loot distribution event (ONLY event the game engine publishes)
@@ -220,9 +726,7 @@ impl NostrClient {
                 GameEngineError::NostrError(format!("Failed to create loot event: {e}"))
             })?;
 
-        self.client.send_event(event).await.map_err(|e| {
-            GameEngineError::NostrError(format!("Failed to send loot event: {e}"))
-        })?;
+        self.send_event_with_failover(event, "loot").await?;
 
         info!(
             "🏆 Published loot distribution for match {}",
@@ -232,8 +736,677 @@ impl NostrClient {
         Ok(())
     }
 
+    /// Publish the validated outcome of a match with the winner's loot token
+    ///
+    /// `fee_cashu_token` carries the fee when it was paid as loot locked to the operator.
+    /// Returns the published award, which completes the match in the tracker.
+    pub async fn publish_loot_award(
+        &self,
+        match_event_id: &str,
+        winner_npub: Option<String>,
+        loot_cashu_token: Option<String>,
+        loot_amount: u64,
+        match_fee: u64,
+        fee_cashu_token: Option<String>,
+    ) -> Result<LootDistribution, GameEngineError> {
+        let loot_distribution = self.loot_award(
+            match_event_id,
+            winner_npub,
+            loot_cashu_token,
+            loot_amount,
+            match_fee,
+            fee_cashu_token,
+        );
+        self.publish_loot_distribution(&loot_distribution, match_event_id)
+            .await?;
+        self.finish_match(match_event_id).await;
+        Ok(loot_distribution)
+    }
+
+    /// Publish each drawn player's share of split loot, one award per player
+    ///
+    /// The match is only forgotten once every share is announced. Returns the last award.
+    pub async fn publish_split_loot_awards(
+        &self,
+        match_event_id: &str,
+        shares: &[(String, LootShare)],
+    ) -> Result<Option<LootDistribution>, GameEngineError> {
+        let mut last_award = None;
+        for (player_npub, share) in shares {
+            let loot_distribution = self.loot_award(
+                match_event_id,
+                Some(player_npub.clone()),
+                Some(share.token.clone()),
+                share.amount,
+                0,
+                None,
+            );
+            self.publish_loot_distribution(&loot_distribution, match_event_id)
+                .await?;
+            last_award = Some(loot_distribution);
+        }
+        self.finish_match(match_event_id).await;
+        Ok(last_award)
+    }
+
+    /// Loot award for a match the engine validated
+    fn loot_award(
+        &self,
+        match_event_id: &str,
+        winner_npub: Option<String>,
+        loot_cashu_token: Option<String>,
+        loot_amount: u64,
+        match_fee: u64,
+        fee_cashu_token: Option<String>,
+    ) -> LootDistribution {
+        LootDistribution {
+            game_engine_npub: self.public_key(),
+            match_event_id: match_event_id.to_string(),
+            winner_npub,
+            loot_cashu_token,
+            loot_amount,
+            match_fee,
+            fee_cashu_token,
+            loot_issued_at: chrono::Utc::now().timestamp() as u64,
+            validation_summary: ValidationSummary {
+                commitments_valid: true,
+                combat_verified: true,
+                signatures_valid: true,
+                winner_confirmed: true,
+                error_details: None,
+            },
+            rules_hash: shared_game_logic::rules_hash().to_string(),
+        }
+    }
+
+    /// Publish a round summary spectators can follow, with reveal pacing for the players
+    pub async fn publish_round_summary(
+        &self,
+        match_event_id: &str,
+        round_number: u32,
+        combat: Option<RoundCombat>,
+    ) -> Result<(), GameEngineError> {
+        let summary = RoundSummary {
+            game_engine_npub: self.public_key(),
+            match_event_id: match_event_id.to_string(),
+            round_number,
+            recommended_reveal_delay_ms: self.latency.recommended_reveal_delay_ms(match_event_id),
+            combat,
+            published_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = summary.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create round summary event: {e}"))
+        })?;
+
+        self.deliver_match_event(event, "round summary", match_event_id)
+            .await?;
+
+        info!(
+            "📊 Published round {} summary for match {} (reveal delay: {}ms)",
+            round_number, match_event_id, summary.recommended_reveal_delay_ms
+        );
+
+        Ok(())
+    }
+
+    /// Publish a match invalidation so players can reclaim their wagers
+    pub async fn publish_match_invalidation(
+        &self,
+        match_event_id: &str,
+        reason: &str,
+        offending_npub: Option<String>,
+        evidence_hashes: Vec<String>,
+    ) -> Result<(), GameEngineError> {
+        let invalidation = MatchInvalidation {
+            game_engine_npub: self.public_key(),
+            match_event_id: match_event_id.to_string(),
+            reason: reason.to_string(),
+            offending_npub,
+            evidence_hashes,
+            invalidated_at: chrono::Utc::now().timestamp() as u64,
+            rules_hash: shared_game_logic::rules_hash().to_string(),
+        };
+
+        let event = invalidation.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create match invalidation event: {e}"))
+        })?;
+
+        self.deliver_match_event(event, "match invalidation", match_event_id)
+            .await?;
+        self.finish_match(match_event_id).await;
+
+        info!(
+            "🚫 Published invalidation for match {} ({})",
+            match_event_id, reason
+        );
+
+        Ok(())
+    }
+
+    /// Publish a challenge cancellation so the challenger's wallet can unlock the committed tokens
+    pub async fn publish_challenge_cancelled(
+        &self,
+        match_event_id: &str,
+        challenger_npub: &str,
+        reason: &str,
+        expired_at: u64,
+    ) -> Result<(), GameEngineError> {
+        let cancellation = ChallengeCancelled {
+            game_engine_npub: self.public_key(),
+            match_event_id: match_event_id.to_string(),
+            challenger_npub: challenger_npub.to_string(),
+            reason: reason.to_string(),
+            expired_at,
+            cancelled_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = cancellation.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create challenge cancelled event: {e}"))
+        })?;
+
+        self.deliver_match_event(event, "challenge cancellation", match_event_id)
+            .await?;
+        self.finish_match(match_event_id).await;
+
+        info!(
+            "⌛ Published cancellation of challenge {} ({})",
+            match_event_id, reason
+        );
+
+        Ok(())
+    }
+
+    /// Tell a challenger their challenge was not taken on, so they can retry later
+    pub async fn publish_challenge_rejected(
+        &self,
+        challenge: &MatchChallenge,
+        reason: &str,
+    ) -> Result<(), GameEngineError> {
+        let rejection = ChallengeRejected {
+            game_engine_npub: self.public_key(),
+            match_event_id: challenge.match_event_id.clone(),
+            challenger_npub: challenge.challenger_npub.clone(),
+            reason: reason.to_string(),
+            rejected_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = rejection.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create challenge rejected event: {e}"))
+        })?;
+
+        let delivered = self
+            .deliver_match_event(event, "challenge rejection", &challenge.match_event_id)
+            .await;
+        // The challenge is never tracked, so nothing else will clean up its recipients
+        self.private_matches
+            .lock()
+            .unwrap()
+            .remove(&challenge.match_event_id);
+        delivered?;
+
+        info!(
+            "🚧 Published rejection of challenge {} ({})",
+            challenge.match_event_id, reason
+        );
+
+        Ok(())
+    }
+
+    /// Invite a seeking player to accept the open challenge the matchmaker paired them with
+    pub async fn publish_match_pairing(&self, pairing: &Pairing) -> Result<(), GameEngineError> {
+        let pairing_event = MatchPairing {
+            game_engine_npub: self.public_key(),
+            match_event_id: pairing.match_event_id.clone(),
+            challenger_npub: pairing.challenger_npub.clone(),
+            acceptor_npub: pairing.acceptor_npub.clone(),
+            league_id: pairing.league_id,
+            wager_amount: pairing.wager_amount,
+            paired_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = pairing_event.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create match pairing event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "match pairing")
+            .await?;
+
+        info!(
+            "🤝 Paired {} with challenge {} from {}",
+            pairing.acceptor_npub, pairing.match_event_id, pairing.challenger_npub
+        );
+
+        Ok(())
+    }
+
+    /// Publish proof of a cheat so other engines can screen the offender
+    ///
+    /// Always public, even for private matches: the event names only the offender
+    /// and hashes, not the moves or tokens of the match.
+    pub async fn publish_cheat_evidence(
+        &self,
+        match_event_id: &str,
+        reason: &str,
+        evidence_hashes: Vec<String>,
+        reputation: &PlayerReputation,
+    ) -> Result<(), GameEngineError> {
+        let evidence = CheatEvidence {
+            game_engine_npub: self.public_key(),
+            offender_npub: reputation.npub.clone(),
+            match_event_id: match_event_id.to_string(),
+            reason: reason.to_string(),
+            evidence_hashes,
+            detections: reputation.cheat_detections,
+            blacklisted: reputation.blacklisted,
+            detected_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = evidence.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create cheat evidence event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "cheat evidence")
+            .await?;
+
+        info!(
+            "🕵️ Published cheat evidence against {} for match {}",
+            reputation.npub, match_event_id
+        );
+
+        Ok(())
+    }
+
+    /// Publish a temporary ban to the moderation log so clients and operators can audit it
+    pub async fn publish_temporary_ban(
+        &self,
+        target_npub: &str,
+        reason: &str,
+        banned_until: u64,
+    ) -> Result<(), GameEngineError> {
+        let log = ModerationLog {
+            game_engine_npub: self.public_key(),
+            target_npub: target_npub.to_string(),
+            action: "temporary_ban".to_string(),
+            reason: reason.to_string(),
+            expires_at: Some(banned_until),
+            issued_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = log.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create moderation log event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "moderation log")
+            .await?;
+
+        info!("🚫 Published temporary ban of {} ({})", target_npub, reason);
+
+        Ok(())
+    }
+
+    /// Publish the machine-readable ruleset clients configure themselves against
+    pub async fn publish_ruleset(&self, game_config: &GameConfig) -> Result<(), GameEngineError> {
+        let ruleset = EngineRuleset {
+            game_engine_npub: self.public_key(),
+            protocol_version: PROTOCOL_VERSION,
+            event_kinds: EngineRuleset::protocol_event_kinds(),
+            league_registry_hash: EngineRuleset::league_registry_hash(),
+            class_matrix_version: shared_game_logic::classes::active_class_matrix().version,
+            rules_hash: shared_game_logic::rules_hash().to_string(),
+            fee_schedule: FeeSchedule {
+                match_fee_percent: game_config.match_fee_percent,
+                loot_reward_per_match: game_config.loot_reward_per_match,
+            },
+            timeouts: TimeoutParameters {
+                round_timeout_seconds: game_config.round_timeout_seconds,
+                match_timeout_seconds: game_config.match_timeout_seconds,
+            },
+            published_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = ruleset.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create ruleset event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "ruleset").await?;
+
+        info!(
+            "📜 Published engine ruleset (protocol v{}, league registry {})",
+            ruleset.protocol_version, ruleset.league_registry_hash
+        );
+
+        Ok(())
+    }
+
+    /// Publish the engine-signed leaderboard, replacing the previous one
+    pub async fn publish_leaderboard(
+        &self,
+        entries: Vec<LeaderboardEntry>,
+    ) -> Result<(), GameEngineError> {
+        let leaderboard = Leaderboard {
+            game_engine_npub: self.public_key(),
+            entries,
+            published_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = leaderboard.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create leaderboard event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "leaderboard").await?;
+
+        info!(
+            "🏅 Published leaderboard of {} players",
+            leaderboard.entries.len()
+        );
+
+        Ok(())
+    }
+
+    /// Publish the engine heartbeat, replacing the previous one
+    pub async fn publish_heartbeat(
+        &self,
+        active_matches: usize,
+        mints: Vec<MintConnectivity>,
+    ) -> Result<(), GameEngineError> {
+        let heartbeat = EngineHeartbeat {
+            game_engine_npub: self.public_key(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            active_matches: active_matches as u32,
+            mints,
+            published_at: chrono::Utc::now().timestamp() as u64,
+        };
+
+        let event = heartbeat.to_nostr_event(&self.keys).map_err(|e| {
+            GameEngineError::NostrError(format!("Failed to create heartbeat event: {e}"))
+        })?;
+
+        self.send_event_with_failover(event, "heartbeat").await?;
+
+        debug!(
+            "💓 Published heartbeat ({} active matches)",
+            heartbeat.active_matches
+        );
+
+        Ok(())
+    }
+
+    /// Mark a match as private so its engine events go only to the given players
+    ///
+    /// Used to restore private matches tracked before a restart.
+    pub fn register_private_match(&self, match_event_id: &str, players: Vec<String>) {
+        self.private_matches
+            .lock()
+            .unwrap()
+            .insert(match_event_id.to_string(), players);
+    }
+
+    /// Record who to DM about a match negotiated over direct messages
+    ///
+    /// A challenge sent by DM makes the match private; whoever accepts it is then a
+    /// player to DM, even if the acceptance itself was published.
+    fn remember_private_player(&self, player_event: &PlayerMatchEvent, private: bool) {
+        let mut private_matches = self.private_matches.lock().unwrap();
+        match player_event {
+            PlayerMatchEvent::Challenge(challenge) if private => {
+                private_matches.insert(
+                    challenge.match_event_id.clone(),
+                    vec![challenge.challenger_npub.clone()],
+                );
+            }
+            PlayerMatchEvent::Acceptance(acceptance) => {
+                if let Some(players) = private_matches.get_mut(&acceptance.match_event_id) {
+                    if !players.contains(&acceptance.acceptor_npub) {
+                        players.push(acceptance.acceptor_npub.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Publish an engine event, or send it to the players by NIP-17 DM if the match is private
+    async fn deliver_match_event(
+        &self,
+        event: Event,
+        label: &str,
+        match_event_id: &str,
+    ) -> Result<(), GameEngineError> {
+        let players = self
+            .private_matches
+            .lock()
+            .unwrap()
+            .get(match_event_id)
+            .cloned();
+        let Some(players) = players else {
+            return self.send_event_with_failover(event, label).await;
+        };
+
+        for npub in players {
+            let receiver = PublicKey::parse(&npub).map_err(|e| {
+                GameEngineError::NostrError(format!("Invalid player npub {npub}: {e}"))
+            })?;
+            let message = wrap_for_player(&self.keys, receiver, &event).map_err(|e| {
+                GameEngineError::NostrError(format!("Failed to wrap {label} event for {npub}: {e}"))
+            })?;
+            self.send_event_with_failover(message, label).await?;
+        }
+
+        debug!(
+            "🔒 Sent {} for private match {} by direct message",
+            label, match_event_id
+        );
+        Ok(())
+    }
+
+    /// Publish to every relay, then fail over relay by relay if none accepted the event
+    async fn send_event_with_failover(
+        &self,
+        event: Event,
+        label: &str,
+    ) -> Result<(), GameEngineError> {
+        let mut last_error = match self.client.send_event(event.clone()).await {
+            Ok(output) if !output.success.is_empty() => return Ok(()),
+            Ok(output) => {
+                if self.retry_after_auth(&event, &output.failed).await {
+                    return Ok(());
+                }
+                format!("no relay accepted it: {:?}", output.failed)
+            }
+            Err(e) => e.to_string(),
+        };
+
+        for relay_url in &self.relay_urls {
+            warn!(
+                "🔀 Failing over {} event {} to {}: {}",
+                label, event.id, relay_url, last_error
+            );
+
+            if let Err(e) = self.client.connect_relay(relay_url.as_str()).await {
+                debug!("Reconnect to {} failed: {}", relay_url, e);
+            }
+
+            match self
+                .client
+                .send_event_to([relay_url.as_str()], event.clone())
+                .await
+            {
+                Ok(output) if !output.success.is_empty() => return Ok(()),
+                Ok(output) => last_error = format!("rejected: {:?}", output.failed),
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+
+        Err(GameEngineError::transient(format!(
+            "Failed to send {label} event: {last_error}"
+        )))
+    }
+
+    /// Resend an event to the auth-enabled relays that rejected it for missing AUTH
+    ///
+    /// The listener answers the relay's challenge, so a short wait is usually enough.
+    async fn retry_after_auth(
+        &self,
+        event: &Event,
+        failed: &HashMap<Url, Option<String>>,
+    ) -> bool {
+        let auth_relays: Vec<Url> = failed
+            .iter()
+            .filter(|(url, reason)| {
+                self.config.auth_enabled(url.as_str())
+                    && reason
+                        .as_deref()
+                        .is_some_and(|reason| reason.starts_with(AUTH_REQUIRED_PREFIX))
+            })
+            .map(|(url, _)| url.clone())
+            .collect();
+        if auth_relays.is_empty() {
+            return false;
+        }
+
+        tokio::time::sleep(AUTH_RETRY_DELAY).await;
+        match self.client.send_event_to(auth_relays, event.clone()).await {
+            Ok(output) => !output.success.is_empty(),
+            Err(e) => {
+                debug!("Retry after AUTH failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Latency tracker used for reveal pacing recommendations
+    pub fn latency(&self) -> &LatencyTracker {
+        &self.latency
+    }
+
     /// Get the bot's public key
     pub fn public_key(&self) -> String {
         self.keys.public_key().to_string()
     }
 }
+
+/// Wrap an engine event as a NIP-17 direct message to one player of a private match
+fn wrap_for_player(
+    keys: &Keys,
+    receiver: PublicKey,
+    event: &Event,
+) -> Result<Event, nostr::event::builder::Error> {
+    let rumor = EventBuilder::private_msg_rumor(receiver, event.as_json(), None)
+        .to_unsigned_event(keys.public_key());
+    EventBuilder::gift_wrap(keys, &receiver, rumor, None)
+}
+
+/// Reject events whose claimed player is not the key that signed them
+fn verify_signer(event: &Event, player_event: &PlayerMatchEvent) -> Result<(), GameEngineError> {
+    let claimed_npub = player_event.player_npub();
+    match PublicKey::parse(claimed_npub) {
+        Ok(claimed) if claimed == event.pubkey => Ok(()),
+        _ => Err(protocol_error(
+            event,
+            format!(
+                "Event {} signed by {} claims to be from {}",
+                event.id, event.pubkey, claimed_npub
+            ),
+        )),
+    }
+}
+
+/// Player event the engine refuses because it breaks the protocol for its kind
+fn protocol_error(event: &Event, reason: String) -> GameEngineError {
+    GameEngineError::Protocol {
+        kind: event.kind.as_u16(),
+        reason,
+    }
+}
+
+/// Whether an event is a NIP-04 or NIP-17 direct message rather than a public game event
+fn is_direct_message(event: &Event) -> bool {
+    event.kind == Kind::EncryptedDirectMessage || event.kind == Kind::GiftWrap
+}
+
+/// Subscription filter for the player-published game events
+fn game_events_filter() -> Filter {
+    // OPTIMIZED FILTERING: Only process game-related Nostr events (KIND 31000-31005)
+    // This prevents wasting computational resources on non-game events
+    let since_timestamp = nostr::Timestamp::now() - 3600; // 1 hour ago for integration testing
+
+    // Single efficient filter for all game event types
+    Filter::new()
+        .kinds(vec![
+            KIND_MATCH_CHALLENGE,      // 21000 - Player creates match
+            KIND_MATCH_ACCEPTANCE,     // 21001 - Player accepts challenge
+            KIND_TOKEN_REVEAL,         // 21002 - Player reveals Cashu tokens
+            KIND_DRAFT_BAN_COMMITMENT, // 31008 - Player commits to draft-phase bans
+            KIND_DRAFT_BAN_REVEAL,     // 31009 - Player reveals draft-phase bans
+            KIND_COMBAT_MOVE,          // 21003 - Player submits combat move
+            KIND_MATCH_RESULT,         // 21004 - Player submits final match state
+            KIND_MATCH_SEEK,           // 21009 - Player asks the matchmaker for an opponent
+                                       // NOTE: KIND_LOOT_DISTRIBUTION (21005) excluded - game engine publishes this
+        ])
+        .since(since_timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_match_event_is_gift_wrapped_for_the_player() {
+        let engine = Keys::generate();
+        let player = Keys::generate();
+        let event = EventBuilder::new(KIND_LOOT_DISTRIBUTION, "loot", [])
+            .to_event(&engine)
+            .unwrap();
+
+        let message = wrap_for_player(&engine, player.public_key(), &event).unwrap();
+        assert_eq!(message.kind, Kind::GiftWrap);
+        assert_ne!(message.pubkey, engine.public_key());
+
+        // Only the player can open it, and it carries the engine's signed event intact
+        assert!(UnwrappedGift::from_gift_wrap(&Keys::generate(), &message).is_err());
+        let gift = UnwrappedGift::from_gift_wrap(&player, &message).unwrap();
+        assert_eq!(gift.sender, engine.public_key());
+        assert_eq!(gift.rumor.kind, Kind::PrivateDirectMessage);
+        let delivered = Event::from_json(&gift.rumor.content).unwrap();
+        assert_eq!(delivered, event);
+        assert!(delivered.verify().is_ok());
+    }
+
+    fn seek_from(player_npub: &str) -> PlayerMatchEvent {
+        PlayerMatchEvent::Seek(MatchSeek {
+            player_npub: player_npub.to_string(),
+            league_id: 0,
+            min_wager: 100,
+            max_wager: 500,
+            expires_at: 1690003600,
+            created_at: 1690000000,
+        })
+    }
+
+    #[test]
+    fn test_verify_signer_requires_the_claimed_player_to_sign() {
+        use nostr::ToBech32;
+
+        let player = Keys::generate();
+        let event = EventBuilder::new(KIND_MATCH_SEEK, "seek", [])
+            .to_event(&player)
+            .unwrap();
+
+        let own_npub = player.public_key().to_bech32().unwrap();
+        assert!(verify_signer(&event, &seek_from(&own_npub)).is_ok());
+
+        // Posting on behalf of another player is refused
+        let other_npub = Keys::generate().public_key().to_bech32().unwrap();
+        let impersonated = verify_signer(&event, &seek_from(&other_npub));
+        assert!(matches!(
+            impersonated,
+            Err(GameEngineError::Protocol { kind, .. }) if kind == KIND_MATCH_SEEK.as_u16()
+        ));
+
+        assert!(matches!(
+            verify_signer(&event, &seek_from("npub1notakey")),
+            Err(GameEngineError::Protocol { .. })
+        ));
+    }
+}
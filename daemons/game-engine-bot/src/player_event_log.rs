@@ -0,0 +1,112 @@
+use nostr::{Event, JsonUtil};
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::errors::GameEngineError;
+use crate::match_store::persistence_error;
+
+/// Players' signed match events exactly as received, kept for proof bundles
+///
+/// A bundle carrying the original events lets anyone check the players really
+/// committed to and revealed what the engine replayed, without trusting the engine.
+pub struct PlayerEventLog {
+    connection: Mutex<Connection>,
+}
+
+impl PlayerEventLog {
+    /// Open (or create) the event table in the engine database
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Log that only keeps events for the life of the process
+    pub fn in_memory() -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS player_events (
+                    event_id TEXT PRIMARY KEY,
+                    match_id TEXT NOT NULL,
+                    event TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS player_events_match_id ON player_events (match_id);",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Keep a player's signed event under the match it belongs to
+    pub fn record(&self, match_id: &str, event: &Event) -> Result<(), GameEngineError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR IGNORE INTO player_events (event_id, match_id, event) VALUES (?1, ?2, ?3)",
+                params![event.id.to_hex(), match_id, event.as_json()],
+            )
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+
+    /// Every signed event kept for a match, in the order received
+    pub fn events(&self, match_id: &str) -> Result<Vec<Event>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT event FROM player_events WHERE match_id = ?1 ORDER BY rowid")
+            .map_err(persistence_error)?;
+        let rows = statement
+            .query_map(params![match_id], |row| row.get::<_, String>(0))
+            .map_err(persistence_error)?;
+
+        let mut events = Vec::new();
+        for row in rows {
+            let json = row.map_err(persistence_error)?;
+            events.push(
+                Event::from_json(&json).map_err(|e| {
+                    GameEngineError::Persistence(format!("Corrupt player event: {e}"))
+                })?,
+            );
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr::{EventBuilder, Keys, Kind};
+
+    #[test]
+    fn test_events_are_kept_per_match_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.sqlite");
+        let keys = Keys::generate();
+        let event = |content: &str| {
+            EventBuilder::new(Kind::Custom(21000), content, [])
+                .to_event(&keys)
+                .unwrap()
+        };
+        let (first, second) = (event("first"), event("second"));
+
+        {
+            let log = PlayerEventLog::open(&path).unwrap();
+            log.record("match_1", &first).unwrap();
+            log.record("match_1", &first).unwrap();
+            log.record("match_1", &second).unwrap();
+            log.record("match_2", &event("other")).unwrap();
+        }
+
+        let log = PlayerEventLog::open(&path).unwrap();
+        assert_eq!(log.events("match_1").unwrap(), vec![first, second]);
+        assert!(log.events("match_3").unwrap().is_empty());
+    }
+}
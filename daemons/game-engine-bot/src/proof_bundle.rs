@@ -0,0 +1,597 @@
+use nostr::{Event, EventBuilder, JsonUtil, Keys, PublicKey, Tag};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use shared_game_logic::commitment::{
+    verify_bans_commitment, verify_cashu_commitment, verify_moves_commitment,
+};
+
+use crate::errors::GameEngineError;
+use crate::match_events::{
+    CombatMove, DraftBanCommitment, DraftBanReveal, EngineRuleset, MatchAcceptance, MatchChallenge,
+    PlayerCommitments, PlayerReveals, RoundCombat, TokenReveal, KIND_COMBAT_MOVE,
+    KIND_DRAFT_BAN_COMMITMENT, KIND_DRAFT_BAN_REVEAL, KIND_MATCH_ACCEPTANCE, KIND_MATCH_CHALLENGE,
+    KIND_PROOF_BUNDLE, KIND_TOKEN_REVEAL,
+};
+use crate::match_state_machine::{MatchData, MatchState};
+
+/// Layout of the bundle; verifiers refuse versions they do not know
+pub const PROOF_BUNDLE_VERSION: u32 = 2;
+
+/// Everything needed to re-verify a finished match without the engine or the relays
+///
+/// The match data is the engine's view of the match; the players' own signed
+/// challenge, acceptance, reveals and moves ride along so a verifier can check that
+/// view against what the players actually published. The bundle is sealed in a
+/// Nostr event signed by the engine, so its verdict cannot be altered after export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofBundle {
+    pub version: u32,
+    pub match_data: MatchData,
+    pub player_events: Vec<Event>, // As the players signed them
+    pub rounds: Vec<RoundCombat>,  // Combat after each game, as the engine resolved it
+    pub verdict: MatchVerdict,
+    pub league_registry_hash: String, // League modifiers the match was played under
+}
+
+/// The engine's decision on a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchVerdict {
+    pub winner_npub: Option<String>, // None for a draw
+    pub game_wins: [u32; 2],
+    pub completed_at: u64,
+}
+
+impl ProofBundle {
+    /// Bundle a completed match with its players' signed events; other states carry
+    /// no final verdict to prove
+    pub fn from_state(
+        state: &MatchState,
+        player_events: Vec<Event>,
+    ) -> Result<Self, GameEngineError> {
+        let MatchState::Completed {
+            match_data,
+            completed_at,
+            ..
+        } = state
+        else {
+            return Err(GameEngineError::Validation {
+                reason: format!(
+                    "Only completed matches can be exported, not {}",
+                    state.phase_name()
+                ),
+            });
+        };
+
+        Ok(Self {
+            version: PROOF_BUNDLE_VERSION,
+            match_data: match_data.clone(),
+            player_events,
+            rounds: resolve_rounds(match_data),
+            verdict: MatchVerdict {
+                winner_npub: match_data.format_winner(),
+                game_wins: match_data.game_wins,
+                completed_at: completed_at.timestamp() as u64,
+            },
+            league_registry_hash: EngineRuleset::league_registry_hash(),
+        })
+    }
+
+    /// Check the match data against the players' signed events, then re-execute it
+    /// and check it reaches the recorded verdict
+    pub fn reverify(&self) -> Result<(), GameEngineError> {
+        if self.version != PROOF_BUNDLE_VERSION {
+            return Err(invalid(format!(
+                "Unsupported proof bundle version {}",
+                self.version
+            )));
+        }
+        if self.league_registry_hash != EngineRuleset::league_registry_hash() {
+            return Err(invalid(
+                "Bundle was produced under different league modifiers".to_string(),
+            ));
+        }
+
+        let signed = SignedPlayerEvents::open(&self.player_events)?;
+        signed.check_against(&self.match_data)?;
+
+        // Armies are derived again rather than trusted from the bundle
+        let data = &self.match_data;
+        let (replayed, rounds) = replay_match(data)?;
+        if replayed.player1_army != data.player1_army || replayed.player2_army != data.player2_army
+        {
+            return Err(invalid(
+                "Armies do not follow from the revealed tokens".to_string(),
+            ));
+        }
+
+        if let Some(round) = (0..rounds.len().max(self.rounds.len()))
+            .find(|&index| rounds.get(index) != self.rounds.get(index))
+        {
+            return Err(invalid(format!(
+                "Round {} does not replay to the recorded combat",
+                round + 1
+            )));
+        }
+
+        if replayed.game_wins != self.verdict.game_wins
+            || replayed.format_winner() != self.verdict.winner_npub
+        {
+            return Err(invalid(
+                "Replayed games do not reach the recorded verdict".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sign the bundle as the engine; the returned JSON is the portable proof file
+    pub fn seal(&self, keys: &Keys) -> Result<String, GameEngineError> {
+        let content =
+            serde_json::to_string(self).map_err(|e| GameEngineError::Internal(e.to_string()))?;
+        let tags = [Tag::custom(
+            nostr::TagKind::Custom("match_event_id".into()),
+            vec![self.match_data.match_event_id.clone()],
+        )];
+
+        let event = EventBuilder::new(KIND_PROOF_BUNDLE, content, tags)
+            .to_event(keys)
+            .map_err(|e| GameEngineError::Internal(format!("Failed to sign proof bundle: {e}")))?;
+        Ok(event.as_json())
+    }
+}
+
+/// Check a sealed proof bundle offline: the engine's signature, then the match itself
+///
+/// `engine_pubkey` (hex or npub) pins the engine the bundle must come from.
+pub fn verify_bundle(sealed: &str, engine_pubkey: &str) -> Result<ProofBundle, GameEngineError> {
+    let engine = PublicKey::parse(engine_pubkey)
+        .map_err(|e| invalid(format!("Invalid engine public key {engine_pubkey}: {e}")))?;
+    let event = Event::from_json(sealed)
+        .map_err(|e| invalid(format!("Proof bundle is not a Nostr event: {e}")))?;
+
+    if event.kind != KIND_PROOF_BUNDLE {
+        return Err(invalid(format!(
+            "Kind {} is not a proof bundle",
+            event.kind
+        )));
+    }
+    event
+        .verify()
+        .map_err(|e| invalid(format!("Proof bundle signature is invalid: {e}")))?;
+    if event.pubkey != engine {
+        return Err(invalid(format!(
+            "Proof bundle was signed by {}, not the engine",
+            event.pubkey
+        )));
+    }
+
+    let bundle: ProofBundle = serde_json::from_str(&event.content)
+        .map_err(|e| invalid(format!("Malformed proof bundle: {e}")))?;
+    bundle.reverify()?;
+    Ok(bundle)
+}
+
+/// Player events of a match whose signatures and signers have been checked
+#[derive(Debug, Default)]
+struct SignedPlayerEvents {
+    challenges: Vec<MatchChallenge>,
+    acceptances: Vec<MatchAcceptance>,
+    token_reveals: Vec<TokenReveal>,
+    ban_commitments: Vec<DraftBanCommitment>,
+    ban_reveals: Vec<DraftBanReveal>,
+    moves: Vec<CombatMove>,
+}
+
+impl SignedPlayerEvents {
+    /// Verify each event's signature and that it is signed by the player it names
+    ///
+    /// Other kinds, such as the players' result claims, play no part in the replay.
+    fn open(events: &[Event]) -> Result<Self, GameEngineError> {
+        let mut signed = Self::default();
+        for event in events {
+            event.verify().map_err(|e| {
+                invalid(format!(
+                    "Player event {} has an invalid signature: {e}",
+                    event.id
+                ))
+            })?;
+
+            match event.kind {
+                kind if kind == KIND_MATCH_CHALLENGE => {
+                    let challenge: MatchChallenge = parse_content(event)?;
+                    check_signer(event, &challenge.challenger_npub)?;
+                    signed.challenges.push(challenge);
+                }
+                kind if kind == KIND_MATCH_ACCEPTANCE => {
+                    let acceptance: MatchAcceptance = parse_content(event)?;
+                    check_signer(event, &acceptance.acceptor_npub)?;
+                    signed.acceptances.push(acceptance);
+                }
+                kind if kind == KIND_TOKEN_REVEAL => {
+                    let reveal: TokenReveal = parse_content(event)?;
+                    check_signer(event, &reveal.player_npub)?;
+                    signed.token_reveals.push(reveal);
+                }
+                kind if kind == KIND_DRAFT_BAN_COMMITMENT => {
+                    let commitment: DraftBanCommitment = parse_content(event)?;
+                    check_signer(event, &commitment.player_npub)?;
+                    signed.ban_commitments.push(commitment);
+                }
+                kind if kind == KIND_DRAFT_BAN_REVEAL => {
+                    let reveal: DraftBanReveal = parse_content(event)?;
+                    check_signer(event, &reveal.player_npub)?;
+                    signed.ban_reveals.push(reveal);
+                }
+                kind if kind == KIND_COMBAT_MOVE => {
+                    let combat_move: CombatMove = parse_content(event)?;
+                    check_signer(event, &combat_move.player_npub)?;
+                    signed.moves.push(combat_move);
+                }
+                _ => {}
+            }
+        }
+        Ok(signed)
+    }
+
+    /// Check every commitment and reveal in the match data was signed by its player
+    fn check_against(&self, data: &MatchData) -> Result<(), GameEngineError> {
+        let match_id = data.match_event_id.as_str();
+
+        let challenged = self.challenges.iter().any(|challenge| {
+            challenge.challenger_npub == data.player1_npub
+                && challenge.wager_amount == data.wager_amount
+                && challenge.league_id as u32 == data.league_id
+                && data.player1_commitments.cashu_tokens.as_ref()
+                    == Some(&challenge.cashu_token_commitment)
+        });
+        if !challenged {
+            return Err(missing("challenge", &data.player1_npub));
+        }
+        let accepted = self.acceptances.iter().any(|acceptance| {
+            acceptance.acceptor_npub == data.player2_npub
+                && acceptance.match_event_id == match_id
+                && data.player2_commitments.cashu_tokens.as_ref()
+                    == Some(&acceptance.cashu_token_commitment)
+        });
+        if !accepted {
+            return Err(missing("acceptance", &data.player2_npub));
+        }
+
+        let players = [
+            (&data.player1_npub, &data.player1_reveals),
+            (&data.player2_npub, &data.player2_reveals),
+        ];
+        for (index, (player, reveals)) in players.into_iter().enumerate() {
+            let revealed = self.token_reveals.iter().any(|reveal| {
+                &reveal.player_npub == player
+                    && reveal.match_event_id == match_id
+                    && reveals.cashu_tokens.as_ref() == Some(&reveal.cashu_tokens)
+                    && reveals.token_nonce.as_ref() == Some(&reveal.token_secrets_nonce)
+            });
+            if !revealed {
+                return Err(missing("token reveal", player));
+            }
+
+            // A player's bans shape the opponent's army
+            let banned = &data.banned_units[1 - index];
+            let banned_as_committed = self.ban_reveals.iter().any(|reveal| {
+                &reveal.player_npub == player
+                    && reveal.match_event_id == match_id
+                    && &reveal.banned_units == banned
+                    && self.ban_commitments.iter().any(|commitment| {
+                        &commitment.player_npub == player
+                            && commitment.match_event_id == match_id
+                            && verify_bans_commitment(
+                                &commitment.ban_commitment,
+                                banned,
+                                &reveal.ban_nonce,
+                            )
+                    })
+            });
+            if !banned.is_empty() && !banned_as_committed {
+                return Err(missing("ban commitment and reveal", player));
+            }
+
+            for (round, (positions, abilities, _)) in &reveals.moves_by_round {
+                let moved = self.moves.iter().any(|combat_move| {
+                    &combat_move.player_npub == player
+                        && combat_move.match_event_id == match_id
+                        && combat_move.round_number == *round
+                        && &combat_move.unit_positions == positions
+                        && &combat_move.unit_abilities == abilities
+                });
+                if !moved {
+                    return Err(missing(&format!("round {round} move"), player));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_content<T: DeserializeOwned>(event: &Event) -> Result<T, GameEngineError> {
+    serde_json::from_str(&event.content)
+        .map_err(|e| invalid(format!("Malformed player event {}: {e}", event.id)))
+}
+
+/// Refuse a player event signed by anyone but the player it names
+fn check_signer(event: &Event, player_npub: &str) -> Result<(), GameEngineError> {
+    match PublicKey::parse(player_npub) {
+        Ok(signer) if signer == event.pubkey => Ok(()),
+        _ => Err(invalid(format!(
+            "Player event {} signed by {} claims to be from {player_npub}",
+            event.id, event.pubkey
+        ))),
+    }
+}
+
+fn missing(what: &str, player_npub: &str) -> GameEngineError {
+    invalid(format!(
+        "No {what} signed by {player_npub} matches the match data"
+    ))
+}
+
+/// Re-execute a match from its revealed data alone
+///
+/// Both token reveals and every committed move are checked against their
+/// commitments, the armies are derived again and every game is resolved anew. Returns the match data with the
+/// replayed armies and game wins, and the combat after each game.
+pub fn replay_match(
+    match_data: &MatchData,
+) -> Result<(MatchData, Vec<RoundCombat>), GameEngineError> {
+    verify_tokens(
+        &match_data.player1_npub,
+        &match_data.player1_commitments,
+        &match_data.player1_reveals,
+    )?;
+    verify_tokens(
+        &match_data.player2_npub,
+        &match_data.player2_commitments,
+        &match_data.player2_reveals,
+    )?;
+    verify_moves(
+        &match_data.player1_npub,
+        &match_data.player1_commitments,
+        &match_data.player1_reveals,
+    )?;
+    verify_moves(
+        &match_data.player2_npub,
+        &match_data.player2_commitments,
+        &match_data.player2_reveals,
+    )?;
+
+    let mut replayed = match_data.clone();
+    replayed.generate_armies();
+    let rounds = resolve_rounds(&replayed);
+    replayed.game_wins = rounds.last().map_or([0, 0], |combat| combat.score);
+    Ok((replayed, rounds))
+}
+
+/// Combat after every game both players moved in, in order
+fn resolve_rounds(match_data: &MatchData) -> Vec<RoundCombat> {
+    (1..)
+        .map_while(|round| match_data.round_combat(round))
+        .collect()
+}
+
+fn verify_tokens(
+    player_npub: &str,
+    commitments: &PlayerCommitments,
+    reveals: &PlayerReveals,
+) -> Result<(), GameEngineError> {
+    let (Some(commitment), Some(tokens), Some(nonce)) = (
+        &commitments.cashu_tokens,
+        &reveals.cashu_tokens,
+        &reveals.token_nonce,
+    ) else {
+        return Err(invalid(format!(
+            "Match lacks the token commitment or reveal of {player_npub}"
+        )));
+    };
+
+    if !verify_cashu_commitment(commitment, tokens, nonce) {
+        return Err(invalid(format!(
+            "Tokens revealed by {player_npub} do not match their commitment"
+        )));
+    }
+    Ok(())
+}
+
+/// Moves of every round the player committed to must open their commitment
+fn verify_moves(
+    player_npub: &str,
+    commitments: &PlayerCommitments,
+    reveals: &PlayerReveals,
+) -> Result<(), GameEngineError> {
+    for (round, commitment) in &commitments.moves_by_round {
+        let opened =
+            reveals
+                .moves_by_round
+                .get(round)
+                .is_some_and(|(positions, abilities, nonce)| {
+                    verify_moves_commitment(commitment, positions, abilities, nonce)
+                });
+        if !opened {
+            return Err(invalid(format!(
+                "Round {round} moves of {player_npub} do not match their commitment"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(reason: String) -> GameEngineError {
+    GameEngineError::Validation { reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::match_events::{test_support, MatchFormat};
+    use nostr::Kind;
+    use shared_game_logic::commitment::commit_to_cashu_tokens;
+
+    fn sign(keys: &Keys, kind: Kind, content: &impl Serialize) -> Event {
+        EventBuilder::new(kind, serde_json::to_string(content).unwrap(), [])
+            .to_event(keys)
+            .unwrap()
+    }
+
+    /// A completed single-game match and the events its players signed along the way
+    fn completed_match() -> (MatchState, Vec<Event>) {
+        let (alice, bob) = (Keys::generate(), Keys::generate());
+        let (alice_npub, bob_npub) = (alice.public_key().to_hex(), bob.public_key().to_hex());
+        let tokens = |player: &str| vec![format!("{player}_mana_secret")];
+        let challenge = MatchChallenge {
+            challenger_npub: alice_npub.clone(),
+            cashu_token_commitment: commit_to_cashu_tokens(&tokens("alice"), "nonce_a"),
+            match_format: MatchFormat::SingleRound,
+            ..test_support::challenge("challenge_event_id")
+        };
+        let acceptance = MatchAcceptance {
+            acceptor_npub: bob_npub.clone(),
+            cashu_token_commitment: commit_to_cashu_tokens(&tokens("bob"), "nonce_b"),
+            ..test_support::acceptance("challenge_event_id")
+        };
+        let mut events = vec![
+            sign(&alice, KIND_MATCH_CHALLENGE, &challenge),
+            sign(&bob, KIND_MATCH_ACCEPTANCE, &acceptance),
+        ];
+
+        let mut match_data = MatchData::new(&challenge, &acceptance);
+        for (keys, npub, player, nonce) in [
+            (&alice, &alice_npub, "alice", "nonce_a"),
+            (&bob, &bob_npub, "bob", "nonce_b"),
+        ] {
+            let reveal = TokenReveal {
+                player_npub: npub.clone(),
+                match_event_id: "challenge_event_id".to_string(),
+                cashu_tokens: tokens(player),
+                token_secrets_nonce: nonce.to_string(),
+                revealed_at: 1690000200,
+            };
+            let combat_move = CombatMove {
+                player_npub: npub.clone(),
+                match_event_id: "challenge_event_id".to_string(),
+                previous_event_hash: None,
+                round_number: 1,
+                unit_positions: vec![0],
+                unit_abilities: Vec::new(),
+                move_timestamp: 1690000300,
+            };
+            events.push(sign(keys, KIND_TOKEN_REVEAL, &reveal));
+            events.push(sign(keys, KIND_COMBAT_MOVE, &combat_move));
+        }
+
+        match_data.player1_reveals.cashu_tokens = Some(tokens("alice"));
+        match_data.player1_reveals.token_nonce = Some("nonce_a".to_string());
+        match_data.player2_reveals.cashu_tokens = Some(tokens("bob"));
+        match_data.player2_reveals.token_nonce = Some("nonce_b".to_string());
+        match_data.generate_armies();
+        for reveals in [
+            &mut match_data.player1_reveals,
+            &mut match_data.player2_reveals,
+        ] {
+            reveals
+                .moves_by_round
+                .insert(1, (vec![0], Vec::new(), String::new()));
+        }
+        match_data.game_wins = match_data.round_combat(1).unwrap().score;
+
+        let state = MatchState::Completed {
+            result: crate::match_events::MatchResult {
+                player_npub: alice_npub,
+                match_event_id: "challenge_event_id".to_string(),
+                final_army_state: serde_json::json!({}),
+                all_round_results: Vec::new(),
+                calculated_winner: match_data.format_winner(),
+                match_completed_at: 1690000400,
+                rules_hash: None,
+            },
+            match_data,
+            loot_distribution: None,
+            completed_at: chrono::Utc::now(),
+        };
+        (state, events)
+    }
+
+    #[test]
+    fn test_tampered_bundle_fails_reverification() {
+        let (state, events) = completed_match();
+        let bundle = ProofBundle::from_state(&state, events).unwrap();
+        assert_eq!(bundle.rounds.len(), 1);
+        bundle.reverify().unwrap();
+
+        let mut forged_verdict = bundle.clone();
+        forged_verdict.verdict.game_wins[0] += 1;
+        assert!(forged_verdict.reverify().is_err());
+
+        let mut forged_tokens = bundle;
+        forged_tokens.match_data.player2_reveals.cashu_tokens =
+            Some(vec!["other_mana_secret".to_string()]);
+        assert!(forged_tokens.reverify().is_err());
+    }
+
+    #[test]
+    fn test_match_data_must_follow_the_signed_player_events() {
+        let (state, events) = completed_match();
+        let bundle = ProofBundle::from_state(&state, events).unwrap();
+
+        // A move the player never signed
+        let mut forged_move = bundle.clone();
+        forged_move
+            .match_data
+            .player1_reveals
+            .moves_by_round
+            .insert(1, (vec![1], Vec::new(), String::new()));
+        let error = forged_move.reverify().unwrap_err().to_string();
+        assert!(error.contains("round 1 move"), "{error}");
+
+        // An event re-signed by someone other than the player it names
+        let mut forged_signer = bundle.clone();
+        let reveal = &forged_signer.player_events[2];
+        forged_signer.player_events[2] = EventBuilder::new(reveal.kind, reveal.content.clone(), [])
+            .to_event(&Keys::generate())
+            .unwrap();
+        let error = forged_signer.reverify().unwrap_err().to_string();
+        assert!(error.contains("claims to be from"), "{error}");
+
+        // An event altered after signing
+        let mut altered = bundle.clone();
+        altered.player_events[0].content = altered.player_events[0].content.replace("100", "1");
+        assert!(altered.reverify().is_err());
+
+        let mut stripped = bundle;
+        stripped.player_events.truncate(2);
+        assert!(stripped.reverify().is_err());
+    }
+
+    #[test]
+    fn test_replay_checks_committed_moves() {
+        let (MatchState::Completed { mut match_data, .. }, _) = completed_match() else {
+            unreachable!();
+        };
+        match_data
+            .player1_commitments
+            .moves_by_round
+            .insert(1, "not_the_committed_moves".to_string());
+
+        let error = replay_match(&match_data).unwrap_err().to_string();
+        assert!(error.contains("do not match their commitment"), "{error}");
+    }
+
+    #[test]
+    fn test_replay_ignores_recorded_game_wins() {
+        let (MatchState::Completed { mut match_data, .. }, _) = completed_match() else {
+            unreachable!();
+        };
+        let (replayed, rounds) = replay_match(&match_data).unwrap();
+        assert_eq!(rounds.len(), 1);
+        let winner = replayed.format_winner();
+
+        // Game wins claimed in the match data do not sway the replay
+        match_data.game_wins = [0, 0];
+        let (replayed, _) = replay_match(&match_data).unwrap();
+        assert_eq!(replayed.format_winner(), winner);
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+use crate::config::RankingConfig;
+use crate::errors::GameEngineError;
+use crate::match_events::LeaderboardEntry;
+use crate::match_store::persistence_error;
+use crate::nostr_client::NostrClient;
+
+/// A player's Elo rating and record over validated wagered matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerRating {
+    pub npub: String,
+    pub rating: f64,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl PlayerRating {
+    /// Fold one result into the rating; `score` is 1 for a win, 0.5 for a draw and 0 for a loss
+    fn apply(&mut self, opponent_rating: f64, score: f64, k_factor: f64) {
+        self.rating += k_factor * (score - expected_score(self.rating, opponent_rating));
+        match score {
+            s if s > 0.5 => self.wins += 1,
+            s if s < 0.5 => self.losses += 1,
+            _ => self.draws += 1,
+        }
+    }
+}
+
+/// Probability the Elo model gives a player of beating an opponent
+fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+/// Per-player Elo ratings, persisted beside the match archive
+///
+/// Each match is rated at most once, so retried loot actions cannot double count a result.
+pub struct RankingLedger {
+    connection: Mutex<Connection>,
+    config: RankingConfig,
+    changed: AtomicBool, // Ratings moved since the leaderboard was last published
+}
+
+impl RankingLedger {
+    /// Open (or create) the rating tables in the given SQLite database
+    pub fn open(path: impl AsRef<Path>, config: RankingConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    /// Ledger that only lives as long as the process, for when persistence is disabled
+    pub fn in_memory(config: RankingConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    fn with_connection(
+        connection: Connection,
+        config: RankingConfig,
+    ) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS player_ratings (
+                    npub TEXT PRIMARY KEY,
+                    rating REAL NOT NULL,
+                    wins INTEGER NOT NULL,
+                    losses INTEGER NOT NULL,
+                    draws INTEGER NOT NULL,
+                    updated_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS player_ratings_rating ON player_ratings (rating);
+                CREATE TABLE IF NOT EXISTS rated_matches (
+                    match_id TEXT PRIMARY KEY,
+                    rated_at INTEGER NOT NULL
+                );",
+            )
+            .map_err(persistence_error)?;
+
+        // Republish on startup so relays added since the last run receive the standings
+        let rated_players: i64 = connection
+            .query_row("SELECT COUNT(*) FROM player_ratings", [], |row| row.get(0))
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            config,
+            changed: AtomicBool::new(rated_players > 0),
+        })
+    }
+
+    /// Current rating of a player, or the initial rating if they have not been rated
+    pub fn rating(&self, npub: &str) -> Result<PlayerRating, GameEngineError> {
+        load_rating(&self.connection.lock().unwrap(), npub, &self.config)
+    }
+
+    /// Rate a validated match between two players; `winner_npub` is None for a draw
+    ///
+    /// Returns false if the match was already rated.
+    pub fn record_result(
+        &self,
+        match_id: &str,
+        player1_npub: &str,
+        player2_npub: &str,
+        winner_npub: Option<&str>,
+    ) -> Result<bool, GameEngineError> {
+        let player1_score = match winner_npub {
+            None => 0.5,
+            Some(winner) if winner == player1_npub => 1.0,
+            Some(winner) if winner == player2_npub => 0.0,
+            Some(winner) => {
+                return Err(GameEngineError::Internal(format!(
+                    "Winner {winner} did not play match {match_id}"
+                )))
+            }
+        };
+
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction().map_err(persistence_error)?;
+        let now = Utc::now().timestamp();
+
+        let newly_rated = transaction
+            .execute(
+                "INSERT OR IGNORE INTO rated_matches (match_id, rated_at) VALUES (?1, ?2)",
+                params![match_id, now],
+            )
+            .map_err(persistence_error)?;
+        if newly_rated == 0 {
+            return Ok(false);
+        }
+
+        let mut player1 = load_rating(&transaction, player1_npub, &self.config)?;
+        let mut player2 = load_rating(&transaction, player2_npub, &self.config)?;
+        let player1_before = player1.rating;
+        player1.apply(player2.rating, player1_score, self.config.k_factor);
+        player2.apply(player1_before, 1.0 - player1_score, self.config.k_factor);
+
+        for player in [&player1, &player2] {
+            transaction
+                .execute(
+                    "INSERT INTO player_ratings (npub, rating, wins, losses, draws, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT(npub) DO UPDATE SET
+                         rating = excluded.rating,
+                         wins = excluded.wins,
+                         losses = excluded.losses,
+                         draws = excluded.draws,
+                         updated_at = excluded.updated_at",
+                    params![
+                        player.npub,
+                        player.rating,
+                        player.wins,
+                        player.losses,
+                        player.draws,
+                        now
+                    ],
+                )
+                .map_err(persistence_error)?;
+        }
+        transaction.commit().map_err(persistence_error)?;
+
+        self.changed.store(true, Ordering::Relaxed);
+        debug!(
+            "🏅 Rated match {}: {} {:.0}, {} {:.0}",
+            match_id, player1.npub, player1.rating, player2.npub, player2.rating
+        );
+        Ok(true)
+    }
+
+    /// Highest rated players first, at most `leaderboard_size` of them
+    pub fn leaderboard(&self) -> Result<Vec<LeaderboardEntry>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(
+                "SELECT npub, rating, wins, losses, draws FROM player_ratings
+                 ORDER BY rating DESC, npub ASC
+                 LIMIT ?1",
+            )
+            .map_err(persistence_error)?;
+
+        let rows = statement
+            .query_map(params![self.config.leaderboard_size], |row| {
+                Ok(PlayerRating {
+                    npub: row.get(0)?,
+                    rating: row.get(1)?,
+                    wins: row.get(2)?,
+                    losses: row.get(3)?,
+                    draws: row.get(4)?,
+                })
+            })
+            .map_err(persistence_error)?;
+
+        let mut entries = Vec::new();
+        for (index, row) in rows.enumerate() {
+            let player = row.map_err(persistence_error)?;
+            entries.push(LeaderboardEntry {
+                rank: index as u32 + 1,
+                npub: player.npub,
+                rating: player.rating.round() as i64,
+                wins: player.wins,
+                losses: player.losses,
+                draws: player.draws,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// Whether ratings moved since the last call
+    fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::Relaxed)
+    }
+}
+
+fn load_rating(
+    connection: &Connection,
+    npub: &str,
+    config: &RankingConfig,
+) -> Result<PlayerRating, GameEngineError> {
+    let stored = connection
+        .query_row(
+            "SELECT rating, wins, losses, draws FROM player_ratings WHERE npub = ?1",
+            params![npub],
+            |row| {
+                Ok(PlayerRating {
+                    npub: npub.to_string(),
+                    rating: row.get(0)?,
+                    wins: row.get(1)?,
+                    losses: row.get(2)?,
+                    draws: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(persistence_error)?;
+
+    Ok(stored.unwrap_or_else(|| PlayerRating {
+        npub: npub.to_string(),
+        rating: config.initial_rating,
+        wins: 0,
+        losses: 0,
+        draws: 0,
+    }))
+}
+
+/// Background task publishing the leaderboard whenever ratings have moved
+pub async fn run_leaderboard_task(ledger: Arc<RankingLedger>, nostr_client: Arc<NostrClient>) {
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+        ledger.config.publish_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+        if !ledger.take_changed() {
+            continue;
+        }
+
+        let published = match ledger.leaderboard() {
+            Ok(entries) => nostr_client.publish_leaderboard(entries).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = published {
+            warn!("⚠️ Failed to publish leaderboard: {}", e);
+            // Try again next interval
+            ledger.changed.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratings_move_once_per_match() {
+        let ledger = RankingLedger::in_memory(RankingConfig::default()).unwrap();
+        assert!(!ledger.take_changed());
+
+        assert!(ledger
+            .record_result("match_1", "npub1alice", "npub1bob", Some("npub1alice"))
+            .unwrap());
+        // A retried loot action must not rate the same match twice
+        assert!(!ledger
+            .record_result("match_1", "npub1alice", "npub1bob", Some("npub1alice"))
+            .unwrap());
+        assert!(ledger.take_changed());
+
+        let alice = ledger.rating("npub1alice").unwrap();
+        let bob = ledger.rating("npub1bob").unwrap();
+        assert_eq!(alice.rating, 1216.0);
+        assert_eq!(bob.rating, 1184.0);
+        assert_eq!((alice.wins, bob.losses), (1, 1));
+
+        ledger
+            .record_result("match_2", "npub1bob", "npub1carol", None)
+            .unwrap();
+        let leaderboard = ledger.leaderboard().unwrap();
+        let standings: Vec<_> = leaderboard
+            .iter()
+            .map(|entry| (entry.rank, entry.npub.as_str()))
+            .collect();
+        assert_eq!(
+            standings,
+            [(1, "npub1alice"), (2, "npub1carol"), (3, "npub1bob")]
+        );
+
+        assert!(ledger
+            .record_result("match_3", "npub1alice", "npub1bob", Some("npub1dave"))
+            .is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::config::RateLimitConfig;
+
+/// Pubkeys tracked before idle entries are pruned
+const MAX_TRACKED_PUBKEYS: usize = 10_000;
+
+/// Token bucket refilled continuously up to its burst capacity
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, refill_per_second: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            refill_per_second,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.tokens >= self.capacity
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PubkeyState {
+    events: TokenBucket,
+    challenges: TokenBucket,
+    strikes: u32,
+    banned_until: Option<Instant>,
+}
+
+/// Temporary ban issued to a pubkey that kept flooding after being throttled
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemporaryBan {
+    pub npub: String,
+    pub reason: String,
+    pub strikes: u32,
+    pub banned_until: DateTime<Utc>,
+}
+
+/// What to do with an incoming player event
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateDecision {
+    Allow,
+    /// Over the rate limit; drop the event and count a strike
+    Throttle,
+    /// This event earned the pubkey a temporary ban
+    Ban(TemporaryBan),
+    /// Pubkey is serving a temporary ban
+    Banned,
+}
+
+/// Per-pubkey rate limiting of incoming player events
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    pubkeys: Mutex<HashMap<String, PubkeyState>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            pubkeys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Decide whether an event from this pubkey may reach the state machine
+    pub fn check(&self, npub: &str, is_challenge: bool) -> RateDecision {
+        self.check_at(npub, is_challenge, Instant::now())
+    }
+
+    fn check_at(&self, npub: &str, is_challenge: bool, now: Instant) -> RateDecision {
+        if !self.config.enabled {
+            return RateDecision::Allow;
+        }
+
+        let mut pubkeys = self.pubkeys.lock().unwrap();
+        if pubkeys.len() >= MAX_TRACKED_PUBKEYS && !pubkeys.contains_key(npub) {
+            prune_idle(&mut pubkeys, now);
+        }
+
+        let state = pubkeys
+            .entry(npub.to_string())
+            .or_insert_with(|| PubkeyState {
+                events: TokenBucket::new(self.config.burst, self.config.refill_per_second, now),
+                challenges: TokenBucket::new(
+                    self.config.challenge_burst,
+                    self.config.challenges_per_minute / 60.0,
+                    now,
+                ),
+                strikes: 0,
+                banned_until: None,
+            });
+
+        match state.banned_until {
+            Some(until) if now < until => return RateDecision::Banned,
+            Some(_) => {
+                debug!("🔓 Temporary ban on {} expired", npub);
+                state.banned_until = None;
+                state.strikes = 0;
+            }
+            None => {}
+        }
+
+        // Challenges draw from both buckets, so they can never exceed the general rate
+        let allowed =
+            state.events.try_take(now) && (!is_challenge || state.challenges.try_take(now));
+        if allowed {
+            return RateDecision::Allow;
+        }
+
+        state.strikes += 1;
+        if state.strikes < self.config.strikes_before_ban {
+            return RateDecision::Throttle;
+        }
+
+        let ban_duration = Duration::from_secs(self.config.ban_seconds);
+        state.banned_until = Some(now + ban_duration);
+        let ban = TemporaryBan {
+            npub: npub.to_string(),
+            reason: format!(
+                "Exceeded the event rate limit {} times",
+                self.config.strikes_before_ban
+            ),
+            strikes: state.strikes,
+            banned_until: Utc::now() + chrono::Duration::seconds(self.config.ban_seconds as i64),
+        };
+        warn!(
+            "🚫 Temporarily banned {} until {} ({} strikes)",
+            npub, ban.banned_until, ban.strikes
+        );
+        RateDecision::Ban(ban)
+    }
+}
+
+/// Forget pubkeys that are neither banned nor holding a partly drained bucket
+fn prune_idle(pubkeys: &mut HashMap<String, PubkeyState>, now: Instant) {
+    pubkeys.retain(|_, state| {
+        state.events.refill(now);
+        state.challenges.refill(now);
+        state.banned_until.is_some_and(|until| now < until)
+            || !state.events.is_full()
+            || !state.challenges.is_full()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_flood_is_throttled_then_banned() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            challenge_burst: 2,
+            strikes_before_ban: 3,
+            ..RateLimitConfig::default()
+        });
+        let start = Instant::now();
+
+        assert_eq!(
+            limiter.check_at("npub1spam", true, start),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check_at("npub1spam", true, start),
+            RateDecision::Allow
+        );
+        assert_eq!(
+            limiter.check_at("npub1spam", true, start),
+            RateDecision::Throttle
+        );
+        assert_eq!(
+            limiter.check_at("npub1spam", true, start),
+            RateDecision::Throttle
+        );
+        assert!(matches!(
+            limiter.check_at("npub1spam", true, start),
+            RateDecision::Ban(TemporaryBan { strikes: 3, .. })
+        ));
+
+        // Banned pubkeys are dropped outright, other players are unaffected
+        assert_eq!(
+            limiter.check_at("npub1spam", false, start),
+            RateDecision::Banned
+        );
+        assert_eq!(
+            limiter.check_at("npub1alice", true, start),
+            RateDecision::Allow
+        );
+
+        let after_ban = start + Duration::from_secs(RateLimitConfig::default().ban_seconds + 1);
+        assert_eq!(
+            limiter.check_at("npub1spam", true, after_ban),
+            RateDecision::Allow
+        );
+    }
+}
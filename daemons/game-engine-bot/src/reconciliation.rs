@@ -0,0 +1,780 @@
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info, warn};
+
+use crate::cashu_client::CashuClient;
+use crate::config::ReconciliationConfig;
+use crate::errors::GameEngineError;
+use crate::loot_token::Proof;
+use crate::match_store::persistence_error;
+
+/// Engine-side record of a match payout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PayoutRecord {
+    pub match_id: String,
+    pub winner_npub: Option<String>, // None while escrowed or for draws
+    pub amount: u64,
+    pub validated: bool,
+    pub loot_quote: Option<String>,
+    #[serde(default)]
+    pub league_id: Option<u8>, // Selects the league's mint, if it has its own
+    #[serde(default)]
+    pub escrowed_ys: BTreeMap<String, Vec<String>>, // Y values the mint holds, per player npub
+    #[serde(default)]
+    pub wagers_settled: bool, // Wagers burned or refunded at the mint
+    #[serde(default)]
+    pub loot_token: Option<String>, // Kept so a retried payout republishes instead of reminting
+    #[serde(default)]
+    pub match_fee: Option<CollectedFee>,
+    #[serde(default)]
+    pub split_shares: BTreeMap<String, LootShare>, // Split loot of a drawn match, per player npub
+    #[serde(default)]
+    pub minted_loot: BTreeMap<String, MintedLoot>, // Minted to the engine, not yet swapped, per recipient npub
+    #[serde(default)]
+    pub validated_at: Option<u64>,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl PayoutRecord {
+    fn new(match_id: &str) -> Self {
+        Self {
+            match_id: match_id.to_string(),
+            winner_npub: None,
+            amount: 0,
+            validated: false,
+            loot_quote: None,
+            league_id: None,
+            escrowed_ys: BTreeMap::new(),
+            wagers_settled: false,
+            loot_token: None,
+            match_fee: None,
+            split_shares: BTreeMap::new(),
+            minted_loot: BTreeMap::new(),
+            validated_at: None,
+            updated_at: 0,
+        }
+    }
+
+    /// Whether every stage the match needs is done: wagers settled, loot and fee paid
+    pub fn is_settled(&self) -> bool {
+        self.wagers_settled
+            && self.minted_loot.is_empty()
+            && (self.winner_npub.is_none()
+                || (self.loot_token.is_some() && self.match_fee.is_some()))
+    }
+
+    /// Whether the payout may still be under way, validated less than `grace_seconds` ago
+    pub fn in_flight(&self, now: u64, grace_seconds: u64) -> bool {
+        !self.is_settled()
+            && self
+                .validated_at
+                .is_some_and(|validated_at| now < validated_at.saturating_add(grace_seconds))
+    }
+}
+
+/// One drawn player's half of the loot, kept so a retried split never pays a player twice
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootShare {
+    pub quote: String,
+    pub token: String,
+    pub amount: u64,
+}
+
+/// Loot minted to engine-held secrets for one recipient but not yet swapped to them
+///
+/// Kept so a payout that fails between the mint and the swap resumes from these
+/// proofs instead of minting the award a second time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintedLoot {
+    pub quote: String,
+    pub amount: u64,
+    pub proofs: Vec<Proof>,
+}
+
+/// Match fee taken from a decided match, with its token when paid as locked loot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectedFee {
+    pub amount: u64,
+    pub token: Option<String>,
+}
+
+/// Loot the mint reports having issued for a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintLedgerEntry {
+    pub match_id: String,
+    pub amount_issued: u64,
+    pub quotes: Vec<String>,
+}
+
+/// Mismatch between the engine's payout records and the mint ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Discrepancy {
+    /// Mint issued loot for a match the engine never validated
+    PaidWithoutValidation {
+        match_id: String,
+        amount_issued: u64,
+    },
+    /// Engine validated a winner but the mint never issued the loot
+    ValidatedButUnpaid {
+        match_id: String,
+        winner_npub: String,
+        amount: u64,
+    },
+}
+
+/// Escrowed, validated and paid matches known to the engine, keyed by match id
+///
+/// Each payout stage is recorded as it completes, so a retried payout resumes
+/// where it failed instead of burning or minting a second time.
+#[derive(Debug)]
+pub struct PayoutLedger {
+    connection: Mutex<Connection>,
+}
+
+impl PayoutLedger {
+    /// Open (or create) the ledger in the given SQLite database
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Ledger that only lives as long as the process, for when persistence is disabled
+    pub fn in_memory() -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS payout_ledger (
+                    match_id TEXT PRIMARY KEY,
+                    record TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS daily_mint_spend (
+                    mint_url TEXT NOT NULL,
+                    day TEXT NOT NULL,
+                    minted INTEGER NOT NULL,
+                    PRIMARY KEY (mint_url, day)
+                );",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Record that the mint holds a player's wager in escrow and no payout is owed yet
+    pub fn record_escrowed(
+        &self,
+        match_id: &str,
+        league_id: Option<u8>,
+        player_npub: &str,
+        ys: Vec<String>,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.league_id = record.league_id.or(league_id);
+            record.escrowed_ys.insert(player_npub.to_string(), ys);
+        })
+    }
+
+    /// Record that a match result was validated and what loot, if any, is owed
+    pub fn record_validated(
+        &self,
+        match_id: &str,
+        winner_npub: Option<&str>,
+        amount: u64,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.winner_npub = winner_npub.map(str::to_string);
+            record.amount = if winner_npub.is_some() { amount } else { 0 };
+            record.validated = true;
+            record.validated_at.get_or_insert_with(now);
+        })
+    }
+
+    /// Record that the match's wagers were burned or refunded
+    pub fn record_wagers_settled(&self, match_id: &str) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| record.wagers_settled = true)
+    }
+
+    /// Record that loot for a match was issued by the mint
+    pub fn record_paid(
+        &self,
+        match_id: &str,
+        loot_quote: &str,
+        loot_token: &str,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.loot_quote = Some(loot_quote.to_string());
+            record.loot_token = Some(loot_token.to_string());
+        })
+    }
+
+    /// Record a drawn player's share of split loot as issued by the mint
+    pub fn record_share_paid(
+        &self,
+        match_id: &str,
+        player_npub: &str,
+        share: LootShare,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.split_shares.insert(player_npub.to_string(), share);
+        })
+    }
+
+    /// Record loot minted to the engine for a recipient, before it is swapped to them
+    pub fn record_minted(
+        &self,
+        match_id: &str,
+        recipient_npub: &str,
+        minted: MintedLoot,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record
+                .minted_loot
+                .insert(recipient_npub.to_string(), minted);
+        })
+    }
+
+    /// Loot minted for a recipient by an earlier attempt that never reached them
+    pub fn minted_loot(
+        &self,
+        match_id: &str,
+        recipient_npub: &str,
+    ) -> Result<Option<MintedLoot>, GameEngineError> {
+        Ok(self
+            .record(match_id)?
+            .and_then(|mut record| record.minted_loot.remove(recipient_npub)))
+    }
+
+    /// Forget minted loot once it has been swapped to its recipient
+    pub fn clear_minted(
+        &self,
+        match_id: &str,
+        recipient_npub: &str,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.minted_loot.remove(recipient_npub);
+        })
+    }
+
+    /// Record the match fee taken from a decided match
+    pub fn record_fee(
+        &self,
+        match_id: &str,
+        amount: u64,
+        token: Option<String>,
+    ) -> Result<(), GameEngineError> {
+        self.update(match_id, |record| {
+            record.match_fee = Some(CollectedFee { amount, token });
+        })
+    }
+
+    /// Add loot minted by a mint to its tally for the day, which survives restarts
+    pub fn record_daily_mint(
+        &self,
+        mint_url: &str,
+        day: NaiveDate,
+        amount: u64,
+    ) -> Result<(), GameEngineError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO daily_mint_spend (mint_url, day, minted) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(mint_url, day) DO UPDATE SET minted = minted + excluded.minted",
+                params![mint_url, day.to_string(), amount as i64],
+            )
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+
+    /// Loot a mint issued to the engine on `day`
+    pub fn minted_on(&self, mint_url: &str, day: NaiveDate) -> Result<u64, GameEngineError> {
+        let minted: Option<i64> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT minted FROM daily_mint_spend WHERE mint_url = ?1 AND day = ?2",
+                params![mint_url, day.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(persistence_error)?;
+        Ok(minted.unwrap_or(0).max(0) as u64)
+    }
+
+    pub fn record(&self, match_id: &str) -> Result<Option<PayoutRecord>, GameEngineError> {
+        let encoded: Option<String> = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT record FROM payout_ledger WHERE match_id = ?1",
+                params![match_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(persistence_error)?;
+
+        encoded.map(|json| decode_record(&json)).transpose()
+    }
+
+    /// Forget settled records last updated before `before`, returning how many were pruned
+    pub fn prune_settled(&self, before: u64) -> Result<usize, GameEngineError> {
+        let expired: Vec<String> = self
+            .records()?
+            .into_iter()
+            .filter(|record| record.is_settled() && record.updated_at < before)
+            .map(|record| record.match_id)
+            .collect();
+
+        let connection = self.connection.lock().unwrap();
+        for match_id in &expired {
+            connection
+                .execute(
+                    "DELETE FROM payout_ledger WHERE match_id = ?1",
+                    params![match_id],
+                )
+                .map_err(persistence_error)?;
+        }
+        Ok(expired.len())
+    }
+
+    pub fn records(&self) -> Result<Vec<PayoutRecord>, GameEngineError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT record FROM payout_ledger ORDER BY match_id")
+            .map_err(persistence_error)?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(persistence_error)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(decode_record(&row.map_err(persistence_error)?)?);
+        }
+        Ok(records)
+    }
+
+    fn update(
+        &self,
+        match_id: &str,
+        change: impl FnOnce(&mut PayoutRecord),
+    ) -> Result<(), GameEngineError> {
+        let mut record = self
+            .record(match_id)?
+            .unwrap_or_else(|| PayoutRecord::new(match_id));
+        change(&mut record);
+        record.updated_at = now();
+        let encoded = serde_json::to_string(&record).map_err(|e| {
+            GameEngineError::Persistence(format!("Failed to encode payout record: {e}"))
+        })?;
+
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO payout_ledger (match_id, record) VALUES (?1, ?2)
+                 ON CONFLICT(match_id) DO UPDATE SET record = excluded.record",
+                params![match_id, encoded],
+            )
+            .map_err(persistence_error)?;
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
+
+fn decode_record(json: &str) -> Result<PayoutRecord, GameEngineError> {
+    serde_json::from_str(json)
+        .map_err(|e| GameEngineError::Persistence(format!("Corrupt payout record: {e}")))
+}
+
+/// Compare an engine payout record against the mint's ledger entry for the same match
+pub fn reconcile(
+    record: &PayoutRecord,
+    mint_entry: Option<&MintLedgerEntry>,
+) -> Option<Discrepancy> {
+    let amount_issued = mint_entry.map(|entry| entry.amount_issued).unwrap_or(0);
+
+    if !record.validated && amount_issued > 0 {
+        return Some(Discrepancy::PaidWithoutValidation {
+            match_id: record.match_id.clone(),
+            amount_issued,
+        });
+    }
+
+    if record.validated && amount_issued == 0 {
+        // Draws owe nothing, so only a validated winner can be left unpaid
+        if let Some(winner_npub) = &record.winner_npub {
+            return Some(Discrepancy::ValidatedButUnpaid {
+                match_id: record.match_id.clone(),
+                winner_npub: winner_npub.clone(),
+                amount: record.amount,
+            });
+        }
+    }
+
+    None
+}
+
+/// Counters describing reconciliation activity
+#[derive(Debug, Default)]
+pub struct ReconciliationMetrics {
+    pub runs: AtomicU64,
+    pub matches_checked: AtomicU64,
+    pub paid_without_validation: AtomicU64,
+    pub validated_but_unpaid: AtomicU64,
+    pub repairs_attempted: AtomicU64,
+    pub mint_errors: AtomicU64,
+}
+
+impl ReconciliationMetrics {
+    pub fn record_discrepancy(&self, discrepancy: &Discrepancy) {
+        match discrepancy {
+            Discrepancy::PaidWithoutValidation { .. } => {
+                self.paid_without_validation.fetch_add(1, Ordering::Relaxed)
+            }
+            Discrepancy::ValidatedButUnpaid { .. } => {
+                self.validated_but_unpaid.fetch_add(1, Ordering::Relaxed)
+            }
+        };
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "runs": self.runs.load(Ordering::Relaxed),
+            "matches_checked": self.matches_checked.load(Ordering::Relaxed),
+            "paid_without_validation": self.paid_without_validation.load(Ordering::Relaxed),
+            "validated_but_unpaid": self.validated_but_unpaid.load(Ordering::Relaxed),
+            "repairs_attempted": self.repairs_attempted.load(Ordering::Relaxed),
+            "mint_errors": self.mint_errors.load(Ordering::Relaxed)
+        })
+    }
+}
+
+/// Background task cross-checking engine payout records against the mint ledger
+pub async fn run_reconciliation_task(
+    ledger: Arc<PayoutLedger>,
+    cashu_client: Arc<CashuClient>,
+    metrics: Arc<ReconciliationMetrics>,
+    config: ReconciliationConfig,
+) {
+    let mut interval =
+        tokio::time::interval(tokio::time::Duration::from_secs(config.interval_seconds));
+
+    loop {
+        interval.tick().await;
+        metrics.runs.fetch_add(1, Ordering::Relaxed);
+        let now = now();
+
+        match ledger.prune_settled(now.saturating_sub(config.settled_retention_seconds)) {
+            Ok(0) => {}
+            Ok(pruned) => debug!("🧾 Reconciliation pruned {} settled payouts", pruned),
+            Err(e) => warn!("⚠️ Reconciliation: could not prune payout ledger: {}", e),
+        }
+
+        let records = match ledger.records() {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("⚠️ Reconciliation: payout ledger unreadable: {}", e);
+                continue;
+            }
+        };
+        for record in records {
+            // The engine may be minting this loot right now; a repair would pay it twice
+            if record.in_flight(now, config.repair_grace_seconds) {
+                continue;
+            }
+            metrics.matches_checked.fetch_add(1, Ordering::Relaxed);
+            let cashu_client = cashu_client.for_league(record.league_id);
+
+            let mint_entry = match cashu_client.get_match_ledger(&record.match_id).await {
+                Ok(entry) => entry,
+                Err(e) => {
+                    metrics.mint_errors.fetch_add(1, Ordering::Relaxed);
+                    warn!(
+                        "⚠️ Reconciliation: mint ledger unavailable for match {}: {}",
+                        record.match_id, e
+                    );
+                    continue;
+                }
+            };
+
+            let Some(discrepancy) = reconcile(&record, mint_entry.as_ref()) else {
+                continue;
+            };
+
+            metrics.record_discrepancy(&discrepancy);
+            error!("🚨 Reconciliation discrepancy: {:?}", discrepancy);
+
+            if let Discrepancy::ValidatedButUnpaid {
+                match_id,
+                winner_npub,
+                amount,
+            } = &discrepancy
+            {
+                // A payout the engine believes it already made needs an operator, not a retry
+                if config.auto_repair && record.loot_quote.is_none() {
+                    metrics.repairs_attempted.fetch_add(1, Ordering::Relaxed);
+                    match cashu_client
+                        .create_loot_token(winner_npub, *amount, match_id)
+                        .await
+                    {
+                        Ok(loot_result) => {
+                            if let Err(e) =
+                                ledger.record_paid(match_id, &loot_result.quote, &loot_result.token)
+                            {
+                                error!(
+                                    "❌ Reconciliation could not record repaired payout for match {}: {}",
+                                    match_id, e
+                                );
+                            }
+                            info!(
+                                "🔧 Reconciliation repaired payout for match {} ({})",
+                                match_id, loot_result.quote
+                            );
+                        }
+                        Err(e) => {
+                            error!(
+                                "❌ Reconciliation repair failed for match {}: {}",
+                                match_id, e
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        debug!("🧾 Reconciliation cycle: {}", metrics.to_json());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint_entry(match_id: &str, amount_issued: u64) -> MintLedgerEntry {
+        MintLedgerEntry {
+            match_id: match_id.to_string(),
+            amount_issued,
+            quotes: vec!["quote_1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_validated_and_paid_reconciles() {
+        let ledger = PayoutLedger::in_memory().unwrap();
+        ledger
+            .record_escrowed("match_1", None, "npub1winner", vec!["y1".to_string()])
+            .unwrap();
+        ledger
+            .record_validated("match_1", Some("npub1winner"), 100)
+            .unwrap();
+        ledger
+            .record_paid("match_1", "quote_1", "cashuAloot")
+            .unwrap();
+
+        let record = &ledger.records().unwrap()[0];
+        assert_eq!(record.loot_quote.as_deref(), Some("quote_1"));
+        assert_eq!(reconcile(record, Some(&mint_entry("match_1", 100))), None);
+    }
+
+    #[test]
+    fn test_flags_validated_but_unpaid() {
+        let ledger = PayoutLedger::in_memory().unwrap();
+        ledger
+            .record_validated("match_1", Some("npub1winner"), 100)
+            .unwrap();
+
+        assert_eq!(
+            reconcile(&ledger.records().unwrap()[0], None),
+            Some(Discrepancy::ValidatedButUnpaid {
+                match_id: "match_1".to_string(),
+                winner_npub: "npub1winner".to_string(),
+                amount: 100,
+            })
+        );
+    }
+
+    #[test]
+    fn test_flags_paid_without_validation() {
+        let ledger = PayoutLedger::in_memory().unwrap();
+        ledger
+            .record_escrowed("match_1", None, "npub1winner", vec!["y1".to_string()])
+            .unwrap();
+
+        let discrepancy = reconcile(
+            &ledger.records().unwrap()[0],
+            Some(&mint_entry("match_1", 100)),
+        )
+        .unwrap();
+        let metrics = ReconciliationMetrics::default();
+        metrics.record_discrepancy(&discrepancy);
+
+        assert!(matches!(
+            discrepancy,
+            Discrepancy::PaidWithoutValidation {
+                amount_issued: 100,
+                ..
+            }
+        ));
+        assert_eq!(metrics.to_json()["paid_without_validation"], 1);
+    }
+
+    #[test]
+    fn test_draw_owes_no_payout() {
+        let ledger = PayoutLedger::in_memory().unwrap();
+        ledger
+            .record_escrowed("match_1", None, "npub1winner", vec!["y1".to_string()])
+            .unwrap();
+        ledger.record_validated("match_1", None, 100).unwrap();
+
+        assert_eq!(reconcile(&ledger.records().unwrap()[0], None), None);
+    }
+
+    #[test]
+    fn test_recent_payouts_are_in_flight_and_settled_ones_pruned() {
+        let ledger = PayoutLedger::in_memory().unwrap();
+        ledger
+            .record_validated("match_1", Some("npub1winner"), 100)
+            .unwrap();
+        ledger.record_validated("match_2", None, 0).unwrap();
+        ledger.record_wagers_settled("match_2").unwrap();
+
+        let record = ledger.record("match_1").unwrap().unwrap();
+        let validated_at = record.validated_at.unwrap();
+        assert!(record.in_flight(validated_at + 60, 900));
+        assert!(!record.in_flight(validated_at + 900, 900));
+
+        // Only the settled draw is old enough to forget
+        assert_eq!(ledger.prune_settled(now() + 1).unwrap(), 1);
+        assert_eq!(ledger.record("match_2").unwrap(), None);
+        assert!(ledger.record("match_1").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_payout_stages_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.db");
+        {
+            let ledger = PayoutLedger::open(&path).unwrap();
+            ledger
+                .record_escrowed("match_1", Some(2), "npub1winner", vec!["y1".to_string()])
+                .unwrap();
+            ledger
+                .record_validated("match_1", Some("npub1winner"), 100)
+                .unwrap();
+            ledger.record_wagers_settled("match_1").unwrap();
+            ledger
+                .record_paid("match_1", "quote_1", "cashuAloot")
+                .unwrap();
+        }
+
+        let ledger = PayoutLedger::open(&path).unwrap();
+        let record = ledger.record("match_1").unwrap().unwrap();
+        assert!(record.wagers_settled);
+        assert_eq!(record.league_id, Some(2));
+        assert_eq!(record.escrowed_ys["npub1winner"], vec!["y1".to_string()]);
+        assert_eq!(record.loot_token.as_deref(), Some("cashuAloot"));
+        assert_eq!(record.match_fee, None);
+        assert_eq!(ledger.record("match_2").unwrap(), None);
+    }
+
+    #[test]
+    fn test_split_loot_shares_are_kept_per_player() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.db");
+        let share = LootShare {
+            quote: "quote_1".to_string(),
+            token: "cashuAalice".to_string(),
+            amount: 50,
+        };
+        {
+            let ledger = PayoutLedger::open(&path).unwrap();
+            ledger.record_validated("match_1", None, 0).unwrap();
+            ledger
+                .record_share_paid("match_1", "npub1alice", share.clone())
+                .unwrap();
+        }
+
+        // A retry after the second share failed finds only the first one paid
+        let ledger = PayoutLedger::open(&path).unwrap();
+        let record = ledger.record("match_1").unwrap().unwrap();
+        assert_eq!(record.split_shares.len(), 1);
+        assert_eq!(record.split_shares["npub1alice"], share);
+        assert_eq!(record.loot_token, None);
+    }
+
+    #[test]
+    fn test_daily_mint_tally_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.db");
+        let today = Utc::now().date_naive();
+        {
+            let ledger = PayoutLedger::open(&path).unwrap();
+            ledger.record_daily_mint("http://mint", today, 100).unwrap();
+            ledger.record_daily_mint("http://mint", today, 50).unwrap();
+            ledger.record_daily_mint("http://other", today, 7).unwrap();
+        }
+
+        let ledger = PayoutLedger::open(&path).unwrap();
+        assert_eq!(ledger.minted_on("http://mint", today).unwrap(), 150);
+        assert_eq!(ledger.minted_on("http://other", today).unwrap(), 7);
+        assert_eq!(
+            ledger
+                .minted_on("http://mint", today.pred_opt().unwrap())
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_minted_loot_is_kept_until_swapped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.db");
+        let minted = MintedLoot {
+            quote: "quote_1".to_string(),
+            amount: 3,
+            proofs: vec![Proof {
+                amount: 1,
+                id: "keyset".to_string(),
+                secret: "secret".to_string(),
+                signature: "02ab".to_string(),
+            }],
+        };
+        {
+            let ledger = PayoutLedger::open(&path).unwrap();
+            ledger.record_validated("match_1", None, 0).unwrap();
+            ledger.record_wagers_settled("match_1").unwrap();
+            ledger
+                .record_minted("match_1", "npub1winner", minted.clone())
+                .unwrap();
+        }
+
+        // A retry after the swap failed finds the proofs, and the record stays unsettled
+        let ledger = PayoutLedger::open(&path).unwrap();
+        assert_eq!(
+            ledger.minted_loot("match_1", "npub1winner").unwrap(),
+            Some(minted)
+        );
+        assert_eq!(ledger.minted_loot("match_1", "npub1other").unwrap(), None);
+        assert!(!ledger.record("match_1").unwrap().unwrap().is_settled());
+
+        ledger.clear_minted("match_1", "npub1winner").unwrap();
+        assert_eq!(ledger.minted_loot("match_1", "npub1winner").unwrap(), None);
+        assert!(ledger.record("match_1").unwrap().unwrap().is_settled());
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::{HashMap, HashSet};
+
+/// Relays the engine follows for each tracked match, beyond its configured ones
+///
+/// Players advertise where they publish with a NIP-65 relay list (kind 10002). The
+/// engine subscribes to those relays while a match is tracked and drops a relay once
+/// no tracked match needs it any more.
+#[derive(Debug, Default)]
+pub struct MatchRelays {
+    configured: HashSet<String>,
+    by_match: HashMap<String, HashSet<String>>,
+}
+
+impl MatchRelays {
+    pub fn new(configured: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            configured: configured.into_iter().map(|url| normalize(&url)).collect(),
+            by_match: HashMap::new(),
+        }
+    }
+
+    /// Follow a player's relays for a match, returning those the engine must start subscribing to
+    pub fn follow(
+        &mut self,
+        match_event_id: &str,
+        relays: impl IntoIterator<Item = String>,
+    ) -> Vec<String> {
+        let mut added = Vec::new();
+        for relay in relays {
+            let relay = normalize(&relay);
+            if self.configured.contains(&relay) || added.contains(&relay) {
+                continue;
+            }
+            if !self.is_followed(&relay) {
+                added.push(relay.clone());
+            }
+            self.by_match
+                .entry(match_event_id.to_string())
+                .or_default()
+                .insert(relay);
+        }
+        added
+    }
+
+    /// Stop following a finished match, returning the relays no other match needs
+    pub fn release(&mut self, match_event_id: &str) -> Vec<String> {
+        let Some(relays) = self.by_match.remove(match_event_id) else {
+            return Vec::new();
+        };
+        relays
+            .into_iter()
+            .filter(|relay| !self.is_followed(relay))
+            .collect()
+    }
+
+    fn is_followed(&self, relay: &str) -> bool {
+        self.by_match.values().any(|relays| relays.contains(relay))
+    }
+}
+
+/// Relay URLs differ only by a trailing slash between clients
+fn normalize(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay_shared_by_matches_is_kept_until_both_finish() {
+        let mut relays = MatchRelays::new(["ws://localhost:7777".to_string()]);
+
+        let added = relays.follow(
+            "match_1",
+            [
+                "ws://localhost:7777/".to_string(),
+                "wss://alice.relay".to_string(),
+            ],
+        );
+        assert_eq!(added, ["wss://alice.relay"]);
+
+        // Bob shares Alice's relay in another match: nothing new to subscribe to
+        let added = relays.follow(
+            "match_2",
+            [
+                "wss://alice.relay/".to_string(),
+                "wss://bob.relay".to_string(),
+            ],
+        );
+        assert_eq!(added, ["wss://bob.relay"]);
+
+        assert!(relays.release("match_1").is_empty());
+        let mut released = relays.release("match_2");
+        released.sort();
+        assert_eq!(released, ["wss://alice.relay", "wss://bob.relay"]);
+        assert!(relays.release("match_2").is_empty());
+    }
+}
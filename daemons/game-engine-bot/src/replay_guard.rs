@@ -0,0 +1,261 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use tracing::{debug, info};
+
+use crate::errors::GameEngineError;
+use crate::match_store::persistence_error;
+
+/// Raw 32-byte Nostr event id
+pub type EventIdBytes = [u8; 32];
+
+/// How long processed event ids are remembered
+///
+/// Must outlast the furthest any subscription reaches back (two days for direct
+/// messages), or a relay redelivering an old event would have it processed again.
+pub const RETENTION_SECONDS: u64 = 3 * 24 * 60 * 60;
+
+/// Recent ids answered from memory, covering copies of one event delivered by every relay
+const RECENT_CAPACITY: usize = 10_000;
+
+/// Bloom filter sized for under 1% false positives at 100k remembered ids
+const BLOOM_BITS: u64 = 1 << 20;
+const BLOOM_HASHES: u64 = 7;
+
+/// New ids recorded between sweeps of expired ids
+const PRUNE_INTERVAL: u64 = 1_000;
+
+/// Bit set answering "definitely new" for most ids without a disk lookup
+struct BloomFilter {
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    fn new() -> Self {
+        Self {
+            words: vec![0; (BLOOM_BITS / 64) as usize],
+        }
+    }
+
+    /// Bit positions for an id by double hashing; event ids are SHA-256 digests and already uniform
+    fn positions(id: &EventIdBytes) -> impl Iterator<Item = u64> {
+        let h1 = u64::from_le_bytes(id[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(id[8..16].try_into().unwrap()) | 1;
+        (0..BLOOM_HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % BLOOM_BITS)
+    }
+
+    fn insert(&mut self, id: &EventIdBytes) {
+        for bit in Self::positions(id) {
+            self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    fn might_contain(&self, id: &EventIdBytes) -> bool {
+        Self::positions(id).all(|bit| self.words[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+}
+
+/// Bounded set of the most recently seen ids, evicting the oldest first
+struct RecentIds {
+    ids: HashSet<EventIdBytes>,
+    order: VecDeque<EventIdBytes>,
+}
+
+impl RecentIds {
+    fn new() -> Self {
+        Self {
+            ids: HashSet::with_capacity(RECENT_CAPACITY),
+            order: VecDeque::with_capacity(RECENT_CAPACITY),
+        }
+    }
+
+    fn contains(&self, id: &EventIdBytes) -> bool {
+        self.ids.contains(id)
+    }
+
+    fn insert(&mut self, id: EventIdBytes) {
+        if !self.ids.insert(id) {
+            return;
+        }
+
+        self.order.push_back(id);
+        if self.order.len() > RECENT_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Processed Nostr event ids, persisted so replayed events are dropped across restarts
+///
+/// Lookups go recent ids, then the bloom filter, then SQLite; only bloom hits touch disk.
+pub struct ReplayGuard {
+    connection: Connection,
+    bloom: BloomFilter,
+    recent: RecentIds,
+    recorded_since_prune: u64,
+}
+
+impl ReplayGuard {
+    /// Open (or create) the seen-event table in the engine database
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Guard that only remembers events for the life of the process
+    pub fn in_memory() -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS seen_events (
+                    event_id BLOB PRIMARY KEY,
+                    seen_at INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS seen_events_seen_at ON seen_events (seen_at);",
+            )
+            .map_err(persistence_error)?;
+
+        let mut guard = Self {
+            connection,
+            bloom: BloomFilter::new(),
+            recent: RecentIds::new(),
+            recorded_since_prune: 0,
+        };
+        let remembered = guard.prune(now())?;
+        if remembered > 0 {
+            info!("🔁 Replay guard remembers {} processed events", remembered);
+        }
+        Ok(guard)
+    }
+
+    /// Whether an event id was already processed, even before a restart
+    pub fn already_seen(&mut self, id: &EventIdBytes) -> Result<bool, GameEngineError> {
+        if self.recent.contains(id) {
+            return Ok(true);
+        }
+        if self.bloom.might_contain(id) && self.is_persisted(id)? {
+            self.recent.insert(*id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Remember an event id once the event has been processed
+    pub fn record(&mut self, id: &EventIdBytes) -> Result<(), GameEngineError> {
+        self.record_at(id, now())
+    }
+
+    fn record_at(&mut self, id: &EventIdBytes, now: u64) -> Result<(), GameEngineError> {
+        if self.recent.contains(id) {
+            return Ok(());
+        }
+
+        self.connection
+            .execute(
+                "INSERT OR IGNORE INTO seen_events (event_id, seen_at) VALUES (?1, ?2)",
+                params![&id[..], now as i64],
+            )
+            .map_err(persistence_error)?;
+        self.bloom.insert(id);
+        self.recent.insert(*id);
+
+        self.recorded_since_prune += 1;
+        if self.recorded_since_prune >= PRUNE_INTERVAL {
+            self.prune(now)?;
+        }
+        Ok(())
+    }
+
+    fn is_persisted(&self, id: &EventIdBytes) -> Result<bool, GameEngineError> {
+        self.connection
+            .query_row(
+                "SELECT 1 FROM seen_events WHERE event_id = ?1",
+                params![&id[..]],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|row| row.is_some())
+            .map_err(persistence_error)
+    }
+
+    /// Forget ids past retention and rebuild the bloom filter from the rest
+    ///
+    /// Returns how many ids are still remembered.
+    fn prune(&mut self, now: u64) -> Result<usize, GameEngineError> {
+        let cutoff = now.saturating_sub(RETENTION_SECONDS) as i64;
+        let expired = self
+            .connection
+            .execute(
+                "DELETE FROM seen_events WHERE seen_at < ?1",
+                params![cutoff],
+            )
+            .map_err(persistence_error)?;
+
+        let mut bloom = BloomFilter::new();
+        let mut remembered = 0;
+        {
+            let mut statement = self
+                .connection
+                .prepare("SELECT event_id FROM seen_events")
+                .map_err(persistence_error)?;
+            let rows = statement
+                .query_map([], |row| row.get::<_, Vec<u8>>(0))
+                .map_err(persistence_error)?;
+            for row in rows {
+                let row = row.map_err(persistence_error)?;
+                if let Ok(id) = EventIdBytes::try_from(row.as_slice()) {
+                    bloom.insert(&id);
+                    remembered += 1;
+                }
+            }
+        }
+
+        self.bloom = bloom;
+        self.recorded_since_prune = 0;
+        debug!(
+            "🔁 Replay guard forgot {} expired events, remembers {}",
+            expired, remembered
+        );
+        Ok(remembered)
+    }
+}
+
+fn now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replayed_events_are_dropped_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.sqlite");
+        let first = [1u8; 32];
+        let second = [2u8; 32];
+        let start = now();
+
+        {
+            let mut guard = ReplayGuard::open(&path).unwrap();
+            assert!(!guard.already_seen(&first).unwrap());
+            guard.record_at(&first, start).unwrap();
+            assert!(guard.already_seen(&first).unwrap());
+            guard
+                .record_at(&second, start - RETENTION_SECONDS - 1)
+                .unwrap();
+        }
+
+        // A fresh process still rejects the replay, while expired ids are forgotten
+        let mut guard = ReplayGuard::open(&path).unwrap();
+        assert!(guard.already_seen(&first).unwrap());
+        assert!(!guard.already_seen(&second).unwrap());
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::config::ReputationConfig;
+use crate::errors::GameEngineError;
+use crate::match_store::persistence_error;
+
+/// A player's cheating record with this engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerReputation {
+    pub npub: String,
+    pub cheat_detections: u32,
+    pub blacklisted: bool,
+}
+
+/// Cheat detections per player, persisted beside the match archive
+///
+/// Players with too many detections are blacklisted and may not join new matches.
+pub struct ReputationStore {
+    connection: Mutex<Connection>,
+    config: ReputationConfig,
+}
+
+impl ReputationStore {
+    /// Open (or create) the reputation table in the given SQLite database
+    pub fn open(path: impl AsRef<Path>, config: ReputationConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open(path.as_ref()).map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    /// Store that only lives as long as the process, for when persistence is disabled
+    pub fn in_memory(config: ReputationConfig) -> Result<Self, GameEngineError> {
+        let connection = Connection::open_in_memory().map_err(persistence_error)?;
+        Self::with_connection(connection, config)
+    }
+
+    fn with_connection(
+        connection: Connection,
+        config: ReputationConfig,
+    ) -> Result<Self, GameEngineError> {
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS cheat_detections (
+                    npub TEXT NOT NULL,
+                    match_id TEXT NOT NULL,
+                    reason TEXT NOT NULL,
+                    evidence_hashes TEXT NOT NULL,
+                    detected_at INTEGER NOT NULL,
+                    PRIMARY KEY (npub, match_id)
+                );",
+            )
+            .map_err(persistence_error)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            config,
+        })
+    }
+
+    /// Record a cheat proven in a match and return the offender's updated reputation
+    ///
+    /// A match counts once per offender, so a retried invalidation is not a second strike.
+    pub fn record_cheat(
+        &self,
+        npub: &str,
+        match_id: &str,
+        reason: &str,
+        evidence_hashes: &[String],
+    ) -> Result<PlayerReputation, GameEngineError> {
+        let evidence_hashes = serde_json::to_string(evidence_hashes)
+            .map_err(|e| GameEngineError::Internal(e.to_string()))?;
+        let detected_at = Utc::now().timestamp();
+        let newly_recorded = self
+            .connection
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT OR IGNORE INTO cheat_detections
+                 (npub, match_id, reason, evidence_hashes, detected_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![npub, match_id, reason, evidence_hashes, detected_at],
+            )
+            .map_err(persistence_error)?;
+
+        let reputation = self.reputation(npub)?;
+        if newly_recorded > 0 && reputation.blacklisted {
+            warn!(
+                "⛔ {} blacklisted after {} cheat detections",
+                npub, reputation.cheat_detections
+            );
+        }
+        Ok(reputation)
+    }
+
+    pub fn reputation(&self, npub: &str) -> Result<PlayerReputation, GameEngineError> {
+        let cheat_detections: u32 = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM cheat_detections WHERE npub = ?1",
+                params![npub],
+                |row| row.get(0),
+            )
+            .map_err(persistence_error)?;
+
+        Ok(PlayerReputation {
+            npub: npub.to_string(),
+            cheat_detections,
+            blacklisted: cheat_detections >= self.config.strikes_before_blacklist.max(1),
+        })
+    }
+
+    /// Whether the player may no longer join matches
+    ///
+    /// Fails open: an unreadable store must not lock every player out.
+    pub fn is_blacklisted(&self, npub: &str) -> bool {
+        match self.reputation(npub) {
+            Ok(reputation) => reputation.blacklisted,
+            Err(e) => {
+                warn!("⚠️ Could not read reputation of {}: {}", npub, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blacklist_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.db");
+        let config = ReputationConfig {
+            enabled: true,
+            strikes_before_blacklist: 2,
+        };
+        let evidence = ["reveal_event_hash".to_string()];
+
+        let store = ReputationStore::open(&path, config.clone()).unwrap();
+        let first = store
+            .record_cheat("npub1bob", "match_1", "commitment mismatch", &evidence)
+            .unwrap();
+        assert_eq!(first.cheat_detections, 1);
+        assert!(!first.blacklisted);
+
+        // A retried invalidation of the same match is not a second strike
+        store
+            .record_cheat("npub1bob", "match_1", "commitment mismatch", &evidence)
+            .unwrap();
+        assert!(!store.is_blacklisted("npub1bob"));
+
+        store
+            .record_cheat("npub1bob", "match_2", "commitment mismatch", &evidence)
+            .unwrap();
+        drop(store);
+
+        let reopened = ReputationStore::open(&path, config).unwrap();
+        assert!(reopened.is_blacklisted("npub1bob"));
+        assert!(!reopened.is_blacklisted("npub1alice"));
+    }
+}
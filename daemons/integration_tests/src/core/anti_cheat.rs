@@ -1,8 +1,10 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::time::Duration;
 use tracing::info;
 
 use super::gaming_wallet::GamingWallet;
 use super::shared::TestSuiteCore;
+use crate::engine_outcome::{wait_for_match_outcome, MatchOutcome};
 
 /// Tests anti-cheat commitment verification
 ///
@@ -34,6 +36,21 @@ pub async fn test_commitment_verification(core: &TestSuiteCore) -> Result<()> {
     core.publish_token_reveal(&cheating_player, &challenge.match_event_id)
         .await?;
 
+    // Only an invalidation signed by the pinned engine proves the cheat was caught
+    let outcome = wait_for_match_outcome(
+        &core.nostr_client,
+        &core.pinned_engine,
+        &challenge.match_event_id,
+        Duration::from_secs(30),
+    )
+    .await?;
+    if !matches!(outcome, MatchOutcome::Invalidated(_)) {
+        return Err(anyhow!(
+            "Engine paid out match {} despite the mismatched reveal",
+            challenge.match_event_id
+        ));
+    }
+
     info!("✅ Anti-cheat commitment verification working correctly");
     Ok(())
 }
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use nostr::{EventBuilder, EventId, Keys};
 use nostr_sdk::Client as NostrClient;
 use reqwest::Client;
@@ -9,6 +9,8 @@ use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{debug, info};
 
+use crate::engine_identity::{DeploymentProfile, PinnedEngine};
+use crate::engine_outcome::{wait_for_match_outcome, MatchOutcome};
 use crate::matches::{
     MatchAcceptance, MatchChallenge, MatchResult, CombatMove, TokenReveal,
 };
@@ -24,6 +26,7 @@ pub struct TestSuiteCore {
     pub mint_url: String,
     pub relay_url: String,
     pub nostr_client: NostrClient,
+    pub pinned_engine: PinnedEngine,
 }
 
 impl TestSuiteCore {
@@ -38,11 +41,14 @@ impl TestSuiteCore {
         nostr_client.add_relay(relay_url.clone()).await?;
         nostr_client.connect().await;
 
+        let pinned_engine = PinnedEngine::for_profile(DeploymentProfile::Local)?;
+
         Ok(Self {
             http_client,
             mint_url,
             relay_url,
             nostr_client,
+            pinned_engine,
         })
     }
 
@@ -50,6 +56,9 @@ impl TestSuiteCore {
     pub async fn wait_for_services(&self) -> Result<()> {
         info!("⏳ Waiting for services to be ready...");
 
+        // Refuse to run against a mint the pinned deployment profile doesn't know about
+        self.pinned_engine.verify_mint_url(&self.mint_url)?;
+
         // Wait for Cashu mint
         for attempt in 1..=30 {
             match self
@@ -167,6 +176,8 @@ impl TestSuiteCore {
             expires_at: (chrono::Utc::now().timestamp() + 3600) as u64,
             created_at: chrono::Utc::now().timestamp() as u64,
             match_event_id: String::new(),
+            // Zero-wager challenges are practice matches: no escrow, no loot
+            practice: wager_amount == 0,
         };
 
         let content_str = serde_json::to_string(&challenge_data)?;
@@ -183,6 +194,7 @@ impl TestSuiteCore {
             expires_at: challenge_data.expires_at,
             created_at: challenge_data.created_at,
             match_event_id: real_event_id.to_hex(),
+            practice: challenge_data.practice,
         };
 
         player.nostr_client.send_event(event).await?;
@@ -309,7 +321,12 @@ impl TestSuiteCore {
             previous_event_hash,
             round_number: round,
             unit_positions: vec![1, 2, 3, 4],
-            unit_abilities: vec!["boost".to_string(), "shield".to_string()],
+            // Boost every other round, within its cooldown and the player's mana
+            unit_abilities: if round % 2 == 1 {
+                vec!["boost".to_string()]
+            } else {
+                Vec::new()
+            },
             move_timestamp: chrono::Utc::now().timestamp() as u64,
         };
 
@@ -387,26 +404,31 @@ impl TestSuiteCore {
 
         sleep(Duration::from_millis(500)).await;
 
-        info!(
-            "📡 Phase 8c: Publishing authoritative KIND 21005 Loot Distribution event"
-        );
-
-        // Create and publish loot distribution event (the ONLY event the Game Engine creates)
-        let _loot_distribution = crate::matches::LootDistribution {
-            game_engine_npub: "game_engine_test_npub".to_string(),
-            match_event_id: match_id.to_string(),
-            winner_npub: winner_npub.to_string(),
-            loot_cashu_token: loot_tokens.first().map(|t| t.c_value.clone()).unwrap_or_default(),
-            match_fee: system_fee,
-            loot_issued_at: chrono::Utc::now().timestamp() as u64,
-            validation_summary: crate::validation::ValidationSummary::success(),
+        info!("📡 Phase 8c: Waiting for the authoritative KIND 21005 Loot Distribution event");
+
+        // The award only counts if the pinned engine signed it
+        let outcome = wait_for_match_outcome(
+            &self.nostr_client,
+            &self.pinned_engine,
+            match_id,
+            Duration::from_secs(30),
+        )
+        .await?;
+        let MatchOutcome::Loot(award) = outcome else {
+            return Err(anyhow!(
+                "Engine invalidated match {match_id} instead of paying out"
+            ));
         };
-
-        // In a real implementation, this would be published by the Game Engine's Nostr keys
+        if award.winner_npub.as_deref() != Some(winner_npub) {
+            return Err(anyhow!(
+                "Engine paid {:?} for match {match_id}, expected {winner_npub}",
+                award.winner_npub
+            ));
+        }
         info!(
-            "📡 Publishing KIND 21005 Loot Distribution - the ONLY authoritative Game Engine event"
+            "🏆 Loot distribution complete: {} loot issued to winner by the pinned engine",
+            award.loot_amount
         );
-        info!("🏆 Loot distribution complete: {} loot tokens issued to winner", loot_amount);
         info!("✅ Zero-coordination gaming cycle complete with real token operations!");
 
         Ok(())
@@ -0,0 +1,158 @@
+use anyhow::{anyhow, Result};
+use nostr::{Event, Kind, PublicKey};
+use tracing::{debug, warn};
+
+/// Event kinds that only the game engine is allowed to publish
+pub const ENGINE_EVENT_KINDS: [Kind; 7] = [
+    Kind::Custom(21005), // Loot distribution
+    Kind::Custom(21006), // Match invalidation
+    Kind::Custom(21011), // Cheat evidence
+    Kind::Custom(31010), // Round summary
+    Kind::Custom(31011), // Engine ruleset
+    Kind::Custom(31012), // Leaderboard
+    Kind::Custom(31099), // Engine heartbeat
+];
+
+/// Public key of the deterministic local engine key (secret key 0x...02 in game-engine.toml)
+pub const LOCAL_ENGINE_PUBKEY: &str =
+    "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+
+/// Deployment profiles with a known engine identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeploymentProfile {
+    /// Local development stack started by the orchestrator or integration runner
+    Local,
+    /// Any other deployment - engine key and mint URL supplied by the operator
+    Custom {
+        engine_pubkey: String,
+        mint_url: String,
+    },
+}
+
+/// Engine identity pinned by a player client
+///
+/// Clients must only trust payouts and invalidations signed by the engine they
+/// expect for the deployment they are playing on. A malicious engine answering
+/// a match could otherwise trick a player into accepting a fake loot token.
+#[derive(Debug, Clone)]
+pub struct PinnedEngine {
+    pub profile: DeploymentProfile,
+    pub engine_pubkey: PublicKey,
+    pub mint_url: String,
+}
+
+impl PinnedEngine {
+    /// Pin the engine identity for a deployment profile
+    pub fn for_profile(profile: DeploymentProfile) -> Result<Self> {
+        let (engine_pubkey, mint_url) = match &profile {
+            DeploymentProfile::Local => (
+                LOCAL_ENGINE_PUBKEY.to_string(),
+                "http://localhost:3333".to_string(),
+            ),
+            DeploymentProfile::Custom {
+                engine_pubkey,
+                mint_url,
+            } => (engine_pubkey.clone(), mint_url.clone()),
+        };
+
+        let engine_pubkey = PublicKey::parse(&engine_pubkey)
+            .map_err(|e| anyhow!("Invalid pinned engine pubkey: {e}"))?;
+
+        Ok(Self {
+            profile,
+            engine_pubkey,
+            mint_url: normalize_url(&mint_url),
+        })
+    }
+
+    /// Check whether an event kind is reserved for the game engine
+    pub fn is_engine_kind(kind: Kind) -> bool {
+        ENGINE_EVENT_KINDS.contains(&kind)
+    }
+
+    /// Verify an engine-published event against the pinned identity
+    ///
+    /// Rejects events with an invalid signature and events signed by any key
+    /// other than the pinned engine, logging a loud warning for the latter.
+    pub fn verify_engine_event(&self, event: &Event) -> Result<()> {
+        if !Self::is_engine_kind(event.kind) {
+            return Err(anyhow!(
+                "Event kind {} is not an engine-published kind",
+                event.kind
+            ));
+        }
+
+        event
+            .verify()
+            .map_err(|e| anyhow!("Engine event {} has an invalid signature: {e}", event.id))?;
+
+        if event.pubkey != self.engine_pubkey {
+            warn!(
+                "🚨 PHISHING WARNING: unknown engine {} published kind {} event {} (pinned engine: {})",
+                event.pubkey, event.kind, event.id, self.engine_pubkey
+            );
+            return Err(anyhow!(
+                "Event {} was signed by unknown engine {}",
+                event.id,
+                event.pubkey
+            ));
+        }
+
+        debug!("✅ Engine event {} verified against pinned key", event.id);
+        Ok(())
+    }
+
+    /// Verify that a mint URL referenced by the engine is the pinned mint
+    pub fn verify_mint_url(&self, mint_url: &str) -> Result<()> {
+        if normalize_url(mint_url) != self.mint_url {
+            warn!(
+                "🚨 PHISHING WARNING: engine referenced unexpected mint {} (pinned mint: {})",
+                mint_url, self.mint_url
+            );
+            return Err(anyhow!("Unexpected mint URL: {mint_url}"));
+        }
+        Ok(())
+    }
+}
+
+fn normalize_url(url: &str) -> String {
+    url.trim_end_matches('/').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nostr::{EventBuilder, Keys};
+
+    fn engine_keys() -> Keys {
+        Keys::parse("0000000000000000000000000000000000000000000000000000000000000002").unwrap()
+    }
+
+    #[test]
+    fn test_local_profile_matches_engine_key() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+        assert_eq!(pinned.engine_pubkey, engine_keys().public_key());
+    }
+
+    #[test]
+    fn test_rejects_unknown_engine() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+
+        let genuine = EventBuilder::new(Kind::Custom(21005), "{}", vec![])
+            .to_event(&engine_keys())
+            .unwrap();
+        assert!(pinned.verify_engine_event(&genuine).is_ok());
+
+        let impostor = EventBuilder::new(Kind::Custom(21005), "{}", vec![])
+            .to_event(&Keys::generate())
+            .unwrap();
+        assert!(pinned.verify_engine_event(&impostor).is_err());
+    }
+
+    #[test]
+    fn test_mint_url_pinning() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+        assert!(pinned.verify_mint_url("http://localhost:3333/").is_ok());
+        assert!(pinned.verify_mint_url("http://evil-mint:3333").is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{anyhow, Result};
+use nostr::{Event, EventId, Filter, Kind};
+use nostr_sdk::{Client, EventSource};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+use tracing::{info, warn};
+
+use crate::engine_identity::PinnedEngine;
+
+/// Kind of the engine's loot distribution event
+pub const KIND_LOOT_DISTRIBUTION: Kind = Kind::Custom(21005);
+
+/// Kind of the engine's match invalidation event
+pub const KIND_MATCH_INVALIDATION: Kind = Kind::Custom(21006);
+
+/// Loot award as published by the engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootAward {
+    pub game_engine_npub: String,
+    pub match_event_id: String,
+    pub winner_npub: Option<String>, // None for draws
+    pub loot_cashu_token: Option<String>,
+    #[serde(default)]
+    pub loot_amount: u64,
+    pub match_fee: u64,
+    pub loot_issued_at: u64,
+}
+
+/// Match invalidation as published by the engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchInvalidation {
+    pub game_engine_npub: String,
+    pub match_event_id: String,
+    pub reason: String,
+    pub offending_npub: Option<String>,
+    pub invalidated_at: u64,
+}
+
+/// The engine's final word on a match
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchOutcome {
+    Loot(LootAward),
+    Invalidated(MatchInvalidation),
+}
+
+impl MatchOutcome {
+    /// Parse a loot or invalidation event, accepting it only from the pinned engine
+    pub fn from_event(event: &Event, pinned_engine: &PinnedEngine) -> Result<Self> {
+        pinned_engine.verify_engine_event(event)?;
+
+        match event.kind {
+            kind if kind == KIND_LOOT_DISTRIBUTION => serde_json::from_str(&event.content)
+                .map(MatchOutcome::Loot)
+                .map_err(|e| anyhow!("Failed to parse loot distribution: {e}")),
+            kind if kind == KIND_MATCH_INVALIDATION => serde_json::from_str(&event.content)
+                .map(MatchOutcome::Invalidated)
+                .map_err(|e| anyhow!("Failed to parse match invalidation: {e}")),
+            kind => Err(anyhow!("Event kind {kind} is not a match outcome")),
+        }
+    }
+}
+
+/// Wait for the pinned engine to pay out or invalidate a match
+///
+/// Outcomes are fetched from any author, so an impostor's event is seen and
+/// rejected rather than silently filtered out by the relay.
+pub async fn wait_for_match_outcome(
+    client: &Client,
+    pinned_engine: &PinnedEngine,
+    match_event_id: &str,
+    timeout: Duration,
+) -> Result<MatchOutcome> {
+    let filter = Filter::new()
+        .kinds([KIND_LOOT_DISTRIBUTION, KIND_MATCH_INVALIDATION])
+        .event(EventId::from_hex(match_event_id)?);
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let events = client
+            .get_events_of(
+                vec![filter.clone()],
+                EventSource::relays(Some(Duration::from_secs(5))),
+            )
+            .await?;
+
+        for event in events.iter() {
+            match MatchOutcome::from_event(event, pinned_engine) {
+                Ok(outcome) => {
+                    info!("📨 Engine outcome for match {} verified", match_event_id);
+                    return Ok(outcome);
+                }
+                Err(e) => warn!("⚠️ Ignoring outcome event {}: {}", event.id, e),
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(anyhow!(
+                "Pinned engine published no outcome for match {match_event_id}"
+            ));
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_identity::DeploymentProfile;
+    use nostr::{EventBuilder, Keys};
+
+    fn engine_keys() -> Keys {
+        Keys::parse("0000000000000000000000000000000000000000000000000000000000000002").unwrap()
+    }
+
+    fn loot_event(keys: &Keys) -> Event {
+        let award = LootAward {
+            game_engine_npub: keys.public_key().to_string(),
+            match_event_id: "challenge_event_id".to_string(),
+            winner_npub: Some("npub1alice".to_string()),
+            loot_cashu_token: Some("cashuAloot".to_string()),
+            loot_amount: 100,
+            match_fee: 10,
+            loot_issued_at: 1690000500,
+        };
+        EventBuilder::new(
+            KIND_LOOT_DISTRIBUTION,
+            serde_json::to_string(&award).unwrap(),
+            vec![],
+        )
+        .to_event(keys)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_outcomes_are_only_accepted_from_the_pinned_engine() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+
+        let outcome = MatchOutcome::from_event(&loot_event(&engine_keys()), &pinned).unwrap();
+        let MatchOutcome::Loot(award) = outcome else {
+            panic!("expected a loot award");
+        };
+        assert_eq!(award.winner_npub.as_deref(), Some("npub1alice"));
+        assert_eq!(award.loot_amount, 100);
+
+        assert!(MatchOutcome::from_event(&loot_event(&Keys::generate()), &pinned).is_err());
+
+        let invalidation = |keys: &Keys| {
+            let invalidation = MatchInvalidation {
+                game_engine_npub: keys.public_key().to_string(),
+                match_event_id: "challenge_event_id".to_string(),
+                reason: "Token commitment mismatch".to_string(),
+                offending_npub: Some("npub1bob".to_string()),
+                invalidated_at: 1690000450,
+            };
+            EventBuilder::new(
+                KIND_MATCH_INVALIDATION,
+                serde_json::to_string(&invalidation).unwrap(),
+                vec![],
+            )
+            .to_event(keys)
+            .unwrap()
+        };
+        assert!(matches!(
+            MatchOutcome::from_event(&invalidation(&engine_keys()), &pinned),
+            Ok(MatchOutcome::Invalidated(_))
+        ));
+        assert!(MatchOutcome::from_event(&invalidation(&Keys::generate()), &pinned).is_err());
+    }
+}
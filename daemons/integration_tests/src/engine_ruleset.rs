@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Result};
+use nostr::{Event, Filter, Kind};
+use nostr_sdk::{Client, EventSource};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::engine_identity::PinnedEngine;
+
+/// Kind of the engine-published ruleset event (parameterized replaceable)
+pub const KIND_ENGINE_RULESET: Kind = Kind::Custom(31011);
+
+/// Highest protocol version this client understands
+pub const SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// Machine-readable ruleset published by the game engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineRuleset {
+    pub game_engine_npub: String,
+    pub protocol_version: u32,
+    pub event_kinds: BTreeMap<String, u16>,
+    pub league_registry_hash: String,
+    #[serde(default)]
+    pub class_matrix_version: u32,
+    #[serde(default)]
+    pub rules_hash: String,
+    pub fee_schedule: FeeSchedule,
+    pub timeouts: TimeoutParameters,
+    pub published_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeSchedule {
+    pub match_fee_percent: u64,
+    pub loot_reward_per_match: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeoutParameters {
+    pub round_timeout_seconds: u64,
+    pub match_timeout_seconds: u64,
+}
+
+impl EngineRuleset {
+    /// Parse a ruleset event, accepting it only from the pinned engine
+    pub fn from_event(event: &Event, pinned_engine: &PinnedEngine) -> Result<Self> {
+        if event.kind != KIND_ENGINE_RULESET {
+            return Err(anyhow!(
+                "Event kind {} is not an engine ruleset",
+                event.kind
+            ));
+        }
+        pinned_engine.verify_engine_event(event)?;
+
+        let ruleset: EngineRuleset = serde_json::from_str(&event.content)
+            .map_err(|e| anyhow!("Failed to parse engine ruleset: {e}"))?;
+
+        if ruleset.protocol_version > SUPPORTED_PROTOCOL_VERSION {
+            return Err(anyhow!(
+                "Engine speaks protocol v{}, client supports up to v{}",
+                ruleset.protocol_version,
+                SUPPORTED_PROTOCOL_VERSION
+            ));
+        }
+
+        Ok(ruleset)
+    }
+
+    /// Nostr kind the engine uses for a named protocol event
+    pub fn kind(&self, event_name: &str) -> Option<Kind> {
+        self.event_kinds.get(event_name).copied().map(Kind::from)
+    }
+
+    /// Check the engine's league registry against the local shared game logic
+    pub fn league_registry_matches_local(&self) -> bool {
+        let leagues = shared_game_logic::league::get_all_league_modifiers();
+        let local_hash =
+            shared_game_logic::commitment::hash_data(&serde_json::to_string(&leagues).unwrap());
+
+        if local_hash != self.league_registry_hash {
+            warn!(
+                "⚠️ League registry drift: engine {} vs local {}",
+                self.league_registry_hash, local_hash
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Check the engine resolves class matchups with the same matrix as the local logic
+    pub fn class_matrix_matches_local(&self) -> bool {
+        let local_version = shared_game_logic::classes::active_class_matrix().version;
+        if local_version != self.class_matrix_version {
+            warn!(
+                "⚠️ Class matrix drift: engine v{} vs local v{}",
+                self.class_matrix_version, local_version
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Check the engine was built against the same compiled rules as the local logic
+    pub fn rules_match_local(&self) -> bool {
+        let local_hash = shared_game_logic::rules_hash();
+        if local_hash != self.rules_hash {
+            warn!(
+                "⚠️ Rules drift: engine {} vs local {}",
+                self.rules_hash, local_hash
+            );
+            return false;
+        }
+        true
+    }
+}
+
+/// Fetch the pinned engine's current ruleset from the relay
+pub async fn fetch_engine_ruleset(
+    client: &Client,
+    pinned_engine: &PinnedEngine,
+) -> Result<EngineRuleset> {
+    let filter = Filter::new()
+        .kind(KIND_ENGINE_RULESET)
+        .author(pinned_engine.engine_pubkey)
+        .limit(1);
+
+    let events = client
+        .get_events_of(
+            vec![filter],
+            EventSource::relays(Some(Duration::from_secs(5))),
+        )
+        .await?;
+
+    let event = events
+        .iter()
+        .max_by_key(|event| event.created_at)
+        .ok_or_else(|| anyhow!("Engine has not published a ruleset"))?;
+
+    let ruleset = EngineRuleset::from_event(event, pinned_engine)?;
+    info!(
+        "📜 Configured against engine ruleset v{} ({} event kinds)",
+        ruleset.protocol_version,
+        ruleset.event_kinds.len()
+    );
+    Ok(ruleset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_identity::DeploymentProfile;
+    use nostr::{EventBuilder, Keys, Tag};
+
+    fn ruleset_event(keys: &Keys, protocol_version: u32) -> Event {
+        let leagues = shared_game_logic::league::get_all_league_modifiers();
+        let ruleset = EngineRuleset {
+            game_engine_npub: keys.public_key().to_string(),
+            protocol_version,
+            event_kinds: BTreeMap::from([("match_challenge".to_string(), 21000)]),
+            league_registry_hash: shared_game_logic::commitment::hash_data(
+                &serde_json::to_string(&leagues).unwrap(),
+            ),
+            class_matrix_version: shared_game_logic::classes::CLASS_MATRIX_VERSION,
+            rules_hash: shared_game_logic::rules_hash().to_string(),
+            fee_schedule: FeeSchedule {
+                match_fee_percent: 5,
+                loot_reward_per_match: 100,
+            },
+            timeouts: TimeoutParameters {
+                round_timeout_seconds: 30,
+                match_timeout_seconds: 300,
+            },
+            published_at: 1690000000,
+        };
+
+        EventBuilder::new(
+            KIND_ENGINE_RULESET,
+            serde_json::to_string(&ruleset).unwrap(),
+            vec![Tag::identifier("manastr-ruleset")],
+        )
+        .to_event(keys)
+        .unwrap()
+    }
+
+    fn engine_keys() -> Keys {
+        Keys::parse("0000000000000000000000000000000000000000000000000000000000000002").unwrap()
+    }
+
+    #[test]
+    fn test_parses_pinned_engine_ruleset() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+        let ruleset =
+            EngineRuleset::from_event(&ruleset_event(&engine_keys(), 1), &pinned).unwrap();
+
+        assert_eq!(ruleset.kind("match_challenge"), Some(Kind::Custom(21000)));
+        assert!(ruleset.league_registry_matches_local());
+        assert!(ruleset.class_matrix_matches_local());
+        assert!(ruleset.rules_match_local());
+    }
+
+    #[test]
+    fn test_rejects_unknown_engine_and_newer_protocol() {
+        let pinned = PinnedEngine::for_profile(DeploymentProfile::Local).unwrap();
+
+        assert!(EngineRuleset::from_event(&ruleset_event(&Keys::generate(), 1), &pinned).is_err());
+        assert!(EngineRuleset::from_event(&ruleset_event(&engine_keys(), 2), &pinned).is_err());
+    }
+}
@@ -4,6 +4,9 @@
 // truly decentralized multiplayer gaming system.
 
 pub mod core;
+pub mod engine_identity;
+pub mod engine_outcome;
+pub mod engine_ruleset;
 pub mod gaming_auth_test;
 pub mod matches;
 pub mod players;
@@ -11,4 +14,7 @@ pub mod test_suite;
 pub mod utils;
 pub mod validation;
 
+pub use engine_identity::{DeploymentProfile, PinnedEngine};
+pub use engine_outcome::{wait_for_match_outcome, MatchOutcome};
+pub use engine_ruleset::{fetch_engine_ruleset, EngineRuleset};
 pub use test_suite::PlayerDrivenTestSuite;
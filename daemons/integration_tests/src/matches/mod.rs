@@ -11,6 +11,8 @@ pub struct MatchChallenge {
     pub expires_at: u64,
     pub created_at: u64,
     pub match_event_id: String,
+    #[serde(default)]
+    pub practice: bool,
 }
 
 /// Represents acceptance of a match challenge
@@ -1,21 +1,31 @@
 use anyhow::{Context, Result};
-use axum::Router;
-use clap::Parser;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
-    path::PathBuf,
-    process::{Child, Command, Stdio},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, IsTerminal, Read, Write},
+    path::{Path as FsPath, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::{
-    net::TcpListener,
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
     signal,
     sync::Mutex,
-    time::sleep,
+    time::{sleep, timeout},
 };
 use tower_http::{cors::CorsLayer, services::ServeDir, trace::TraceLayer};
 use tracing::{error, info, warn};
@@ -32,6 +42,10 @@ struct Args {
     #[arg(long)]
     skip_build: bool,
 
+    /// Rebuild every step, even those whose inputs are unchanged
+    #[arg(long)]
+    rebuild: bool,
+
     /// Run backend services only (no web server)
     #[arg(long)]
     backend_only: bool,
@@ -39,32 +53,762 @@ struct Args {
     /// Enable verbose logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Times a crashed service is restarted in a row before it is left down;
+    /// a service up for five minutes starts counting afresh
+    #[arg(long, default_value = "5")]
+    max_restarts: u32,
+
+    /// Environment to bring up: binaries, mint backend and relays
+    #[arg(long, value_enum, default_value = "test")]
+    profile: Profile,
+
+    /// Run the services in docker containers from a generated docker-compose.yml
+    /// (backend only, as the web client is not built in the container)
+    #[arg(long)]
+    containers: bool,
+
+    /// Port for the orchestrator control API, served on localhost only
+    #[arg(long, default_value = "8081")]
+    control_port: u16,
 }
 
-#[derive(Debug)]
+/// Named environment selecting the binaries and config bundle the stack runs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Profile {
+    /// Debug binaries, fake-wallet mint, local relay
+    Dev,
+    /// Release binaries, fake-wallet mint, local relay
+    Test,
+    /// Release binaries, mint paying through a regtest LND node, local relay
+    Regtest,
+    /// Release binaries, mainnet mint, public relays
+    Prod,
+}
+
+impl Profile {
+    fn release(self) -> bool {
+        self != Profile::Dev
+    }
+
+    /// Cargo output directory of the profile's binaries, relative to a crate root
+    fn target_dir(self) -> &'static str {
+        if self.release() {
+            "target/release"
+        } else {
+            "target/debug"
+        }
+    }
+
+    fn mint_config(self) -> &'static str {
+        match self {
+            Profile::Dev | Profile::Test => "daemons/config/cdk-mintd-deterministic.toml",
+            Profile::Regtest => "daemons/config/profiles/regtest/cdk-mintd.toml",
+            Profile::Prod => "daemons/config/profiles/prod/cdk-mintd.toml",
+        }
+    }
+
+    fn engine_config(self) -> &'static str {
+        match self {
+            Profile::Prod => "daemons/config/profiles/prod/game-engine.toml",
+            _ => "daemons/game-engine-bot/game-engine.toml",
+        }
+    }
+
+    /// Whether the stack runs its own relay rather than using public ones
+    fn local_relay(self) -> bool {
+        self != Profile::Prod
+    }
+}
+
+#[derive(Debug, Clone)]
 struct ServiceConfig {
     name: String,
     command: String,
     args: Vec<String>,
     working_dir: PathBuf,
-    health_check_url: Option<String>,
+    readiness_probe: Option<ReadinessProbe>,
     health_check_timeout: Duration,
+    depends_on: Vec<String>, // Services that must be ready before this one starts
+}
+
+/// How to tell that a started service accepts connections
+#[derive(Debug, Clone)]
+enum ReadinessProbe {
+    /// GET returns a success status
+    Http(String),
+    /// A WebSocket upgrade is accepted, e.g. by the Nostr relay
+    WebSocket(String),
+}
+
+impl ReadinessProbe {
+    /// Shell command a compose healthcheck runs inside the service's container
+    fn healthcheck_command(&self) -> String {
+        match self {
+            ReadinessProbe::Http(url) => format!("curl -fsS -o /dev/null {url}"),
+            ReadinessProbe::WebSocket(url) => format!(
+                "curl -s -o /dev/null -m 2 -w '%{{http_code}}' -H 'Connection: Upgrade' \
+                 -H 'Upgrade: websocket' -H 'Sec-WebSocket-Version: 13' \
+                 -H 'Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==' {} | grep -q 101",
+                url.replacen("ws://", "http://", 1)
+            ),
+        }
+    }
+}
+
+/// Compose project the `--containers` stack runs under
+const COMPOSE_PROJECT: &str = "manastr";
+/// Where the repository is mounted inside the containers
+const CONTAINER_ROOT: &str = "/manastr";
+/// Toolchain image the binaries are built and run in
+const CONTAINER_IMAGE: &str = "rust:1-bookworm";
+
+/// docker-compose.yml generated from the service configs for `--containers`
+#[derive(Debug, Serialize)]
+struct ComposeFile {
+    name: String,
+    services: BTreeMap<String, ComposeService>,
+    volumes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeService {
+    image: String,
+    working_dir: String,
+    command: Vec<String>,
+    volumes: Vec<String>,
+    network_mode: String, // Host networking keeps the services' localhost URLs working
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    depends_on: BTreeMap<String, ComposeDependency>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    healthcheck: Option<ComposeHealthcheck>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeDependency {
+    condition: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct ComposeHealthcheck {
+    test: Vec<String>,
+    interval: String,
+    retries: u64,
+}
+
+/// Services ordered so each one comes after everything it depends on
+fn startup_order(configs: &[ServiceConfig]) -> Result<Vec<&ServiceConfig>> {
+    for config in configs {
+        if let Some(missing) = config
+            .depends_on
+            .iter()
+            .find(|dependency| !configs.iter().any(|c| &c.name == *dependency))
+        {
+            return Err(anyhow::anyhow!(
+                "Service {} depends on unknown service {}",
+                config.name,
+                missing
+            ));
+        }
+    }
+
+    let mut ordered: Vec<&ServiceConfig> = Vec::with_capacity(configs.len());
+    while ordered.len() < configs.len() {
+        let ready = configs.iter().find(|config| {
+            !ordered.iter().any(|started| started.name == config.name)
+                && config
+                    .depends_on
+                    .iter()
+                    .all(|dependency| ordered.iter().any(|started| &started.name == dependency))
+        });
+        match ready {
+            Some(config) => ordered.push(config),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Service dependencies form a cycle among: {}",
+                    configs
+                        .iter()
+                        .filter(|config| !ordered.iter().any(|started| started.name == config.name))
+                        .map(|config| config.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+        }
+    }
+    Ok(ordered)
+}
+
+/// Whether the server at a ws:// URL answers a WebSocket upgrade
+/// Wait for a just-spawned service to become ready
+///
+/// Takes only the shutdown flag, so callers can probe without holding the service manager.
+async fn wait_for_service(config: &ServiceConfig, running: &AtomicBool) -> Result<()> {
+    if let Some(probe) = &config.readiness_probe {
+        wait_until_ready(probe, config.health_check_timeout, running)
+            .await
+            .with_context(|| format!("Service {} never became ready", config.name))?;
+    } else {
+        // Just wait a bit for services without health checks
+        sleep(Duration::from_secs(2)).await;
+    }
+
+    info!("✅ Service ready: {}", config.name);
+    Ok(())
+}
+
+async fn wait_until_ready(
+    probe: &ReadinessProbe,
+    timeout_duration: Duration,
+    running: &AtomicBool,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let start_time = std::time::Instant::now();
+
+    while start_time.elapsed() < timeout_duration && running.load(Ordering::Relaxed) {
+        let ready = match probe {
+            ReadinessProbe::Http(url) => client
+                .get(url)
+                .send()
+                .await
+                .is_ok_and(|response| response.status().is_success()),
+            ReadinessProbe::WebSocket(url) => websocket_ready(url).await,
+        };
+        if ready {
+            return Ok(());
+        }
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    Err(anyhow::anyhow!("Readiness probe failed: {:?}", probe))
+}
+
+async fn websocket_ready(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("ws://") else {
+        return false;
+    };
+    let (authority, path) = match rest.split_once('/') {
+        Some((authority, path)) => (authority, format!("/{path}")),
+        None => (rest, "/".to_string()),
+    };
+
+    let handshake = async {
+        let mut stream = TcpStream::connect(authority).await?;
+        let request = format!(
+            "GET {path} HTTP/1.1\r\nHost: {authority}\r\nUpgrade: websocket\r\n\
+             Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+             Sec-WebSocket-Version: 13\r\n\r\n"
+        );
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = [0u8; 32];
+        let read = stream.read(&mut response).await?;
+        Ok::<_, std::io::Error>(response[..read].starts_with(b"HTTP/1.1 101"))
+    };
+    matches!(timeout(Duration::from_secs(2), handshake).await, Ok(Ok(true)))
+}
+
+/// One step of the build pipeline
+#[derive(Debug, Clone)]
+struct BuildStep {
+    name: &'static str,
+    program: &'static str,
+    args: Vec<String>,
+    dir: PathBuf,                  // Relative to the project root, like the paths below
+    inputs: Vec<PathBuf>,          // Files and directories whose contents decide a rebuild
+    output: PathBuf,               // Rebuilt when missing, even with unchanged inputs
+    depends_on: Vec<&'static str>, // Steps that must have built before this one starts
+}
+
+/// Directories never hashed when walking inputs outside git: build outputs,
+/// installed packages and data the services write at runtime
+const UNHASHED_DIRS: [&str; 7] = [
+    ".git",
+    "data",
+    "dist",
+    "logs",
+    "node_modules",
+    "pkg",
+    "target",
+];
+
+/// Source files under an input: those git tracks or would track, ignored files left out
+///
+/// Submodules are listed by their own repository. Returns None outside a git checkout.
+fn tracked_input_files(path: &FsPath) -> Option<Vec<PathBuf>> {
+    let (dir, pathspec) = if path.is_dir() {
+        (path, std::ffi::OsStr::new("."))
+    } else {
+        (path.parent()?, path.file_name()?)
+    };
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+            "--",
+        ])
+        .arg(pathspec)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut files = Vec::new();
+    let entries = output.stdout.split(|&byte| byte == 0);
+    for entry in entries.filter(|entry| !entry.is_empty()) {
+        let file = dir.join(String::from_utf8_lossy(entry).as_ref());
+        if file.is_dir() {
+            files.extend(tracked_input_files(&file).unwrap_or_default());
+        } else if file.is_file() {
+            files.push(file);
+        }
+    }
+    Some(files)
+}
+
+fn collect_input_files(path: &FsPath, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    match tracked_input_files(path) {
+        Some(tracked) => files.extend(tracked),
+        None => walk_input_files(path, files)?,
+    }
+    Ok(())
+}
+
+fn walk_input_files(path: &FsPath, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if !UNHASHED_DIRS.iter().any(|dir| entry.file_name() == *dir) {
+                walk_input_files(&entry.path(), files)?;
+            }
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Content hash of a step's inputs, missing inputs counting as empty
+fn hash_inputs(root: &FsPath, step: &BuildStep) -> std::io::Result<String> {
+    let mut files = Vec::new();
+    for input in &step.inputs {
+        collect_input_files(&root.join(input), &mut files)?;
+    }
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(step.program.as_bytes());
+    hasher.update(step.args.join(" ").as_bytes());
+    for file in files {
+        let contents = fs::read(&file)?;
+        let relative = file.strip_prefix(root).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Input hashes of the steps as of their last successful build
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuildCache {
+    steps: HashMap<String, String>,
+}
+
+impl BuildCache {
+    /// The saved cache, or an empty one when there is none or it is unreadable
+    fn load(path: &FsPath) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &FsPath) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write build cache {}", path.display()))
+    }
+}
+
+/// Run a step unless its inputs match its last build, returning the input hash and
+/// whether it ran
+fn run_build_step(
+    root: &FsPath,
+    step: &BuildStep,
+    cached: Option<&str>,
+    log: ServiceLog,
+) -> Result<(String, bool)> {
+    let hash = hash_inputs(root, step)
+        .with_context(|| format!("Failed to hash the inputs of {}", step.name))?;
+    if cached == Some(hash.as_str()) && root.join(&step.output).exists() {
+        return Ok((hash, false));
+    }
+
+    let mut child = Command::new(step.program)
+        .args(&step.args)
+        .current_dir(root.join(&step.dir))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {} for {}", step.program, step.name))?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let readers = [
+        stdout.map(|stdout| capture_output(stdout, log.clone())),
+        stderr.map(|stderr| capture_output(stderr, log)),
+    ];
+    let status = child.wait()?;
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+
+    if !status.success() {
+        return Err(anyhow::anyhow!("{} failed ({})", step.name, status));
+    }
+    Ok((hash, true))
+}
+
+/// How the supervisor restarts services that exit on their own
+#[derive(Debug, Clone)]
+struct RestartPolicy {
+    max_restarts: u32,
+    base_backoff: Duration, // Doubled per restart of the same service
+    max_backoff: Duration,
+    stable_uptime: Duration, // A service up this long starts counting restarts afresh
+}
+
+impl RestartPolicy {
+    /// Delay before the given restart of a service, counting from 1
+    fn backoff(&self, restart: u32) -> Duration {
+        let factor = 2u32.saturating_pow(restart.saturating_sub(1));
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Most recent output lines of a service, stdout and stderr interleaved
+type LogBuffer = Arc<std::sync::Mutex<VecDeque<String>>>;
+
+const LOG_LINES_KEPT: usize = 1000;
+const LOG_FILE_MAX_BYTES: u64 = 10 * 1024 * 1024;
+const LOG_FILES_KEPT: u32 = 5;
+
+/// Prefix colors handed out to services in the order they first start
+const SERVICE_COLORS: [&str; 5] = ["\x1b[36m", "\x1b[35m", "\x1b[33m", "\x1b[32m", "\x1b[34m"];
+
+/// Append-only log file, renamed to `<name>.1`, `<name>.2`, ... once it grows too large
+struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    files_kept: u32, // Rotated files kept besides the current one
+}
+
+impl RotatingLog {
+    fn open(path: PathBuf, max_bytes: u64, files_kept: u32) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            files_kept,
+        })
+    }
+
+    fn rotated(&self, index: u32) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        for index in (1..self.files_kept).rev() {
+            let from = self.rotated(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated(index + 1))?;
+            }
+        }
+        if self.files_kept > 0 {
+            fs::rename(&self.path, self.rotated(1))?;
+        }
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+/// Where a service's output goes: its line buffer, its log file and the orchestrator's stdout
+#[derive(Clone)]
+struct ServiceLog {
+    name: String,
+    color: &'static str,
+    buffer: LogBuffer,
+    file: Option<Arc<std::sync::Mutex<RotatingLog>>>,
+}
+
+impl ServiceLog {
+    fn write(&self, line: &str) {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ");
+        let tagged = format!("{timestamp} [{}] {line}", self.name);
+
+        if let Some(file) = &self.file {
+            if let Err(e) = file.lock().unwrap().write_line(&tagged) {
+                warn!("Failed to write log file for {}: {}", self.name, e);
+            }
+        }
+
+        let stdout = std::io::stdout();
+        let prefix = format!("[{}]", self.name);
+        if stdout.is_terminal() {
+            println!("{}{prefix:<14}\x1b[0m {line}", self.color);
+        } else {
+            println!("{prefix:<14} {line}");
+        }
+
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() == LOG_LINES_KEPT {
+            buffer.pop_front();
+        }
+        buffer.push_back(tagged);
+    }
+}
+
+/// Copy a child's output line by line into its service log
+fn capture_output(
+    reader: impl Read + Send + 'static,
+    log: ServiceLog,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(|line| line.ok()) {
+            log.write(&line);
+        }
+    })
+}
+
+/// Service state reported by `/orchestrator/status`
+#[derive(Debug, Serialize)]
+struct ServiceStatus {
+    name: String,
+    running: bool,
+    pid: Option<u32>,
+    restarts: usize,
+}
+
+/// One unexpected exit of a supervised service
+#[derive(Debug)]
+struct RestartRecord {
+    exited_at: SystemTime,
+    exit_status: String,
 }
 
 struct ServiceManager {
     services: HashMap<String, Child>,
+    configs: HashMap<String, ServiceConfig>, // Kept to restart services that exit
+    restart_history: HashMap<String, Vec<RestartRecord>>,
+    crash_streaks: HashMap<String, u32>, // Restarts since the service last ran stably
+    started_at: HashMap<String, Instant>,
+    restart_policy: RestartPolicy,
+    logs: HashMap<String, ServiceLog>, // Kept across restarts of the service
+    log_dir: Option<PathBuf>,          // Rotating `<service>.log` files are written here
     running: Arc<AtomicBool>,
 }
 
 impl ServiceManager {
-    fn new() -> Self {
+    fn new(restart_policy: RestartPolicy) -> Self {
         Self {
             services: HashMap::new(),
+            configs: HashMap::new(),
+            restart_history: HashMap::new(),
+            crash_streaks: HashMap::new(),
+            started_at: HashMap::new(),
+            restart_policy,
+            logs: HashMap::new(),
+            log_dir: None,
             running: Arc::new(AtomicBool::new(true)),
         }
     }
 
+    /// Also write each service's output to rotating files in the directory
+    fn with_log_dir(mut self, log_dir: PathBuf) -> Self {
+        self.log_dir = Some(log_dir);
+        self
+    }
+
+    /// The service's log, opening its file the first time the service starts
+    fn service_log(&mut self, name: &str) -> ServiceLog {
+        let color = SERVICE_COLORS[self.logs.len() % SERVICE_COLORS.len()];
+        let log_dir = self.log_dir.clone();
+        self.logs
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let file = log_dir.and_then(|dir| {
+                    let path = dir.join(format!("{name}.log"));
+                    match RotatingLog::open(path, LOG_FILE_MAX_BYTES, LOG_FILES_KEPT) {
+                        Ok(file) => Some(Arc::new(std::sync::Mutex::new(file))),
+                        Err(e) => {
+                            warn!("Failed to open log file for {}: {}", name, e);
+                            None
+                        }
+                    }
+                });
+                ServiceLog {
+                    name: name.to_string(),
+                    color,
+                    buffer: LogBuffer::default(),
+                    file,
+                }
+            })
+            .clone()
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Services whose process exited since the last check, no longer tracked as running
+    fn reap_exited(&mut self) -> Vec<(String, ExitStatus)> {
+        let exited: Vec<(String, ExitStatus)> = self
+            .services
+            .iter_mut()
+            .filter_map(|(name, child)| match child.try_wait() {
+                Ok(Some(status)) => Some((name.clone(), status)),
+                Ok(None) => None,
+                Err(e) => {
+                    warn!("Failed to check service {}: {}", name, e);
+                    None
+                }
+            })
+            .collect();
+        for (name, _) in &exited {
+            self.services.remove(name);
+        }
+        exited
+    }
+
+    /// Record an unexpected exit, returning the delay before the restart or None
+    /// once the service used up its restarts
+    ///
+    /// A service that stayed up for the policy's stable uptime has its restarts
+    /// counted afresh, so occasional crashes over a long run never exhaust them.
+    fn record_exit(&mut self, name: &str, status: ExitStatus) -> Option<Duration> {
+        self.restart_history
+            .entry(name.to_string())
+            .or_default()
+            .push(RestartRecord {
+                exited_at: SystemTime::now(),
+                exit_status: status.to_string(),
+            });
+
+        let ran_stably = self
+            .started_at
+            .get(name)
+            .is_some_and(|started_at| started_at.elapsed() >= self.restart_policy.stable_uptime);
+        let streak = self.crash_streaks.entry(name.to_string()).or_default();
+        if ran_stably {
+            *streak = 0;
+        }
+        *streak += 1;
+
+        let restart = *streak;
+        if restart > self.restart_policy.max_restarts {
+            error!(
+                "💀 Service {} exited ({}) and used up its {} restarts, leaving it down",
+                name, status, self.restart_policy.max_restarts
+            );
+            return None;
+        }
+
+        let delay = self.restart_policy.backoff(restart);
+        warn!(
+            "💥 Service {} exited ({}), restart {} of {} in {:?}",
+            name, status, restart, self.restart_policy.max_restarts, delay
+        );
+        Some(delay)
+    }
+
+    /// Every configured service, whether or not it is currently up
+    fn status(&self) -> Vec<ServiceStatus> {
+        let mut status: Vec<ServiceStatus> = self
+            .configs
+            .keys()
+            .map(|name| ServiceStatus {
+                name: name.clone(),
+                running: self.services.contains_key(name),
+                pid: self.services.get(name).map(Child::id),
+                restarts: self.restart_history.get(name).map_or(0, Vec::len),
+            })
+            .collect();
+        status.sort_by(|a, b| a.name.cmp(&b.name));
+        status
+    }
+
+    /// The last `lines` output lines of a service, or None for an unknown service
+    fn recent_logs(&self, name: &str, lines: usize) -> Option<Vec<String>> {
+        let buffer = self.logs.get(name)?.buffer.lock().unwrap();
+        Some(buffer.iter().skip(buffer.len().saturating_sub(lines)).cloned().collect())
+    }
+
+    /// Stop a service on request; the supervisor does not count it as a crash
+    fn stop_service(&mut self, name: &str) {
+        if let Some(mut child) = self.services.remove(name) {
+            info!("🛑 Stopping service: {}", name);
+            if let Err(e) = child.kill() {
+                warn!("Failed to kill service {}: {}", name, e);
+            }
+            if let Err(e) = child.wait() {
+                warn!("Error waiting for service {} to exit: {}", name, e);
+            }
+        }
+    }
+
+    /// Log how often each service had to be restarted
+    fn log_restart_history(&self) {
+        for (name, history) in &self.restart_history {
+            if let Some(last) = history.last() {
+                let seconds_ago = last.exited_at.elapsed().unwrap_or_default().as_secs();
+                info!(
+                    "🔁 {} exited {} times, last {}s ago ({})",
+                    name,
+                    history.len(),
+                    seconds_ago,
+                    last.exit_status
+                );
+            }
+        }
+    }
+
     async fn start_service(&mut self, config: &ServiceConfig) -> Result<()> {
+        self.spawn_service(config)?;
+        wait_for_service(config, &self.running).await
+    }
+
+    /// Launch a service's process without waiting for it to become ready
+    fn spawn_service(&mut self, config: &ServiceConfig) -> Result<()> {
         info!("🚀 Starting service: {}", config.name);
         info!("   Command: {}", config.command);
         info!("   Args: {:?}", config.args);
@@ -76,49 +820,28 @@ impl ServiceManager {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let child = cmd
+        let mut child = cmd
             .spawn()
             .with_context(|| format!("Failed to start service: {} (command: {})", config.name, config.command))?;
 
-        self.services.insert(config.name.clone(), child);
-        
-        // Wait for service to be ready
-        if let Some(health_url) = &config.health_check_url {
-            self.wait_for_health_check(health_url, config.health_check_timeout)
-                .await?;
-        } else {
-            // Just wait a bit for services without health checks
-            sleep(Duration::from_secs(2)).await;
+        let log = self.service_log(&config.name);
+        if let Some(stdout) = child.stdout.take() {
+            capture_output(stdout, log.clone());
         }
-
-        info!("✅ Service ready: {}", config.name);
-        Ok(())
-    }
-
-    async fn wait_for_health_check(&self, url: &str, timeout_duration: Duration) -> Result<()> {
-        let client = reqwest::Client::new();
-        let start_time = std::time::Instant::now();
-
-        while start_time.elapsed() < timeout_duration && self.running.load(Ordering::Relaxed) {
-            match client.get(url).send().await {
-                Ok(response) if response.status().is_success() => {
-                    return Ok(());
-                }
-                Ok(_) => {
-                    sleep(Duration::from_millis(500)).await;
-                }
-                Err(_) => {
-                    sleep(Duration::from_millis(500)).await;
-                }
-            }
+        if let Some(stderr) = child.stderr.take() {
+            capture_output(stderr, log);
         }
 
-        Err(anyhow::anyhow!("Health check failed for URL: {}", url))
+        self.services.insert(config.name.clone(), child);
+        self.configs.insert(config.name.clone(), config.clone());
+        self.started_at.insert(config.name.clone(), Instant::now());
+        Ok(())
     }
 
     async fn stop_all_services(&mut self) -> Result<()> {
         info!("🛑 Stopping all services...");
         self.running.store(false, Ordering::Relaxed);
+        self.log_restart_history();
 
         for (name, mut child) in self.services.drain() {
             info!("🛑 Stopping service: {}", name);
@@ -142,11 +865,13 @@ impl ServiceManager {
 
 struct ManastrOrchestrator {
     project_root: PathBuf,
+    profile: Profile,
+    containers: bool, // Services run through docker compose instead of as local binaries
     service_manager: Arc<Mutex<ServiceManager>>,
 }
 
 impl ManastrOrchestrator {
-    fn new() -> Result<Self> {
+    fn new(profile: Profile, restart_policy: RestartPolicy, containers: bool) -> Result<Self> {
         let current_dir = std::env::current_dir()
             .context("Failed to get current directory")?;
             
@@ -164,159 +889,458 @@ impl ManastrOrchestrator {
         };
 
         Ok(Self {
+            profile,
+            containers,
+            service_manager: Arc::new(Mutex::new(
+                ServiceManager::new(restart_policy).with_log_dir(project_root.join("logs")),
+            )),
             project_root,
-            service_manager: Arc::new(Mutex::new(ServiceManager::new())),
         })
     }
 
-    async fn build_all(&self) -> Result<()> {
-        info!("🏗️ Building all Manastr components...");
-        
-        // Build Rust components
-        info!("⚙️ Building Rust workspace...");
-        let rust_build = Command::new("cargo")
-            .args(&["build", "--release"])
-            .current_dir(&self.project_root)
-            .status()
-            .context("Failed to build Rust workspace")?;
-
-        if !rust_build.success() {
-            return Err(anyhow::anyhow!("Rust workspace build failed"));
+    /// `cargo build` arguments for the profile's binaries
+    fn cargo_build_args(&self, extra: &[&'static str]) -> Vec<&'static str> {
+        let mut args = vec!["build"];
+        if self.profile.release() {
+            args.push("--release");
         }
+        args.extend_from_slice(extra);
+        args
+    }
 
-        // Build CDK separately
-        info!("💰 Building CDK mint...");
-        let cdk_build = Command::new("cargo")
-            .args(&["build", "--release", "--bin", "cdk-mintd"])
-            .current_dir(&self.project_root.join("daemons/cdk"))
-            .status()
-            .context("Failed to build CDK mint")?;
+    /// Steps building every component, each only as dependent on others as it must be
+    fn build_steps(&self) -> Vec<BuildStep> {
+        let target = self.profile.target_dir();
+        let cargo = |extra: &[&'static str]| -> Vec<String> {
+            self.cargo_build_args(extra)
+                .into_iter()
+                .map(String::from)
+                .collect()
+        };
+        let paths = |paths: &[&str]| -> Vec<PathBuf> { paths.iter().map(PathBuf::from).collect() };
+        let bash = |script: &str| vec!["-c".to_string(), script.to_string()];
 
-        if !cdk_build.success() {
-            return Err(anyhow::anyhow!("CDK mint build failed"));
-        }
+        let mut steps = vec![
+            BuildStep {
+                name: "rust-workspace",
+                program: "cargo",
+                args: cargo(&[]),
+                dir: PathBuf::from("."),
+                inputs: paths(&[
+                    "Cargo.toml",
+                    "Cargo.lock",
+                    "daemons/shared-game-logic",
+                    "daemons/game-engine-bot",
+                    "daemons/integration_tests",
+                    "daemons/service-orchestrator",
+                ]),
+                output: PathBuf::from(format!("{target}/game-engine-bot")),
+                depends_on: vec![],
+            },
+            BuildStep {
+                name: "cdk-mint",
+                program: "cargo",
+                args: cargo(&["--bin", "cdk-mintd"]),
+                dir: PathBuf::from("daemons/cdk"),
+                inputs: paths(&["daemons/cdk"]),
+                output: PathBuf::from(format!("daemons/cdk/{target}/cdk-mintd")),
+                depends_on: vec![],
+            },
+            BuildStep {
+                name: "wasm",
+                program: "wasm-pack",
+                args: ["build", "--target", "web", "--out-dir", "pkg"]
+                    .map(String::from)
+                    .to_vec(),
+                dir: PathBuf::from("daemons/shared-game-logic"),
+                inputs: paths(&["daemons/shared-game-logic"]),
+                output: PathBuf::from("daemons/shared-game-logic/pkg"),
+                depends_on: vec![],
+            },
+            BuildStep {
+                name: "web-deps",
+                program: "bash",
+                args: bash("npm install"),
+                dir: PathBuf::from("daemons/manastr-web"),
+                inputs: paths(&[
+                    "daemons/manastr-web/package.json",
+                    "daemons/manastr-web/package-lock.json",
+                ]),
+                output: PathBuf::from("daemons/manastr-web/node_modules"),
+                depends_on: vec![],
+            },
+            BuildStep {
+                name: "web",
+                program: "bash",
+                args: bash("npm run build"),
+                dir: PathBuf::from("daemons/manastr-web"),
+                inputs: paths(&["daemons/manastr-web", "daemons/shared-game-logic/pkg"]),
+                output: PathBuf::from("daemons/manastr-web/dist"),
+                depends_on: vec!["wasm", "web-deps"],
+            },
+        ];
 
-        // Build Nostr relay
-        info!("📡 Building Nostr relay...");
-        let relay_build = Command::new("cargo")
-            .args(&["build", "--release"])
-            .current_dir(&self.project_root.join("daemons/nostr-relay/nostr-rs-relay"))
-            .status()
-            .context("Failed to build Nostr relay")?;
+        if self.profile.local_relay() {
+            let relay = "daemons/nostr-relay/nostr-rs-relay";
+            steps.push(BuildStep {
+                name: "nostr-relay",
+                program: "cargo",
+                args: cargo(&[]),
+                dir: PathBuf::from(relay),
+                inputs: paths(&[relay]),
+                output: PathBuf::from(format!("{relay}/{target}/nostr-rs-relay")),
+                depends_on: vec![],
+            });
+        }
+        steps
+    }
 
-        if !relay_build.success() {
-            return Err(anyhow::anyhow!("Nostr relay build failed"));
+    async fn build_all(&self, rebuild: bool) -> Result<()> {
+        if self.containers {
+            return self.build_in_container();
         }
 
-        // Build WASM
-        info!("🌐 Building WASM components...");
-        let wasm_dir = self.project_root.join("daemons/shared-game-logic");
-        let wasm_build = Command::new("wasm-pack")
-            .args(&["build", "--target", "web", "--out-dir", "pkg"])
-            .current_dir(&wasm_dir)
-            .status()
-            .context("Failed to build WASM components")?;
+        info!(
+            "🏗️ Building all Manastr components for the {:?} profile...",
+            self.profile
+        );
+        let started = Instant::now();
+        let built = self.run_build_pipeline(self.build_steps(), rebuild).await?;
+        info!(
+            "✅ All components built in {:.1}s ({} rebuilt)",
+            started.elapsed().as_secs_f64(),
+            built.len()
+        );
+        Ok(())
+    }
 
-        if !wasm_build.success() {
-            return Err(anyhow::anyhow!("WASM build failed"));
-        }
+    /// Run the steps in parallel as soon as their dependencies are built, returning
+    /// the steps that actually ran
+    ///
+    /// Input hashes of successful steps are cached under `target/`, so the next run
+    /// skips steps whose inputs did not change. After a failure no further steps
+    /// start; those already running finish first.
+    async fn run_build_pipeline(
+        &self,
+        steps: Vec<BuildStep>,
+        rebuild: bool,
+    ) -> Result<Vec<&'static str>> {
+        let cache_path = self.project_root.join("target/manastr-build-cache.json");
+        let mut cache = if rebuild {
+            BuildCache::default()
+        } else {
+            BuildCache::load(&cache_path)
+        };
 
-        // Build web client
-        info!("🚀 Building quantum web client...");
-        let web_dir = self.project_root.join("daemons/manastr-web");
-        
-        // Check if node_modules exists, install if not
-        if !web_dir.join("node_modules").exists() {
-            info!("📦 Installing web dependencies...");
-            let npm_install = Command::new("bash")
-                .args(&["-c", "npm install"])
-                .current_dir(&web_dir)
-                .status()
-                .context("Failed to install npm dependencies")?;
-
-            if !npm_install.success() {
-                return Err(anyhow::anyhow!("npm install failed"));
-            }
-        }
+        let mut pending = steps;
+        let mut done: HashSet<&'static str> = HashSet::new();
+        let mut built = Vec::new();
+        let mut failed = Vec::new();
+        let mut running = tokio::task::JoinSet::new();
 
-        let web_build = Command::new("bash")
-            .args(&["-c", "npm run build"])
-            .current_dir(&web_dir)
-            .status()
-            .context("Failed to build web client")?;
+        loop {
+            if failed.is_empty() {
+                let (ready, waiting): (Vec<BuildStep>, Vec<BuildStep>) = pending
+                    .into_iter()
+                    .partition(|step| step.depends_on.iter().all(|d| done.contains(d)));
+                pending = waiting;
+
+                for step in ready {
+                    info!("▶️ Building {}...", step.name);
+                    let log = self
+                        .service_manager
+                        .lock()
+                        .await
+                        .service_log(&format!("build-{}", step.name));
+                    let root = self.project_root.clone();
+                    let cached = cache.steps.get(step.name).cloned();
+                    running.spawn_blocking(move || {
+                        let started = Instant::now();
+                        let result = run_build_step(&root, &step, cached.as_deref(), log);
+                        (step.name, started.elapsed(), result)
+                    });
+                }
+            }
 
-        if !web_build.success() {
-            return Err(anyhow::anyhow!("Web client build failed"));
+            let Some(joined) = running.join_next().await else {
+                break;
+            };
+            let (name, elapsed, result) = joined.context("Build step panicked")?;
+            match result {
+                Ok((hash, ran)) => {
+                    if ran {
+                        info!("✅ {} built in {:.1}s", name, elapsed.as_secs_f64());
+                        built.push(name);
+                    } else {
+                        info!("⏭️ {} is up to date", name);
+                    }
+                    cache.steps.insert(name.to_string(), hash);
+                    done.insert(name);
+                }
+                Err(e) => {
+                    error!("❌ {:#}", e);
+                    cache.steps.remove(name);
+                    failed.push(name);
+                }
+            }
         }
 
-        info!("✅ All components built successfully!");
-        Ok(())
+        cache.save(&cache_path)?;
+        if !failed.is_empty() {
+            return Err(anyhow::anyhow!("Build failed: {}", failed.join(", ")));
+        }
+        if !pending.is_empty() {
+            let names: Vec<&str> = pending.iter().map(|step| step.name).collect();
+            return Err(anyhow::anyhow!(
+                "Build steps depend on unknown steps: {}",
+                names.join(", ")
+            ));
+        }
+        Ok(built)
     }
 
     fn get_service_configs(&self) -> Vec<ServiceConfig> {
-        vec![
-            // Nostr Relay
-            ServiceConfig {
-                name: "nostr-relay".to_string(),
-                command: self.project_root
-                    .join("daemons/nostr-relay/nostr-rs-relay/target/release/nostr-rs-relay")
-                    .to_string_lossy()
-                    .to_string(),
-                args: vec![
-                    "--config".to_string(),
-                    self.project_root
-                        .join("daemons/nostr-relay/config.toml")
-                        .to_string_lossy()
-                        .to_string(),
-                ],
-                working_dir: self.project_root.join("daemons/nostr-relay"),
-                health_check_url: None, // Nostr relay doesn't have HTTP endpoint
-                health_check_timeout: Duration::from_secs(5),
-            },
+        let path = |relative: String| self.project_root.join(relative).to_string_lossy().to_string();
+        let target = self.profile.target_dir();
+        let mut engine_depends_on = vec!["cdk-mint".to_string()];
+        if self.profile.local_relay() {
+            engine_depends_on.insert(0, "nostr-relay".to_string());
+        }
+
+        let mut configs = vec![
             // CDK Mint
             ServiceConfig {
                 name: "cdk-mint".to_string(),
-                command: self.project_root
-                    .join("daemons/cdk/target/release/cdk-mintd")
-                    .to_string_lossy()
-                    .to_string(),
+                command: path(format!("daemons/cdk/{target}/cdk-mintd")),
                 args: vec![
                     "--config".to_string(),
-                    self.project_root
-                        .join("daemons/config/cdk-mintd-deterministic.toml")
-                        .to_string_lossy()
-                        .to_string(),
+                    path(self.profile.mint_config().to_string()),
                 ],
                 working_dir: self.project_root.join("daemons/cdk"),
-                health_check_url: Some("http://localhost:3333/v1/info".to_string()),
+                readiness_probe: Some(ReadinessProbe::Http("http://localhost:3333/v1/info".to_string())),
                 health_check_timeout: Duration::from_secs(30),
+                depends_on: vec![],
             },
             // Game Engine (No HTTP endpoints - Pure Nostr communication)
             ServiceConfig {
                 name: "game-engine".to_string(),
-                command: self.project_root
-                    .join("target/release/game-engine-bot")
-                    .to_string_lossy()
-                    .to_string(),
+                command: path(format!("{target}/game-engine-bot")),
                 args: vec![
                     "--config".to_string(),
-                    self.project_root
-                        .join("daemons/game-engine-bot/game-engine.toml")
-                        .to_string_lossy()
-                        .to_string(),
+                    path(self.profile.engine_config().to_string()),
                 ],
                 working_dir: self.project_root.join("daemons/game-engine-bot"),
-                health_check_url: None, // No HTTP endpoints - communicates via Nostr only
+                readiness_probe: None, // No HTTP endpoints - communicates via Nostr only
                 health_check_timeout: Duration::from_secs(5),
+                depends_on: engine_depends_on,
             },
-        ]
+        ];
+
+        // Profiles on public relays have no relay of their own to start
+        if self.profile.local_relay() {
+            configs.insert(
+                0,
+                ServiceConfig {
+                    name: "nostr-relay".to_string(),
+                    command: path(format!("daemons/nostr-relay/nostr-rs-relay/{target}/nostr-rs-relay")),
+                    args: vec![
+                        "--config".to_string(),
+                        path("daemons/nostr-relay/config.toml".to_string()),
+                    ],
+                    working_dir: self.project_root.join("daemons/nostr-relay"),
+                    readiness_probe: Some(ReadinessProbe::WebSocket("ws://localhost:7777".to_string())),
+                    health_check_timeout: Duration::from_secs(30),
+                    depends_on: vec![],
+                },
+            );
+        }
+        configs
+    }
+
+    /// Shell script the compose `build` service runs to build the Rust binaries
+    fn container_build_script(&self) -> String {
+        let mint = self.cargo_build_args(&["--bin", "cdk-mintd"]);
+        let mut steps = vec![(".", self.cargo_build_args(&[])), ("daemons/cdk", mint)];
+        if self.profile.local_relay() {
+            let relay = "daemons/nostr-relay/nostr-rs-relay";
+            steps.push((relay, self.cargo_build_args(&[])));
+        }
+        steps
+            .iter()
+            .map(|(dir, args)| format!("(cd {dir} && cargo {})", args.join(" ")))
+            .collect::<Vec<_>>()
+            .join(" && ")
+    }
+
+    /// The same path inside the containers, which mount the repository at `CONTAINER_ROOT`
+    fn container_path(&self, path: impl AsRef<FsPath>) -> String {
+        match path.as_ref().strip_prefix(&self.project_root) {
+            Ok(relative) => FsPath::new(CONTAINER_ROOT)
+                .join(relative)
+                .to_string_lossy()
+                .to_string(),
+            Err(_) => path.as_ref().to_string_lossy().to_string(),
+        }
+    }
+
+    /// Compose definition of the stack, built from the same configs as local runs
+    fn compose_file(&self, configs: &[ServiceConfig]) -> ComposeFile {
+        let repo_volume = format!("{}:{CONTAINER_ROOT}", self.project_root.display());
+        let service = |working_dir: String, command: Vec<String>| ComposeService {
+            image: CONTAINER_IMAGE.to_string(),
+            working_dir,
+            command,
+            volumes: vec![repo_volume.clone()],
+            network_mode: "host".to_string(),
+            depends_on: BTreeMap::new(),
+            healthcheck: None,
+        };
+
+        let mut services = BTreeMap::new();
+        let mut build = service(
+            CONTAINER_ROOT.to_string(),
+            vec![
+                "bash".to_string(),
+                "-c".to_string(),
+                self.container_build_script(),
+            ],
+        );
+        build
+            .volumes
+            .push("cargo-registry:/usr/local/cargo/registry".to_string());
+        services.insert("build".to_string(), build);
+
+        for config in configs {
+            let mut command = vec![self.container_path(&config.command)];
+            command.extend(config.args.iter().map(|arg| self.container_path(arg)));
+            let mut container = service(self.container_path(&config.working_dir), command);
+
+            container.depends_on.insert(
+                "build".to_string(),
+                ComposeDependency {
+                    condition: "service_completed_successfully",
+                },
+            );
+            for dependency in &config.depends_on {
+                let probed = configs
+                    .iter()
+                    .any(|c| &c.name == dependency && c.readiness_probe.is_some());
+                let condition = if probed {
+                    "service_healthy"
+                } else {
+                    "service_started"
+                };
+                container
+                    .depends_on
+                    .insert(dependency.clone(), ComposeDependency { condition });
+            }
+
+            if let Some(probe) = &config.readiness_probe {
+                container.healthcheck = Some(ComposeHealthcheck {
+                    test: vec!["CMD-SHELL".to_string(), probe.healthcheck_command()],
+                    interval: "1s".to_string(),
+                    retries: config.health_check_timeout.as_secs().max(1),
+                });
+            }
+            services.insert(config.name.clone(), container);
+        }
+
+        ComposeFile {
+            name: COMPOSE_PROJECT.to_string(),
+            services,
+            volumes: BTreeMap::from([("cargo-registry".to_string(), BTreeMap::new())]),
+        }
+    }
+
+    /// Write the compose file to the project root, replacing an earlier one
+    fn write_compose_file(&self) -> Result<PathBuf> {
+        let path = self.project_root.join("docker-compose.yml");
+        let yaml = serde_yaml::to_string(&self.compose_file(&self.get_service_configs()))
+            .context("Failed to serialize docker-compose.yml")?;
+        fs::write(
+            &path,
+            format!("# Generated by manastr-serve --containers, changes are overwritten\n{yaml}"),
+        )
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// `docker compose` invocation on the generated file
+    fn compose_args(&self, compose_file: &FsPath, extra: &[&str]) -> Vec<String> {
+        let mut args: Vec<String> = ["compose", "-f", &compose_file.to_string_lossy(), "-p"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        args.push(COMPOSE_PROJECT.to_string());
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        args
+    }
+
+    fn run_compose(&self, compose_file: &FsPath, extra: &[&str]) -> Result<()> {
+        let status = Command::new("docker")
+            .args(self.compose_args(compose_file, extra))
+            .current_dir(&self.project_root)
+            .status()
+            .context("Failed to run docker compose")?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("docker compose {} failed", extra.join(" ")));
+        }
+        Ok(())
+    }
+
+    /// Build the binaries inside the toolchain image instead of with local toolchains
+    fn build_in_container(&self) -> Result<()> {
+        info!(
+            "🐳 Building Manastr binaries in {} for the {:?} profile...",
+            CONTAINER_IMAGE, self.profile
+        );
+        let compose_file = self.write_compose_file()?;
+        self.run_compose(&compose_file, &["run", "--rm", "build"])
+            .context("Container build failed")?;
+        info!("✅ All binaries built successfully!");
+        Ok(())
+    }
+
+    /// Configs that run each service attached to its compose container
+    ///
+    /// The orchestrator keeps ordering, probing, supervising and logging the
+    /// services; `docker compose up` just runs them in their containers.
+    fn container_service_configs(&self) -> Result<Vec<ServiceConfig>> {
+        let compose_file = self.write_compose_file()?;
+        info!("🐳 Running services from {}", compose_file.display());
+        let pull = ["pull", "--quiet", "--ignore-pull-failures"];
+        self.run_compose(&compose_file, &pull)?;
+
+        Ok(self
+            .get_service_configs()
+            .into_iter()
+            .map(|config| {
+                let up = ["up", "--no-deps", "--force-recreate", "--no-log-prefix"];
+                let mut args = self.compose_args(&compose_file, &up);
+                args.push(config.name.clone());
+                ServiceConfig {
+                    command: "docker".to_string(),
+                    args,
+                    working_dir: self.project_root.clone(),
+                    ..config
+                }
+            })
+            .collect())
     }
 
     async fn start_all_services(&self) -> Result<()> {
-        let configs = self.get_service_configs();
+        let configs = if self.containers {
+            self.container_service_configs()?
+        } else {
+            self.get_service_configs()
+        };
         let mut manager = self.service_manager.lock().await;
 
-        for config in &configs {
+        // Each service starts only once everything it depends on passed its readiness probe
+        for config in startup_order(&configs)? {
+            if !config.depends_on.is_empty() {
+                info!("⏳ {} waits on: {}", config.name, config.depends_on.join(", "));
+            }
             manager.start_service(config).await
                 .with_context(|| format!("Failed to start service: {}", config.name))?;
         }
@@ -325,7 +1349,72 @@ impl ManastrOrchestrator {
         Ok(())
     }
 
-    async fn serve_web(&self, port: u16) -> Result<()> {
+    /// Restart services that exit on their own until shutdown
+    async fn supervise(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+
+            let exited = {
+                let mut manager = self.service_manager.lock().await;
+                if !manager.is_running() {
+                    return;
+                }
+                manager.reap_exited()
+            };
+
+            for (name, status) in exited {
+                let Some(delay) = self.service_manager.lock().await.record_exit(&name, status)
+                else {
+                    continue;
+                };
+                // Back off without holding the manager, so shutdown is never blocked
+                sleep(delay).await;
+
+                let (config, running) = {
+                    let mut manager = self.service_manager.lock().await;
+                    if !manager.is_running() {
+                        return;
+                    }
+                    let Some(config) = manager.configs.get(&name).cloned() else {
+                        continue;
+                    };
+                    if let Err(e) = manager.spawn_service(&config) {
+                        error!("❌ Failed to restart service {}: {:#}", name, e);
+                        continue;
+                    }
+                    (config, Arc::clone(&manager.running))
+                };
+                // Probe without the manager too, so Ctrl+C is not held up by the wait
+                if let Err(e) = wait_for_service(&config, &running).await {
+                    error!("❌ Failed to restart service {}: {:#}", name, e);
+                }
+            }
+        }
+    }
+
+    /// `/orchestrator` endpoints for scripts and CI to inspect and manage the stack
+    fn control_api(self: &Arc<Self>) -> Router {
+        Router::new()
+            .route("/orchestrator/status", get(status_handler))
+            .route("/orchestrator/restart/:service", post(restart_handler))
+            .route("/orchestrator/logs/:service", get(logs_handler))
+            .with_state(Arc::clone(self))
+    }
+
+    /// Serve the control API on localhost only; it can restart services and read their logs
+    async fn serve_control_api(self: &Arc<Self>, port: u16) -> Result<()> {
+        let listener = TcpListener::bind(&format!("127.0.0.1:{}", port)).await
+            .context("Failed to bind to address")?;
+        info!("🎛️ Control API ready at http://localhost:{}/orchestrator/status", port);
+
+        axum::serve(listener, self.control_api()).await
+            .context("Control API error")?;
+
+        Ok(())
+    }
+
+    async fn serve_web(self: &Arc<Self>, port: u16) -> Result<()> {
         let web_dist_path = self.project_root.join("daemons/manastr-web/dist");
         
         if !web_dist_path.exists() {
@@ -340,7 +1429,7 @@ impl ManastrOrchestrator {
         let serve_dir = ServeDir::new(&web_dist_path);
 
         let app = Router::new()
-            .nest_service("/", serve_dir)
+            .fallback_service(serve_dir)
             .layer(CorsLayer::permissive())
             .layer(TraceLayer::new_for_http());
 
@@ -367,11 +1456,69 @@ impl ManastrOrchestrator {
         info!("🛑 Shutting down Manastr system...");
         let mut manager = self.service_manager.lock().await;
         manager.stop_all_services().await?;
+        if self.containers {
+            let compose_file = self.project_root.join("docker-compose.yml");
+            if let Err(e) = self.run_compose(&compose_file, &["down"]) {
+                warn!("Failed to remove containers: {:#}", e);
+            }
+        }
         info!("👋 Manastr system shutdown complete");
         Ok(())
     }
 }
 
+async fn status_handler(
+    State(orchestrator): State<Arc<ManastrOrchestrator>>,
+) -> Json<serde_json::Value> {
+    let manager = orchestrator.service_manager.lock().await;
+    Json(serde_json::json!({
+        "profile": orchestrator.profile,
+        "services": manager.status(),
+    }))
+}
+
+async fn restart_handler(
+    State(orchestrator): State<Arc<ManastrOrchestrator>>,
+    Path(service): Path<String>,
+) -> (StatusCode, String) {
+    let (config, running) = {
+        let mut manager = orchestrator.service_manager.lock().await;
+        let Some(config) = manager.configs.get(&service).cloned() else {
+            return (StatusCode::NOT_FOUND, format!("Unknown service: {service}"));
+        };
+
+        info!("🔁 Restarting service {} on request", service);
+        manager.stop_service(&service);
+        if let Err(e) = manager.spawn_service(&config) {
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}"));
+        }
+        (config, Arc::clone(&manager.running))
+    };
+
+    // Probe without the manager, so status, logs and shutdown are not held up
+    match wait_for_service(&config, &running).await {
+        Ok(()) => (StatusCode::OK, format!("Restarted {service}")),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")),
+    }
+}
+
+#[derive(Deserialize)]
+struct LogsQuery {
+    lines: Option<usize>,
+}
+
+async fn logs_handler(
+    State(orchestrator): State<Arc<ManastrOrchestrator>>,
+    Path(service): Path<String>,
+    Query(query): Query<LogsQuery>,
+) -> (StatusCode, String) {
+    let manager = orchestrator.service_manager.lock().await;
+    match manager.recent_logs(&service, query.lines.unwrap_or(100)) {
+        Some(lines) => (StatusCode::OK, lines.join("\n")),
+        None => (StatusCode::NOT_FOUND, format!("Unknown service: {service}")),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
@@ -387,12 +1534,27 @@ async fn main() -> Result<()> {
     info!("Revolutionary Zero-Coordination Gaming System");
     info!("");
 
-    let orchestrator = ManastrOrchestrator::new()
+    let restart_policy = RestartPolicy {
+        max_restarts: args.max_restarts,
+        base_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(60),
+        stable_uptime: Duration::from_secs(300),
+    };
+    info!("🧭 Profile: {:?}", args.profile);
+    if args.containers {
+        info!("🐳 Running services in docker containers");
+    }
+    // The build container only has the Rust toolchain, so the web client is never built there
+    let backend_only = args.backend_only || args.containers;
+    if backend_only && !args.backend_only {
+        info!("🐳 Container mode runs the backend only; serve the web client separately");
+    }
+    let orchestrator = ManastrOrchestrator::new(args.profile, restart_policy, args.containers)
         .context("Failed to initialize orchestrator")?;
 
     // Build everything (unless skipped)
     if !args.skip_build {
-        orchestrator.build_all().await
+        orchestrator.build_all(args.rebuild).await
             .context("Build failed")?;
     } else {
         info!("⏭️ Skipping build (--skip-build specified)");
@@ -405,13 +1567,26 @@ async fn main() -> Result<()> {
     // Set up signal handling for graceful shutdown
     let orchestrator_clone = Arc::new(orchestrator);
     let shutdown_orchestrator = orchestrator_clone.clone();
+
+    // Restart crashed services in the background
+    let supervisor = orchestrator_clone.clone();
+    tokio::spawn(async move { supervisor.supervise().await });
     
+    // The control API is a convenience; the services keep running without it
+    let control_api = orchestrator_clone.clone();
+    let control_port = args.control_port;
+    tokio::spawn(async move {
+        if let Err(e) = control_api.serve_control_api(control_port).await {
+            error!("Control API error: {:#}", e);
+        }
+    });
+
     let shutdown_signal = async {
         let _ = signal::ctrl_c().await;
         info!("🛑 Received shutdown signal");
     };
 
-    if args.backend_only {
+    if backend_only {
         // Backend services only - just wait for shutdown signal
         info!("🚀 Backend services operational! All services ready for connections:");
         info!("📡 Nostr Relay: ws://localhost:7777");
@@ -419,7 +1594,7 @@ async fn main() -> Result<()> {
         info!("🎮 Game Engine: Nostr communication only");
         info!("");
         info!("Press Ctrl+C to shutdown all services");
-        
+
         // Just wait for shutdown signal
         shutdown_signal.await;
     } else {
@@ -443,4 +1618,314 @@ async fn main() -> Result<()> {
         .context("Shutdown failed")?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(name: &str, depends_on: &[&str]) -> ServiceConfig {
+        ServiceConfig {
+            name: name.to_string(),
+            command: name.to_string(),
+            args: vec![],
+            working_dir: PathBuf::from("."),
+            readiness_probe: None,
+            health_check_timeout: Duration::from_secs(1),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_restarts_back_off_until_the_limit() {
+        let mut manager = ServiceManager::new(RestartPolicy {
+            max_restarts: 3,
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+            stable_uptime: Duration::from_secs(300),
+        });
+        let status = Command::new("false").status().unwrap();
+
+        let delays: Vec<Option<Duration>> = (0..4)
+            .map(|_| manager.record_exit("nostr-relay", status))
+            .collect();
+        let secs = |secs| Some(Duration::from_secs(secs));
+        assert_eq!(delays, [secs(1), secs(2), secs(3), None]);
+        assert_eq!(manager.restart_history["nostr-relay"].len(), 4);
+
+        // After a stable run the service gets its full restarts again
+        let started_at = Instant::now()
+            .checked_sub(Duration::from_secs(300))
+            .unwrap();
+        manager
+            .started_at
+            .insert("nostr-relay".to_string(), started_at);
+        assert_eq!(manager.record_exit("nostr-relay", status), secs(1));
+        assert_eq!(manager.restart_history["nostr-relay"].len(), 5);
+    }
+
+    #[test]
+    fn test_profiles_select_binaries_and_relays() {
+        let orchestrator = |profile| ManastrOrchestrator {
+            project_root: PathBuf::from("/manastr"),
+            profile,
+            containers: false,
+            service_manager: Arc::new(Mutex::new(ServiceManager::new(RestartPolicy {
+                max_restarts: 0,
+                base_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+                stable_uptime: Duration::ZERO,
+            }))),
+        };
+
+        let dev = orchestrator(Profile::Dev).get_service_configs();
+        assert_eq!(dev.len(), 3);
+        assert_eq!(dev[2].command, "/manastr/target/debug/game-engine-bot");
+        assert_eq!(dev[2].depends_on, ["nostr-relay", "cdk-mint"]);
+
+        // Production talks to public relays and runs release binaries
+        let prod = orchestrator(Profile::Prod).get_service_configs();
+        let names: Vec<&str> = prod.iter().map(|config| config.name.as_str()).collect();
+        assert_eq!(names, ["cdk-mint", "game-engine"]);
+        assert_eq!(prod[1].depends_on, ["cdk-mint"]);
+        assert_eq!(
+            prod[1].args[1],
+            "/manastr/daemons/config/profiles/prod/game-engine.toml"
+        );
+        assert!(prod[0].command.ends_with("daemons/cdk/target/release/cdk-mintd"));
+        assert!(startup_order(&prod).is_ok());
+    }
+
+    #[test]
+    fn test_compose_file_mirrors_the_service_configs() {
+        let orchestrator = ManastrOrchestrator {
+            project_root: PathBuf::from("/srv/manastr"),
+            profile: Profile::Test,
+            containers: true,
+            service_manager: Arc::new(Mutex::new(ServiceManager::new(RestartPolicy {
+                max_restarts: 0,
+                base_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+                stable_uptime: Duration::ZERO,
+            }))),
+        };
+        let compose = orchestrator.compose_file(&orchestrator.get_service_configs());
+
+        let names: Vec<&str> = compose.services.keys().map(String::as_str).collect();
+        assert_eq!(names, ["build", "cdk-mint", "game-engine", "nostr-relay"]);
+        assert!(compose.services["build"].command[2].contains("--bin cdk-mintd"));
+
+        // Paths point into the mounted repository
+        let mint = &compose.services["cdk-mint"];
+        assert_eq!(mint.volumes, ["/srv/manastr:/manastr"]);
+        assert_eq!(
+            mint.command[0],
+            "/manastr/daemons/cdk/target/release/cdk-mintd"
+        );
+        assert_eq!(
+            mint.command[2],
+            "/manastr/daemons/config/cdk-mintd-deterministic.toml"
+        );
+        assert_eq!(mint.working_dir, "/manastr/daemons/cdk");
+
+        // Dependencies wait on the healthchecks made from the readiness probes
+        let engine = &compose.services["game-engine"];
+        assert!(engine.healthcheck.is_none());
+        for dependency in ["cdk-mint", "nostr-relay"] {
+            assert_eq!(engine.depends_on[dependency].condition, "service_healthy");
+        }
+        assert_eq!(
+            engine.depends_on["build"].condition,
+            "service_completed_successfully"
+        );
+        let relay = &compose.services["nostr-relay"];
+        let relay_check = relay.healthcheck.as_ref().unwrap();
+        assert!(relay_check.test[1].contains("http://localhost:7777"));
+
+        let yaml = serde_yaml::to_string(&compose).unwrap();
+        assert!(yaml.contains("network_mode: host"));
+    }
+
+    #[tokio::test]
+    async fn test_build_pipeline_skips_unchanged_steps() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("wasm.src"), "v1").unwrap();
+        fs::write(root.path().join("web.src"), "v1").unwrap();
+        let orchestrator = ManastrOrchestrator {
+            project_root: root.path().to_path_buf(),
+            profile: Profile::Dev,
+            containers: false,
+            service_manager: Arc::new(Mutex::new(ServiceManager::new(RestartPolicy {
+                max_restarts: 0,
+                base_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+                stable_uptime: Duration::ZERO,
+            }))),
+        };
+        let step = |name: &'static str, depends_on: Vec<&'static str>| BuildStep {
+            name,
+            program: "sh",
+            args: vec!["-c".to_string(), format!("cat {name}.src > {name}.out")],
+            dir: PathBuf::from("."),
+            inputs: vec![PathBuf::from(format!("{name}.src"))],
+            output: PathBuf::from(format!("{name}.out")),
+            depends_on,
+        };
+        let steps = || vec![step("web", vec!["wasm"]), step("wasm", vec![])];
+        let build = |steps, rebuild| orchestrator.run_build_pipeline(steps, rebuild);
+
+        assert_eq!(build(steps(), false).await.unwrap(), ["wasm", "web"]);
+        assert!(build(steps(), false).await.unwrap().is_empty());
+
+        // Changed inputs, missing outputs and --rebuild each run a step again
+        fs::write(root.path().join("web.src"), "v2").unwrap();
+        fs::remove_file(root.path().join("wasm.out")).unwrap();
+        assert_eq!(build(steps(), false).await.unwrap(), ["wasm", "web"]);
+        let web = fs::read_to_string(root.path().join("web.out")).unwrap();
+        assert_eq!(web, "v2");
+        assert_eq!(build(steps(), true).await.unwrap().len(), 2);
+
+        // A failed step keeps its dependents from starting
+        let mut failing = steps();
+        failing[1].args[1] = "exit 1".to_string();
+        let error = build(failing, true).await.unwrap_err();
+        assert_eq!(error.to_string(), "Build failed: wasm");
+    }
+
+    #[test]
+    fn test_runtime_data_is_not_a_build_input() {
+        let root = tempfile::tempdir().unwrap();
+        let crate_dir = root.path().join("daemons/game-engine-bot");
+        fs::create_dir_all(crate_dir.join("data")).unwrap();
+        fs::write(crate_dir.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(crate_dir.join("data/match-tracker.sqlite"), "v1").unwrap();
+        let step = BuildStep {
+            name: "rust-workspace",
+            program: "cargo",
+            args: vec!["build".to_string()],
+            dir: PathBuf::from("."),
+            inputs: vec![PathBuf::from("daemons/game-engine-bot")],
+            output: PathBuf::from("target"),
+            depends_on: vec![],
+        };
+        let hash = || hash_inputs(root.path(), &step).unwrap();
+
+        // Outside git, known runtime directories are skipped
+        let before = hash();
+        fs::write(crate_dir.join("data/match-tracker.sqlite"), "v2").unwrap();
+        assert_eq!(hash(), before);
+
+        // In a checkout, only files git would track count
+        let initialized = std::process::Command::new("git")
+            .args(["init", "--quiet"])
+            .current_dir(root.path())
+            .status()
+            .is_ok_and(|status| status.success());
+        if initialized {
+            fs::write(root.path().join(".gitignore"), "*.sqlite\n").unwrap();
+            let before = hash();
+            fs::write(crate_dir.join("data/match-tracker.sqlite"), "v3").unwrap();
+            assert_eq!(hash(), before);
+            fs::write(crate_dir.join("main.rs"), "fn main() { run() }").unwrap();
+            assert_ne!(hash(), before);
+        }
+    }
+
+    #[test]
+    fn test_log_buffer_keeps_the_latest_lines() {
+        let mut manager = ServiceManager::new(RestartPolicy {
+            max_restarts: 0,
+            base_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            stable_uptime: Duration::ZERO,
+        });
+        let log = manager.service_log("nostr-relay");
+        let output: String = (0..LOG_LINES_KEPT + 5)
+            .map(|i| format!("line {i}\n"))
+            .collect();
+        capture_output(std::io::Cursor::new(output), log)
+            .join()
+            .unwrap();
+
+        // Lines are tagged with a timestamp and the service name
+        let last = LOG_LINES_KEPT + 4;
+        let recent = manager.recent_logs("nostr-relay", 2).unwrap();
+        assert!(recent[0].ends_with(&format!(" [nostr-relay] line {}", last - 1)));
+        assert!(recent[1].ends_with(&format!(" [nostr-relay] line {last}")));
+        assert!(manager.recent_logs("nostr-relay", usize::MAX).unwrap()[0].ends_with("] line 5"));
+        assert!(manager.recent_logs("cdk-mint", 10).is_none());
+    }
+
+    #[test]
+    fn test_log_files_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cdk-mint.log");
+        let mut log = RotatingLog::open(path.clone(), 10, 2).unwrap();
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            log.write_line(line).unwrap();
+        }
+
+        let read =
+            |suffix: &str| fs::read_to_string(format!("{}{suffix}", path.display())).unwrap();
+        assert_eq!(read(""), "fourth line\n");
+        assert_eq!(read(".1"), "third line\n");
+        assert_eq!(read(".2"), "second line\n");
+        assert!(!dir.path().join("cdk-mint.log.3").exists());
+    }
+
+    #[tokio::test]
+    async fn test_control_api_reports_and_rejects_unknown_services() {
+        use tower::ServiceExt;
+
+        let orchestrator = Arc::new(ManastrOrchestrator {
+            project_root: PathBuf::from("/manastr"),
+            profile: Profile::Dev,
+            containers: false,
+            service_manager: Arc::new(Mutex::new(ServiceManager::new(RestartPolicy {
+                max_restarts: 0,
+                base_backoff: Duration::ZERO,
+                max_backoff: Duration::ZERO,
+                stable_uptime: Duration::ZERO,
+            }))),
+        });
+        let request = |method: &str, uri: &str| {
+            axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let status = orchestrator
+            .control_api()
+            .oneshot(request("GET", "/orchestrator/status"))
+            .await
+            .unwrap();
+        assert_eq!(status.status(), StatusCode::OK);
+
+        let restart = orchestrator
+            .control_api()
+            .oneshot(request("POST", "/orchestrator/restart/nostr-relay"))
+            .await
+            .unwrap();
+        assert_eq!(restart.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_dependencies_start_first() {
+        let configs = [
+            service("game-engine", &["nostr-relay", "cdk-mint"]),
+            service("cdk-mint", &[]),
+            service("nostr-relay", &[]),
+        ];
+        let order: Vec<&str> = startup_order(&configs)
+            .unwrap()
+            .iter()
+            .map(|config| config.name.as_str())
+            .collect();
+        assert_eq!(order, ["cdk-mint", "nostr-relay", "game-engine"]);
+
+        assert!(startup_order(&[service("a", &["b"]), service("b", &["a"])]).is_err());
+        assert!(startup_order(&[service("a", &["missing"])]).is_err());
+    }
+}
@@ -1,4 +1,178 @@
-use crate::game_state::{Ability, Unit};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::game_state::{Ability, GameLogicError, StatusEffect, Unit};
+
+/// Mana each player gains per round, before spending it on abilities
+pub const MANA_PER_ROUND: u8 = 2;
+/// Mana a player can bank across rounds
+pub const MAX_MANA: u8 = 6;
+
+/// Ability a player can activate in a combat move, by its `unit_abilities` key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AbilityId {
+    Boost,
+    Shield,
+    Heal,
+    Poison,
+    Stun,
+}
+
+/// Unit an activated ability lands on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbilityTarget {
+    Own,   // The player's unit fighting this round
+    Enemy, // The opposing unit
+}
+
+/// Rules for activating an ability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AbilitySpec {
+    pub id: AbilityId,
+    pub key: &'static str, // As sent in `unit_abilities`
+    pub cost: u8,          // Mana spent on activation
+    pub cooldown: u32,     // Rounds after activation before it can be used again
+    pub target: AbilityTarget,
+}
+
+/// Every ability players can activate
+pub const ABILITY_REGISTRY: [AbilitySpec; 5] = [
+    AbilitySpec {
+        id: AbilityId::Boost,
+        key: "boost",
+        cost: 2,
+        cooldown: 2,
+        target: AbilityTarget::Own,
+    },
+    AbilitySpec {
+        id: AbilityId::Shield,
+        key: "shield",
+        cost: 3,
+        cooldown: 3,
+        target: AbilityTarget::Own,
+    },
+    AbilitySpec {
+        id: AbilityId::Heal,
+        key: "heal",
+        cost: 2,
+        cooldown: 2,
+        target: AbilityTarget::Own,
+    },
+    AbilitySpec {
+        id: AbilityId::Poison,
+        key: "poison",
+        cost: 3,
+        cooldown: 3,
+        target: AbilityTarget::Enemy,
+    },
+    AbilitySpec {
+        id: AbilityId::Stun,
+        key: "stun",
+        cost: 4,
+        cooldown: 4,
+        target: AbilityTarget::Enemy,
+    },
+];
+
+impl AbilityId {
+    pub fn spec(self) -> &'static AbilitySpec {
+        ABILITY_REGISTRY
+            .iter()
+            .find(|spec| spec.id == self)
+            .expect("every ability is registered")
+    }
+
+    /// Look up an ability by its key, ignoring case
+    pub fn parse(key: &str) -> Result<Self, GameLogicError> {
+        ABILITY_REGISTRY
+            .iter()
+            .find(|spec| spec.key.eq_ignore_ascii_case(key))
+            .map(|spec| spec.id)
+            .ok_or_else(|| GameLogicError::InvalidInput(format!("Unknown ability '{key}'")))
+    }
+}
+
+/// A player's mana and ability cooldowns over the rounds of a match
+///
+/// Replaying a player's moves through the ledger in round order rejects any
+/// round using an unknown ability, one still on cooldown, or more than the
+/// player's mana can pay for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AbilityLedger {
+    mana: u8,
+    last_round: u32,
+    last_used: HashMap<AbilityId, u32>, // Ability -> round it was last activated
+}
+
+impl AbilityLedger {
+    pub fn mana(&self) -> u8 {
+        self.mana
+    }
+
+    /// Activate the round's abilities, leaving the ledger untouched if any is not allowed
+    pub fn use_abilities(
+        &mut self,
+        round: u32,
+        keys: &[String],
+    ) -> Result<Vec<AbilityId>, GameLogicError> {
+        if round <= self.last_round {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Round {round} is not after round {}",
+                self.last_round
+            )));
+        }
+
+        let mut next = self.clone();
+        let gained = (round - self.last_round).saturating_mul(u32::from(MANA_PER_ROUND));
+        next.mana = u32::from(next.mana)
+            .saturating_add(gained)
+            .min(u32::from(MAX_MANA)) as u8;
+        next.last_round = round;
+
+        let mut used = Vec::with_capacity(keys.len());
+        for key in keys {
+            let ability = AbilityId::parse(key)?;
+            let spec = ability.spec();
+            if let Some(last) = next.last_used.get(&ability) {
+                if round - last < spec.cooldown {
+                    return Err(GameLogicError::InvalidInput(format!(
+                        "Ability '{}' is on cooldown until round {}",
+                        spec.key,
+                        last + spec.cooldown
+                    )));
+                }
+            }
+            next.mana = next.mana.checked_sub(spec.cost).ok_or_else(|| {
+                GameLogicError::InsufficientMana {
+                    ability: spec.key.to_string(),
+                    cost: spec.cost,
+                    available: next.mana,
+                }
+            })?;
+            next.last_used.insert(ability, round);
+            used.push(ability);
+        }
+
+        *self = next;
+        Ok(used)
+    }
+}
+
+/// Apply an activated ability to the unit its target rule selects
+pub fn apply_activated(ability: AbilityId, user: &mut Unit, opponent: &mut Unit) {
+    let target = match ability.spec().target {
+        AbilityTarget::Own => user,
+        AbilityTarget::Enemy => opponent,
+    };
+
+    match ability {
+        AbilityId::Boost => target.attack = target.attack.saturating_mul(2),
+        AbilityId::Shield => target.apply_status(StatusEffect::Shield, 1),
+        AbilityId::Heal => target.apply_status(StatusEffect::Regen, 3),
+        AbilityId::Poison => target.apply_status(StatusEffect::Poison, 3),
+        AbilityId::Stun => target.apply_status(StatusEffect::Stun, 1),
+    }
+}
 
 /// Apply pre-combat abilities (like Boost)
 pub fn apply_pre_combat(unit1: &mut Unit, unit2: &mut Unit) {
@@ -26,6 +200,48 @@ pub fn apply_post_combat(unit1: &mut Unit, unit2: &mut Unit) {
     }
 }
 
+/// Attack and defense for this round after status effects, leaving the unit's stats as is
+pub fn combat_stats(unit: &Unit) -> (u8, u8) {
+    // Stunned units deal no damage
+    let attack = if unit.has_status(StatusEffect::Stun) {
+        0
+    } else {
+        unit.attack
+    };
+
+    // Burning units lose half their defense
+    let defense = if unit.has_status(StatusEffect::Burn) {
+        unit.defense / 2
+    } else {
+        unit.defense
+    };
+
+    (attack, defense)
+}
+
+/// Check if the unit takes no damage this round, from its ability or a status effect
+pub fn is_shielded(unit: &Unit) -> bool {
+    unit.ability == Ability::Shield || unit.has_status(StatusEffect::Shield)
+}
+
+/// Resolve damage and healing over time, then count the round off every effect
+pub fn tick_status_effects(unit: &mut Unit) {
+    let effects = unit.status_effects;
+    for active in effects.iter() {
+        if !unit.is_alive() {
+            break;
+        }
+        match active.effect {
+            StatusEffect::Poison => unit.take_damage((unit.max_health / 8).max(1)),
+            StatusEffect::Burn => unit.take_damage((unit.max_health / 10).max(1)),
+            StatusEffect::Regen => unit.heal((unit.max_health / 8).max(1)),
+            StatusEffect::Stun | StatusEffect::Shield => {}
+        }
+    }
+
+    unit.status_effects.tick();
+}
+
 /// Get ability description for UI display
 pub fn get_ability_description(ability: Ability) -> &'static str {
     match ability {
@@ -59,6 +275,8 @@ pub fn has_post_combat_effect(ability: Ability) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::classes::UnitClass;
+    use crate::game_state::StatusEffects;
 
     #[test]
     fn test_boost_doubles_attack() {
@@ -68,6 +286,8 @@ mod tests {
             health: 20,
             max_health: 20,
             ability: Ability::Boost,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let mut unit2 = Unit {
@@ -76,6 +296,8 @@ mod tests {
             health: 15,
             max_health: 15,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         apply_pre_combat(&mut unit1, &mut unit2);
@@ -92,6 +314,8 @@ mod tests {
             health: 10, // Damaged
             max_health: 40,
             ability: Ability::Heal,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let mut unit2 = Unit {
@@ -100,6 +324,8 @@ mod tests {
             health: 5, // Damaged
             max_health: 20,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         apply_post_combat(&mut unit1, &mut unit2);
@@ -118,6 +344,8 @@ mod tests {
             health: 35, // Close to max
             max_health: 40,
             ability: Ability::Heal,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let mut dummy = Unit::default();
@@ -135,6 +363,8 @@ mod tests {
             health: 0, // Dead
             max_health: 40,
             ability: Ability::Heal,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let mut dummy = Unit::default();
@@ -144,6 +374,38 @@ mod tests {
         assert_eq!(unit.health, 0);
     }
 
+    #[test]
+    fn test_ability_ledger_enforces_cooldowns_and_mana() {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let mut ledger = AbilityLedger::default();
+
+        assert_eq!(
+            ledger.use_abilities(1, &keys(&["Boost"])).unwrap(),
+            vec![AbilityId::Boost]
+        );
+        assert_eq!(ledger.mana(), 0);
+
+        // Boost is on cooldown in round 2, and a rejected round costs nothing
+        assert!(ledger.use_abilities(2, &keys(&["boost"])).is_err());
+        assert!(ledger
+            .use_abilities(2, &keys(&["heal", "fireball"]))
+            .is_err());
+        assert_eq!(ledger.mana(), 0);
+
+        // Round 3 banks 4 mana: boost is available again but stun is too expensive after it
+        assert!(ledger.use_abilities(3, &keys(&["boost", "stun"])).is_err());
+        ledger.use_abilities(3, &keys(&["poison"])).unwrap();
+        assert_eq!(ledger.mana(), 1);
+        assert!(ledger.use_abilities(3, &[]).is_err());
+
+        // Poison lands on the opposing unit
+        let mut user = Unit::default();
+        let mut opponent = Unit::default();
+        apply_activated(AbilityId::Poison, &mut user, &mut opponent);
+        assert!(opponent.has_status(StatusEffect::Poison));
+        assert!(user.status_effects.is_empty());
+    }
+
     #[test]
     fn test_ability_descriptions() {
         assert_eq!(get_ability_name(Ability::None), "None");
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game_state::{Ability, GameLogicError, Unit};
+use crate::league::{self, LeagueDefinition};
+
+/// Most units an army may field
+pub const MAX_ARMY_SIZE: usize = 8;
+
+/// Point budget of the built-in leagues, which every army either generator produces fits within
+pub const DEFAULT_ARMY_BUDGET: u32 = 1024;
+
+/// Why an army is not allowed in a league
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ArmyValidationError {
+    UnknownLeague(u8),
+    Empty,
+    TooManyUnits { count: usize, max: usize },
+    InvalidUnit { index: usize, reason: String },
+    BannedAbility { index: usize, ability: Ability },
+    OverBudget { cost: u32, budget: u32 },
+}
+
+impl std::fmt::Display for ArmyValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArmyValidationError::UnknownLeague(id) => write!(f, "Unknown league {id}"),
+            ArmyValidationError::Empty => write!(f, "Army has no units"),
+            ArmyValidationError::TooManyUnits { count, max } => {
+                write!(f, "Army has {count} units, at most {max} allowed")
+            }
+            ArmyValidationError::InvalidUnit { index, reason } => {
+                write!(f, "Unit {index} is invalid: {reason}")
+            }
+            ArmyValidationError::BannedAbility { index, ability } => {
+                write!(f, "Unit {index} has banned ability {ability:?}")
+            }
+            ArmyValidationError::OverBudget { cost, budget } => {
+                write!(f, "Army costs {cost} points, budget is {budget}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArmyValidationError {}
+
+impl From<ArmyValidationError> for GameLogicError {
+    fn from(error: ArmyValidationError) -> Self {
+        GameLogicError::InvalidInput(error.to_string())
+    }
+}
+
+/// Points a unit costs: its attack and defense, half its max health, plus its ability
+pub fn unit_cost(unit: &Unit) -> u32 {
+    let ability_cost = match unit.ability {
+        Ability::None => 0,
+        Ability::Boost => 4,
+        Ability::Heal => 5,
+        Ability::Shield => 6,
+    };
+    u32::from(unit.attack) + u32::from(unit.defense) + u32::from(unit.max_health) / 2 + ability_cost
+}
+
+/// Points the whole army costs
+pub fn army_cost(units: &[Unit]) -> u32 {
+    units.iter().map(unit_cost).sum()
+}
+
+/// Check an army against the active registry's rules for the league
+pub fn validate_army(units: &[Unit], league_id: u8) -> Result<(), ArmyValidationError> {
+    let league = league::active_registry()
+        .get(league_id)
+        .ok_or(ArmyValidationError::UnknownLeague(league_id))?;
+    validate_army_for(units, league)
+}
+
+/// Check an army against a league's size, unit, ability and budget rules
+pub fn validate_army_for(
+    units: &[Unit],
+    league: &LeagueDefinition,
+) -> Result<(), ArmyValidationError> {
+    if units.is_empty() {
+        return Err(ArmyValidationError::Empty);
+    }
+    if units.len() > MAX_ARMY_SIZE {
+        return Err(ArmyValidationError::TooManyUnits {
+            count: units.len(),
+            max: MAX_ARMY_SIZE,
+        });
+    }
+
+    for (index, unit) in units.iter().enumerate() {
+        if unit.max_health == 0 || unit.health > unit.max_health {
+            return Err(ArmyValidationError::InvalidUnit {
+                index,
+                reason: format!("health {}/{}", unit.health, unit.max_health),
+            });
+        }
+        if unit.ability != Ability::None && league.banned_abilities.contains(&unit.ability) {
+            return Err(ArmyValidationError::BannedAbility {
+                index,
+                ability: unit.ability,
+            });
+        }
+    }
+
+    match league.army_budget {
+        Some(budget) if army_cost(units) > budget => Err(ArmyValidationError::OverBudget {
+            cost: army_cost(units),
+            budget,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::generate_units_from_token_secret;
+    use crate::league::LeagueRegistry;
+
+    #[test]
+    fn test_armies_are_checked_against_league_budget() {
+        // Generated armies always fit the built-in leagues
+        for league_id in 0..4 {
+            let army = generate_units_from_token_secret("token_secret", league_id);
+            assert_eq!(validate_army(&army, league_id), Ok(()));
+        }
+
+        let registry = LeagueRegistry::from_toml_str(
+            r#"
+            [[leagues]]
+            id = 9
+            name = "Budget League"
+            banned_abilities = ["Shield"]
+            army_budget = 100
+            "#,
+        )
+        .unwrap();
+        let league = registry.get(9).unwrap();
+
+        let soldier = Unit::new(20, 10, 30, 30, Ability::None);
+        assert_eq!(unit_cost(&soldier), 45);
+        assert_eq!(validate_army_for(&[soldier, soldier], league), Ok(()));
+        assert_eq!(
+            validate_army_for(&[soldier; 3], league),
+            Err(ArmyValidationError::OverBudget {
+                cost: 135,
+                budget: 100
+            })
+        );
+
+        let guard = Unit::new(10, 10, 20, 20, Ability::Shield);
+        assert_eq!(
+            validate_army_for(&[soldier, guard], league),
+            Err(ArmyValidationError::BannedAbility {
+                index: 1,
+                ability: Ability::Shield
+            })
+        );
+        assert_eq!(
+            validate_army_for(&[soldier; 9], league),
+            Err(ArmyValidationError::TooManyUnits { count: 9, max: 8 })
+        );
+        assert_eq!(
+            validate_army_for(&[], league),
+            Err(ArmyValidationError::Empty)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game_state::{GameLogicError, RoundResult};
+use crate::league;
+
+/// Matrix version combat uses unless a ruleset file configures its own
+pub const CLASS_MATRIX_VERSION: u32 = 1;
+
+/// Combat class of a unit; every class hits one class hard and is countered by another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UnitClass {
+    #[default]
+    Warrior, // Strong against Archer
+    Archer, // Strong against Mage
+    Mage,   // Strong against Tank
+    Tank,   // Strong against Warrior
+}
+
+impl UnitClass {
+    pub const ALL: [UnitClass; 4] = [
+        UnitClass::Warrior,
+        UnitClass::Archer,
+        UnitClass::Mage,
+        UnitClass::Tank,
+    ];
+
+    /// Class picked by a byte of a unit's generation seed
+    pub fn from_byte(byte: u8) -> Self {
+        Self::ALL[(byte % 4) as usize]
+    }
+}
+
+/// Damage multipliers between classes, in percent
+///
+/// The version goes into every `RoundResult`, so validators can tell a client
+/// resolved combat with a different matrix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassMatrix {
+    pub version: u32,
+    pub multipliers: [[u16; 4]; 4], // [attacker class][defender class], in `UnitClass::ALL` order
+}
+
+impl Default for ClassMatrix {
+    fn default() -> Self {
+        Self {
+            version: CLASS_MATRIX_VERSION,
+            multipliers: [
+                [100, 150, 100, 75], // Warrior
+                [75, 100, 150, 100], // Archer
+                [100, 75, 100, 150], // Mage
+                [150, 100, 75, 100], // Tank
+            ],
+        }
+    }
+}
+
+impl ClassMatrix {
+    pub fn multiplier(&self, attacker: UnitClass, defender: UnitClass) -> u16 {
+        self.multipliers[attacker as usize][defender as usize]
+    }
+
+    /// Scale damage that got through defense by the attacker's matchup
+    pub fn apply(&self, damage: u8, attacker: UnitClass, defender: UnitClass) -> u8 {
+        let scaled = u32::from(damage) * u32::from(self.multiplier(attacker, defender)) / 100;
+        scaled.min(u32::from(u8::MAX)) as u8
+    }
+
+    /// Reject a round resolved with another version of the matrix
+    pub fn check_version(&self, result: &RoundResult) -> Result<(), GameLogicError> {
+        if result.class_matrix_version == self.version {
+            Ok(())
+        } else {
+            Err(GameLogicError::InvalidInput(format!(
+                "Round {} was resolved with class matrix v{}, expected v{}",
+                result.round, result.class_matrix_version, self.version
+            )))
+        }
+    }
+}
+
+/// Matrix of the active league registry
+pub fn active_class_matrix() -> &'static ClassMatrix {
+    league::active_registry().class_matrix()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::process_combat;
+    use crate::game_state::{Ability, Unit};
+    use crate::league::LeagueRegistry;
+
+    #[test]
+    fn test_class_matchups_scale_damage() {
+        let mut archer = Unit::new(30, 10, 50, 50, Ability::None);
+        archer.class = UnitClass::Archer;
+        let mut mage = Unit::new(30, 10, 50, 50, Ability::None);
+        mage.class = UnitClass::Mage;
+
+        // The archer counters the mage: 20 through defense, 150% and 75% of it
+        let result = process_combat(archer, mage, "p1", "p2").unwrap();
+        assert_eq!(result.damage_dealt, [30, 15]);
+        assert_eq!(result.class_matrix_version, CLASS_MATRIX_VERSION);
+        assert!(active_class_matrix().check_version(&result).is_ok());
+
+        let registry = LeagueRegistry::from_toml_str(
+            r#"
+            [[leagues]]
+            id = 0
+            name = "Flat League"
+
+            [class_matrix]
+            version = 2
+            multipliers = [[100, 100, 100, 100], [100, 100, 100, 100], [100, 100, 100, 100], [100, 100, 100, 100]]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            registry
+                .class_matrix()
+                .apply(20, UnitClass::Archer, UnitClass::Mage),
+            20
+        );
+        assert!(registry.class_matrix().check_version(&result).is_err());
+    }
+}
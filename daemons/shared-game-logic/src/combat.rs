@@ -1,7 +1,12 @@
-use crate::abilities;
-use crate::game_state::{Ability, GameLogicError, RoundResult, Unit};
+use crate::abilities::{self, AbilityId};
+use crate::classes::{self, UnitClass};
+use crate::game_state::{Ability, GameLogicError, RoundResult, StatusEffects, Unit};
+use crate::grid::{self, Board};
 use crate::league;
+use crate::rng::{self, AttackRoll};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 
 /// Generate a complete army from a Cashu token C value (deterministic)
 /// Uses 256-bit unblinded signature C value from Cashu mint for tamper-proof randomness
@@ -78,6 +83,8 @@ fn generate_unit_from_seed(seed: u64, league_id: u8) -> Unit {
         health: base_health,
         max_health: base_health,
         ability: ability_from_c_value(ability_selector, unit_type),
+        status_effects: StatusEffects::default(),
+        class: UnitClass::from_byte(unit_type),
     };
 
     // Apply league scaling (maintains existing league mechanics)
@@ -115,6 +122,8 @@ pub fn generate_units_from_token_secret(token_secret: &str, league_id: u8) -> [U
             health: base_health,
             max_health: base_health,
             ability: ability_from_byte(ability_byte),
+            status_effects: StatusEffects::default(),
+            class: UnitClass::from_byte(ability_byte / 4), // Bits the ability doesn't use
         };
 
         // Apply league modifiers
@@ -126,37 +135,246 @@ pub fn generate_units_from_token_secret(token_secret: &str, league_id: u8) -> [U
     units
 }
 
+/// Army generator of challenges that name none, so matches created before v2 still validate
+pub const ARMY_GENERATOR_V1: u8 = 1;
+/// Eight units with rarity tiers, drawn from all 256 bits of the C value
+pub const ARMY_GENERATOR_V2: u8 = 2;
+
+/// Rarity tier of a v2 unit, scaling its base stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common, // 60%
+    Rare,      // 25%
+    Epic,      // 12%
+    Legendary, // 3%
+}
+
+impl Rarity {
+    /// Tier for a roll in 0..100
+    pub fn from_roll(roll: u8) -> Self {
+        match roll {
+            0..=59 => Rarity::Common,
+            60..=84 => Rarity::Rare,
+            85..=96 => Rarity::Epic,
+            _ => Rarity::Legendary,
+        }
+    }
+
+    /// Base stat scaling in percent
+    pub fn stat_percent(self) -> u32 {
+        match self {
+            Rarity::Common => 100,
+            Rarity::Rare => 115,
+            Rarity::Epic => 130,
+            Rarity::Legendary => 150,
+        }
+    }
+}
+
+/// Unit attributes packed in 32 bits of a C value, read as mixed-radix digits
+struct UnitRollV2 {
+    attack: u8,
+    defense: u8,
+    health: u8,
+    ability_selector: u8,
+    unit_type: u8,
+    rarity: Rarity,
+}
+
+impl UnitRollV2 {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut bits = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let mut digit = |radix: u32| {
+            let value = bits % radix;
+            bits /= radix;
+            value as u8
+        };
+
+        Self {
+            attack: digit(20) + 10, // 10-29 base attack
+            defense: digit(15) + 5, // 5-19 base defense
+            health: digit(30) + 20, // 20-49 base health
+            ability_selector: digit(16),
+            unit_type: digit(8),
+            rarity: Rarity::from_roll(digit(100)),
+        }
+    }
+}
+
+/// Rarity of each unit `generate_army_v2` derives from the C value
+pub fn army_rarities_v2(c_value_bytes: &[u8; 32]) -> [Rarity; 8] {
+    let mut rarities = [Rarity::default(); 8];
+    for (rarity, chunk) in rarities.iter_mut().zip(c_value_bytes.chunks(4)) {
+        *rarity = UnitRollV2::from_bytes(chunk).rarity;
+    }
+    rarities
+}
+
+/// Generate an 8-unit army from a 256-bit C value, 32 bits per unit, with rarity tiers
+pub fn generate_army_v2(c_value_bytes: &[u8; 32], league_id: u8) -> [Unit; 8] {
+    let mut units = [Unit::default(); 8];
+    for (unit, chunk) in units.iter_mut().zip(c_value_bytes.chunks(4)) {
+        let roll = UnitRollV2::from_bytes(chunk);
+        let scale = |stat: u8| (u32::from(stat) * roll.rarity.stat_percent() / 100) as u8;
+        let health = scale(roll.health);
+
+        *unit = Unit {
+            attack: scale(roll.attack),
+            defense: scale(roll.defense),
+            health,
+            max_health: health,
+            ability: ability_from_c_value(roll.ability_selector, roll.unit_type),
+            status_effects: StatusEffects::default(),
+            class: UnitClass::from_byte(roll.unit_type),
+        };
+        league::apply_modifiers(unit, league_id);
+    }
+    units
+}
+
+/// Whether this build can generate armies with the generator version
+pub fn supports_army_generator(generator_version: u8) -> bool {
+    matches!(generator_version, ARMY_GENERATOR_V1 | ARMY_GENERATOR_V2)
+}
+
+/// Army for a revealed token secret under the challenge's generator version
+///
+/// v1 is `generate_units_from_token_secret`; v2 runs `generate_army_v2` on the
+/// secret's SHA-256 rather than the token's C value. Reveals carry only the committed
+/// secrets, and the engine cannot check a C value a player claims without the mint's
+/// DLEQ proof (NUT-12), so seeding from it would let players pick their own army.
+pub fn generate_army_for_version(
+    token_secret: &str,
+    league_id: u8,
+    generator_version: u8,
+) -> Result<[Unit; 8], GameLogicError> {
+    match generator_version {
+        ARMY_GENERATOR_V1 => Ok(generate_units_from_token_secret(token_secret, league_id)),
+        ARMY_GENERATOR_V2 => {
+            let c_value: [u8; 32] = Sha256::digest(token_secret.as_bytes()).into();
+            Ok(generate_army_v2(&c_value, league_id))
+        }
+        version => Err(GameLogicError::InvalidInput(format!(
+            "Unknown army generator version {version}"
+        ))),
+    }
+}
+
 /// Process combat between two units using identical server logic
 pub fn process_combat(
+    unit1: Unit,
+    unit2: Unit,
+    player1_npub: &str,
+    player2_npub: &str,
+) -> Result<RoundResult, GameLogicError> {
+    let rolls = [AttackRoll::default(); 2];
+    resolve_combat(
+        unit1,
+        unit2,
+        [true, true],
+        rolls,
+        player1_npub,
+        player2_npub,
+    )
+}
+
+/// Process combat with crit and dodge rolls, e.g. from a `CombatRng` seeded by the round's nonces
+pub fn process_combat_with_rolls(
+    unit1: Unit,
+    unit2: Unit,
+    rolls: [AttackRoll; 2],
+    player1_npub: &str,
+    player2_npub: &str,
+) -> Result<RoundResult, GameLogicError> {
+    resolve_combat(
+        unit1,
+        unit2,
+        [true, true],
+        rolls,
+        player1_npub,
+        player2_npub,
+    )
+}
+
+/// Process combat between two units standing on the board
+///
+/// Positions must be distinct passable tiles. Activated abilities and rolls apply as in
+/// `process_combat_with_abilities`, but a unit only deals damage if the enemy is within
+/// its range and in its line of sight.
+///
+/// Only clients that lay their armies out on a board use this: the engine's verifier,
+/// `replay_match` and `validate_full_match` field one unit per round by army index
+/// (`unit_positions[0] % 8`), always in range of each other.
+pub fn process_combat_on_board(
+    board: &Board,
+    [(unit1, position1), (unit2, position2)]: [(Unit, u8); 2],
+    activated: [&[AbilityId]; 2],
+    rolls: [AttackRoll; 2],
+    player1_npub: &str,
+    player2_npub: &str,
+) -> Result<RoundResult, GameLogicError> {
+    for position in [position1, position2] {
+        if !board.is_passable(position) {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Tile {position} cannot hold a unit"
+            )));
+        }
+    }
+    if position1 == position2 {
+        return Err(GameLogicError::InvalidInput(format!(
+            "Both units are on tile {position1}"
+        )));
+    }
+
+    let in_range = [
+        board.in_range(position1, position2, grid::profile(&unit1).range),
+        board.in_range(position2, position1, grid::profile(&unit2).range),
+    ];
+    let (unit1, unit2) = apply_activated(unit1, unit2, activated);
+    resolve_combat(unit1, unit2, in_range, rolls, player1_npub, player2_npub)
+}
+
+/// One round of combat, where each unit only hits the other if `in_range`
+///
+/// `rolls[0]` is for unit1's attack on unit2 and `rolls[1]` for unit2's on unit1.
+fn resolve_combat(
     mut unit1: Unit,
     mut unit2: Unit,
+    in_range: [bool; 2],
+    rolls: [AttackRoll; 2],
     player1_npub: &str,
     player2_npub: &str,
 ) -> Result<RoundResult, GameLogicError> {
-    // Store original units for result
-    let _original_unit1 = unit1;
-    let _original_unit2 = unit2;
-
-    // Apply pre-combat abilities
+    // Apply pre-combat abilities, then status effects (Stun, Burn) for this round only
     abilities::apply_pre_combat(&mut unit1, &mut unit2);
+    let (attack1, defense1) = abilities::combat_stats(&unit1);
+    let (attack2, defense2) = abilities::combat_stats(&unit2);
 
-    // Calculate damage (attack - defense, minimum 0)
-    let damage_to_unit2 = if unit2.ability == Ability::Shield {
-        0 // Shield negates all damage
+    // Calculate damage (attack - defense, minimum 0), scaled by the class matchup
+    let matrix = classes::active_class_matrix();
+    let damage_to_unit2 = if abilities::is_shielded(&unit2) || !in_range[0] || rolls[0].dodged {
+        0 // Shield negates all damage, out of range units can't attack, dodges miss
     } else {
-        unit1.attack.saturating_sub(unit2.defense)
+        let damage = matrix.apply(attack1.saturating_sub(defense2), unit1.class, unit2.class);
+        critical(damage, rolls[0])
     };
 
-    let damage_to_unit1 = if unit1.ability == Ability::Shield {
-        0 // Shield negates all damage
+    let damage_to_unit1 = if abilities::is_shielded(&unit1) || !in_range[1] || rolls[1].dodged {
+        0 // Shield negates all damage, out of range units can't attack, dodges miss
     } else {
-        unit2.attack.saturating_sub(unit1.defense)
+        let damage = matrix.apply(attack2.saturating_sub(defense1), unit2.class, unit1.class);
+        critical(damage, rolls[1])
     };
 
     // Apply damage
     unit1.take_damage(damage_to_unit1);
     unit2.take_damage(damage_to_unit2);
 
+    // Damage and healing over time; not counted in damage_dealt
+    abilities::tick_status_effects(&mut unit1);
+    abilities::tick_status_effects(&mut unit2);
+
     // Apply post-combat abilities (healing)
     abilities::apply_post_combat(&mut unit1, &mut unit2);
 
@@ -169,9 +387,45 @@ pub fn process_combat(
         player2_unit: unit2,
         damage_dealt: [damage_to_unit2, damage_to_unit1],
         winner,
+        class_matrix_version: matrix.version,
     })
 }
 
+/// Critical hits double the damage that got through defense
+fn critical(damage: u8, roll: AttackRoll) -> u8 {
+    if roll.critical {
+        damage.saturating_mul(2)
+    } else {
+        damage
+    }
+}
+
+/// Process combat after applying the abilities each player activated this round
+///
+/// Abilities should already be validated against the players' `AbilityLedger`s.
+pub fn process_combat_with_abilities(
+    unit1: Unit,
+    unit2: Unit,
+    activated: [&[AbilityId]; 2],
+    rolls: [AttackRoll; 2],
+    player1_npub: &str,
+    player2_npub: &str,
+) -> Result<RoundResult, GameLogicError> {
+    let (unit1, unit2) = apply_activated(unit1, unit2, activated);
+    process_combat_with_rolls(unit1, unit2, rolls, player1_npub, player2_npub)
+}
+
+/// Apply each player's activated abilities, player 1's first
+fn apply_activated(mut unit1: Unit, mut unit2: Unit, activated: [&[AbilityId]; 2]) -> (Unit, Unit) {
+    for ability in activated[0] {
+        abilities::apply_activated(*ability, &mut unit1, &mut unit2);
+    }
+    for ability in activated[1] {
+        abilities::apply_activated(*ability, &mut unit2, &mut unit1);
+    }
+    (unit1, unit2)
+}
+
 /// Determine the winner of a combat round
 fn determine_round_winner(
     unit1: &Unit,
@@ -196,6 +450,37 @@ fn determine_round_winner(
     }
 }
 
+/// Whether both fighting units fell in the round, leaving it for `break_tie`
+pub fn is_double_knockout(result: &RoundResult) -> bool {
+    !result.player1_unit.is_alive() && !result.player2_unit.is_alive()
+}
+
+/// Winner of a double-knockout round, as a player index
+///
+/// Whoever has more health left across their army's unfallen units takes the round;
+/// if that is level too, a coin flip seeded by both players' move nonces decides.
+pub fn break_tie(
+    armies: [&[Unit; 8]; 2],
+    fallen: [[bool; 8]; 2],
+    nonces: [&str; 2],
+    round: u32,
+) -> u8 {
+    let remaining = |player: usize| -> u32 {
+        armies[player]
+            .iter()
+            .zip(fallen[player])
+            .filter(|(_, fallen)| !fallen)
+            .map(|(unit, _)| u32::from(unit.health))
+            .sum()
+    };
+
+    match remaining(0).cmp(&remaining(1)) {
+        Ordering::Greater => 0,
+        Ordering::Less => 1,
+        Ordering::Equal => rng::tie_break_flip(nonces, round),
+    }
+}
+
 /// Convert a byte to an ability (deterministic)
 /// Generate ability from C value-derived selector and unit type
 /// Provides more sophisticated ability selection based on Cashu randomness
@@ -265,6 +550,45 @@ pub fn simulate_match(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::game_state::StatusEffect;
+
+    #[test]
+    fn test_army_generator_versions() {
+        let secret = "generator_secret";
+        assert_eq!(
+            generate_army_for_version(secret, 0, ARMY_GENERATOR_V1).unwrap(),
+            generate_units_from_token_secret(secret, 0)
+        );
+        assert!(generate_army_for_version(secret, 0, 3).is_err());
+
+        // Tiers land near their odds across many C values
+        let mut counts = [0usize; 4];
+        for i in 0..1000u32 {
+            let c_value: [u8; 32] = Sha256::digest(i.to_le_bytes()).into();
+            for rarity in army_rarities_v2(&c_value) {
+                counts[rarity as usize] += 1;
+            }
+        }
+        assert!((4400..5200).contains(&counts[0]), "{counts:?}");
+        assert!((1700..2300).contains(&counts[1]), "{counts:?}");
+        assert!((750..1200).contains(&counts[2]), "{counts:?}");
+        assert!((120..380).contains(&counts[3]), "{counts:?}");
+
+        // Strongest possible unit: top stats, Shield, legendary; a full army still fits every league
+        let top = 19 + 20 * (14 + 15 * (29 + 30 * (7 + 16 * (2 + 8 * 99))));
+        let c_value: [u8; 32] = std::array::from_fn(|i| (top as u32).to_le_bytes()[i % 4]);
+        assert_eq!(army_rarities_v2(&c_value), [Rarity::Legendary; 8]);
+        for league_id in 0..4 {
+            let army = generate_army_v2(&c_value, league_id);
+            assert_eq!(army[0].ability, Ability::Shield);
+            assert_eq!(crate::army::validate_army(&army, league_id), Ok(()));
+        }
+        let army = generate_army_v2(&c_value, 1); // Ice League: +20 health only
+        assert_eq!(
+            (army[0].attack, army[0].defense, army[0].max_health),
+            (43, 28, 93)
+        );
+    }
 
     #[test]
     fn test_deterministic_unit_generation() {
@@ -295,6 +619,8 @@ mod tests {
             health: 50,
             max_health: 50,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let unit2 = Unit {
@@ -303,6 +629,8 @@ mod tests {
             health: 40,
             max_health: 40,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let result = process_combat(unit1, unit2, "player1", "player2").unwrap();
@@ -322,6 +650,8 @@ mod tests {
             health: 50,
             max_health: 50,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let unit2 = Unit {
@@ -330,6 +660,8 @@ mod tests {
             health: 40,
             max_health: 40,
             ability: Ability::Shield,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let result = process_combat(unit1, unit2, "player1", "player2").unwrap();
@@ -348,6 +680,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: Ability::Boost,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let unit2 = Unit {
@@ -356,6 +690,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let result = process_combat(unit1, unit2, "player1", "player2").unwrap();
@@ -367,6 +703,34 @@ mod tests {
         assert_eq!(result.winner, Some("player1".to_string()));
     }
 
+    #[test]
+    fn test_combat_status_effects() {
+        let mut unit1 = Unit::new(20, 10, 40, 40, Ability::None);
+        unit1.apply_status(StatusEffect::Stun, 1);
+        let mut unit2 = Unit::new(15, 5, 40, 40, Ability::None);
+        unit2.apply_status(StatusEffect::Poison, 2);
+
+        let result = process_combat(unit1, unit2, "player1", "player2").unwrap();
+
+        // Stunned unit1 deals no damage; poison takes 40/8=5 from unit2 after combat
+        assert_eq!(result.damage_dealt, [0, 5]);
+        assert_eq!(result.player1_unit.health, 35);
+        assert_eq!(result.player2_unit.health, 35);
+        assert_eq!(result.winner, None);
+
+        // Stun ran out, one round of poison is left
+        assert!(!result.player1_unit.has_status(StatusEffect::Stun));
+        let result = process_combat(
+            result.player1_unit,
+            result.player2_unit,
+            "player1",
+            "player2",
+        )
+        .unwrap();
+        assert_eq!(result.player2_unit.health, 35 - 15 - 5);
+        assert!(result.player2_unit.status_effects.is_empty());
+    }
+
     #[test]
     fn test_combat_heal_ability() {
         let unit1 = Unit {
@@ -375,6 +739,8 @@ mod tests {
             health: 20,
             max_health: 40,
             ability: Ability::Heal,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let unit2 = Unit {
@@ -383,6 +749,8 @@ mod tests {
             health: 20,
             max_health: 40,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         };
 
         let result = process_combat(unit1, unit2, "player1", "player2").unwrap();
@@ -393,4 +761,31 @@ mod tests {
         assert_eq!(result.player2_unit.health, 15);
         assert_eq!(result.winner, Some("player1".to_string()));
     }
+
+    #[test]
+    fn test_double_knockout_tie_break() {
+        let glass = Unit::new(30, 0, 10, 10, Ability::None);
+        let result = process_combat(glass, glass, "player1", "player2").unwrap();
+        assert!(is_double_knockout(&result));
+        assert_eq!(result.winner, None);
+
+        // More health left across the unfallen units wins
+        let army1 = [Unit::new(10, 5, 20, 20, Ability::None); 8];
+        let army2 = [Unit::new(10, 5, 15, 15, Ability::None); 8];
+        let mut fallen = [[false; 8]; 2];
+        fallen[0][0] = true;
+        assert_eq!(break_tie([&army1, &army2], fallen, ["a", "b"], 1), 0);
+        fallen[0][..3].fill(true);
+        assert_eq!(break_tie([&army1, &army2], fallen, ["a", "b"], 3), 1);
+
+        // Level on health, the nonce-seeded flip decides and lands both ways
+        let flips: Vec<u8> = (1..=32)
+            .map(|round| break_tie([&army1, &army1], [[false; 8]; 2], ["a", "b"], round))
+            .collect();
+        assert!(flips.contains(&0) && flips.contains(&1));
+        assert_eq!(
+            flips[0],
+            break_tie([&army1, &army1], [[false; 8]; 2], ["a", "b"], 1)
+        );
+    }
 }
@@ -17,6 +17,19 @@ pub enum CommitmentType {
     CashuTokens, // Commitment to Cashu token secrets
     Army,        // Commitment to generated army
     Moves,       // Commitment to round moves (positions + abilities)
+    Bans,        // Commitment to draft-phase unit bans
+}
+
+impl CommitmentType {
+    /// Domain separation tag, so identical bytes committed as different types never collide
+    pub fn domain_tag(&self) -> &'static [u8] {
+        match self {
+            CommitmentType::CashuTokens => b"manastr/cashu_tokens",
+            CommitmentType::Army => b"manastr/army",
+            CommitmentType::Moves => b"manastr/moves",
+            CommitmentType::Bans => b"manastr/bans",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +47,32 @@ pub fn create_commitment(data: &str, nonce: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Marks a commitment over the canonical encoding; bare hex commitments are v1
+pub const COMMITMENT_V2_PREFIX: &str = "v2:";
+const CANONICAL_ENCODING_VERSION: u8 = 2;
+
+/// Canonical byte encoding of committed fields
+///
+/// A version byte, then the type's domain tag and every field, each prefixed with
+/// its length as a big-endian u32. Unlike the v1 JSON-and-concatenate format, no
+/// field boundary or formatting choice is left to the client.
+pub fn canonical_encoding(data_type: &CommitmentType, fields: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = vec![CANONICAL_ENCODING_VERSION];
+    for field in std::iter::once(data_type.domain_tag()).chain(fields.iter().copied()) {
+        bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(field);
+    }
+    bytes
+}
+
+/// v2 commitment to fields under a type's domain; the nonce is the last field
+pub fn create_commitment_v2(data_type: &CommitmentType, fields: &[&[u8]], nonce: &str) -> String {
+    let mut all_fields = fields.to_vec();
+    all_fields.push(nonce.as_bytes());
+    let hash = Sha256::digest(canonical_encoding(data_type, &all_fields));
+    format!("{COMMITMENT_V2_PREFIX}{hash:x}")
+}
+
 /// Verify that revealed data matches the original commitment
 pub fn verify_commitment(commitment: &str, revealed_data: &str, nonce: &str) -> bool {
     let computed_commitment = create_commitment(revealed_data, nonce);
@@ -66,14 +105,54 @@ pub fn commit_to_moves(positions: &[u8], abilities: &[String], nonce: &str) -> S
     create_commitment(&moves_data, nonce)
 }
 
+/// v2 commitment to Cashu token secrets, one field per secret
+pub fn commit_to_cashu_tokens_v2(token_secrets: &[String], nonce: &str) -> String {
+    let fields: Vec<&[u8]> = token_secrets.iter().map(|s| s.as_bytes()).collect();
+    create_commitment_v2(&CommitmentType::CashuTokens, &fields, nonce)
+}
+
+/// v2 commitment to army data
+pub fn commit_to_army_v2(army_data: &str, nonce: &str) -> String {
+    create_commitment_v2(&CommitmentType::Army, &[army_data.as_bytes()], nonce)
+}
+
+/// v2 commitment to round moves: the positions as one field, then one field per ability
+pub fn commit_to_moves_v2(positions: &[u8], abilities: &[String], nonce: &str) -> String {
+    let fields: Vec<&[u8]> = std::iter::once(positions)
+        .chain(abilities.iter().map(|a| a.as_bytes()))
+        .collect();
+    create_commitment_v2(&CommitmentType::Moves, &fields, nonce)
+}
+
+/// Commitment to the opponent's unit indices a player bans in the draft phase
+///
+/// Bans postdate the v1 format, so they are only ever committed as v2.
+pub fn commit_to_bans(banned_units: &[u8], nonce: &str) -> String {
+    create_commitment_v2(&CommitmentType::Bans, &[banned_units], nonce)
+}
+
+/// Verify a draft-phase ban commitment
+pub fn verify_bans_commitment(commitment: &str, banned_units: &[u8], nonce: &str) -> bool {
+    commitment == commit_to_bans(banned_units, nonce)
+}
+
+// Verifiers accept both formats: a `v2:` commitment is checked against the
+// canonical encoding, anything else against the v1 format older clients still send
+
 /// Verify Cashu token commitment
 pub fn verify_cashu_commitment(commitment: &str, revealed_tokens: &[String], nonce: &str) -> bool {
+    if commitment.starts_with(COMMITMENT_V2_PREFIX) {
+        return commitment == commit_to_cashu_tokens_v2(revealed_tokens, nonce);
+    }
     let revealed_data = serde_json::to_string(revealed_tokens).unwrap();
     verify_commitment(commitment, &revealed_data, nonce)
 }
 
 /// Verify army commitment
 pub fn verify_army_commitment(commitment: &str, revealed_army: &str, nonce: &str) -> bool {
+    if commitment.starts_with(COMMITMENT_V2_PREFIX) {
+        return commitment == commit_to_army_v2(revealed_army, nonce);
+    }
     verify_commitment(commitment, revealed_army, nonce)
 }
 
@@ -84,10 +163,148 @@ pub fn verify_moves_commitment(
     revealed_abilities: &[String],
     nonce: &str,
 ) -> bool {
+    if commitment.starts_with(COMMITMENT_V2_PREFIX) {
+        return commitment == commit_to_moves_v2(revealed_positions, revealed_abilities, nonce);
+    }
     let revealed_data = serde_json::to_string(&(revealed_positions, revealed_abilities)).unwrap();
     verify_commitment(commitment, &revealed_data, nonce)
 }
 
+/// Inclusion proof for one token secret under a Merkle token commitment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub index: usize,          // Position of the revealed token in the committed list
+    pub leaf_count: usize,     // Tokens committed to; fixes the shape of the tree
+    pub siblings: Vec<String>, // Hex sibling hashes from the leaf level up
+}
+
+// Domain tags keep a leaf hash from ever being passed off as an inner node
+const MERKLE_LEAF_TAG: u8 = 0x00;
+const MERKLE_NODE_TAG: u8 = 0x01;
+
+fn merkle_leaf(token_secret: &str, nonce: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_LEAF_TAG]);
+    hasher.update((nonce.len() as u64).to_le_bytes());
+    hasher.update(nonce.as_bytes());
+    hasher.update(token_secret.as_bytes());
+    hasher.finalize().into()
+}
+
+fn merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([MERKLE_NODE_TAG]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Every level of the tree, leaves first; an unpaired last node is carried up unchanged
+fn merkle_levels(token_secrets: &[String], nonce: &str) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![token_secrets
+        .iter()
+        .map(|secret| merkle_leaf(secret, nonce))
+        .collect::<Vec<_>>()];
+    while levels.last().is_some_and(|level| level.len() > 1) {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_node(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+fn to_hex(hash: &[u8]) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Merkle root over Cashu token secrets, so single tokens can be revealed later
+///
+/// Unlike `commit_to_cashu_tokens`, a player can open one token with a `MerkleProof`
+/// without exposing the rest of the list. An empty list commits to the empty hash.
+pub fn commit_to_cashu_tokens_merkle(token_secrets: &[String], nonce: &str) -> String {
+    match merkle_levels(token_secrets, nonce)
+        .last()
+        .and_then(|level| level.first())
+    {
+        Some(root) => to_hex(root),
+        None => to_hex(&Sha256::digest([])),
+    }
+}
+
+/// Proof that the token at `index` is part of the Merkle token commitment
+pub fn merkle_proof(token_secrets: &[String], index: usize, nonce: &str) -> Option<MerkleProof> {
+    if index >= token_secrets.len() {
+        return None;
+    }
+
+    let mut siblings = Vec::new();
+    let mut position = index;
+    for level in merkle_levels(token_secrets, nonce) {
+        if let Some(sibling) = level.get(position ^ 1) {
+            siblings.push(to_hex(sibling));
+        }
+        position /= 2;
+    }
+
+    Some(MerkleProof {
+        index,
+        leaf_count: token_secrets.len(),
+        siblings,
+    })
+}
+
+/// Verify a single revealed token against a Merkle token commitment
+pub fn verify_merkle_reveal(
+    commitment: &str,
+    revealed_token: &str,
+    proof: &MerkleProof,
+    nonce: &str,
+) -> bool {
+    if proof.index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut hash = merkle_leaf(revealed_token, nonce);
+    let mut siblings = proof.siblings.iter();
+    let (mut position, mut width) = (proof.index, proof.leaf_count);
+    while width > 1 {
+        // The last node of an odd level has no sibling and is carried up
+        if position ^ 1 < width {
+            let Some(sibling) = siblings.next().and_then(|hex| from_hex(hex)) else {
+                return false;
+            };
+            hash = if position % 2 == 0 {
+                merkle_node(&hash, &sibling)
+            } else {
+                merkle_node(&sibling, &hash)
+            };
+        }
+        position /= 2;
+        width = width.div_ceil(2);
+    }
+
+    siblings.next().is_none() && to_hex(&hash) == commitment
+}
+
+fn from_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
 /// Hash function for Nostr event IDs and other data integrity
 pub fn hash_data(data: &str) -> String {
     let mut hasher = Sha256::new();
@@ -95,7 +312,91 @@ pub fn hash_data(data: &str) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Known-answer vector pinning the hashing rules the validator enforces
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitmentTestVector {
+    pub version: u8, // 1 for the bare-hex format, 2 for `v2:` canonical commitments
+    pub data_type: CommitmentType,
+    pub input: serde_json::Value,
+    pub nonce: String,
+    pub commitment: String,
+}
+
+/// Test vectors for every commitment builder, shared with the web client
+pub fn commitment_test_vectors() -> Vec<CommitmentTestVector> {
+    vec![
+        CommitmentTestVector {
+            version: 1,
+            data_type: CommitmentType::CashuTokens,
+            input: serde_json::json!(["token_secret_1", "token_secret_2"]),
+            nonce: "vector_nonce".to_string(),
+            commitment: "e14c0427dbe2e83e52364d8550ddbd54bc881047e4dad97ee34c85941571e5a5"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 1,
+            data_type: CommitmentType::Army,
+            input: serde_json::json!("army_data"),
+            nonce: "vector_nonce".to_string(),
+            commitment: "fc1b406d390e6a6a18fa8391faf843cfc3cc54d13c2e413ebf757f05b36431c8"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 1,
+            data_type: CommitmentType::Moves,
+            input: serde_json::json!({
+                "positions": [1, 2, 3, 4],
+                "abilities": ["boost", "shield"]
+            }),
+            nonce: "vector_nonce".to_string(),
+            commitment: "26b776cc9b8c86e1e1c7a84a537518f54e7908a6a5fb72d3f6b8b9183ce27cd7"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 2,
+            data_type: CommitmentType::CashuTokens,
+            input: serde_json::json!(["token_secret_1", "token_secret_2"]),
+            nonce: "vector_nonce".to_string(),
+            commitment: "v2:ef1a84f1846327f0ed14289b737143e2b13391ff508c44a9064768e2031fd526"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 2,
+            data_type: CommitmentType::Army,
+            input: serde_json::json!("army_data"),
+            nonce: "vector_nonce".to_string(),
+            commitment: "v2:82f189e61086b9cec8b93595b1a87d2a2c2968264e569d5876d7049b4cb2939b"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 2,
+            data_type: CommitmentType::Moves,
+            input: serde_json::json!({
+                "positions": [1, 2, 3, 4],
+                "abilities": ["boost", "shield"]
+            }),
+            nonce: "vector_nonce".to_string(),
+            commitment: "v2:8ec570f9ae2cb9a35beda333a3974be9c31c47440d81b60d2d73717a0620f0b1"
+                .to_string(),
+        },
+        CommitmentTestVector {
+            version: 2,
+            data_type: CommitmentType::Bans,
+            input: serde_json::json!([2, 5]),
+            nonce: "vector_nonce".to_string(),
+            commitment: "v2:18483da926030710abec6bebc2af48143cdf51b375b44bd68ab509668680990b"
+                .to_string(),
+        },
+    ]
+}
+
 // WASM exports for web client usage
+// Array arguments are plain JS arrays; malformed input throws instead of aborting the module
+fn strings_from_js(value: JsValue, argument: &str) -> Result<Vec<String>, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|e| JsValue::from_str(&format!("{argument} must be an array of strings: {e}")))
+}
+
 #[wasm_bindgen]
 pub fn wasm_create_commitment(data: &str, nonce: &str) -> String {
     create_commitment(data, nonce)
@@ -112,9 +413,9 @@ pub fn wasm_generate_nonce() -> String {
 }
 
 #[wasm_bindgen]
-pub fn wasm_commit_to_cashu_tokens(token_secrets: JsValue, nonce: &str) -> String {
-    let tokens: Vec<String> = serde_wasm_bindgen::from_value(token_secrets).unwrap();
-    commit_to_cashu_tokens(&tokens, nonce)
+pub fn wasm_commit_to_cashu_tokens(token_secrets: JsValue, nonce: &str) -> Result<String, JsValue> {
+    let tokens = strings_from_js(token_secrets, "token_secrets")?;
+    Ok(commit_to_cashu_tokens(&tokens, nonce))
 }
 
 #[wasm_bindgen]
@@ -122,15 +423,67 @@ pub fn wasm_verify_cashu_commitment(
     commitment: &str,
     revealed_tokens: JsValue,
     nonce: &str,
-) -> bool {
-    let tokens: Vec<String> = serde_wasm_bindgen::from_value(revealed_tokens).unwrap();
-    verify_cashu_commitment(commitment, &tokens, nonce)
+) -> Result<bool, JsValue> {
+    let tokens = strings_from_js(revealed_tokens, "revealed_tokens")?;
+    Ok(verify_cashu_commitment(commitment, &tokens, nonce))
 }
 
 #[wasm_bindgen]
-pub fn wasm_commit_to_moves(positions: &[u8], abilities: JsValue, nonce: &str) -> String {
-    let abilities_vec: Vec<String> = serde_wasm_bindgen::from_value(abilities).unwrap();
-    commit_to_moves(positions, &abilities_vec, nonce)
+pub fn wasm_commit_to_cashu_tokens_merkle(
+    token_secrets: JsValue,
+    nonce: &str,
+) -> Result<String, JsValue> {
+    let tokens = strings_from_js(token_secrets, "token_secrets")?;
+    Ok(commit_to_cashu_tokens_merkle(&tokens, nonce))
+}
+
+#[wasm_bindgen]
+pub fn wasm_merkle_proof(
+    token_secrets: JsValue,
+    index: usize,
+    nonce: &str,
+) -> Result<JsValue, JsValue> {
+    let tokens = strings_from_js(token_secrets, "token_secrets")?;
+    let proof = merkle_proof(&tokens, index, nonce)
+        .ok_or_else(|| JsValue::from_str(&format!("No token at index {index}")))?;
+    Ok(serde_wasm_bindgen::to_value(&proof)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_verify_merkle_reveal(
+    commitment: &str,
+    revealed_token: &str,
+    proof: JsValue,
+    nonce: &str,
+) -> Result<bool, JsValue> {
+    let proof: MerkleProof = serde_wasm_bindgen::from_value(proof)
+        .map_err(|e| JsValue::from_str(&format!("proof must be a Merkle proof: {e}")))?;
+    Ok(verify_merkle_reveal(
+        commitment,
+        revealed_token,
+        &proof,
+        nonce,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_army(army_data: &str, nonce: &str) -> String {
+    commit_to_army(army_data, nonce)
+}
+
+#[wasm_bindgen]
+pub fn wasm_verify_army_commitment(commitment: &str, revealed_army: &str, nonce: &str) -> bool {
+    verify_army_commitment(commitment, revealed_army, nonce)
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_moves(
+    positions: &[u8],
+    abilities: JsValue,
+    nonce: &str,
+) -> Result<String, JsValue> {
+    let abilities_vec = strings_from_js(abilities, "abilities")?;
+    Ok(commit_to_moves(positions, &abilities_vec, nonce))
 }
 
 #[wasm_bindgen]
@@ -139,9 +492,62 @@ pub fn wasm_verify_moves_commitment(
     positions: &[u8],
     abilities: JsValue,
     nonce: &str,
-) -> bool {
-    let abilities_vec: Vec<String> = serde_wasm_bindgen::from_value(abilities).unwrap();
-    verify_moves_commitment(commitment, positions, &abilities_vec, nonce)
+) -> Result<bool, JsValue> {
+    let abilities_vec = strings_from_js(abilities, "abilities")?;
+    Ok(verify_moves_commitment(
+        commitment,
+        positions,
+        &abilities_vec,
+        nonce,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn wasm_canonical_encoding(data_type: JsValue, fields: JsValue) -> Result<Vec<u8>, JsValue> {
+    let data_type: CommitmentType = serde_wasm_bindgen::from_value(data_type)
+        .map_err(|e| JsValue::from_str(&format!("data_type must be a commitment type: {e}")))?;
+    let fields = strings_from_js(fields, "fields")?;
+    let fields: Vec<&[u8]> = fields.iter().map(|f| f.as_bytes()).collect();
+    Ok(canonical_encoding(&data_type, &fields))
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_cashu_tokens_v2(
+    token_secrets: JsValue,
+    nonce: &str,
+) -> Result<String, JsValue> {
+    let tokens = strings_from_js(token_secrets, "token_secrets")?;
+    Ok(commit_to_cashu_tokens_v2(&tokens, nonce))
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_army_v2(army_data: &str, nonce: &str) -> String {
+    commit_to_army_v2(army_data, nonce)
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_moves_v2(
+    positions: &[u8],
+    abilities: JsValue,
+    nonce: &str,
+) -> Result<String, JsValue> {
+    let abilities_vec = strings_from_js(abilities, "abilities")?;
+    Ok(commit_to_moves_v2(positions, &abilities_vec, nonce))
+}
+
+#[wasm_bindgen]
+pub fn wasm_commit_to_bans(banned_units: &[u8], nonce: &str) -> String {
+    commit_to_bans(banned_units, nonce)
+}
+
+#[wasm_bindgen]
+pub fn wasm_verify_bans_commitment(commitment: &str, banned_units: &[u8], nonce: &str) -> bool {
+    verify_bans_commitment(commitment, banned_units, nonce)
+}
+
+#[wasm_bindgen]
+pub fn wasm_commitment_test_vectors() -> JsValue {
+    serde_wasm_bindgen::to_value(&commitment_test_vectors()).unwrap()
 }
 
 #[cfg(test)]
@@ -184,6 +590,54 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_merkle_commitment_reveals_single_tokens() {
+        let tokens: Vec<String> = (1..=5).map(|i| format!("token_secret_{i}")).collect();
+        let nonce = "tree_nonce";
+        let root = commit_to_cashu_tokens_merkle(&tokens, nonce);
+
+        // Every token opens on its own, including the carried-up fifth one
+        for (index, token) in tokens.iter().enumerate() {
+            let proof = merkle_proof(&tokens, index, nonce).unwrap();
+            assert!(verify_merkle_reveal(&root, token, &proof, nonce));
+        }
+
+        let proof = merkle_proof(&tokens, 1, nonce).unwrap();
+        assert!(!verify_merkle_reveal(
+            &root,
+            "token_secret_3",
+            &proof,
+            nonce
+        ));
+        assert!(!verify_merkle_reveal(
+            &root,
+            "token_secret_2",
+            &proof,
+            "wrong_nonce"
+        ));
+
+        let mut moved = proof.clone();
+        moved.index = 0;
+        assert!(!verify_merkle_reveal(
+            &root,
+            "token_secret_2",
+            &moved,
+            nonce
+        ));
+
+        let mut padded = proof;
+        padded.siblings.push(root.clone());
+        assert!(!verify_merkle_reveal(
+            &root,
+            "token_secret_2",
+            &padded,
+            nonce
+        ));
+
+        assert!(merkle_proof(&tokens, 5, nonce).is_none());
+        assert_ne!(root, commit_to_cashu_tokens_merkle(&tokens[..4], nonce));
+    }
+
     #[test]
     fn test_moves_commitment() {
         let positions = vec![1, 2, 3, 4];
@@ -208,6 +662,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_bans_commitment() {
+        let commitment = commit_to_bans(&[2, 5], "ban_nonce");
+        assert!(commitment.starts_with(COMMITMENT_V2_PREFIX));
+        assert!(verify_bans_commitment(&commitment, &[2, 5], "ban_nonce"));
+        assert!(!verify_bans_commitment(&commitment, &[5, 2], "ban_nonce"));
+
+        // The same bytes committed as moves never pass as bans
+        let moves = commit_to_moves_v2(&[2, 5], &[], "ban_nonce");
+        assert!(!verify_bans_commitment(&moves, &[2, 5], "ban_nonce"));
+    }
+
     #[test]
     fn test_deterministic_hashing() {
         let data = "deterministic_test";
@@ -220,6 +686,87 @@ mod tests {
         assert_eq!(commitment1, commitment2);
     }
 
+    #[test]
+    fn test_commitment_schema_snapshot() {
+        let commitment = Commitment {
+            hash: create_commitment("army_data", "fixed_nonce"),
+            data_type: CommitmentType::Army,
+            created_at: 1_700_000_000,
+        };
+        insta::assert_json_snapshot!("commitment", commitment);
+
+        let commit_reveal = CommitReveal {
+            commitment: commitment.hash.clone(),
+            data: "army_data".to_string(),
+            nonce: "fixed_nonce".to_string(),
+        };
+        insta::assert_json_snapshot!("commit_reveal", commit_reveal);
+
+        let types = [
+            CommitmentType::CashuTokens,
+            CommitmentType::Army,
+            CommitmentType::Moves,
+            CommitmentType::Bans,
+        ];
+        insta::assert_json_snapshot!("commitment_types", types);
+    }
+
+    #[test]
+    fn test_commitment_test_vectors() {
+        for vector in commitment_test_vectors() {
+            let v2 = vector.version == 2;
+            let computed = match vector.data_type {
+                CommitmentType::CashuTokens => {
+                    let tokens: Vec<String> = serde_json::from_value(vector.input.clone()).unwrap();
+                    assert!(verify_cashu_commitment(
+                        &vector.commitment,
+                        &tokens,
+                        &vector.nonce
+                    ));
+                    if v2 {
+                        commit_to_cashu_tokens_v2(&tokens, &vector.nonce)
+                    } else {
+                        commit_to_cashu_tokens(&tokens, &vector.nonce)
+                    }
+                }
+                CommitmentType::Army => {
+                    let army = vector.input.as_str().unwrap();
+                    assert!(verify_army_commitment(
+                        &vector.commitment,
+                        army,
+                        &vector.nonce
+                    ));
+                    if v2 {
+                        commit_to_army_v2(army, &vector.nonce)
+                    } else {
+                        commit_to_army(army, &vector.nonce)
+                    }
+                }
+                CommitmentType::Moves => {
+                    let positions: Vec<u8> =
+                        serde_json::from_value(vector.input["positions"].clone()).unwrap();
+                    let abilities: Vec<String> =
+                        serde_json::from_value(vector.input["abilities"].clone()).unwrap();
+                    if v2 {
+                        commit_to_moves_v2(&positions, &abilities, &vector.nonce)
+                    } else {
+                        commit_to_moves(&positions, &abilities, &vector.nonce)
+                    }
+                }
+                CommitmentType::Bans => {
+                    let banned_units: Vec<u8> =
+                        serde_json::from_value(vector.input.clone()).unwrap();
+                    commit_to_bans(&banned_units, &vector.nonce)
+                }
+            };
+            assert_eq!(
+                computed, vector.commitment,
+                "{:?} v{} vector drifted",
+                vector.data_type, vector.version
+            );
+        }
+    }
+
     #[test]
     fn test_nonce_generation() {
         let nonce1 = generate_nonce();
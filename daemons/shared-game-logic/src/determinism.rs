@@ -0,0 +1,131 @@
+//! Cross-target determinism harness
+//!
+//! Combat and league math is integer-only (float arithmetic is denied crate-wide), so the
+//! native engine and the WASM client must agree bit for bit. The digest below pins
+//! the outputs for fixed inputs; the web client checks `wasm_determinism_digest`
+//! against it, and any drift there would otherwise surface as a false cheat detection.
+//!
+//! `rules_hash` covers the rule tables themselves, so the engine can tell when a
+//! client was built against different rules and reject what it computed.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::abilities::{AbilitySpec, ABILITY_REGISTRY};
+use crate::classes::ClassMatrix;
+use crate::combat::{
+    generate_army_v2, generate_units_from_token_secret, process_combat_with_rolls,
+};
+use crate::commitment::hash_data;
+use crate::game_state::{RoundResult, Unit};
+use crate::league::{get_league_modifier, LeagueDefinition, LeagueRegistry, StatMultipliers};
+use crate::rng::{tie_break_flip, CombatRng};
+
+/// Digest of `determinism_vectors` every target must reproduce
+pub const DETERMINISM_DIGEST: &str =
+    "b1d7ef29bcb35a1fd19d8e1d1b3dc3f7b7631b343d786452fa4871088d381ece";
+
+/// Outputs of the deterministic rules for fixed inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeterminismVectors {
+    pub armies: Vec<[Unit; 8]>,   // One per built-in league, same token secret
+    pub scaled_units: Vec<Unit>,  // One per multiplier set, same base unit
+    pub rounds: Vec<RoundResult>, // Combat seeded from fixed nonces
+}
+
+/// Run the army generation, league modifier and combat rules on fixed inputs
+pub fn determinism_vectors() -> DeterminismVectors {
+    let armies: Vec<[Unit; 8]> = (0..4)
+        .map(|league_id| generate_units_from_token_secret("determinism_token", league_id))
+        .collect();
+
+    let scaled_units = [(150, 75, 133), (33, 250, 67), (100, 100, 1)]
+        .into_iter()
+        .map(|(attack, defense, health)| {
+            let league = LeagueDefinition {
+                stat_multipliers: StatMultipliers {
+                    attack,
+                    defense,
+                    health,
+                },
+                ..LeagueDefinition::from(get_league_modifier(0))
+            };
+            let mut unit = armies[0][0];
+            league.apply_modifiers(&mut unit);
+            unit
+        })
+        .collect();
+
+    let rounds = (1..=8u32)
+        .map(|round| {
+            let rolls = CombatRng::new(["nonce_a", "nonce_b"], round).attack_rolls();
+            let index = round as usize % 8;
+            process_combat_with_rolls(armies[1][index], armies[2][index], rolls, "p1", "p2")
+                .unwrap()
+        })
+        .collect();
+
+    DeterminismVectors {
+        armies,
+        scaled_units,
+        rounds,
+    }
+}
+
+/// Hash of the serialized vectors, compared across targets
+pub fn determinism_digest() -> String {
+    hash_data(&serde_json::to_string(&determinism_vectors()).unwrap())
+}
+
+/// Version of the rules hash encoding, bumped if `RulesManifest` changes shape
+pub const RULES_HASH_VERSION: u32 = 1;
+
+/// Hash of the compiled rules every engine and client build must agree on
+pub const RULES_HASH: &str = "v1:1ee4690395ee293f6e829800e5c08fd132d4d9bbded787c05523c74e307cb451";
+
+/// Rule tables the rules hash is taken over
+#[derive(Serialize)]
+struct RulesManifest<'a> {
+    leagues: Vec<LeagueDefinition>,
+    class_matrix: ClassMatrix,
+    abilities: &'a [AbilitySpec],
+    army_v2: [Unit; 8], // Pins the v2 stat ranges and rarity tiers
+    tie_break_flips: Vec<u8>,
+    determinism_digest: String,
+}
+
+/// Versioned hash of the compiled unit tables, matchup matrix and ability registry
+///
+/// Leagues from a ruleset file are not included; those are covered by the engine's
+/// league registry hash.
+pub fn rules_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let registry = LeagueRegistry::builtin();
+        let manifest = RulesManifest {
+            leagues: registry.leagues().cloned().collect(),
+            class_matrix: registry.class_matrix().clone(),
+            abilities: &ABILITY_REGISTRY,
+            army_v2: generate_army_v2(&[0x5a; 32], 0),
+            tie_break_flips: (1..=8)
+                .map(|round| tie_break_flip(["nonce_a", "nonce_b"], round))
+                .collect(),
+            determinism_digest: determinism_digest(),
+        };
+        format!(
+            "v{RULES_HASH_VERSION}:{}",
+            hash_data(&serde_json::to_string(&manifest).unwrap())
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_reproduce_pinned_digest() {
+        assert_eq!(determinism_digest(), DETERMINISM_DIGEST);
+        assert_eq!(rules_hash(), RULES_HASH);
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::classes::{self, UnitClass};
+
 /// A battle unit with stats and special ability
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Unit {
@@ -8,6 +10,10 @@ pub struct Unit {
     pub health: u8,
     pub max_health: u8,
     pub ability: Ability,
+    #[serde(default, skip_serializing_if = "StatusEffects::is_empty")]
+    pub status_effects: StatusEffects,
+    #[serde(default)]
+    pub class: UnitClass,
 }
 
 /// Special abilities that units can have
@@ -19,6 +25,105 @@ pub enum Ability {
     Heal,   // Restore 50% max health post-combat
 }
 
+/// Lingering condition on a unit, lasting a number of combat rounds
+///
+/// Declaration order is the order effects resolve in each round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum StatusEffect {
+    Poison, // Lose 1/8 max health at the end of each round
+    Stun,   // Deal no damage
+    Shield, // Take no damage
+    Regen,  // Recover 1/8 max health at the end of each round
+    Burn,   // Defense halved, and lose 1/10 max health at the end of each round
+}
+
+/// A status effect and the rounds it still lasts, including the current one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveEffect {
+    pub effect: StatusEffect,
+    pub rounds_remaining: u8,
+}
+
+/// Most status effects a unit can carry at once
+pub const MAX_STATUS_EFFECTS: usize = 4;
+
+/// Status effects on a unit, serialized as a list
+///
+/// Held in a fixed array rather than a Vec so units stay `Copy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<ActiveEffect>", into = "Vec<ActiveEffect>")]
+pub struct StatusEffects([Option<ActiveEffect>; MAX_STATUS_EFFECTS]);
+
+impl StatusEffects {
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(Option::is_none)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ActiveEffect> + '_ {
+        self.0.iter().flatten().copied()
+    }
+
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.iter().any(|active| active.effect == effect)
+    }
+
+    /// Add an effect for `rounds` rounds
+    ///
+    /// Reapplying an effect keeps the longer of the two durations. Effects are kept
+    /// in `StatusEffect` order, and past `MAX_STATUS_EFFECTS` the last one is dropped,
+    /// so the result doesn't depend on which effect was applied first.
+    pub fn apply(&mut self, effect: StatusEffect, rounds: u8) {
+        if rounds == 0 {
+            return;
+        }
+        if let Some(active) = self.0.iter_mut().flatten().find(|a| a.effect == effect) {
+            active.rounds_remaining = active.rounds_remaining.max(rounds);
+            return;
+        }
+
+        let mut effects: Vec<ActiveEffect> = self.iter().collect();
+        effects.push(ActiveEffect {
+            effect,
+            rounds_remaining: rounds,
+        });
+        effects.sort_by_key(|active| active.effect);
+        effects.truncate(MAX_STATUS_EFFECTS);
+
+        self.0 = [None; MAX_STATUS_EFFECTS];
+        for (slot, active) in self.0.iter_mut().zip(effects) {
+            *slot = Some(active);
+        }
+    }
+
+    /// Count one round off every effect, dropping those that run out
+    pub fn tick(&mut self) {
+        for slot in &mut self.0 {
+            if let Some(active) = slot {
+                active.rounds_remaining -= 1;
+                if active.rounds_remaining == 0 {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+impl From<Vec<ActiveEffect>> for StatusEffects {
+    fn from(effects: Vec<ActiveEffect>) -> Self {
+        let mut status_effects = StatusEffects::default();
+        for active in effects {
+            status_effects.apply(active.effect, active.rounds_remaining);
+        }
+        status_effects
+    }
+}
+
+impl From<StatusEffects> for Vec<ActiveEffect> {
+    fn from(status_effects: StatusEffects) -> Self {
+        status_effects.iter().collect()
+    }
+}
+
 /// Result of a combat round between two units
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoundResult {
@@ -27,6 +132,8 @@ pub struct RoundResult {
     pub player2_unit: Unit,
     pub damage_dealt: [u8; 2], // [damage to unit2, damage to unit1]
     pub winner: Option<String>,
+    #[serde(default)]
+    pub class_matrix_version: u32, // Class matrix the round was resolved with
 }
 
 /// Error type for game logic operations
@@ -35,6 +142,11 @@ pub enum GameLogicError {
     InvalidInput(String),
     CombatError(String),
     SerializationError(String),
+    InsufficientMana {
+        ability: String, // Key of the ability that could not be paid for
+        cost: u8,
+        available: u8,
+    },
 }
 
 impl std::fmt::Display for GameLogicError {
@@ -43,6 +155,11 @@ impl std::fmt::Display for GameLogicError {
             GameLogicError::InvalidInput(msg) => write!(f, "Invalid input: {msg}"),
             GameLogicError::CombatError(msg) => write!(f, "Combat error: {msg}"),
             GameLogicError::SerializationError(msg) => write!(f, "Serialization error: {msg}"),
+            GameLogicError::InsufficientMana {
+                ability,
+                cost,
+                available,
+            } => write!(f, "Ability '{ability}' costs {cost} mana, {available} left"),
         }
     }
 }
@@ -58,6 +175,8 @@ impl Unit {
             health,
             max_health,
             ability,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         }
     }
 
@@ -73,7 +192,16 @@ impl Unit {
 
     /// Heal the unit by specified amount (capped at max_health)
     pub fn heal(&mut self, amount: u8) {
-        self.health = (self.health + amount).min(self.max_health);
+        self.health = self.health.saturating_add(amount).min(self.max_health);
+    }
+
+    /// Afflict or bless the unit with a status effect for `rounds` combat rounds
+    pub fn apply_status(&mut self, effect: StatusEffect, rounds: u8) {
+        self.status_effects.apply(effect, rounds);
+    }
+
+    pub fn has_status(&self, effect: StatusEffect) -> bool {
+        self.status_effects.has(effect)
     }
 }
 
@@ -92,6 +220,7 @@ impl RoundResult {
             player2_unit,
             damage_dealt,
             winner,
+            class_matrix_version: classes::active_class_matrix().version,
         }
     }
 }
@@ -104,6 +233,60 @@ impl Default for Unit {
             health: 25,
             max_health: 25,
             ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_schema_snapshot() {
+        let unit = Unit::new(20, 10, 35, 40, Ability::Shield);
+        insta::assert_json_snapshot!("unit", unit);
+    }
+
+    #[test]
+    fn test_ability_schema_snapshot() {
+        let abilities = [Ability::None, Ability::Boost, Ability::Shield, Ability::Heal];
+        insta::assert_json_snapshot!("abilities", abilities);
+    }
+
+    #[test]
+    fn test_status_effects_schema_snapshot() {
+        let mut unit = Unit::new(20, 10, 35, 40, Ability::None);
+        unit.apply_status(StatusEffect::Poison, 3);
+        unit.apply_status(StatusEffect::Shield, 1);
+        insta::assert_json_snapshot!("unit_with_status_effects", unit);
+    }
+
+    #[test]
+    fn test_round_result_schema_snapshot() {
+        let result = RoundResult::new(
+            3,
+            Unit::new(20, 10, 35, 40, Ability::Boost),
+            Unit::new(15, 5, 0, 30, Ability::None),
+            [30, 5],
+            Some("npub1alice".to_string()),
+        );
+        insta::assert_json_snapshot!("round_result", result);
+    }
+
+    #[test]
+    fn test_game_logic_error_schema_snapshot() {
+        let errors = vec![
+            GameLogicError::InvalidInput("bad league".to_string()),
+            GameLogicError::CombatError("dead unit".to_string()),
+            GameLogicError::SerializationError("truncated".to_string()),
+            GameLogicError::InsufficientMana {
+                ability: "stun".to_string(),
+                cost: 4,
+                available: 2,
+            },
+        ];
+        insta::assert_json_snapshot!("game_logic_errors", errors);
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::game_state::{Ability, GameLogicError, Unit};
+
+/// Tiles per side of the square board; tiles are numbered row by row from 0
+pub const BOARD_WIDTH: u8 = 8;
+pub const BOARD_TILES: usize = BOARD_WIDTH as usize * BOARD_WIDTH as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Terrain {
+    #[default]
+    Open,
+    Forest, // Passable, blocks line of sight
+    Water,  // Impassable, can be shot across
+    Wall,   // Impassable, blocks line of sight
+}
+
+impl Terrain {
+    pub fn is_passable(self) -> bool {
+        matches!(self, Terrain::Open | Terrain::Forest)
+    }
+
+    pub fn blocks_sight(self) -> bool {
+        matches!(self, Terrain::Forest | Terrain::Wall)
+    }
+}
+
+/// How far a unit moves and attacks per round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MovementProfile {
+    pub movement: u8, // Tiles per round, diagonals included
+    pub range: u8,    // Attack reach; 1 is adjacent only
+}
+
+/// Movement and range for the unit's type, which its ability decides
+pub fn profile(unit: &Unit) -> MovementProfile {
+    match unit.ability {
+        Ability::None => MovementProfile {
+            movement: 2,
+            range: 1,
+        },
+        Ability::Boost => MovementProfile {
+            movement: 3,
+            range: 1,
+        },
+        Ability::Shield => MovementProfile {
+            movement: 1,
+            range: 1,
+        },
+        Ability::Heal => MovementProfile {
+            movement: 2,
+            range: 2,
+        },
+    }
+}
+
+/// Square battlefield of `BOARD_WIDTH` x `BOARD_WIDTH` tiles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Terrain>", into = "Vec<Terrain>")]
+pub struct Board {
+    tiles: Vec<Terrain>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self {
+            tiles: vec![Terrain::Open; BOARD_TILES],
+        }
+    }
+}
+
+impl TryFrom<Vec<Terrain>> for Board {
+    type Error = GameLogicError;
+
+    fn try_from(tiles: Vec<Terrain>) -> Result<Self, Self::Error> {
+        if tiles.len() != BOARD_TILES {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Board needs {BOARD_TILES} tiles, got {}",
+                tiles.len()
+            )));
+        }
+        Ok(Self { tiles })
+    }
+}
+
+impl From<Board> for Vec<Terrain> {
+    fn from(board: Board) -> Self {
+        board.tiles
+    }
+}
+
+impl Board {
+    pub fn terrain(&self, position: u8) -> Option<Terrain> {
+        self.tiles.get(position as usize).copied()
+    }
+
+    pub fn set_terrain(&mut self, position: u8, terrain: Terrain) {
+        if let Some(tile) = self.tiles.get_mut(position as usize) {
+            *tile = terrain;
+        }
+    }
+
+    /// On the board and open to units
+    pub fn is_passable(&self, position: u8) -> bool {
+        self.terrain(position).is_some_and(Terrain::is_passable)
+    }
+
+    /// Tiles between two positions, counting a diagonal step as one
+    pub fn distance(from: u8, to: u8) -> u8 {
+        let ((x1, y1), (x2, y2)) = (coords(from), coords(to));
+        x1.abs_diff(x2).max(y1.abs_diff(y2)) as u8
+    }
+
+    /// No sight-blocking tile on the straight line between the two positions
+    pub fn line_of_sight(&self, from: u8, to: u8) -> bool {
+        let ((x1, y1), (x2, y2)) = (coords(from), coords(to));
+        let steps = x1.abs_diff(x2).max(y1.abs_diff(y2)) as i32;
+
+        // Tiles the line passes through, rounded to the nearest, endpoints excluded
+        (1..steps).all(|step| {
+            let x = x1 + ((x2 - x1) * step + steps / 2).div_euclid(steps);
+            let y = y1 + ((y2 - y1) * step + steps / 2).div_euclid(steps);
+            let position = (y * BOARD_WIDTH as i32 + x) as u8;
+            !self.terrain(position).is_some_and(Terrain::blocks_sight)
+        })
+    }
+
+    /// A unit at `from` can hit `to`: within range and in line of sight
+    pub fn in_range(&self, from: u8, to: u8, range: u8) -> bool {
+        Self::distance(from, to) <= range && self.line_of_sight(from, to)
+    }
+
+    /// A unit at `from` can reach `to` in at most `movement` steps over passable tiles
+    pub fn can_move(&self, from: u8, to: u8, movement: u8) -> bool {
+        if !self.is_passable(from) || !self.is_passable(to) {
+            return false;
+        }
+
+        let mut steps = [None; BOARD_TILES];
+        steps[from as usize] = Some(0u8);
+        let mut queue = VecDeque::from([from]);
+        while let Some(position) = queue.pop_front() {
+            let taken = steps[position as usize].unwrap_or_default();
+            if position == to {
+                return true;
+            }
+            if taken == movement {
+                continue;
+            }
+            for next in neighbours(position) {
+                if self.is_passable(next) && steps[next as usize].is_none() {
+                    steps[next as usize] = Some(taken + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+fn coords(position: u8) -> (i32, i32) {
+    let width = BOARD_WIDTH as i32;
+    (position as i32 % width, position as i32 / width)
+}
+
+/// Tiles surrounding a position, diagonals included
+fn neighbours(position: u8) -> impl Iterator<Item = u8> {
+    let (x, y) = coords(position);
+    let width = BOARD_WIDTH as i32;
+    (-1..=1)
+        .flat_map(move |dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+        .filter(move |&(nx, ny)| {
+            (nx, ny) != (x, y) && (0..width).contains(&nx) && (0..width).contains(&ny)
+        })
+        .map(move |(nx, ny)| (ny * width + nx) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abilities::AbilityId;
+    use crate::combat::{process_combat_on_board, process_combat_with_abilities};
+    use crate::rng::AttackRoll;
+
+    #[test]
+    fn test_range_sight_and_movement() {
+        // Wall at (2, 0) between tiles 0 and 4; water at (1, 1)
+        let mut board = Board::default();
+        board.set_terrain(2, Terrain::Wall);
+        board.set_terrain(9, Terrain::Water);
+
+        assert_eq!(Board::distance(0, 9), 1);
+        assert!(!board.line_of_sight(0, 4));
+        assert!(board.line_of_sight(0, 18)); // Across the water
+        assert!(board.in_range(0, 18, 2));
+        assert!(!board.in_range(0, 27, 2));
+
+        // Around the wall takes two steps; onto water never works
+        assert!(!board.can_move(1, 3, 1));
+        assert!(board.can_move(1, 3, 2));
+        assert!(!board.can_move(0, 9, 3));
+
+        // The healer reaches two tiles away, the plain unit only adjacent ones
+        let healer = Unit::new(20, 5, 30, 30, Ability::Heal);
+        let soldier = Unit::new(20, 5, 30, 30, Ability::None);
+        let no_rolls = [AttackRoll::default(); 2];
+        let result = process_combat_on_board(
+            &board,
+            [(healer, 0), (soldier, 16)],
+            [&[], &[]],
+            no_rolls,
+            "p1",
+            "p2",
+        )
+        .unwrap();
+        assert_eq!(result.damage_dealt, [15, 0]);
+        assert!(process_combat_on_board(
+            &board,
+            [(healer, 9), (soldier, 16)],
+            [&[], &[]],
+            no_rolls,
+            "p1",
+            "p2"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_adjacent_units_fight_as_off_board() {
+        let board = Board::default();
+        let unit1 = Unit::new(20, 5, 30, 30, Ability::None);
+        let unit2 = Unit::new(15, 8, 40, 40, Ability::None);
+        let activated: [&[AbilityId]; 2] = [&[AbilityId::Boost], &[AbilityId::Shield]];
+        let rolls = [
+            AttackRoll {
+                critical: true,
+                dodged: false,
+            },
+            AttackRoll::default(),
+        ];
+
+        let on_board = process_combat_on_board(
+            &board,
+            [(unit1, 0), (unit2, 1)],
+            activated,
+            rolls,
+            "p1",
+            "p2",
+        )
+        .unwrap();
+        let off_board =
+            process_combat_with_abilities(unit1, unit2, activated, rolls, "p1", "p2").unwrap();
+        assert_eq!(on_board.damage_dealt, off_board.damage_dealt);
+        assert_eq!(on_board.winner, off_board.winner);
+    }
+}
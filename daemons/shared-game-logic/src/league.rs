@@ -1,5 +1,10 @@
-use crate::game_state::Unit;
+use crate::classes::ClassMatrix;
+use crate::game_state::{Ability, GameLogicError, Unit};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
 
 /// League modifiers that affect unit stats
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,19 +16,352 @@ pub struct LeagueModifier {
     pub health_bonus: i8,
 }
 
+/// Stat multipliers in percent, applied before a league's flat bonuses
+///
+/// Integer percentages rather than floats, so native and WASM builds scale stats identically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatMultipliers {
+    pub attack: u16,
+    pub defense: u16,
+    pub health: u16,
+}
+
+impl Default for StatMultipliers {
+    fn default() -> Self {
+        Self {
+            attack: 100,
+            defense: 100,
+            health: 100,
+        }
+    }
+}
+
+/// Full league rules as loaded from a ruleset file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeagueDefinition {
+    pub id: u8,
+    pub name: String,
+    #[serde(default)]
+    pub attack_bonus: i8,
+    #[serde(default)]
+    pub defense_bonus: i8,
+    #[serde(default)]
+    pub health_bonus: i8,
+    #[serde(default)]
+    pub stat_multipliers: StatMultipliers,
+    #[serde(default)]
+    pub banned_abilities: Vec<Ability>,
+    #[serde(default)]
+    pub rounds: Option<u32>, // None = no round limit
+    #[serde(default)]
+    pub max_wager: Option<u64>, // None = uncapped
+    #[serde(default)]
+    pub army_budget: Option<u32>, // None = no point limit, see `army::army_cost`
+}
+
+impl LeagueDefinition {
+    /// Scale and boost a unit's stats, stripping any banned ability
+    pub fn apply_modifiers(&self, unit: &mut Unit) {
+        let multipliers = self.stat_multipliers;
+        unit.attack = apply_stat_modifier(
+            scale_stat(unit.attack, multipliers.attack),
+            self.attack_bonus,
+        );
+        unit.defense = apply_stat_modifier(
+            scale_stat(unit.defense, multipliers.defense),
+            self.defense_bonus,
+        );
+
+        let new_max_health = apply_stat_modifier(
+            scale_stat(unit.max_health, multipliers.health),
+            self.health_bonus,
+        );
+        let health_increase = new_max_health.saturating_sub(unit.max_health);
+
+        unit.max_health = new_max_health;
+        // Current health scales with max
+        unit.health = unit
+            .health
+            .saturating_add(health_increase)
+            .min(new_max_health);
+
+        if self.banned_abilities.contains(&unit.ability) {
+            unit.ability = Ability::None;
+        }
+    }
+
+    /// Whether a wager of this size may be staked in the league
+    pub fn allows_wager(&self, amount: u64) -> bool {
+        self.max_wager.is_none_or(|cap| amount <= cap)
+    }
+
+    /// Whether a match in this league may still play the given round
+    pub fn allows_round(&self, round: u32) -> bool {
+        self.rounds.is_none_or(|rounds| round <= rounds)
+    }
+}
+
+impl From<LeagueModifier> for LeagueDefinition {
+    fn from(modifier: LeagueModifier) -> Self {
+        Self {
+            id: modifier.id,
+            name: modifier.name.to_string(),
+            attack_bonus: modifier.attack_bonus,
+            defense_bonus: modifier.defense_bonus,
+            health_bonus: modifier.health_bonus,
+            stat_multipliers: StatMultipliers::default(),
+            banned_abilities: Vec::new(),
+            rounds: None,
+            max_wager: None,
+            army_budget: Some(crate::army::DEFAULT_ARMY_BUDGET),
+        }
+    }
+}
+
+/// Season length when a schedule names none: 30 days
+pub const DEFAULT_SEASON_LENGTH_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Largest bonus or penalty a season gives any stat
+pub const MAX_SEASON_BONUS: i8 = 3;
+
+fn default_season_length() -> u64 {
+    DEFAULT_SEASON_LENGTH_SECONDS
+}
+
+/// Schedule of rotating seasons, each with stat bonuses derived from the seed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonSchedule {
+    pub seed: String,
+    pub starts_at: u64, // Unix timestamp season 1 begins
+    #[serde(default = "default_season_length")]
+    pub length_seconds: u64,
+}
+
+/// One season of a schedule and the bonuses every unit gets during it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Season {
+    pub number: u32, // Counted from 1
+    pub starts_at: u64,
+    pub ends_at: u64, // Exclusive
+    pub attack_bonus: i8,
+    pub defense_bonus: i8,
+    pub health_bonus: i8,
+}
+
+impl SeasonSchedule {
+    /// Season running at the timestamp, or None before the first one starts
+    pub fn season_at(&self, timestamp: u64) -> Option<Season> {
+        if timestamp < self.starts_at || self.length_seconds == 0 {
+            return None;
+        }
+        let index = (timestamp - self.starts_at) / self.length_seconds;
+        let number = u32::try_from(index + 1).ok()?;
+
+        let mut hasher = Sha256::new();
+        hasher.update((self.seed.len() as u64).to_le_bytes());
+        hasher.update(self.seed.as_bytes());
+        hasher.update(number.to_le_bytes());
+        let hash = hasher.finalize();
+        let bonus = |byte: u8| (byte % (2 * MAX_SEASON_BONUS as u8 + 1)) as i8 - MAX_SEASON_BONUS;
+
+        let starts_at = self.starts_at + index * self.length_seconds;
+        Some(Season {
+            number,
+            starts_at,
+            ends_at: starts_at + self.length_seconds,
+            attack_bonus: bonus(hash[0]),
+            defense_bonus: bonus(hash[1]),
+            health_bonus: bonus(hash[2]),
+        })
+    }
+}
+
+impl Season {
+    /// Add the season's bonuses on top of the league's, in every league alike
+    pub fn apply(&self, unit: &mut Unit) {
+        unit.attack = apply_stat_modifier(unit.attack, self.attack_bonus);
+        unit.defense = apply_stat_modifier(unit.defense, self.defense_bonus);
+
+        let new_max_health = apply_stat_modifier(unit.max_health, self.health_bonus);
+        let health_increase = new_max_health.saturating_sub(unit.max_health);
+        unit.max_health = new_max_health;
+        unit.health = unit
+            .health
+            .saturating_add(health_increase)
+            .min(new_max_health);
+    }
+}
+
+/// Layout of a ruleset file: `[[leagues]]` tables in TOML or a `leagues` array in JSON
+#[derive(Debug, Deserialize)]
+struct RulesetFile {
+    leagues: Vec<LeagueDefinition>,
+    #[serde(default)]
+    class_matrix: ClassMatrix,
+    #[serde(default)]
+    season: Option<SeasonSchedule>,
+}
+
+/// League definitions keyed by league id, the class matrix they all fight under and
+/// the season schedule, if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeagueRegistry {
+    leagues: BTreeMap<u8, LeagueDefinition>,
+    class_matrix: ClassMatrix,
+    season: Option<SeasonSchedule>,
+}
+
+impl LeagueRegistry {
+    /// The compiled-in leagues, used when no ruleset file is configured
+    pub fn builtin() -> Self {
+        let leagues = (0..=u8::MAX)
+            .map(|id| {
+                let mut league = LeagueDefinition::from(get_league_modifier(id));
+                league.id = id;
+                (id, league)
+            })
+            .collect();
+        Self {
+            leagues,
+            class_matrix: ClassMatrix::default(),
+            season: None,
+        }
+    }
+
+    /// Build a registry, rejecting duplicate league ids
+    pub fn from_definitions(definitions: Vec<LeagueDefinition>) -> Result<Self, GameLogicError> {
+        let mut leagues = BTreeMap::new();
+        for league in definitions {
+            let id = league.id;
+            if leagues.insert(id, league).is_some() {
+                return Err(GameLogicError::InvalidInput(format!(
+                    "League {id} is defined more than once"
+                )));
+            }
+        }
+        Ok(Self {
+            leagues,
+            class_matrix: ClassMatrix::default(),
+            season: None,
+        })
+    }
+
+    /// Resolve combat with another class matrix
+    pub fn with_class_matrix(mut self, class_matrix: ClassMatrix) -> Self {
+        self.class_matrix = class_matrix;
+        self
+    }
+
+    /// Rotate seasons on the schedule
+    pub fn with_season(mut self, season: Option<SeasonSchedule>) -> Self {
+        self.season = season;
+        self
+    }
+
+    pub fn from_toml_str(ruleset: &str) -> Result<Self, GameLogicError> {
+        let file: RulesetFile = toml::from_str(ruleset)
+            .map_err(|e| GameLogicError::SerializationError(e.to_string()))?;
+        Ok(Self::from_definitions(file.leagues)?
+            .with_class_matrix(file.class_matrix)
+            .with_season(file.season))
+    }
+
+    pub fn from_json_str(ruleset: &str) -> Result<Self, GameLogicError> {
+        let file: RulesetFile = serde_json::from_str(ruleset)
+            .map_err(|e| GameLogicError::SerializationError(e.to_string()))?;
+        Ok(Self::from_definitions(file.leagues)?
+            .with_class_matrix(file.class_matrix)
+            .with_season(file.season))
+    }
+
+    /// Load a ruleset file, parsed as JSON for `.json` paths and TOML otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameLogicError> {
+        let path = path.as_ref();
+        let ruleset = std::fs::read_to_string(path).map_err(|e| {
+            GameLogicError::InvalidInput(format!("Failed to read {}: {e}", path.display()))
+        })?;
+
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json_str(&ruleset)
+        } else {
+            Self::from_toml_str(&ruleset)
+        }
+    }
+
+    pub fn get(&self, league_id: u8) -> Option<&LeagueDefinition> {
+        self.leagues.get(&league_id)
+    }
+
+    pub fn class_matrix(&self) -> &ClassMatrix {
+        &self.class_matrix
+    }
+
+    /// Season running at the timestamp; None without a schedule
+    pub fn season_at(&self, timestamp: u64) -> Option<Season> {
+        self.season.as_ref()?.season_at(timestamp)
+    }
+
+    pub fn leagues(&self) -> impl Iterator<Item = &LeagueDefinition> {
+        self.leagues.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.leagues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leagues.is_empty()
+    }
+
+    /// Apply a league's modifiers to a unit; units of unknown leagues are left unchanged
+    pub fn apply_modifiers(&self, unit: &mut Unit, league_id: u8) {
+        if let Some(league) = self.get(league_id) {
+            league.apply_modifiers(unit);
+        }
+    }
+}
+
+static BUILTIN_REGISTRY: OnceLock<LeagueRegistry> = OnceLock::new();
+static INSTALLED_REGISTRY: OnceLock<LeagueRegistry> = OnceLock::new();
+
+/// Replace the built-in leagues for the rest of the process
+///
+/// Must run before any unit is generated; fails if a registry was already installed.
+pub fn install_registry(registry: LeagueRegistry) -> Result<(), GameLogicError> {
+    INSTALLED_REGISTRY.set(registry).map_err(|_| {
+        GameLogicError::InvalidInput("A league registry is already installed".to_string())
+    })
+}
+
+/// Registry loaded from a ruleset file, if one was installed
+pub fn installed_registry() -> Option<&'static LeagueRegistry> {
+    INSTALLED_REGISTRY.get()
+}
+
+/// Registry that unit generation and match validation consult
+pub fn active_registry() -> &'static LeagueRegistry {
+    installed_registry().unwrap_or_else(|| BUILTIN_REGISTRY.get_or_init(LeagueRegistry::builtin))
+}
+
 /// Apply league-specific modifiers to a unit
 pub fn apply_modifiers(unit: &mut Unit, league_id: u8) {
-    let modifier = get_league_modifier(league_id);
-
-    // Apply bonuses (ensuring minimums)
-    unit.attack = apply_stat_modifier(unit.attack, modifier.attack_bonus);
-    unit.defense = apply_stat_modifier(unit.defense, modifier.defense_bonus);
+    active_registry().apply_modifiers(unit, league_id);
+}
 
-    let new_max_health = apply_stat_modifier(unit.max_health, modifier.health_bonus);
-    let health_increase = new_max_health.saturating_sub(unit.max_health);
+/// Season of the active registry running at the timestamp
+pub fn current_season(timestamp: u64) -> Option<Season> {
+    active_registry().season_at(timestamp)
+}
 
-    unit.max_health = new_max_health;
-    unit.health = unit.health.saturating_add(health_increase); // Current health scales with max
+/// Apply the bonuses of the season running at `created_at` to an army
+///
+/// Matches are validated against the season their challenge was created in, so an
+/// army keeps its stats even if the season rolls over mid-match.
+pub fn apply_season(units: &mut [Unit], created_at: u64) {
+    if let Some(season) = current_season(created_at) {
+        units.iter_mut().for_each(|unit| season.apply(unit));
+    }
 }
 
 /// Get league modifier configuration
@@ -70,12 +408,12 @@ pub fn get_league_modifier(league_id: u8) -> LeagueModifier {
 
 /// Apply a stat modifier with minimum bounds
 fn apply_stat_modifier(base: u8, modifier: i8) -> u8 {
-    let result = base as i8 + modifier;
-    if result < 1 {
-        1
-    } else {
-        result as u8
-    }
+    (base as i16 + modifier as i16).clamp(1, u8::MAX as i16) as u8
+}
+
+/// Scale a stat by a league multiplier in percent, rounding half up, keeping it within unit bounds
+fn scale_stat(base: u8, multiplier: u16) -> u8 {
+    ((base as u32 * multiplier as u32 + 50) / 100).clamp(1, u8::MAX as u32) as u8
 }
 
 /// Get all available league modifiers
@@ -127,6 +465,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         apply_modifiers(&mut unit, 0); // Fire League
@@ -145,6 +485,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         apply_modifiers(&mut unit, 1); // Ice League
@@ -163,6 +505,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         apply_modifiers(&mut unit, 2); // Shadow League
@@ -181,6 +525,8 @@ mod tests {
             health: 30,
             max_health: 30,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         apply_modifiers(&mut unit, 3); // Nature League
@@ -199,6 +545,8 @@ mod tests {
             health: 1,
             max_health: 1,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         // Apply negative modifiers (shouldn't happen in practice, but test bounds)
@@ -217,6 +565,8 @@ mod tests {
             health: 20,
             max_health: 20,
             ability: crate::game_state::Ability::None,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
         };
 
         // Fire League: +10 attack
@@ -238,6 +588,125 @@ mod tests {
         assert_eq!(get_league_display_info(3), "Nature League (+5 DEF, +15 HP)");
     }
 
+    #[test]
+    fn test_league_modifier_schema_snapshot() {
+        insta::assert_json_snapshot!("league_modifiers", get_all_league_modifiers());
+    }
+
+    #[test]
+    fn test_registry_loads_league_rules_from_toml() {
+        let registry = LeagueRegistry::from_toml_str(
+            r#"
+            [[leagues]]
+            id = 7
+            name = "Glass League"
+            attack_bonus = 2
+            banned_abilities = ["Heal"]
+            rounds = 3
+            max_wager = 500
+
+            [leagues.stat_multipliers]
+            attack = 200
+            health = 50
+            "#,
+        )
+        .unwrap();
+
+        let glass = registry.get(7).unwrap();
+        assert!(glass.allows_wager(500));
+        assert!(!glass.allows_wager(501));
+        assert!(glass.allows_round(3));
+        assert!(!glass.allows_round(4));
+
+        let mut unit = Unit {
+            attack: 10,
+            defense: 10,
+            health: 30,
+            max_health: 30,
+            ability: crate::game_state::Ability::Heal,
+            status_effects: crate::game_state::StatusEffects::default(),
+            class: crate::classes::UnitClass::default(),
+        };
+        registry.apply_modifiers(&mut unit, 7);
+
+        assert_eq!(unit.attack, 22); // x2 then +2
+        assert_eq!(unit.defense, 10); // Multiplier defaults to 1
+        assert_eq!(unit.max_health, 15); // Halved
+        assert_eq!(unit.health, 15); // Clamped to the new max
+        assert_eq!(unit.ability, crate::game_state::Ability::None); // Banned
+        assert!(registry.get(0).is_none());
+    }
+
+    #[test]
+    fn test_seasons_rotate_on_schedule() {
+        let registry = LeagueRegistry::from_toml_str(
+            r#"
+            [[leagues]]
+            id = 0
+            name = "Seasonal League"
+
+            [season]
+            seed = "season_seed"
+            starts_at = 1700000000
+            length_seconds = 1000
+            "#,
+        )
+        .unwrap();
+
+        assert!(registry.season_at(1699999999).is_none());
+        let first = registry.season_at(1700000000).unwrap();
+        assert_eq!(
+            (first.number, first.starts_at, first.ends_at),
+            (1, 1700000000, 1700001000)
+        );
+        assert_eq!(registry.season_at(1700000999), Some(first.clone()));
+        assert_eq!(registry.season_at(1700001000).unwrap().number, 2);
+
+        // Bonuses stay in range and differ between seasons
+        let seasons: Vec<Season> = (0..12)
+            .map(|i| registry.season_at(1700000000 + i * 1000).unwrap())
+            .collect();
+        for season in &seasons {
+            for bonus in [
+                season.attack_bonus,
+                season.defense_bonus,
+                season.health_bonus,
+            ] {
+                assert!((-MAX_SEASON_BONUS..=MAX_SEASON_BONUS).contains(&bonus));
+            }
+        }
+        assert!(seasons
+            .iter()
+            .any(|season| season.attack_bonus != first.attack_bonus));
+
+        let mut unit = Unit::new(20, 10, 30, 30, crate::game_state::Ability::None);
+        first.apply(&mut unit);
+        assert_eq!(unit.attack as i8, 20 + first.attack_bonus);
+        assert_eq!(unit.max_health as i8, 30 + first.health_bonus);
+        assert_eq!(unit.health, unit.max_health);
+
+        // The built-in leagues have no seasons
+        assert!(LeagueRegistry::builtin().season_at(1700000000).is_none());
+    }
+
+    #[test]
+    fn test_registry_rejects_duplicate_leagues() {
+        let ruleset = r#"{"leagues": [{"id": 1, "name": "A"}, {"id": 1, "name": "B"}]}"#;
+        assert!(LeagueRegistry::from_json_str(ruleset).is_err());
+    }
+
+    #[test]
+    fn test_builtin_registry_matches_hardcoded_leagues() {
+        let registry = LeagueRegistry::builtin();
+        for league_id in [0, 1, 5, 200] {
+            let modifier = get_league_modifier(league_id);
+            let league = registry.get(league_id).unwrap();
+            assert_eq!(league.name, modifier.name);
+            assert_eq!(league.attack_bonus, modifier.attack_bonus);
+            assert_eq!(league.health_bonus, modifier.health_bonus);
+        }
+    }
+
     #[test]
     fn test_all_league_modifiers() {
         let modifiers = get_all_league_modifiers();
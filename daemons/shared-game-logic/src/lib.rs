@@ -1,18 +1,42 @@
+// Floats round differently across targets; all combat and league math is integer-only
+#![deny(clippy::float_arithmetic)]
+
 use wasm_bindgen::prelude::*;
 
 // Import our modules
 pub mod abilities;
+pub mod army;
+pub mod classes;
 pub mod combat;
 pub mod commitment;
+pub mod determinism;
 pub mod game_state;
+pub mod grid;
 pub mod league;
+pub mod match_audit;
+pub mod narration;
+#[cfg(feature = "verify_determinism")]
+pub mod properties;
+pub mod replay;
+pub mod rng;
 
 // Re-export public types
+pub use abilities::{AbilityId, AbilityLedger, AbilitySpec, ABILITY_REGISTRY};
+pub use army::{army_cost, validate_army, ArmyValidationError};
+pub use classes::{ClassMatrix, UnitClass};
 pub use combat::{
-    generate_army_from_cashu_c_value, generate_units_from_token_secret, process_combat,
+    generate_army_for_version, generate_army_from_cashu_c_value, generate_army_v2,
+    generate_units_from_token_secret, process_combat, process_combat_on_board,
+    process_combat_with_abilities, process_combat_with_rolls, Rarity,
 };
 pub use commitment::*;
-pub use game_state::{Ability, RoundResult, Unit};
+pub use determinism::rules_hash;
+pub use game_state::{Ability, RoundResult, StatusEffect, Unit};
+pub use grid::{Board, Terrain};
+pub use match_audit::{validate_full_match, MatchAudit, MatchTranscript};
+pub use narration::{Locale, NarrationContext};
+pub use replay::{mana_by_round, replay_match, CombatEvent, MoveReveal};
+pub use rng::{AttackRoll, CombatRng};
 
 // WASM initialization
 #[wasm_bindgen(start)]
@@ -34,6 +58,27 @@ pub fn wasm_generate_units_from_token_secret(token_secret: &str, league_id: u8)
     serde_wasm_bindgen::to_value(&units).unwrap()
 }
 
+/// Army for a revealed token secret under a challenge's `generator_version`
+#[wasm_bindgen]
+pub fn wasm_generate_army_for_version(
+    token_secret: &str,
+    league_id: u8,
+    generator_version: u8,
+) -> Result<JsValue, JsValue> {
+    let units = combat::generate_army_for_version(token_secret, league_id, generator_version)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&units)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_army_rarities_v2(c_value_bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let c_value: [u8; 32] = c_value_bytes
+        .try_into()
+        .map_err(|_| JsValue::from_str("C value must be 32 bytes"))?;
+    let rarities = combat::army_rarities_v2(&c_value);
+    Ok(serde_wasm_bindgen::to_value(&rarities)?)
+}
+
 #[wasm_bindgen]
 pub fn wasm_process_combat(
     unit1_js: JsValue,
@@ -48,6 +93,176 @@ pub fn wasm_process_combat(
     serde_wasm_bindgen::to_value(&result).unwrap()
 }
 
+#[wasm_bindgen]
+pub fn wasm_process_combat_with_nonces(
+    unit1_js: JsValue,
+    unit2_js: JsValue,
+    player1_nonce: &str,
+    player2_nonce: &str,
+    round: u32,
+    player1_npub: &str,
+    player2_npub: &str,
+) -> JsValue {
+    let unit1: Unit = serde_wasm_bindgen::from_value(unit1_js).unwrap();
+    let unit2: Unit = serde_wasm_bindgen::from_value(unit2_js).unwrap();
+    let rolls = CombatRng::new([player1_nonce, player2_nonce], round).attack_rolls();
+
+    let result =
+        combat::process_combat_with_rolls(unit1, unit2, rolls, player1_npub, player2_npub).unwrap();
+    serde_wasm_bindgen::to_value(&result).unwrap()
+}
+
+/// One round on the board; `fighters_js` is `[[unit1, tile1], [unit2, tile2]]`,
+/// `abilities_js` each player's activated abilities and `rolls_js` both attack rolls
+#[wasm_bindgen]
+pub fn wasm_process_combat_on_board(
+    board_js: JsValue,
+    fighters_js: JsValue,
+    abilities_js: JsValue,
+    rolls_js: JsValue,
+    player1_npub: &str,
+    player2_npub: &str,
+) -> Result<JsValue, JsValue> {
+    let board: Board = serde_wasm_bindgen::from_value(board_js)?;
+    let fighters: [(Unit, u8); 2] = serde_wasm_bindgen::from_value(fighters_js)?;
+    let abilities: [Vec<AbilityId>; 2] = serde_wasm_bindgen::from_value(abilities_js)?;
+    let rolls: [AttackRoll; 2] = serde_wasm_bindgen::from_value(rolls_js)?;
+
+    let result = combat::process_combat_on_board(
+        &board,
+        fighters,
+        [&abilities[0], &abilities[1]],
+        rolls,
+        player1_npub,
+        player2_npub,
+    )
+    .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_can_move(
+    board_js: JsValue,
+    unit_js: JsValue,
+    from: u8,
+    to: u8,
+) -> Result<bool, JsValue> {
+    let board: Board = serde_wasm_bindgen::from_value(board_js)?;
+    let unit: Unit = serde_wasm_bindgen::from_value(unit_js)?;
+    Ok(board.can_move(from, to, grid::profile(&unit).movement))
+}
+
+#[wasm_bindgen]
+pub fn wasm_apply_status_effect(unit_js: JsValue, effect_js: JsValue, rounds: u8) -> JsValue {
+    let mut unit: Unit = serde_wasm_bindgen::from_value(unit_js).unwrap();
+    let effect: StatusEffect = serde_wasm_bindgen::from_value(effect_js).unwrap();
+    unit.apply_status(effect, rounds);
+    serde_wasm_bindgen::to_value(&unit).unwrap()
+}
+
+#[wasm_bindgen]
+pub fn wasm_ability_registry() -> JsValue {
+    serde_wasm_bindgen::to_value(&abilities::ABILITY_REGISTRY).unwrap()
+}
+
+/// Combat events of a match replayed from both players' reveals;
+/// rejects with the first reveal that breaks the rules
+#[wasm_bindgen]
+pub fn wasm_replay_match(
+    army1_js: JsValue,
+    army2_js: JsValue,
+    reveals_js: JsValue,
+) -> Result<JsValue, JsValue> {
+    let army1: [Unit; 8] = serde_wasm_bindgen::from_value(army1_js)?;
+    let army2: [Unit; 8] = serde_wasm_bindgen::from_value(army2_js)?;
+    let reveals: Vec<MoveReveal> = serde_wasm_bindgen::from_value(reveals_js)?;
+
+    let events = replay::replay_match([&army1, &army2], &reveals)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&events)?)
+}
+
+/// Mana a player has left after each of their revealed rounds, keyed by round;
+/// rejects with the first round they can't pay for
+#[wasm_bindgen]
+pub fn wasm_mana_by_round(reveals_js: JsValue, player: u8) -> Result<JsValue, JsValue> {
+    let reveals: Vec<MoveReveal> = serde_wasm_bindgen::from_value(reveals_js)?;
+    let mana =
+        replay::mana_by_round(&reveals, player).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&mana)?)
+}
+
+/// Mana regenerated each round and the most a player can bank
+#[wasm_bindgen]
+pub fn wasm_mana_rules() -> JsValue {
+    serde_wasm_bindgen::to_value(&(abilities::MANA_PER_ROUND, abilities::MAX_MANA)).unwrap()
+}
+
+/// Audit a finished match from its JSON transcript before accepting the result;
+/// rejects with the first check that failed
+#[wasm_bindgen]
+pub fn wasm_validate_full_match(events_json: &str) -> Result<JsValue, JsValue> {
+    let transcript: MatchTranscript =
+        serde_json::from_str(events_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let audit = match_audit::validate_full_match(&transcript)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    Ok(serde_wasm_bindgen::to_value(&audit)?)
+}
+
+/// Battle log lines for replay events, worded the same on every client
+#[wasm_bindgen]
+pub fn wasm_narrate_events(events_js: JsValue, context_js: JsValue) -> Result<JsValue, JsValue> {
+    let events: Vec<CombatEvent> = serde_wasm_bindgen::from_value(events_js)?;
+    let context: NarrationContext = serde_wasm_bindgen::from_value(context_js)?;
+    let lines = context.narrate_events(&events);
+    Ok(serde_wasm_bindgen::to_value(&lines)?)
+}
+
+/// Battle log lines for one resolved round
+#[wasm_bindgen]
+pub fn wasm_narrate_round(result_js: JsValue, context_js: JsValue) -> Result<JsValue, JsValue> {
+    let result: RoundResult = serde_wasm_bindgen::from_value(result_js)?;
+    let context: NarrationContext = serde_wasm_bindgen::from_value(context_js)?;
+    let lines = context.narrate_round(&result);
+    Ok(serde_wasm_bindgen::to_value(&lines)?)
+}
+
+#[wasm_bindgen]
+pub fn wasm_army_cost(units_js: JsValue) -> u32 {
+    let units: Vec<Unit> = serde_wasm_bindgen::from_value(units_js).unwrap();
+    army::army_cost(&units)
+}
+
+/// Pre-check an army before committing to it; rejects with the reason it is not allowed
+#[wasm_bindgen]
+pub fn wasm_validate_army(units_js: JsValue, league_id: u8) -> Result<(), JsValue> {
+    let units: Vec<Unit> = serde_wasm_bindgen::from_value(units_js)?;
+    army::validate_army(&units, league_id).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn wasm_class_matrix() -> JsValue {
+    serde_wasm_bindgen::to_value(classes::active_class_matrix()).unwrap()
+}
+
+/// Digest of the rules run on fixed inputs; must equal the engine's `DETERMINISM_DIGEST`
+#[wasm_bindgen]
+pub fn wasm_determinism_digest() -> String {
+    determinism::determinism_digest()
+}
+
+/// Hash of the rules this build was compiled with; must equal the engine's `rules_hash`
+#[wasm_bindgen]
+pub fn wasm_rules_hash() -> String {
+    determinism::rules_hash().to_string()
+}
+
+/// Season of the active ruleset running at a Unix timestamp, or null without one
+#[wasm_bindgen]
+pub fn wasm_current_season(timestamp: u64) -> JsValue {
+    serde_wasm_bindgen::to_value(&league::current_season(timestamp)).unwrap()
+}
+
 #[wasm_bindgen]
 pub fn wasm_apply_league_modifiers(base_unit_js: JsValue, league_id: u8) -> JsValue {
     let mut unit: Unit = serde_wasm_bindgen::from_value(base_unit_js).unwrap();
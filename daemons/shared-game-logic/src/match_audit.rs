@@ -0,0 +1,249 @@
+//! Full re-verification of a finished match, so a client can audit the result
+//! from the match's events before accepting it, just as the game engine does
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::army::validate_army;
+use crate::combat::{generate_army_for_version, ARMY_GENERATOR_V1};
+use crate::commitment::{verify_cashu_commitment, verify_moves_commitment};
+use crate::game_state::{GameLogicError, Unit};
+use crate::league;
+use crate::replay::{replay_match, CombatEvent, MoveReveal};
+
+/// Everything one player committed to and revealed during the match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerTranscript {
+    pub npub: String,
+    pub cashu_token_commitment: String, // From the challenge or acceptance
+    pub cashu_tokens: Vec<String>,      // From the token reveal; the first one generates the army
+    pub token_secrets_nonce: String,
+    #[serde(default)]
+    pub move_commitments: BTreeMap<u32, String>, // round -> commitment
+}
+
+/// A finished match as collected from its events
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchTranscript {
+    pub league_id: u8,
+    #[serde(default = "default_generator_version")]
+    pub generator_version: u8, // From the challenge; older challenges omit it and get v1
+    #[serde(default)]
+    pub created_at: u64, // Challenge creation time, which picks the league season
+    #[serde(default)]
+    pub banned_units: [Vec<u8>; 2], // Units banned from each army in the draft phase
+    pub players: [PlayerTranscript; 2],
+    pub moves: Vec<MoveReveal>,
+    pub calculated_winner: Option<String>, // Winner npub the result claims, None for a draw
+}
+
+fn default_generator_version() -> u8 {
+    ARMY_GENERATOR_V1
+}
+
+/// Outcome of a match that passed every check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchAudit {
+    pub armies: [[Unit; 8]; 2],
+    pub events: Vec<CombatEvent>,
+    pub score: [u32; 2], // Rounds won, indexed [player1, player2]
+    pub winner: Option<String>,
+}
+
+/// Re-verify commitments, armies, every round and the winner of a match
+///
+/// Fails on the first check that does not hold, naming it in the error.
+pub fn validate_full_match(transcript: &MatchTranscript) -> Result<MatchAudit, GameLogicError> {
+    // Step 1: every reveal matches what the player committed to
+    for (index, player) in transcript.players.iter().enumerate() {
+        if !verify_cashu_commitment(
+            &player.cashu_token_commitment,
+            &player.cashu_tokens,
+            &player.token_secrets_nonce,
+        ) {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Player {} token commitment verification failed",
+                index + 1
+            )));
+        }
+    }
+    for reveal in &transcript.moves {
+        let player = transcript
+            .players
+            .get(reveal.player as usize)
+            .ok_or_else(|| {
+                GameLogicError::InvalidInput(format!("Unknown player {}", reveal.player))
+            })?;
+        let commitment = player.move_commitments.get(&reveal.round).ok_or_else(|| {
+            GameLogicError::InvalidInput(format!(
+                "Player {} missing move commitment for round {}",
+                reveal.player + 1,
+                reveal.round
+            ))
+        })?;
+        if !verify_moves_commitment(
+            commitment,
+            &reveal.unit_positions,
+            &reveal.unit_abilities,
+            &reveal.moves_nonce,
+        ) {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Player {} move commitment verification failed for round {}",
+                reveal.player + 1,
+                reveal.round
+            )));
+        }
+    }
+
+    // Step 2: armies from the first revealed token, within the league's rules
+    let mut armies = [[Unit::default(); 8]; 2];
+    for (index, player) in transcript.players.iter().enumerate() {
+        let secret = player.cashu_tokens.first().ok_or_else(|| {
+            GameLogicError::InvalidInput(format!("Player {} revealed no tokens", index + 1))
+        })?;
+        armies[index] =
+            generate_army_for_version(secret, transcript.league_id, transcript.generator_version)?;
+        league::apply_season(&mut armies[index], transcript.created_at);
+        validate_army(&armies[index], transcript.league_id)
+            .map_err(|e| GameLogicError::InvalidInput(format!("Player {} army: {e}", index + 1)))?;
+    }
+
+    // Step 3: no move fields a banned unit, then every round, exactly as the engine resolves it
+    for reveal in &transcript.moves {
+        let unit = reveal.unit_positions.first().copied().unwrap_or(0) % 8;
+        if transcript.banned_units[reveal.player as usize].contains(&unit) {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Player {} fielded banned unit {unit} in round {}",
+                reveal.player + 1,
+                reveal.round
+            )));
+        }
+    }
+    let events = replay_match([&armies[0], &armies[1]], &transcript.moves)?;
+    let mut score = [0u32; 2];
+    for event in &events {
+        if let CombatEvent::RoundWon {
+            winner: Some(player),
+            ..
+        } = event
+        {
+            score[*player as usize] += 1;
+        }
+    }
+
+    // Step 4: the claimed winner is the player who won more rounds
+    let winner = match score[0].cmp(&score[1]) {
+        std::cmp::Ordering::Greater => Some(transcript.players[0].npub.clone()),
+        std::cmp::Ordering::Less => Some(transcript.players[1].npub.clone()),
+        std::cmp::Ordering::Equal => None,
+    };
+    if winner != transcript.calculated_winner {
+        return Err(GameLogicError::InvalidInput(format!(
+            "Winner mismatch: expected {:?}, claimed {:?}",
+            winner, transcript.calculated_winner
+        )));
+    }
+
+    Ok(MatchAudit {
+        armies,
+        events,
+        score,
+        winner,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::generate_units_from_token_secret;
+    use crate::commitment::{commit_to_cashu_tokens, commit_to_moves};
+
+    fn player(npub: &str, token: &str, moves: &[MoveReveal]) -> PlayerTranscript {
+        let tokens = vec![token.to_string()];
+        PlayerTranscript {
+            npub: npub.to_string(),
+            cashu_token_commitment: commit_to_cashu_tokens(&tokens, "token_nonce"),
+            cashu_tokens: tokens,
+            token_secrets_nonce: "token_nonce".to_string(),
+            move_commitments: moves
+                .iter()
+                .map(|m| {
+                    let commitment =
+                        commit_to_moves(&m.unit_positions, &m.unit_abilities, &m.moves_nonce);
+                    (m.round, commitment)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_full_match_is_reverified_from_transcript() {
+        let moves: Vec<MoveReveal> = (1..=3)
+            .flat_map(|round| {
+                (0..2).map(move |player| MoveReveal {
+                    player,
+                    round,
+                    unit_positions: vec![round as u8 + player],
+                    unit_abilities: vec![],
+                    moves_nonce: format!("nonce_{player}_{round}"),
+                })
+            })
+            .collect();
+        let p1_moves: Vec<_> = moves.iter().filter(|m| m.player == 0).cloned().collect();
+        let p2_moves: Vec<_> = moves.iter().filter(|m| m.player == 1).cloned().collect();
+        let mut transcript = MatchTranscript {
+            league_id: 0,
+            generator_version: ARMY_GENERATOR_V1,
+            created_at: 1_700_000_000,
+            banned_units: [vec![], vec![]],
+            players: [
+                player("npub1alice", "alice_token", &p1_moves),
+                player("npub1bob", "bob_token", &p2_moves),
+            ],
+            moves,
+            calculated_winner: None,
+        };
+
+        // Exactly one claimed winner survives the audit
+        let claims = [None, Some("npub1alice"), Some("npub1bob")];
+        let valid: Vec<_> = claims
+            .into_iter()
+            .filter(|claim| {
+                transcript.calculated_winner = claim.map(str::to_string);
+                validate_full_match(&transcript).is_ok()
+            })
+            .collect();
+        assert_eq!(valid.len(), 1);
+        transcript.calculated_winner = valid[0].map(str::to_string);
+
+        let audit = validate_full_match(&transcript).unwrap();
+        let armies = [
+            generate_units_from_token_secret("alice_token", 0),
+            generate_units_from_token_secret("bob_token", 0),
+        ];
+        assert_eq!(audit.armies, armies);
+        assert_eq!(
+            audit.events,
+            replay_match([&armies[0], &armies[1]], &transcript.moves).unwrap()
+        );
+        assert_eq!(audit.winner, transcript.calculated_winner);
+
+        // A move that differs from its commitment is caught
+        let mut tampered = transcript.clone();
+        tampered.moves[2].unit_positions = vec![7];
+        let error = validate_full_match(&tampered).unwrap_err().to_string();
+        assert!(error.contains("Player 1 move commitment"), "{error}");
+
+        // So is a move fielding a unit the opponent banned
+        let mut tampered = transcript.clone();
+        tampered.banned_units[1] = vec![tampered.moves[1].unit_positions[0]];
+        let error = validate_full_match(&tampered).unwrap_err().to_string();
+        assert!(error.contains("Player 2 fielded banned unit"), "{error}");
+
+        // So is a token reveal that differs from its commitment
+        let mut tampered = transcript;
+        tampered.players[1].cashu_tokens = vec!["other_token".to_string()];
+        let error = validate_full_match(&tampered).unwrap_err().to_string();
+        assert!(error.contains("Player 2 token commitment"), "{error}");
+    }
+}
@@ -0,0 +1,391 @@
+//! Human-readable battle narration, so every client shows the same battle log
+//!
+//! Lines are built from replay events or a single round's result. The wording lives
+//! here rather than in each client, and only changes with this crate.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::abilities::AbilityId;
+use crate::classes::UnitClass;
+use crate::game_state::{Ability, RoundResult, Unit};
+use crate::replay::CombatEvent;
+
+/// Language narration lines are written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    /// Locale for a language tag such as "es" or "es-MX", if narration supports it
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag.split(['-', '_']).next()?.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "es" => Some(Locale::Es),
+            _ => None,
+        }
+    }
+}
+
+/// Who is fighting, for naming players and their units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NarrationContext {
+    pub players: [String; 2], // Display names, indexed [player1, player2]
+    #[serde(default)]
+    pub armies: Option<[[Unit; 8]; 2]>, // Names units by class when known
+    #[serde(default)]
+    pub locale: Locale,
+}
+
+/// A player's unit as the narration names it
+#[derive(Debug, Clone, Copy)]
+enum UnitName {
+    Class(UnitClass),
+    Index(u8), // Army position, when the armies are not known
+}
+
+/// One narrated step, rendered per locale
+enum Line<'a> {
+    Activated(&'a str, AbilityId),
+    ManaSpent(&'a str, u8, u8), // Spent, remaining
+    Triggered(&'a str, UnitName, Ability),
+    Critical(&'a str, UnitName),
+    Dodge(&'a str, UnitName),
+    Hit {
+        attacker: &'a str,
+        attacker_unit: UnitName,
+        defender: &'a str,
+        defender_unit: UnitName,
+        amount: u8,
+        health: u8,
+    },
+    Death(&'a str, UnitName),
+    RoundWon(u32, &'a str),
+    RoundTied(u32),
+}
+
+impl NarrationContext {
+    /// Narrate a replay, one line per step
+    ///
+    /// Attacks are folded into the damage line of the unit they hit.
+    pub fn narrate_events(&self, events: &[CombatEvent]) -> Vec<String> {
+        // Steps before a round's attacks still name its fighting units
+        let fighting: HashMap<(u32, u8), u8> = events
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::Attack {
+                    round,
+                    player,
+                    unit_index,
+                } => Some(((*round, *player), *unit_index)),
+                _ => None,
+            })
+            .collect();
+        let unit = |round: u32, player: u8| {
+            self.unit_name(player, fighting.get(&(round, player)).copied().unwrap_or(0))
+        };
+
+        events
+            .iter()
+            .filter_map(|event| {
+                let line = match *event {
+                    CombatEvent::AbilityActivated {
+                        player, ability, ..
+                    } => Line::Activated(self.player(player), ability),
+                    CombatEvent::ManaSpent {
+                        player,
+                        spent,
+                        remaining,
+                        ..
+                    } => Line::ManaSpent(self.player(player), spent, remaining),
+                    CombatEvent::AbilityTriggered {
+                        round,
+                        player,
+                        ability,
+                    } => Line::Triggered(self.player(player), unit(round, player), ability),
+                    CombatEvent::Attack { .. } => return None,
+                    CombatEvent::Critical { round, player } => {
+                        Line::Critical(self.player(player), unit(round, player))
+                    }
+                    CombatEvent::Dodge { round, player } => {
+                        Line::Dodge(self.player(player), unit(round, player))
+                    }
+                    CombatEvent::Damage {
+                        round,
+                        player,
+                        amount,
+                        health,
+                    } => Line::Hit {
+                        attacker: self.player(1 - player),
+                        attacker_unit: unit(round, 1 - player),
+                        defender: self.player(player),
+                        defender_unit: unit(round, player),
+                        amount,
+                        health,
+                    },
+                    CombatEvent::Death {
+                        player, unit_index, ..
+                    } => Line::Death(self.player(player), self.unit_name(player, unit_index)),
+                    CombatEvent::RoundWon {
+                        round,
+                        winner: Some(player),
+                    } => Line::RoundWon(round, self.player(player)),
+                    CombatEvent::RoundWon {
+                        round,
+                        winner: None,
+                    } => Line::RoundTied(round),
+                };
+                Some(line.render(self.locale))
+            })
+            .collect()
+    }
+
+    /// Narrate one resolved round, naming its winner as the result records it
+    pub fn narrate_round(&self, result: &RoundResult) -> Vec<String> {
+        let units = [
+            UnitName::Class(result.player1_unit.class),
+            UnitName::Class(result.player2_unit.class),
+        ];
+        let survivors = [&result.player1_unit, &result.player2_unit];
+
+        let mut lines: Vec<Line> = (0..2)
+            .map(|player| Line::Hit {
+                attacker: self.player(player as u8),
+                attacker_unit: units[player],
+                defender: self.player(1 - player as u8),
+                defender_unit: units[1 - player],
+                amount: result.damage_dealt[player],
+                health: survivors[1 - player].health,
+            })
+            .collect();
+        for player in 0..2 {
+            if !survivors[player].is_alive() {
+                lines.push(Line::Death(self.player(player as u8), units[player]));
+            }
+        }
+        let round = u32::from(result.round);
+        lines.push(match &result.winner {
+            Some(winner) => Line::RoundWon(round, winner),
+            None => Line::RoundTied(round),
+        });
+
+        lines
+            .into_iter()
+            .map(|line| line.render(self.locale))
+            .collect()
+    }
+
+    fn player(&self, player: u8) -> &str {
+        &self.players[player as usize % 2]
+    }
+
+    fn unit_name(&self, player: u8, unit_index: u8) -> UnitName {
+        match &self.armies {
+            Some(armies) => {
+                UnitName::Class(armies[player as usize % 2][unit_index as usize % 8].class)
+            }
+            None => UnitName::Index(unit_index),
+        }
+    }
+}
+
+impl Line<'_> {
+    fn render(&self, locale: Locale) -> String {
+        let unit = |name: &UnitName| unit_text(*name, locale);
+        match (locale, self) {
+            (Locale::En, Line::Activated(player, ability)) => {
+                format!("{player} activates {}", ability_text(*ability, locale))
+            }
+            (Locale::Es, Line::Activated(player, ability)) => {
+                format!("{player} activa {}", ability_text(*ability, locale))
+            }
+            (Locale::En, Line::ManaSpent(player, spent, remaining)) => {
+                format!("{player} spends {spent} mana ({remaining} left)")
+            }
+            (Locale::Es, Line::ManaSpent(player, spent, remaining)) => {
+                format!("{player} gasta {spent} de maná (le quedan {remaining})")
+            }
+            (Locale::En, Line::Triggered(player, name, ability)) => format!(
+                "{player}'s {} uses {}",
+                unit(name),
+                triggered_text(*ability, locale)
+            ),
+            (Locale::Es, Line::Triggered(player, name, ability)) => format!(
+                "{} de {player} usa {}",
+                unit(name),
+                triggered_text(*ability, locale)
+            ),
+            (Locale::En, Line::Critical(player, name)) => {
+                format!("{player}'s {} lands a critical hit", unit(name))
+            }
+            (Locale::Es, Line::Critical(player, name)) => {
+                format!("{} de {player} asesta un golpe crítico", unit(name))
+            }
+            (Locale::En, Line::Dodge(player, name)) => {
+                format!("{player}'s {} dodges the attack", unit(name))
+            }
+            (Locale::Es, Line::Dodge(player, name)) => {
+                format!("{} de {player} esquiva el ataque", unit(name))
+            }
+            (
+                Locale::En,
+                Line::Hit {
+                    attacker,
+                    attacker_unit,
+                    defender,
+                    defender_unit,
+                    amount,
+                    health,
+                },
+            ) => format!(
+                "{attacker}'s {} hits {defender}'s {} for {amount} damage ({health} health left)",
+                unit(attacker_unit),
+                unit(defender_unit)
+            ),
+            (
+                Locale::Es,
+                Line::Hit {
+                    attacker,
+                    attacker_unit,
+                    defender,
+                    defender_unit,
+                    amount,
+                    health,
+                },
+            ) => format!(
+                "{} de {attacker} golpea a {} de {defender} por {amount} de daño (le quedan {health} de vida)",
+                unit(attacker_unit),
+                unit(defender_unit)
+            ),
+            (Locale::En, Line::Death(player, name)) => format!("{player}'s {} falls", unit(name)),
+            (Locale::Es, Line::Death(player, name)) => format!("{} de {player} cae", unit(name)),
+            (Locale::En, Line::RoundWon(round, player)) => format!("{player} wins round {round}"),
+            (Locale::Es, Line::RoundWon(round, player)) => format!("{player} gana la ronda {round}"),
+            (Locale::En, Line::RoundTied(round)) => format!("Round {round} ends in a tie"),
+            (Locale::Es, Line::RoundTied(round)) => format!("La ronda {round} termina en empate"),
+        }
+    }
+}
+
+fn unit_text(name: UnitName, locale: Locale) -> String {
+    match (locale, name) {
+        (Locale::En, UnitName::Class(class)) => format!("{class:?}"),
+        (Locale::Es, UnitName::Class(class)) => match class {
+            UnitClass::Warrior => "Guerrero",
+            UnitClass::Archer => "Arquero",
+            UnitClass::Mage => "Mago",
+            UnitClass::Tank => "Tanque",
+        }
+        .to_string(),
+        (Locale::En, UnitName::Index(index)) => format!("unit {}", index + 1),
+        (Locale::Es, UnitName::Index(index)) => format!("unidad {}", index + 1),
+    }
+}
+
+fn ability_text(ability: AbilityId, locale: Locale) -> &'static str {
+    match (locale, ability) {
+        (Locale::En, AbilityId::Boost) => "Boost",
+        (Locale::En, AbilityId::Shield) => "Shield",
+        (Locale::En, AbilityId::Heal) => "Heal",
+        (Locale::En, AbilityId::Poison) => "Poison",
+        (Locale::En, AbilityId::Stun) => "Stun",
+        (Locale::Es, AbilityId::Boost) => "Impulso",
+        (Locale::Es, AbilityId::Shield) => "Escudo",
+        (Locale::Es, AbilityId::Heal) => "Curación",
+        (Locale::Es, AbilityId::Poison) => "Veneno",
+        (Locale::Es, AbilityId::Stun) => "Aturdimiento",
+    }
+}
+
+fn triggered_text(ability: Ability, locale: Locale) -> &'static str {
+    match ability {
+        Ability::None => "",
+        Ability::Boost => ability_text(AbilityId::Boost, locale),
+        Ability::Shield => ability_text(AbilityId::Shield, locale),
+        Ability::Heal => ability_text(AbilityId::Heal, locale),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(locale: Locale) -> NarrationContext {
+        let mut armies = [[Unit::default(); 8]; 2];
+        armies[0][1].class = UnitClass::Mage;
+        armies[1][1].class = UnitClass::Tank;
+        NarrationContext {
+            players: ["Alice".to_string(), "Bob".to_string()],
+            armies: Some(armies),
+            locale,
+        }
+    }
+
+    #[test]
+    fn test_replay_is_narrated_per_locale() {
+        let events = [
+            CombatEvent::AbilityActivated {
+                round: 1,
+                player: 0,
+                ability: AbilityId::Boost,
+            },
+            CombatEvent::Attack {
+                round: 1,
+                player: 0,
+                unit_index: 1,
+            },
+            CombatEvent::Attack {
+                round: 1,
+                player: 1,
+                unit_index: 1,
+            },
+            CombatEvent::Critical {
+                round: 1,
+                player: 0,
+            },
+            CombatEvent::Damage {
+                round: 1,
+                player: 1,
+                amount: 12,
+                health: 0,
+            },
+            CombatEvent::Death {
+                round: 1,
+                player: 1,
+                unit_index: 1,
+            },
+            CombatEvent::RoundWon {
+                round: 1,
+                winner: Some(0),
+            },
+        ];
+
+        assert_eq!(
+            context(Locale::En).narrate_events(&events),
+            [
+                "Alice activates Boost",
+                "Alice's Mage lands a critical hit",
+                "Alice's Mage hits Bob's Tank for 12 damage (0 health left)",
+                "Bob's Tank falls",
+                "Alice wins round 1",
+            ]
+        );
+        assert_eq!(
+            context(Locale::Es).narrate_events(&events)[2],
+            "Mago de Alice golpea a Tanque de Bob por 12 de daño (le quedan 0 de vida)"
+        );
+
+        // Without armies, units are named by their army position
+        let anonymous = NarrationContext {
+            armies: None,
+            ..context(Locale::En)
+        };
+        assert_eq!(anonymous.narrate_events(&events)[3], "Bob's unit 2 falls");
+        assert_eq!(Locale::from_tag("es-MX"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("fr"), None);
+    }
+}
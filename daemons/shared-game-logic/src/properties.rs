@@ -0,0 +1,206 @@
+//! Property checks for combat determinism, enabled by the `verify_determinism` feature
+//!
+//! Random units, armies, leagues and move sequences are run through the combat rules
+//! to check they never panic, treat both players alike and give the same result on
+//! every run. `cargo test -p shared-game-logic --features verify_determinism` runs them.
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, FileFailurePersistence, TestCaseError, TestRunner};
+
+use crate::abilities::{AbilityId, ABILITY_REGISTRY};
+use crate::classes::UnitClass;
+use crate::combat::{generate_army_for_version, process_combat_with_abilities};
+use crate::game_state::{Ability, RoundResult, StatusEffect, Unit};
+use crate::replay::{replay_match, MoveReveal};
+use crate::rng::AttackRoll;
+
+/// Any unit, including dead, overhealed and maxed-out ones
+pub fn arb_unit() -> impl Strategy<Value = Unit> {
+    let abilities = prop_oneof![
+        Just(Ability::None),
+        Just(Ability::Boost),
+        Just(Ability::Shield),
+        Just(Ability::Heal),
+    ];
+    let effects = prop::collection::vec(
+        (
+            prop_oneof![
+                Just(StatusEffect::Poison),
+                Just(StatusEffect::Stun),
+                Just(StatusEffect::Shield),
+                Just(StatusEffect::Regen),
+                Just(StatusEffect::Burn),
+            ],
+            any::<u8>(),
+        ),
+        0..6,
+    );
+    (
+        any::<[u8; 4]>(),
+        abilities,
+        effects,
+        prop::sample::select(UnitClass::ALL.to_vec()),
+    )
+        .prop_map(
+            |([attack, defense, health, max_health], ability, effects, class)| {
+                let mut unit = Unit {
+                    attack,
+                    defense,
+                    health,
+                    max_health,
+                    ability,
+                    class,
+                    ..Unit::default()
+                };
+                for (effect, rounds) in effects {
+                    unit.apply_status(effect, rounds);
+                }
+                unit
+            },
+        )
+}
+
+/// Army a generator version derives from a random secret for any league id
+pub fn arb_generated_army() -> impl Strategy<Value = [Unit; 8]> {
+    (any::<[u8; 16]>(), any::<u8>(), 1..=2u8).prop_map(|(secret, league_id, version)| {
+        let secret: String = secret.iter().map(|b| format!("{b:02x}")).collect();
+        generate_army_for_version(&secret, league_id, version)
+            .expect("every generator version in range is supported")
+    })
+}
+
+/// Abilities a player activates in one move, as `unit_abilities` keys
+pub fn arb_ability_keys() -> impl Strategy<Value = Vec<String>> {
+    let keys: Vec<&'static str> = ABILITY_REGISTRY.iter().map(|spec| spec.key).collect();
+    prop::collection::vec(prop::sample::select(keys), 0..3)
+        .prop_map(|keys| keys.into_iter().map(str::to_string).collect())
+}
+
+/// Moves for both players over up to eight rounds, with some rounds left half-revealed
+pub fn arb_moves() -> impl Strategy<Value = Vec<MoveReveal>> {
+    let reveal = (
+        0..2u8,
+        1..=8u32,
+        prop::collection::vec(any::<u8>(), 0..3),
+        arb_ability_keys(),
+        "[a-f0-9]{0,8}",
+    )
+        .prop_map(
+            |(player, round, unit_positions, unit_abilities, moves_nonce)| MoveReveal {
+                player,
+                round,
+                unit_positions,
+                unit_abilities,
+                moves_nonce,
+            },
+        );
+    prop::collection::vec(reveal, 0..16)
+}
+
+fn arb_activated() -> impl Strategy<Value = Vec<AbilityId>> {
+    let ids: Vec<AbilityId> = ABILITY_REGISTRY.iter().map(|spec| spec.id).collect();
+    prop::collection::vec(prop::sample::select(ids), 0..3)
+}
+
+fn arb_rolls() -> impl Strategy<Value = [AttackRoll; 2]> {
+    any::<[(bool, bool); 2]>()
+        .prop_map(|rolls| rolls.map(|(critical, dodged)| AttackRoll { critical, dodged }))
+}
+
+/// One round is total, repeatable, and mirrors exactly when the players swap sides
+pub fn check_combat_round(
+    units: [Unit; 2],
+    activated: [&[AbilityId]; 2],
+    rolls: [AttackRoll; 2],
+) -> Result<(), TestCaseError> {
+    let resolve = |units: [Unit; 2], activated: [&[AbilityId]; 2], rolls| {
+        process_combat_with_abilities(units[0], units[1], activated, rolls, "p1", "p2")
+            .map_err(|e| TestCaseError::fail(format!("combat rejected valid units: {e}")))
+    };
+
+    let result = resolve(units, activated, rolls)?;
+    prop_assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        serde_json::to_string(&resolve(units, activated, rolls)?).unwrap(),
+        "repeated run differs"
+    );
+
+    let [rolls1, rolls2] = rolls;
+    let mirrored = resolve(
+        [units[1], units[0]],
+        [activated[1], activated[0]],
+        [rolls2, rolls1],
+    )?;
+    prop_assert_eq!(
+        serde_json::to_string(&mirror(&mirrored)).unwrap(),
+        serde_json::to_string(&result).unwrap(),
+        "swapping players changes the outcome"
+    );
+    Ok(())
+}
+
+/// A round result as seen from the other side of the board
+fn mirror(result: &RoundResult) -> RoundResult {
+    RoundResult {
+        player1_unit: result.player2_unit,
+        player2_unit: result.player1_unit,
+        damage_dealt: [result.damage_dealt[1], result.damage_dealt[0]],
+        winner: result.winner.as_deref().map(|winner| match winner {
+            "p1" => "p2".to_string(),
+            _ => "p1".to_string(),
+        }),
+        ..result.clone()
+    }
+}
+
+/// Replaying the same armies and moves gives the same events, or the same error
+pub fn check_replay(armies: [[Unit; 8]; 2], moves: &[MoveReveal]) -> Result<(), TestCaseError> {
+    let replay = || {
+        replay_match([&armies[0], &armies[1]], moves)
+            .map_err(|e| e.to_string())
+            .map(|events| serde_json::to_string(&events).unwrap())
+    };
+    prop_assert_eq!(replay(), replay(), "replay is not repeatable");
+    Ok(())
+}
+
+/// Run every property over `cases` random inputs, failing with the shrunk counterexample
+pub fn verify_determinism(cases: u32) -> Result<(), String> {
+    let mut runner = TestRunner::new(Config {
+        cases,
+        failure_persistence: Some(Box::new(FileFailurePersistence::Off)),
+        ..Config::default()
+    });
+
+    runner
+        .run(
+            &(
+                [arb_unit(), arb_unit()],
+                [arb_activated(), arb_activated()],
+                arb_rolls(),
+            ),
+            |(units, activated, rolls)| {
+                check_combat_round(units, [&activated[0], &activated[1]], rolls)
+            },
+        )
+        .map_err(|e| format!("combat round: {e}"))?;
+
+    runner
+        .run(
+            &(arb_generated_army(), arb_generated_army(), arb_moves()),
+            |(army1, army2, moves)| check_replay([army1, army2], &moves),
+        )
+        .map_err(|e| format!("replay: {e}"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combat_properties_hold() {
+        verify_determinism(512).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+//! Step-by-step replay of a match, for clients animating it and for pinpointing
+//! the exact step where a submitted result diverges from the engine's
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::abilities::{AbilityId, AbilityLedger};
+use crate::combat::{break_tie, is_double_knockout, process_combat_with_abilities};
+use crate::game_state::{Ability, GameLogicError, Unit};
+use crate::rng::CombatRng;
+
+/// A player's revealed move for one round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoveReveal {
+    pub player: u8, // 0 for player 1, 1 for player 2
+    pub round: u32,
+    pub unit_positions: Vec<u8>, // First position picks the fighting unit
+    pub unit_abilities: Vec<String>,
+    #[serde(default)]
+    pub moves_nonce: String, // Seeds the round's crit and dodge rolls with the opponent's
+}
+
+/// One step of a replayed match; players are indexed 0 and 1
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CombatEvent {
+    /// Ability the player activated in their move
+    AbilityActivated {
+        round: u32,
+        player: u8,
+        ability: AbilityId,
+    },
+    /// Mana the player's activated abilities cost, after the round's regen
+    ManaSpent {
+        round: u32,
+        player: u8,
+        spent: u8,
+        remaining: u8,
+    },
+    /// The fighting unit's own Boost, Shield or Heal
+    AbilityTriggered {
+        round: u32,
+        player: u8,
+        ability: Ability,
+    },
+    Attack {
+        round: u32,
+        player: u8,
+        unit_index: u8,
+    },
+    /// The player's attack landed as a critical hit
+    Critical { round: u32, player: u8 },
+    /// The player's unit dodged the attack on it
+    Dodge { round: u32, player: u8 },
+    Damage {
+        round: u32,
+        player: u8, // Player whose unit was hit
+        amount: u8,
+        health: u8, // Health left at the end of the round
+    },
+    Death {
+        round: u32,
+        player: u8,
+        unit_index: u8,
+    },
+    RoundWon {
+        round: u32,
+        winner: Option<u8>, // None on a tie with both units standing
+    },
+}
+
+/// Replay every round both players revealed a move for, in order
+///
+/// Rounds resolve exactly as the engine's standard combat does: the first position
+/// picks a fresh unit from the army, abilities go through each player's ledger and
+/// double knockouts are settled by `break_tie`.
+pub fn replay_match(
+    armies: [&[Unit; 8]; 2],
+    reveals: &[MoveReveal],
+) -> Result<Vec<CombatEvent>, GameLogicError> {
+    let mut rounds: BTreeMap<u32, [Option<&MoveReveal>; 2]> = BTreeMap::new();
+    for reveal in reveals {
+        let slot = rounds
+            .entry(reveal.round)
+            .or_default()
+            .get_mut(reveal.player as usize)
+            .ok_or_else(|| {
+                GameLogicError::InvalidInput(format!("Unknown player {}", reveal.player))
+            })?;
+        if slot.replace(reveal).is_some() {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Player {} revealed round {} twice",
+                reveal.player, reveal.round
+            )));
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut ledgers = [AbilityLedger::default(), AbilityLedger::default()];
+    let mut fallen = [[false; 8]; 2];
+    for (round, moves) in rounds {
+        let [Some(p1_move), Some(p2_move)] = moves else {
+            return Err(GameLogicError::InvalidInput(format!(
+                "Round {round} is missing a move at step {}",
+                events.len()
+            )));
+        };
+
+        let mut unit_indexes = [0u8; 2];
+        let mut units = [Unit::default(); 2];
+        let mut activated: [Vec<AbilityId>; 2] = Default::default();
+        for (player, reveal) in [p1_move, p2_move].into_iter().enumerate() {
+            let index = reveal.unit_positions.first().copied().unwrap_or(0) % 8;
+            unit_indexes[player] = index;
+            units[player] = armies[player][index as usize];
+            activated[player] = ledgers[player]
+                .use_abilities(round, &reveal.unit_abilities)
+                .map_err(|e| {
+                    GameLogicError::InvalidInput(format!(
+                        "Player {player} round {round} at step {}: {e}",
+                        events.len()
+                    ))
+                })?;
+        }
+
+        for player in 0..2 {
+            for ability in &activated[player] {
+                events.push(CombatEvent::AbilityActivated {
+                    round,
+                    player: player as u8,
+                    ability: *ability,
+                });
+            }
+            if !activated[player].is_empty() {
+                events.push(CombatEvent::ManaSpent {
+                    round,
+                    player: player as u8,
+                    spent: activated[player].iter().map(|a| a.spec().cost).sum(),
+                    remaining: ledgers[player].mana(),
+                });
+            }
+            if units[player].ability != Ability::None {
+                events.push(CombatEvent::AbilityTriggered {
+                    round,
+                    player: player as u8,
+                    ability: units[player].ability,
+                });
+            }
+        }
+
+        let rolls =
+            CombatRng::new([&p1_move.moves_nonce, &p2_move.moves_nonce], round).attack_rolls();
+        let result = process_combat_with_abilities(
+            units[0],
+            units[1],
+            [&activated[0], &activated[1]],
+            rolls,
+            "player1",
+            "player2",
+        )?;
+        let survivors = [result.player1_unit, result.player2_unit];
+
+        for player in 0..2u8 {
+            events.push(CombatEvent::Attack {
+                round,
+                player,
+                unit_index: unit_indexes[player as usize],
+            });
+        }
+        for player in 0..2u8 {
+            let roll = rolls[player as usize];
+            if roll.dodged {
+                events.push(CombatEvent::Dodge {
+                    round,
+                    player: 1 - player,
+                });
+            } else if roll.critical {
+                events.push(CombatEvent::Critical { round, player });
+            }
+        }
+        for player in 0..2u8 {
+            events.push(CombatEvent::Damage {
+                round,
+                player,
+                amount: result.damage_dealt[1 - player as usize],
+                health: survivors[player as usize].health,
+            });
+        }
+        for player in 0..2u8 {
+            if !survivors[player as usize].is_alive() {
+                fallen[player as usize][unit_indexes[player as usize] as usize] = true;
+                events.push(CombatEvent::Death {
+                    round,
+                    player,
+                    unit_index: unit_indexes[player as usize],
+                });
+            }
+        }
+        let winner = if is_double_knockout(&result) {
+            let nonces = [p1_move.moves_nonce.as_str(), p2_move.moves_nonce.as_str()];
+            Some(break_tie(armies, fallen, nonces, round))
+        } else {
+            match result.winner.as_deref() {
+                Some("player1") => Some(0),
+                Some("player2") => Some(1),
+                _ => None,
+            }
+        };
+        events.push(CombatEvent::RoundWon { round, winner });
+    }
+
+    Ok(events)
+}
+
+/// Mana a player has left after each round they revealed a move for
+///
+/// Lets a client check its moves before committing to them; fails at the first
+/// round whose abilities are on cooldown or cost more mana than the player has.
+pub fn mana_by_round(
+    reveals: &[MoveReveal],
+    player: u8,
+) -> Result<BTreeMap<u32, u8>, GameLogicError> {
+    let mut moves: Vec<&MoveReveal> = reveals.iter().filter(|r| r.player == player).collect();
+    moves.sort_by_key(|reveal| reveal.round);
+
+    let mut ledger = AbilityLedger::default();
+    let mut mana = BTreeMap::new();
+    for reveal in moves {
+        ledger.use_abilities(reveal.round, &reveal.unit_abilities)?;
+        mana.insert(reveal.round, ledger.mana());
+    }
+    Ok(mana)
+}
+
+/// First step at which two replays of the same match disagree
+pub fn diverging_step(expected: &[CombatEvent], actual: &[CombatEvent]) -> Option<usize> {
+    expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .or_else(|| (expected.len() != actual.len()).then(|| expected.len().min(actual.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::UnitClass;
+    use crate::game_state::StatusEffects;
+
+    fn army(attack: u8, defense: u8) -> [Unit; 8] {
+        [Unit {
+            attack,
+            defense,
+            health: 20,
+            max_health: 20,
+            ability: Ability::None,
+            status_effects: StatusEffects::default(),
+            class: UnitClass::default(),
+        }; 8]
+    }
+
+    fn reveal(player: u8, round: u32, abilities: &[&str]) -> MoveReveal {
+        MoveReveal {
+            player,
+            round,
+            unit_positions: vec![round as u8],
+            unit_abilities: abilities.iter().map(|a| a.to_string()).collect(),
+            moves_nonce: format!("nonce_{player}"),
+        }
+    }
+
+    #[test]
+    fn test_replay_steps_and_divergence() {
+        let (army1, army2) = (army(25, 5), army(10, 5));
+        let reveals = [
+            reveal(1, 1, &[]),
+            reveal(0, 1, &["boost"]),
+            reveal(0, 2, &[]),
+            reveal(1, 2, &[]),
+        ];
+
+        let events = replay_match([&army1, &army2], &reveals).unwrap();
+        assert_eq!(
+            &events[..7],
+            &[
+                CombatEvent::AbilityActivated {
+                    round: 1,
+                    player: 0,
+                    ability: AbilityId::Boost,
+                },
+                CombatEvent::ManaSpent {
+                    round: 1,
+                    player: 0,
+                    spent: 2,
+                    remaining: 0,
+                },
+                CombatEvent::Attack {
+                    round: 1,
+                    player: 0,
+                    unit_index: 1,
+                },
+                CombatEvent::Attack {
+                    round: 1,
+                    player: 1,
+                    unit_index: 1,
+                },
+                CombatEvent::Damage {
+                    round: 1,
+                    player: 0,
+                    amount: 5,
+                    health: 15,
+                },
+                CombatEvent::Damage {
+                    round: 1,
+                    player: 1,
+                    amount: 45,
+                    health: 0,
+                },
+                CombatEvent::Death {
+                    round: 1,
+                    player: 1,
+                    unit_index: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(
+                    e,
+                    CombatEvent::RoundWon {
+                        winner: Some(0),
+                        ..
+                    }
+                ))
+                .count(),
+            2
+        );
+
+        // A tampered step is reported where it diverges, a truncated replay where it stops
+        let mut tampered = events.clone();
+        tampered[5] = CombatEvent::Damage {
+            round: 1,
+            player: 1,
+            amount: 0,
+            health: 20,
+        };
+        assert_eq!(diverging_step(&events, &tampered), Some(5));
+        assert_eq!(diverging_step(&events, &events[..7]), Some(7));
+        assert_eq!(diverging_step(&events, &events), None);
+
+        assert!(replay_match([&army1, &army2], &reveals[..3]).is_err());
+
+        // Mana regenerates each round; spending past it fails validation
+        assert_eq!(
+            mana_by_round(&reveals, 0).unwrap(),
+            BTreeMap::from([(1, 0), (2, 2)])
+        );
+        assert!(matches!(
+            mana_by_round(&[reveal(0, 1, &["stun"])], 0),
+            Err(GameLogicError::InsufficientMana {
+                cost: 4,
+                available: 2,
+                ..
+            })
+        ));
+    }
+}
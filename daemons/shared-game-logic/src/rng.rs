@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Chance in percent that an attack lands as a critical hit, doubling its damage
+pub const CRIT_CHANCE_PERCENT: u8 = 10;
+/// Chance in percent that the defending unit dodges an attack entirely
+pub const DODGE_CHANCE_PERCENT: u8 = 5;
+
+/// Outcome of the rolls for one unit's attack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AttackRoll {
+    pub critical: bool,
+    pub dodged: bool,
+}
+
+/// Deterministic roll stream for a combat round
+///
+/// Seeded from both players' revealed move nonces, so neither player can pick the
+/// outcome alone and every validator reproduces the exact same rolls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombatRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl CombatRng {
+    pub fn new(nonces: [&str; 2], round: u32) -> Self {
+        let mut hasher = Sha256::new();
+        for nonce in nonces {
+            // Length-prefixed so ("ab", "c") and ("a", "bc") seed differently
+            hasher.update((nonce.len() as u64).to_le_bytes());
+            hasher.update(nonce.as_bytes());
+        }
+        hasher.update(round.to_le_bytes());
+
+        Self {
+            seed: hasher.finalize().into(),
+            counter: 0,
+        }
+    }
+
+    /// Next roll, uniform in 0..100
+    pub fn roll_percent(&mut self) -> u8 {
+        let mut hasher = Sha256::new();
+        hasher.update(self.seed);
+        hasher.update(self.counter.to_le_bytes());
+        self.counter += 1;
+
+        let hash = hasher.finalize();
+        (u64::from_le_bytes(hash[..8].try_into().unwrap()) % 100) as u8
+    }
+
+    /// Rolls for both units' attacks, indexed [player1 attacking, player2 attacking]
+    pub fn attack_rolls(&mut self) -> [AttackRoll; 2] {
+        [(); 2].map(|_| AttackRoll {
+            critical: self.roll_percent() < CRIT_CHANCE_PERCENT,
+            dodged: self.roll_percent() < DODGE_CHANCE_PERCENT,
+        })
+    }
+}
+
+/// Player index, 0 or 1, a coin flip over both players' nonces hands a tied round to
+///
+/// Domain-separated from the round's attack rolls, so those don't reveal the flip.
+pub fn tie_break_flip(nonces: [&str; 2], round: u32) -> u8 {
+    let mut hasher = Sha256::new();
+    hasher.update(b"manastr/tie-break");
+    hasher.update(CombatRng::new(nonces, round).seed);
+    hasher.finalize()[0] & 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::process_combat_with_rolls;
+    use crate::game_state::{Ability, Unit};
+
+    #[test]
+    fn test_rolls_are_reproducible_and_depend_on_both_nonces() {
+        let rolls = |nonces: [&str; 2], round| {
+            let mut rng = CombatRng::new(nonces, round);
+            (0..200).map(|_| rng.roll_percent()).collect::<Vec<_>>()
+        };
+
+        let base = rolls(["alice_nonce", "bob_nonce"], 1);
+        assert_eq!(base, rolls(["alice_nonce", "bob_nonce"], 1));
+        assert_ne!(base, rolls(["alice_nonce", "mallory_nonce"], 1));
+        assert_ne!(base, rolls(["alice_nonce", "bob_nonce"], 2));
+        assert_ne!(rolls(["ab", "c"], 1), rolls(["a", "bc"], 1));
+        assert!(base.iter().all(|roll| *roll < 100));
+
+        // Over many rounds crits land roughly at their configured rate
+        let crits = (0..1000)
+            .map(|round| CombatRng::new(["alice_nonce", "bob_nonce"], round).attack_rolls())
+            .filter(|rolls| rolls[0].critical)
+            .count();
+        assert!((50..150).contains(&crits), "{crits} crits in 1000 rolls");
+
+        // A critical hit doubles the damage through defense, a dodge negates it
+        let unit = Unit::new(20, 5, 40, 40, Ability::None);
+        let rolls = [
+            AttackRoll {
+                critical: true,
+                dodged: false,
+            },
+            AttackRoll {
+                critical: true,
+                dodged: true,
+            },
+        ];
+        let result = process_combat_with_rolls(unit, unit, rolls, "p1", "p2").unwrap();
+        assert_eq!(result.damage_dealt, [30, 0]);
+    }
+}